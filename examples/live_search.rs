@@ -0,0 +1,24 @@
+extern crate mcts_lib;
+
+use mcts_lib::boards::tic_tac_toe::TicTacToeBoard;
+use mcts_lib::budget::SearchBudget;
+use mcts_lib::mcts::MonteCarloTreeSearch;
+use mcts_lib::random::Xoshiro256PlusPlus;
+use mcts_lib::tui::LiveViewer;
+use std::io;
+
+/// Searches a 5×5 Tic-Tac-Toe board with four in a row, drawing the root moves live.
+///
+/// Run with `cargo run --example live_search --features tui`.
+fn main() -> io::Result<()> {
+    let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::new(5, 4))
+        .with_random_generator(Xoshiro256PlusPlus::new(42))
+        .build();
+
+    let mut viewer = LiveViewer::new(ratatui::init()).with_refresh_interval(2000);
+    let result = viewer.search(&mut mcts, SearchBudget::iterations(200000));
+    ratatui::restore();
+
+    println!("The best move is: {:?}", result?.best_move);
+    Ok(())
+}
@@ -0,0 +1,35 @@
+extern crate mcts_lib;
+
+use mcts_lib::agent::MctsAgent;
+use mcts_lib::board::Player;
+use mcts_lib::boards::tic_tac_toe::TicTacToeBoard;
+use mcts_lib::game_loop::GameLoop;
+use mcts_lib::mcts::MonteCarloTreeSearch;
+use mcts_lib::random::Xoshiro256PlusPlus;
+use std::io;
+
+/// Plays Tic-Tac-Toe against the engine in the terminal.
+///
+/// Run with `cargo run --example play_tic_tac_toe [size] [win length]`, e.g. `5 4` for a 5×5 board
+/// where four in a row win. Type moves such as `b2`, or `undo` and `resign`.
+fn main() -> io::Result<()> {
+    let mut args = std::env::args().skip(1).map(|x| x.parse::<usize>());
+    let size = args.next().unwrap_or(Ok(3)).expect("invalid size");
+    let win_length = args.next().unwrap_or(Ok(size)).expect("invalid win length");
+
+    // The engine searches for the player to move, whichever side that is
+    let mut agent = MctsAgent::new(|mut board: TicTacToeBoard| {
+        board.set_root_player(board.get_player_to_move());
+        MonteCarloTreeSearch::builder(board)
+            .with_random_generator(Xoshiro256PlusPlus::new(42))
+            .build()
+    })
+    .with_iterations(20000);
+
+    // The human plays X and moves first
+    let game_loop = GameLoop::new(TicTacToeBoard::new(size, win_length), Player::Me)
+        .with_renderer(|board| board.to_string());
+    let record = game_loop.play(&mut agent, io::stdin().lock(), io::stdout())?;
+    println!("{} moves played", record.moves.len());
+    Ok(())
+}
@@ -22,14 +22,14 @@ fn main() {
     for node in root.children() {
         println!(
             "Move: {:?} = {:.2?}%",
-            node.value().prev_move,
+            node.value().prev_move(),
             node.value().wins_rate() * 100.0
         );
     }
 
     // Get the most promising move
     let best_move_node = root.get_best_child().unwrap();
-    let best_move = best_move_node.value().prev_move;
+    let best_move = best_move_node.value().prev_move().copied();
 
     println!("The best move is: {:?}", best_move);
     assert_eq!(best_move, Some(4));
@@ -0,0 +1,38 @@
+//! Runs the Tic-Tac-Toe search in the browser.
+//!
+//! Build the example for `wasm32-unknown-unknown` without the default `rand` feature, generate the
+//! JavaScript bindings with `wasm-bindgen`, and serve the `examples/wasm` directory:
+//!
+//! ```text
+//! cargo build --release --no-default-features --target wasm32-unknown-unknown \
+//!     --example wasm_tic_tac_toe
+//! wasm-bindgen --target web --out-dir examples/wasm/pkg \
+//!     target/wasm32-unknown-unknown/release/examples/wasm_tic_tac_toe.wasm
+//! python3 -m http.server --directory examples/wasm
+//! ```
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use mcts_lib::board::Board;
+    use mcts_lib::boards::tic_tac_toe::TicTacToeBoard;
+    use mcts_lib::mcts::MonteCarloTreeSearch;
+    use mcts_lib::random::Xoshiro256PlusPlus;
+    use wasm_bindgen::prelude::*;
+
+    /// Returns the move the engine plays after `moves`, or `undefined` if the game is over.
+    ///
+    /// The engine plays the first player, so `moves` must have an even length.
+    #[wasm_bindgen]
+    pub fn best_move(moves: &[u8], iterations: u32, seed: u32) -> Option<u8> {
+        let mut board = TicTacToeBoard::default();
+        for b_move in moves {
+            board.perform_move(b_move);
+        }
+
+        let mut mcts = MonteCarloTreeSearch::builder(board)
+            .with_random_generator(Xoshiro256PlusPlus::new(seed as u64))
+            .build();
+        mcts.iterate_n_times(iterations);
+        mcts.get_most_perspective_move()
+    }
+}
@@ -0,0 +1,324 @@
+use crate::board::{Board, GameOutcome, Player};
+use crate::book::{BookUsage, OpeningBook};
+use crate::budget::SearchBudget;
+use crate::config::PhaseConfigs;
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::RandomGenerator;
+use std::time::Duration;
+
+/// A function creating a search for the position an agent has to move in.
+pub type SearchFactory<T, K> = Box<dyn FnMut(T) -> MonteCarloTreeSearch<T, K>>;
+
+/// A function consulting an opening book before a search, returning the move to play without
+/// searching, if any.
+type BookHook<T, K> =
+    Box<dyn Fn(&T, &mut MonteCarloTreeSearch<T, K>) -> Option<<T as Board>::Move>>;
+
+/// A player choosing moves, e.g. a search, a scripted opponent or a human.
+///
+/// Any `FnMut(&T) -> T::Move` closure is an agent, which is handy for simple opponents.
+pub trait Agent<T: Board> {
+    /// Chooses the move to play on the given board, which has at least one available move.
+    fn choose_move(&mut self, board: &T) -> T::Move;
+}
+
+impl<T: Board, F: FnMut(&T) -> T::Move> Agent<T> for F {
+    fn choose_move(&mut self, board: &T) -> T::Move {
+        self(board)
+    }
+}
+
+/// An `Agent` running a fresh search for every move.
+///
+/// The search factory receives the board whenever the agent is to move, and must return a search
+/// playing for the side to move, e.g. by calling the board's `set_root_player`. The move played is
+/// the one `MonteCarloTreeSearch::choose_final_move` picks with the temperature of that search.
+pub struct MctsAgent<T: Board, K: RandomGenerator> {
+    search_factory: SearchFactory<T, K>,
+    iterations: u32,
+    time_limit: Option<Duration>,
+    opening_book: Option<BookHook<T, K>>,
+    phase_configs: PhaseConfigs,
+}
+
+impl<T: Board, K: RandomGenerator> MctsAgent<T, K> {
+    /// Creates an agent searching 1000 iterations per move with the searches from the factory.
+    pub fn new(search_factory: impl FnMut(T) -> MonteCarloTreeSearch<T, K> + 'static) -> Self {
+        Self {
+            search_factory: Box::new(search_factory),
+            iterations: 1000,
+            time_limit: None,
+            opening_book: None,
+            phase_configs: PhaseConfigs::default(),
+        }
+    }
+
+    /// Sets the opening book consulted before every search, and how it is used.
+    pub fn with_opening_book(
+        mut self,
+        book: impl OpeningBook<T> + 'static,
+        usage: BookUsage,
+    ) -> Self
+    where
+        T::Move: PartialEq,
+    {
+        self.opening_book = Some(Box::new(move |board, mcts| match usage {
+            BookUsage::PlayMove => book.get_book_move(board),
+            BookUsage::SeedRoot => {
+                mcts.seed_from_book(&book);
+                None
+            }
+        }));
+        self
+    }
+
+    /// Sets the number of iterations searched for every move.
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the time after which the search for a move stops, even if iterations are left.
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Sets the configurations the searches switch to with the phase of the game, as reported by
+    /// `Board::get_phase` for the board the agent is to move on. The search from the factory
+    /// keeps its own configuration in the phases without one, while a phase configuration
+    /// replaces every knob the factory set, see `MonteCarloTreeSearch::set_config`.
+    pub fn with_phase_configs(mut self, phase_configs: PhaseConfigs) -> Self {
+        self.phase_configs = phase_configs;
+        self
+    }
+
+    /// Replaces the number of iterations and the time limit of every move.
+    pub(crate) fn set_limits(&mut self, iterations: u32, time_limit: Option<Duration>) {
+        self.iterations = iterations;
+        self.time_limit = time_limit;
+    }
+}
+
+impl<T: Board, K: RandomGenerator> Agent<T> for MctsAgent<T, K>
+where
+    T::Move: Clone,
+{
+    /// Searches the board and plays the best move, falling back to the first available move if
+    /// the search did not expand the root. A book move is played without searching.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the board has no available moves.
+    fn choose_move(&mut self, board: &T) -> T::Move {
+        let mut mcts = (self.search_factory)(board.clone());
+        if let Some(config) = self.phase_configs.get(board.get_phase()) {
+            mcts.set_config(config);
+        }
+        if let Some(b_move) = self
+            .opening_book
+            .as_ref()
+            .and_then(|book| book(board, &mut mcts))
+        {
+            return b_move;
+        }
+        let budget = SearchBudget {
+            iterations: Some(self.iterations as u64),
+            time: self.time_limit,
+        };
+        mcts.search(budget);
+        mcts.choose_final_move()
+            .or_else(|| board.get_available_moves().into_iter().next())
+            .expect("the board has no available moves")
+    }
+}
+
+/// A game played by a `GameRunner`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord<M> {
+    /// The moves played, in order.
+    pub moves: Vec<M>,
+    /// The outcome for the side reported as `Player::Me` by the starting board. It is
+    /// `GameOutcome::InProgress` if the game was cut off by the ply limit.
+    pub outcome: GameOutcome,
+}
+
+/// Plays full games between two agents from a starting board.
+pub struct GameRunner<T: Board> {
+    board: T,
+    max_plies: Option<u32>,
+}
+
+impl<T: Board> GameRunner<T>
+where
+    T::Move: Clone,
+{
+    /// Creates a runner playing every game from the given board.
+    pub fn new(board: T) -> Self {
+        Self {
+            board,
+            max_plies: None,
+        }
+    }
+
+    /// Sets the number of moves after which a game is cut off.
+    pub fn with_max_plies(mut self, max_plies: u32) -> Self {
+        self.max_plies = Some(max_plies);
+        self
+    }
+
+    /// Plays a game in which `me` moves for the side reported as `Player::Me` and `other` for the
+    /// opponent.
+    pub fn play(&self, me: &mut dyn Agent<T>, other: &mut dyn Agent<T>) -> GameRecord<T::Move> {
+        let mut board = self.board.clone();
+        let mut moves = Vec::new();
+        while board.get_outcome() == GameOutcome::InProgress
+            && self.max_plies.is_none_or(|x| moves.len() < x as usize)
+            && !board.get_available_moves().is_empty()
+        {
+            let b_move = match board.get_current_player() {
+                Player::Me => me.choose_move(&board),
+                Player::Other => other.choose_move(&board),
+            };
+            board.perform_move(&b_move);
+            moves.push(b_move);
+        }
+
+        GameRecord {
+            moves,
+            outcome: board.get_outcome(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::agent::{Agent, GameRunner, MctsAgent};
+    use crate::board::{Board, GameOutcome, GamePhase, MoveList, Player};
+    use crate::boards::breakthrough::BreakthroughBoard;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::config::{MctsConfig, PhaseConfigs};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn first_move(board: &TicTacToeBoard) -> u8 {
+        board.get_available_moves()[0]
+    }
+
+    /// A Tic-Tac-Toe board reporting a fixed phase.
+    #[derive(Clone)]
+    struct PhasedBoard {
+        board: TicTacToeBoard,
+        phase: GamePhase,
+    }
+
+    impl Board for PhasedBoard {
+        type Move = u8;
+
+        fn get_current_player(&self) -> Player {
+            self.board.get_current_player()
+        }
+
+        fn get_outcome(&self) -> GameOutcome {
+            self.board.get_outcome()
+        }
+
+        fn get_available_moves(&self) -> MoveList<u8> {
+            self.board.get_available_moves()
+        }
+
+        fn perform_move(&mut self, b_move: &u8) {
+            self.board.perform_move(b_move);
+        }
+
+        fn get_hash(&self) -> u128 {
+            self.board.get_hash()
+        }
+
+        fn get_phase(&self) -> GamePhase {
+            self.phase
+        }
+    }
+
+    #[test]
+    fn runner_records_the_moves_of_both_agents() {
+        // arrange
+        let runner = GameRunner::new(TicTacToeBoard::default());
+
+        // act
+        let record = runner.play(&mut first_move, &mut first_move);
+
+        // assert
+        assert_eq!(record.moves, vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(record.outcome, GameOutcome::Win);
+    }
+
+    #[test]
+    fn runner_stops_at_the_ply_limit() {
+        // arrange
+        let runner = GameRunner::new(TicTacToeBoard::default()).with_max_plies(4);
+
+        // act
+        let record = runner.play(&mut first_move, &mut first_move);
+
+        // assert
+        assert_eq!(record.moves.len(), 4);
+        assert_eq!(record.outcome, GameOutcome::InProgress);
+    }
+
+    #[test]
+    fn search_agent_switches_the_config_with_the_phase() {
+        // arrange
+        let mut agent = MctsAgent::new(|board: PhasedBoard| {
+            MonteCarloTreeSearch::builder(board)
+                .with_random_generator(CustomNumberGenerator::default())
+                .build()
+        })
+        .with_iterations(500)
+        .with_phase_configs(PhaseConfigs {
+            endgame: Some(MctsConfig {
+                max_nodes: Some(1),
+                ..MctsConfig::default()
+            }),
+            ..PhaseConfigs::default()
+        });
+        let board = |phase: GamePhase| PhasedBoard {
+            board: TicTacToeBoard::from_moves(&[0, 3, 4, 1]),
+            phase,
+        };
+
+        // act
+        let midgame_move = agent.choose_move(&board(GamePhase::Midgame));
+        let endgame_move = agent.choose_move(&board(GamePhase::Endgame));
+
+        // assert
+        assert_eq!(midgame_move, 8);
+        assert_eq!(endgame_move, 2);
+    }
+
+    #[test]
+    fn search_agent_beats_a_naive_agent() {
+        // arrange
+        let mut agent = MctsAgent::new(|mut board: BreakthroughBoard| {
+            board.set_root_player(board.get_color_to_move());
+            MonteCarloTreeSearch::builder(board)
+                .with_random_generator(CustomNumberGenerator::default())
+                .build()
+        })
+        .with_iterations(300);
+        let mut naive = |board: &BreakthroughBoard| board.get_available_moves()[0];
+        let runner = GameRunner::new(BreakthroughBoard::new(5, 3));
+
+        // act
+        let as_first = runner.play(&mut agent, &mut naive);
+        let as_second = runner.play(&mut naive, &mut agent);
+
+        // assert
+        assert_eq!(as_first.outcome, GameOutcome::Win);
+        assert_eq!(as_second.outcome, GameOutcome::Lose);
+        assert_eq!(
+            agent.choose_move(&BreakthroughBoard::new(5, 3)),
+            as_first.moves[0]
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use crate::board::{Board, Bound};
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::RandomGenerator;
+use std::fmt::Debug;
+use std::io::{self, Write};
+
+/// The character separating the fields of an analysis table.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Separator {
+    /// Comma-separated values.
+    #[default]
+    Comma,
+    /// Tab-separated values.
+    Tab,
+}
+
+impl Separator {
+    fn as_char(self) -> char {
+        match self {
+            Separator::Comma => ',',
+            Separator::Tab => '\t',
+        }
+    }
+}
+
+/// The statistics of a single root move after a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveAnalysis {
+    /// The move, formatted with `Debug`.
+    pub b_move: String,
+    /// The number of times the move was visited.
+    pub visits: u32,
+    /// The fraction of simulations through the move that were won.
+    pub win_rate: f64,
+    /// The fraction of simulations through the move that were drawn.
+    pub draw_rate: f64,
+    /// The mean of the values backed up through the move.
+    pub value: f64,
+    /// The standard error of `value`.
+    pub std_error: f64,
+    /// The 95% Wilson interval of `value`.
+    pub confidence_interval: (f64, f64),
+    /// The proven bound of the move.
+    pub bound: Bound,
+    /// The UCB1 value selection currently assigns to the move.
+    pub ucb: f64,
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Debug,
+{
+    /// Returns the statistics of every root move, in the order the moves were created.
+    pub fn analyze_root(&self) -> Vec<MoveAnalysis> {
+        self.get_tree()
+            .root()
+            .children()
+            .map(|x| {
+                let mcts_node = x.value();
+                MoveAnalysis {
+                    b_move: format!("{:?}", mcts_node.prev_move().unwrap()),
+                    visits: mcts_node.stats.visits,
+                    win_rate: mcts_node.wins_rate(),
+                    draw_rate: mcts_node.draws_rate(),
+                    value: mcts_node.value_mean(),
+                    std_error: mcts_node.value_standard_error(),
+                    confidence_interval: mcts_node.wilson_interval(1.96),
+                    bound: mcts_node.stats.bound,
+                    ucb: self.get_child_ucb(x.id()),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Collects the root move statistics of the searches made over a game, e.g. one per ply, and
+/// writes them as a CSV or TSV table for plotting.
+///
+/// The table starts with a header row, followed by one row per root move of every recorded search:
+/// `ply`, `move`, `visits`, `win_rate`, `draw_rate`, `value`, `std_error`, `ci_low`, `ci_high`,
+/// `bound` and `ucb`. Fields containing the separator, a quote or a line break are quoted.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AnalysisLog {
+    separator: Separator,
+    rows: Vec<(u32, MoveAnalysis)>,
+}
+
+impl AnalysisLog {
+    /// Creates an empty log written with the given separator.
+    pub fn new(separator: Separator) -> Self {
+        Self {
+            separator,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Returns the recorded rows with the ply they were recorded at.
+    pub fn get_rows(&self) -> &[(u32, MoveAnalysis)] {
+        &self.rows
+    }
+
+    /// Records the root move statistics of a search made at the given ply.
+    pub fn record<T: Board, K: RandomGenerator>(
+        &mut self,
+        ply: u32,
+        mcts: &MonteCarloTreeSearch<T, K>,
+    ) where
+        T::Move: Debug,
+    {
+        self.rows
+            .extend(mcts.analyze_root().into_iter().map(|x| (ply, x)));
+    }
+
+    /// Writes the header row and every recorded row.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let separator = self.separator.as_char().to_string();
+        let header = [
+            "ply",
+            "move",
+            "visits",
+            "win_rate",
+            "draw_rate",
+            "value",
+            "std_error",
+            "ci_low",
+            "ci_high",
+            "bound",
+            "ucb",
+        ];
+        writeln!(writer, "{}", header.join(&separator))?;
+        for (ply, row) in &self.rows {
+            let fields = vec![
+                ply.to_string(),
+                self.quote(&row.b_move),
+                row.visits.to_string(),
+                row.win_rate.to_string(),
+                row.draw_rate.to_string(),
+                row.value.to_string(),
+                row.std_error.to_string(),
+                row.confidence_interval.0.to_string(),
+                row.confidence_interval.1.to_string(),
+                format!("{:?}", row.bound),
+                row.ucb.to_string(),
+            ];
+            writeln!(writer, "{}", fields.join(&separator))?;
+        }
+        Ok(())
+    }
+
+    /// Quotes a field if it contains the separator, a quote or a line break.
+    fn quote(&self, field: &str) -> String {
+        let separator = self.separator.as_char();
+        if field.contains([separator, '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{AnalysisLog, Separator};
+    use crate::board::Bound;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn searched(iterations: u32) -> MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator> {
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(iterations);
+        mcts
+    }
+
+    #[test]
+    fn analyzes_every_root_move() {
+        // arrange
+        let mcts = searched(500);
+
+        // act
+        let analysis = mcts.analyze_root();
+
+        // assert
+        assert_eq!(analysis.len(), 9);
+        assert_eq!(analysis[4].b_move, "4");
+        assert_eq!(analysis.iter().map(|x| x.visits).sum::<u32>(), 500);
+        assert!(analysis.iter().all(|x| x.bound == Bound::None));
+        let root = mcts.get_root();
+        let center = root.children().nth(4).unwrap();
+        assert_eq!(analysis[4].win_rate, center.value().wins_rate());
+        assert!(analysis[4].ucb > analysis[4].value);
+        let (low, high) = analysis[4].confidence_interval;
+        assert!(low < analysis[4].value && analysis[4].value < high);
+        assert_eq!(analysis[4].std_error, center.value().value_standard_error());
+    }
+
+    #[test]
+    fn writes_a_table_over_several_plies() {
+        // arrange
+        let mut log = AnalysisLog::new(Separator::Tab);
+        log.record(0, &searched(100));
+        log.record(1, &searched(200));
+
+        // act
+        let mut output = Vec::new();
+        log.write(&mut output).unwrap();
+        let table = String::from_utf8(output).unwrap();
+
+        // assert
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 1 + 2 * 9);
+        assert_eq!(
+            lines[0],
+            "ply\tmove\tvisits\twin_rate\tdraw_rate\tvalue\tstd_error\tci_low\tci_high\tbound\tucb"
+        );
+        assert!(lines[1].starts_with("0\t0\t"));
+        assert!(lines[10].starts_with("1\t0\t"));
+        assert!(lines.iter().skip(1).all(|x| x.split('\t').count() == 11));
+        assert_eq!(log.get_rows().len(), 18);
+    }
+
+    #[test]
+    fn quotes_fields_containing_the_separator() {
+        let log = AnalysisLog::new(Separator::Comma);
+        assert_eq!(log.quote("e2,e4"), "\"e2,e4\"");
+        assert_eq!(log.quote("a\"b"), "\"a\"\"b\"");
+        assert_eq!(log.quote("e2e4"), "e2e4");
+    }
+}
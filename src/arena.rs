@@ -0,0 +1,337 @@
+use crate::agent::{GameRunner, MctsAgent};
+use crate::board::{Board, GameOutcome};
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::RandomGenerator;
+use crate::rating::{Sprt, SprtDecision};
+use std::time::Duration;
+
+/// The results of the games between two contestants, from the point of view of the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    /// The name of the first contestant.
+    pub first: String,
+    /// The name of the second contestant.
+    pub second: String,
+    /// The number of games won by the first contestant.
+    pub wins: u32,
+    /// The number of drawn games.
+    pub draws: u32,
+    /// The number of games lost by the first contestant.
+    pub losses: u32,
+}
+
+impl MatchResult {
+    /// Returns the number of games played.
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Returns the score of the first contestant, counting a draw as half a win, in `[0, 1]`.
+    pub fn score(&self) -> f64 {
+        match self.games() {
+            0 => 0.5,
+            games => (self.wins as f64 + self.draws as f64 / 2.0) / games as f64,
+        }
+    }
+
+    /// Returns the interval around `score` that holds the true score with the confidence given by
+    /// the standard normal quantile `z`, e.g. `1.96` for 95%.
+    ///
+    /// The standard error is estimated from the observed wins, draws and losses, so draws narrow
+    /// the interval as they should.
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let games = self.games() as f64;
+        if games == 0.0 {
+            return (0.0, 1.0);
+        }
+
+        let score = self.score();
+        let variance = (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2))
+            / games;
+        let margin = z * (variance / games).sqrt();
+        ((score - margin).max(0.0), (score + margin).min(1.0))
+    }
+}
+
+struct Contestant<T: Board, K: RandomGenerator> {
+    name: String,
+    agent: MctsAgent<T, K>,
+}
+
+/// Plays matches between searches configured by their search factories, to tell whether a change
+/// to the configuration makes the search stronger.
+///
+/// Every pair of contestants plays `games_per_pair` games from the starting board, swapping sides
+/// after every game. Each contestant plays through an `MctsAgent`, so its factory must return a
+/// search playing for the side to move.
+/// Outcomes are read from the starting board, where the side reported as `Player::Me` is the one
+/// the first contestant of a pair plays in even-numbered games.
+pub struct Arena<T: Board, K: RandomGenerator> {
+    board: T,
+    contestants: Vec<Contestant<T, K>>,
+    games_per_pair: u32,
+    iterations_per_move: u32,
+    time_per_move: Option<Duration>,
+    random_opening_plies: u32,
+    random: K,
+}
+
+impl<T: Board, K: RandomGenerator> Arena<T, K>
+where
+    T::Move: Clone,
+{
+    /// Creates an arena without contestants, playing every game from the given board.
+    pub fn new(board: T) -> Self {
+        Self {
+            board,
+            contestants: Vec::new(),
+            games_per_pair: 2,
+            iterations_per_move: 1000,
+            time_per_move: None,
+            random_opening_plies: 0,
+            random: K::default(),
+        }
+    }
+
+    /// Adds a contestant with the given name and search factory.
+    pub fn with_contestant(
+        mut self,
+        name: &str,
+        search_factory: impl FnMut(T) -> MonteCarloTreeSearch<T, K> + 'static,
+    ) -> Self {
+        self.contestants.push(Contestant {
+            name: name.to_string(),
+            agent: MctsAgent::new(search_factory),
+        });
+        self
+    }
+
+    /// Sets the number of games every pair of contestants plays.
+    pub fn with_games_per_pair(mut self, games_per_pair: u32) -> Self {
+        self.games_per_pair = games_per_pair;
+        self
+    }
+
+    /// Sets the number of iterations searched for every move.
+    pub fn with_iterations_per_move(mut self, iterations_per_move: u32) -> Self {
+        self.iterations_per_move = iterations_per_move;
+        self
+    }
+
+    /// Sets the time after which the search for a move stops, even if iterations are left.
+    pub fn with_time_per_move(mut self, time_per_move: Duration) -> Self {
+        self.time_per_move = Some(time_per_move);
+        self
+    }
+
+    /// Sets the number of random moves played before the contestants take over.
+    ///
+    /// Searches with seeded generators play the same game every time; random openings make the
+    /// games differ. The two games of a pair in which the sides are swapped share their opening.
+    pub fn with_random_opening_plies(mut self, random_opening_plies: u32) -> Self {
+        self.random_opening_plies = random_opening_plies;
+        self
+    }
+
+    /// Sets the random number generator choosing the opening moves.
+    pub fn with_random_generator(mut self, random: K) -> Self {
+        self.random = random;
+        self
+    }
+
+    /// Plays the games between every pair of contestants and returns the results of each pair, in
+    /// the order the contestants were added.
+    pub fn run(&mut self) -> Vec<MatchResult> {
+        self.apply_limits();
+        let mut results = Vec::new();
+        for first in 0..self.contestants.len() {
+            for second in first + 1..self.contestants.len() {
+                let result = self.play_match(first, second, self.games_per_pair, |_| false);
+                results.push(result);
+            }
+        }
+        results
+    }
+
+    /// Plays pairs of games between the two contestants until the test is decided or `max_games`
+    /// games have been played, and returns the result with the final decision.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena does not have exactly two contestants.
+    pub fn run_sprt(&mut self, sprt: &Sprt, max_games: u32) -> (MatchResult, SprtDecision) {
+        assert_eq!(self.contestants.len(), 2, "the test needs two contestants");
+        self.apply_limits();
+        let result = self.play_match(0, 1, max_games, |x| {
+            sprt.decide(x) != SprtDecision::Continue
+        });
+        let decision = sprt.decide(&result);
+        (result, decision)
+    }
+
+    fn apply_limits(&mut self) {
+        for contestant in &mut self.contestants {
+            let iterations = self.iterations_per_move.max(1);
+            contestant.agent.set_limits(iterations, self.time_per_move);
+        }
+    }
+
+    /// Plays up to `games` games, stopping early after a pair of games once `is_decided` holds.
+    fn play_match(
+        &mut self,
+        first: usize,
+        second: usize,
+        games: u32,
+        is_decided: impl Fn(&MatchResult) -> bool,
+    ) -> MatchResult {
+        let mut result = MatchResult {
+            first: self.contestants[first].name.clone(),
+            second: self.contestants[second].name.clone(),
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        };
+
+        let mut opening = self.board.clone();
+        for game in 0..games {
+            let is_first_me = game % 2 == 0;
+            if is_first_me {
+                opening = self.play_random_opening();
+            }
+
+            let (me, other) = match is_first_me {
+                true => (first, second),
+                false => (second, first),
+            };
+            let outcome = self.play_game(opening.clone(), me, other);
+            match (outcome, is_first_me) {
+                (GameOutcome::Win, true) | (GameOutcome::Lose, false) => result.wins += 1,
+                (GameOutcome::Lose, true) | (GameOutcome::Win, false) => result.losses += 1,
+                _ => result.draws += 1,
+            }
+            if !is_first_me && is_decided(&result) {
+                break;
+            }
+        }
+        result
+    }
+
+    fn play_random_opening(&mut self) -> T {
+        let mut board = self.board.clone();
+        for _ in 0..self.random_opening_plies {
+            let moves = board.get_available_moves();
+            if board.get_outcome() != GameOutcome::InProgress || moves.is_empty() {
+                break;
+            }
+            let b_move = self.random.get_random_from_vec(&moves);
+            board.perform_move(b_move);
+        }
+        board
+    }
+
+    /// Plays a game to the end and returns its outcome for the side reported as `Player::Me`.
+    fn play_game(&mut self, opening: T, me: usize, other: usize) -> GameOutcome {
+        let [me, other] = self
+            .contestants
+            .get_disjoint_mut([me, other])
+            .expect("a contestant cannot play itself");
+        GameRunner::new(opening)
+            .play(&mut me.agent, &mut other.agent)
+            .outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::{Arena, MatchResult};
+    use crate::boards::breakthrough::BreakthroughBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+    use std::sync::atomic::AtomicBool;
+
+    /// Makes the side to move the side the search plays for.
+    fn search_with_root(mut board: BreakthroughBoard) -> BreakthroughBoard {
+        board.set_root_player(board.get_color_to_move());
+        board
+    }
+
+    fn arena() -> Arena<BreakthroughBoard, CustomNumberGenerator> {
+        Arena::new(BreakthroughBoard::new(5, 3))
+            .with_iterations_per_move(300)
+            .with_random_opening_plies(2)
+    }
+
+    #[test]
+    fn score_counts_draws_as_half() {
+        // arrange
+        let result = MatchResult {
+            first: "a".to_string(),
+            second: "b".to_string(),
+            wins: 6,
+            draws: 2,
+            losses: 2,
+        };
+
+        // act
+        let score = result.score();
+        let (low, high) = result.confidence_interval(1.96);
+
+        // assert
+        assert_eq!(result.games(), 10);
+        assert_eq!(score, 0.7);
+        assert!(low < score && score < high);
+        assert!((high - score - 1.96 * (0.16f64 / 10.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stronger_search_wins_the_match() {
+        // arrange
+        let mut arena = arena()
+            .with_games_per_pair(6)
+            .with_contestant("strong", |board| {
+                MonteCarloTreeSearch::builder(search_with_root(board))
+                    .with_random_generator(CustomNumberGenerator::default())
+                    .build()
+            })
+            .with_contestant("weak", |board| {
+                MonteCarloTreeSearch::builder(search_with_root(board))
+                    .with_random_generator(CustomNumberGenerator::default())
+                    .with_stop_condition(AtomicBool::new(true))
+                    .build()
+            });
+
+        // act
+        let results = arena.run();
+
+        // assert
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].games(), 6);
+        assert!(results[0].score() > 0.5, "{:?}", results[0]);
+    }
+
+    #[test]
+    fn every_pair_plays_a_match() {
+        // arrange
+        let mut arena = arena().with_games_per_pair(2);
+        for name in ["a", "b", "c"] {
+            arena = arena.with_contestant(name, |board| {
+                MonteCarloTreeSearch::builder(search_with_root(board))
+                    .with_random_generator(CustomNumberGenerator::default())
+                    .build()
+            });
+        }
+
+        // act
+        let results = arena.run();
+
+        // assert
+        let pairs: Vec<_> = results
+            .iter()
+            .map(|x| (x.first.as_str(), x.second.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("a", "b"), ("a", "c"), ("b", "c")]);
+        assert!(results.iter().all(|x| x.games() == 2 && x.draws == 0));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::board::Board;
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::RandomGenerator;
+use crate::result::SearchResult;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use tokio::task::JoinHandle;
+
+/// A snapshot of a search running on a background task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchProgress<M> {
+    /// The number of iterations completed so far.
+    pub iterations: u64,
+    /// The time spent in iterations so far.
+    pub elapsed: Duration,
+    /// The summary of the search at this point.
+    pub result: SearchResult<M>,
+}
+
+/// A search running on a background task, started with `MonteCarloTreeSearch::search_async`.
+///
+/// Progress updates are sent every `progress_interval` iterations and once more when the search
+/// ends. Dropping the handle does not stop the search; call `cancel` first.
+pub struct AsyncSearch<T: Board, K: RandomGenerator> {
+    progress: UnboundedReceiver<SearchProgress<T::Move>>,
+    cancelled: Arc<AtomicBool>,
+    task: JoinHandle<MonteCarloTreeSearch<T, K>>,
+}
+
+impl<T: Board, K: RandomGenerator> AsyncSearch<T, K> {
+    /// Waits for the next progress update. Returns `None` once the search has ended and every
+    /// update has been received.
+    pub async fn next_progress(&mut self) -> Option<SearchProgress<T::Move>> {
+        self.progress.recv().await
+    }
+
+    /// Asks the search to stop. It stops before the next phase of the running iteration.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Checks whether `cancel` has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Waits for the search to end and returns it, so it can be inspected or continued.
+    ///
+    /// If the search was cancelled, its last iteration may be unfinished; it is completed by the
+    /// next call to `do_iteration`.
+    pub async fn finish(self) -> MonteCarloTreeSearch<T, K> {
+        match self.task.await {
+            Ok(mcts) => mcts,
+            Err(error) => std::panic::resume_unwind(error.into_panic()),
+        }
+    }
+}
+
+impl<T, K> MonteCarloTreeSearch<T, K>
+where
+    T: Board + Send + 'static,
+    T::Move: Clone + Send,
+    K: RandomGenerator + Send + 'static,
+{
+    /// Runs `iterations` iterations on a blocking task of the current Tokio runtime.
+    ///
+    /// Every `progress_interval` iterations a `SearchProgress` is sent to the returned handle. The
+    /// search ends early if it is cancelled through the handle, if its own stop condition holds, or
+    /// if the whole tree is calculated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime, or if `progress_interval` is zero.
+    pub fn search_async(mut self, iterations: u32, progress_interval: u32) -> AsyncSearch<T, K> {
+        assert!(progress_interval > 0, "progress interval must be positive");
+
+        let (sender, progress) = unbounded_channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.set_cancel_flag(Some(cancelled.clone()));
+
+        let task = tokio::task::spawn_blocking(move || {
+            self.run_with_progress(iterations, progress_interval, &sender);
+            self.set_cancel_flag(None);
+            self
+        });
+
+        AsyncSearch {
+            progress,
+            cancelled,
+            task,
+        }
+    }
+
+    fn run_with_progress(
+        &mut self,
+        iterations: u32,
+        progress_interval: u32,
+        sender: &UnboundedSender<SearchProgress<T::Move>>,
+    ) {
+        let mut remaining = iterations;
+        while remaining > 0 && !self.is_stop_requested() {
+            let started_with = self.get_statistics().iterations;
+            self.iterate_n_times(remaining.min(progress_interval));
+            let statistics = self.get_statistics();
+            let completed = (statistics.iterations - started_with) as u32;
+            remaining = remaining.saturating_sub(completed);
+
+            if completed == 0 {
+                break;
+            }
+            if remaining > 0 && !self.is_stop_requested() {
+                self.send_progress(sender);
+            }
+        }
+        self.send_progress(sender);
+    }
+
+    fn send_progress(&self, sender: &UnboundedSender<SearchProgress<T::Move>>) {
+        let statistics = self.get_statistics();
+        // The receiver may have been dropped by a caller that is not interested in progress.
+        let _ = sender.send(SearchProgress {
+            iterations: statistics.iterations,
+            elapsed: statistics.elapsed,
+            result: self.get_search_result(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+    use tokio::runtime::{Builder, Runtime};
+
+    fn runtime() -> Runtime {
+        Builder::new_current_thread().build().unwrap()
+    }
+
+    #[test]
+    fn reports_progress_until_the_search_ends() {
+        // arrange
+        let mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        let (updates, mcts) = runtime().block_on(async {
+            let mut search = mcts.search_async(1000, 300);
+            let mut updates = Vec::new();
+            while let Some(progress) = search.next_progress().await {
+                updates.push(progress);
+            }
+            (updates, search.finish().await)
+        });
+
+        // assert
+        let iterations: Vec<_> = updates.iter().map(|x| x.iterations).collect();
+        assert_eq!(iterations, vec![300, 600, 900, 1000]);
+        assert_eq!(updates.last().unwrap().result, mcts.get_search_result());
+        assert_eq!(mcts.get_root().value().stats.visits, 1000);
+        assert!(!mcts.is_stop_requested());
+    }
+
+    #[test]
+    fn cancellation_stops_the_search() {
+        // arrange
+        let mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        let (last, mcts) = runtime().block_on(async {
+            let mut search = mcts.search_async(u32::MAX, 100);
+            let first = search.next_progress().await.unwrap();
+            search.cancel();
+            let mut last = first;
+            while let Some(progress) = search.next_progress().await {
+                last = progress;
+            }
+            (last, search.finish().await)
+        });
+
+        // assert
+        assert!(!mcts.is_stop_requested());
+        assert!(last.iterations < u32::MAX as u64);
+        assert_eq!(last.iterations, mcts.get_statistics().iterations);
+    }
+}
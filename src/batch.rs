@@ -0,0 +1,145 @@
+use crate::board::Board;
+use crate::budget::SearchBudget;
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::RandomGenerator;
+use crate::result::SearchResult;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Searches many independent positions within the same budget, one after another, e.g. to
+/// annotate the positions of a game or to label a dataset.
+///
+/// Every position gets a fresh search created by the factory, which decides the configuration
+/// and the player the search plays for. The results are in the order of the positions.
+pub fn analyze_positions<T: Board, K: RandomGenerator>(
+    positions: impl IntoIterator<Item = T>,
+    budget: SearchBudget,
+    mut factory: impl FnMut(T) -> MonteCarloTreeSearch<T, K>,
+) -> Vec<SearchResult<T::Move>>
+where
+    T::Move: Clone,
+{
+    positions
+        .into_iter()
+        .map(|x| factory(x).search(budget))
+        .collect()
+}
+
+/// Like `analyze_positions`, but searches the positions on `threads` threads, every thread taking
+/// the next position not yet searched.
+///
+/// The searches share no state and the results are in the order of the positions, so with an
+/// iteration budget and generators seeded by the factory the results are exactly those of
+/// `analyze_positions`, however the threads are scheduled.
+///
+/// # Panics
+///
+/// Panics if `threads` is zero, or if a search panics.
+pub fn analyze_positions_parallel<T, K: RandomGenerator>(
+    positions: impl IntoIterator<Item = T>,
+    budget: SearchBudget,
+    threads: usize,
+    factory: impl Fn(T) -> MonteCarloTreeSearch<T, K> + Sync,
+) -> Vec<SearchResult<T::Move>>
+where
+    T: Board + Send,
+    T::Move: Clone + Send,
+{
+    assert!(threads > 0, "at least one thread is needed");
+
+    let positions: Vec<Mutex<Option<T>>> =
+        positions.into_iter().map(|x| Mutex::new(Some(x))).collect();
+    let results: Vec<Mutex<Option<SearchResult<T::Move>>>> =
+        positions.iter().map(|_| Mutex::new(None)).collect();
+    let next_index = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..threads.min(positions.len()) {
+            scope.spawn(|| {
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some(position) = positions.get(index) else {
+                        break;
+                    };
+                    let board = position.lock().unwrap().take().unwrap();
+                    let result = factory(board).search(budget);
+                    *results[index].lock().unwrap() = Some(result);
+                }
+            });
+        }
+    });
+    results
+        .into_iter()
+        .map(|x| x.into_inner().unwrap().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::{analyze_positions, analyze_positions_parallel};
+    use crate::board::Bound;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::budget::SearchBudget;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+    use crate::result::SearchResult;
+
+    fn positions() -> Vec<TicTacToeBoard> {
+        [&[][..], &[4], &[0, 3, 1, 4], &[4, 0, 3, 1], &[0, 4, 8]]
+            .into_iter()
+            .map(TicTacToeBoard::from_moves)
+            .collect()
+    }
+
+    fn search(
+        board: TicTacToeBoard,
+    ) -> MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator> {
+        MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build()
+    }
+
+    #[test]
+    fn analyzes_every_position_in_order() {
+        // arrange
+        let budget = SearchBudget {
+            iterations: Some(500),
+            time: None,
+        };
+
+        // act
+        let results = analyze_positions(positions(), budget, search);
+
+        // assert
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].moves.len(), 9);
+        assert_eq!(results[1].moves.len(), 8);
+        assert_eq!(results[2].best_move, Some(2));
+        assert_eq!(results[2].root_bound, Bound::DefoWin);
+        assert_eq!(results[3].root_bound, Bound::DefoWin);
+        assert!(results.iter().all(|x| x.iterations <= 500));
+    }
+
+    #[test]
+    fn parallel_analysis_matches_the_sequential_one() {
+        // arrange
+        let budget = SearchBudget {
+            iterations: Some(300),
+            time: None,
+        };
+        let sequential = analyze_positions(positions(), budget, search);
+
+        // act
+        let parallel = analyze_positions_parallel(positions(), budget, 3, search);
+        let empty = analyze_positions_parallel(Vec::new(), budget, 2, search);
+
+        // assert
+        let strip = |results: Vec<SearchResult<u8>>| {
+            results
+                .into_iter()
+                .map(|x| (x.best_move, x.moves, x.principal_variation, x.iterations))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(strip(parallel), strip(sequential));
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::board::Board;
+use crate::clock::Stopwatch;
+use crate::mcts::{MctsAction, MonteCarloTreeSearch};
+use crate::random::RandomGenerator;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+static IS_COUNTING: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// A global allocator counting allocations, which lets `BenchReport` include allocation counts.
+///
+/// It forwards everything to the system allocator. Install it in the benchmarking binary:
+///
+/// ```
+/// use mcts_lib::bench::CountingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator;
+/// ```
+///
+/// The counters are shared by all threads, so allocations made by other threads during a benchmark
+/// are counted as well.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        IS_COUNTING.store(true, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// Returns the allocations and allocated bytes counted so far, if `CountingAllocator` is installed.
+fn count_allocations() -> Option<(u64, u64)> {
+    IS_COUNTING.load(Ordering::Relaxed).then(|| {
+        (
+            ALLOCATIONS.load(Ordering::Relaxed),
+            ALLOCATED_BYTES.load(Ordering::Relaxed),
+        )
+    })
+}
+
+/// The throughput measured by `MonteCarloTreeSearch::bench`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BenchReport {
+    /// The number of completed iterations.
+    pub iterations: u64,
+    /// The number of moves played in simulations.
+    pub playout_moves: u64,
+    /// The time spent running the iterations.
+    pub elapsed: Duration,
+    /// The number of allocations, or `None` if `CountingAllocator` is not installed.
+    pub allocations: Option<u64>,
+    /// The number of bytes allocated, or `None` if `CountingAllocator` is not installed.
+    pub allocated_bytes: Option<u64>,
+}
+
+impl BenchReport {
+    /// Returns the number of iterations per second.
+    pub fn iterations_per_second(&self) -> f64 {
+        self.iterations as f64 / self.elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+
+    /// Returns the number of simulated moves per second.
+    pub fn playout_moves_per_second(&self) -> f64 {
+        self.playout_moves as f64 / self.elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+
+    /// Returns the average number of allocations per iteration, if they were counted.
+    pub fn allocations_per_iteration(&self) -> Option<f64> {
+        Some(self.allocations? as f64 / self.iterations.max(1) as f64)
+    }
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K> {
+    /// Runs iterations for the given duration and reports the throughput of the search and the
+    /// board, e.g. to catch performance regressions in a `Board` implementation.
+    ///
+    /// The iterations grow the tree like `iterate_n_times`, and the benchmark ends early once the
+    /// tree is fully calculated or the stop condition holds.
+    pub fn bench(&mut self, duration: Duration) -> BenchReport {
+        let before = count_allocations();
+        let stopwatch = Stopwatch::start();
+        let mut report = BenchReport::default();
+        while stopwatch.elapsed() < duration
+            && *self.get_next_mcts_action() != MctsAction::EverythingIsCalculated
+            && !self.is_stop_requested()
+        {
+            if !self.do_iteration().is_empty() {
+                report.iterations += 1;
+                report.playout_moves += self.get_last_playout_length();
+            }
+        }
+        report.elapsed = stopwatch.elapsed();
+
+        if let (Some(before), Some(after)) = (before, count_allocations()) {
+            report.allocations = Some(after.0 - before.0);
+            report.allocated_bytes = Some(after.1 - before.1);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::CountingAllocator;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+    use std::time::Duration;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn bench_reports_throughput_and_allocations() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        let report = mcts.bench(Duration::from_millis(50));
+
+        // assert
+        assert!(report.iterations > 0);
+        assert_eq!(report.iterations, mcts.get_statistics().iterations);
+        assert!(report.playout_moves > 0);
+        assert!(report.iterations_per_second() > 0.0);
+        assert!(report.allocations.unwrap() > 0);
+        assert!(report.allocations_per_iteration().unwrap() > 0.0);
+    }
+}
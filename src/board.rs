@@ -1,3 +1,12 @@
+use smallvec::SmallVec;
+pub use smallvec::smallvec;
+
+/// The list of moves returned by `Board::get_available_moves`.
+///
+/// Up to 16 moves are stored inline, so generating the moves of most positions does not allocate,
+/// which matters in playouts. Longer lists spill to the heap like a `Vec`.
+pub type MoveList<M> = SmallVec<[M; 16]>;
+
 /// The central trait of the library, defining the interface for a game state.
 ///
 /// To use the MCTS algorithm with a custom game, this trait must be implemented.
@@ -14,17 +23,102 @@ pub trait Board: Clone {
     fn get_outcome(&self) -> GameOutcome;
 
     /// Returns a list of all legal moves available from the current state.
-    fn get_available_moves(&self) -> Vec<Self::Move>;
+    ///
+    /// Build the list with the re-exported `smallvec!` macro, `MoveList::new` or by collecting an
+    /// iterator.
+    fn get_available_moves(&self) -> MoveList<Self::Move>;
 
     /// Applies a given move to the board, modifying its state.
     fn perform_move(&mut self, b_move: &Self::Move);
 
+    /// Applies a given move to the board and returns the outcome of the resulting state.
+    ///
+    /// The search computes the outcome of every state exactly once, through this method, and
+    /// caches it in the tree. The default implementation calls `perform_move` followed by
+    /// `get_outcome`; boards able to update their outcome incrementally, e.g. by only checking the
+    /// lines through the last move, should override it.
+    fn outcome_after_move(&mut self, b_move: &Self::Move) -> GameOutcome {
+        self.perform_move(b_move);
+        self.get_outcome()
+    }
+
     /// Returns a hash value for the current board state.
     fn get_hash(&self) -> u128;
+
+    /// Returns a 64-bit hash value for the current board state, used instead of `get_hash` when
+    /// the search is configured with `HashWidth::Bits64`.
+    ///
+    /// The default implementation folds the result of `get_hash`; boards for which a 128-bit hash
+    /// is expensive should compute a cheaper one directly.
+    fn get_hash64(&self) -> u64 {
+        let hash = self.get_hash();
+        (hash as u64) ^ ((hash >> 64) as u64)
+    }
+
+    /// Returns a compact fingerprint of the state computed independently of the hash, or `None`
+    /// if there is none.
+    ///
+    /// With hash verification enabled, see `MonteCarloTreeSearchBuilder::with_hash_verification`,
+    /// the search compares the fingerprints of states with equal hashes to detect collisions. The
+    /// default implementation has no fingerprint.
+    fn get_fingerprint(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns `true` if the position is so clearly lost for `Player::Me` that playing it out
+    /// further would be a waste of time.
+    ///
+    /// Simulations stop as soon as they reach such a position and count it as a loss. The default
+    /// implementation never gives up.
+    fn is_hopeless(&self) -> bool {
+        false
+    }
+
+    /// Returns a heuristic estimate of the value of the position for `Player::Me`, from `0.0` (a
+    /// sure loss) to `1.0` (a sure win), or `None` if there is no estimate.
+    ///
+    /// The estimate is only used by implicit minimax backups, see
+    /// `MonteCarloTreeSearchBuilder::with_implicit_minimax`. The default implementation has none.
+    fn get_heuristic_value(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns a prior estimate of how promising `b_move` is from this position, higher being
+    /// better.
+    ///
+    /// The prior orders moves for progressive unpruning, see
+    /// `MonteCarloTreeSearchBuilder::with_progressive_unpruning`, and serves as the logit of a
+    /// move in `MonteCarloTreeSearch::search_gumbel`. The default implementation rates
+    /// every move the same, so moves keep the order of `get_available_moves`.
+    fn get_move_prior(&self, _b_move: &Self::Move) -> f64 {
+        0.0
+    }
+
+    /// Returns the stage the game is in.
+    ///
+    /// An `MctsAgent` given per-phase configurations searches with the one of this phase, see
+    /// `MctsAgent::with_phase_configs`. The default implementation is always in the midgame.
+    fn get_phase(&self) -> GamePhase {
+        GamePhase::Midgame
+    }
+}
+
+/// The stage of a game, as reported by `Board::get_phase`.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamePhase {
+    /// The first moves of the game.
+    Opening,
+    /// The bulk of the game.
+    #[default]
+    Midgame,
+    /// The last moves of the game, which are often within reach of the solver.
+    Endgame,
 }
 
 /// Represents the possible outcomes of a game.
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameOutcome {
     /// The game is still ongoing.
     InProgress = 0,
@@ -38,6 +132,7 @@ pub enum GameOutcome {
 
 /// Represents the players in the game from the perspective of the MCTS search.
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Player {
     /// The player for whom the MCTS is currently searching for the best move.
     Me = 1,
@@ -46,9 +141,12 @@ pub enum Player {
 }
 
 /// Used for alpha-beta pruning to mark nodes as having a definite outcome.
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
 pub enum Bound {
     /// The outcome of the node is not yet determined.
+    #[default]
     None = 0,
     /// This node is a guaranteed win for the current player.
     DefoWin = 1,
@@ -0,0 +1,304 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+
+/// The color of a piece, and of the player owning it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum BreakthroughColor {
+    /// The player moving first, starting on the first two rows and moving towards the last.
+    White,
+    /// The player moving second, starting on the last two rows and moving towards the first.
+    Black,
+}
+
+impl BreakthroughColor {
+    /// Returns the other color.
+    pub fn opponent(self) -> Self {
+        match self {
+            BreakthroughColor::White => BreakthroughColor::Black,
+            BreakthroughColor::Black => BreakthroughColor::White,
+        }
+    }
+}
+
+/// A move in Breakthrough.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct BreakthroughMove {
+    /// The square the piece leaves, indexed row by row from the first row.
+    pub from: u8,
+    /// The square the piece arrives on.
+    pub to: u8,
+}
+
+/// An implementation of the `Board` trait for the game of Breakthrough.
+///
+/// Each player starts with two full rows of pieces. A piece moves one square straight or
+/// diagonally forward onto an empty square, and captures only diagonally forward. The first
+/// player to reach the opponent's home row, or to capture all of the opponent's pieces, wins, so
+/// the game cannot end in a draw.
+#[derive(Debug, Clone)]
+pub struct BreakthroughBoard {
+    rows: usize,
+    columns: usize,
+    root_player: BreakthroughColor,
+    current_player: BreakthroughColor,
+    squares: Vec<Option<BreakthroughColor>>,
+    winner: Option<BreakthroughColor>,
+}
+
+impl BreakthroughBoard {
+    /// Creates the starting position on a board of the given size, with white to move and
+    /// searching for white.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than five rows or two columns, or more than 80 squares.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        assert!(
+            rows >= 5 && columns >= 2,
+            "the board needs at least 5 rows and 2 columns"
+        );
+        assert!(
+            rows * columns <= 80,
+            "the board may have at most 80 squares"
+        );
+        let squares = (0..rows * columns)
+            .map(|x| match x / columns {
+                0 | 1 => Some(BreakthroughColor::White),
+                row if row >= rows - 2 => Some(BreakthroughColor::Black),
+                _ => None,
+            })
+            .collect();
+        Self {
+            rows,
+            columns,
+            root_player: BreakthroughColor::White,
+            current_player: BreakthroughColor::White,
+            squares,
+            winner: None,
+        }
+    }
+
+    /// Sets the color the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: BreakthroughColor) {
+        self.root_player = root_player;
+    }
+
+    /// Returns the color to move.
+    pub fn get_color_to_move(&self) -> BreakthroughColor {
+        self.current_player
+    }
+
+    /// Returns the piece on the square with the given index, if any.
+    pub fn get_square(&self, index: u8) -> Option<BreakthroughColor> {
+        self.squares[index as usize]
+    }
+
+    /// Returns the color that has won, if any.
+    pub fn get_winner(&self) -> Option<BreakthroughColor> {
+        self.winner
+    }
+
+    fn forward(color: BreakthroughColor) -> isize {
+        match color {
+            BreakthroughColor::White => 1,
+            BreakthroughColor::Black => -1,
+        }
+    }
+
+    fn goal_row(&self, color: BreakthroughColor) -> usize {
+        match color {
+            BreakthroughColor::White => self.rows - 1,
+            BreakthroughColor::Black => 0,
+        }
+    }
+}
+
+impl Default for BreakthroughBoard {
+    /// Creates the standard 8x8 board.
+    fn default() -> Self {
+        BreakthroughBoard::new(8, 8)
+    }
+}
+
+impl Board for BreakthroughBoard {
+    type Move = BreakthroughMove;
+
+    fn get_current_player(&self) -> Player {
+        match self.current_player == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        let winner = self.winner.or_else(|| {
+            // A player without moves cannot avoid losing.
+            self.get_available_moves()
+                .is_empty()
+                .then(|| self.current_player.opponent())
+        });
+        match winner {
+            None => GameOutcome::InProgress,
+            Some(x) if x == self.root_player => GameOutcome::Win,
+            Some(_) => GameOutcome::Lose,
+        }
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.winner.is_some() {
+            return MoveList::new();
+        }
+
+        let color = self.current_player;
+        let row_step = BreakthroughBoard::forward(color);
+        let mut moves = MoveList::new();
+        for (from, square) in self.squares.iter().enumerate() {
+            if *square != Some(color) {
+                continue;
+            }
+
+            let row = (from / self.columns) as isize + row_step;
+            let column = (from % self.columns) as isize;
+            for column_step in [-1, 0, 1] {
+                let column = column + column_step;
+                if !(0..self.columns as isize).contains(&column) {
+                    continue;
+                }
+
+                let to = row as usize * self.columns + column as usize;
+                let is_allowed = match self.squares[to] {
+                    None => true,
+                    Some(x) => x != color && column_step != 0,
+                };
+                if is_allowed {
+                    moves.push(BreakthroughMove {
+                        from: from as u8,
+                        to: to as u8,
+                    });
+                }
+            }
+        }
+        moves
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        let color = self.current_player;
+        self.squares[b_move.from as usize] = None;
+        self.squares[b_move.to as usize] = Some(color);
+
+        let has_reached_goal = b_move.to as usize / self.columns == self.goal_row(color);
+        let has_captured_all = !self.squares.contains(&Some(color.opponent()));
+        if has_reached_goal || has_captured_all {
+            self.winner = Some(color);
+        }
+        self.current_player = color.opponent();
+    }
+
+    fn get_hash(&self) -> u128 {
+        let mut hash = 0;
+        for square in self.squares.iter().rev() {
+            let square_value = match square {
+                None => 0,
+                Some(BreakthroughColor::White) => 1,
+                Some(BreakthroughColor::Black) => 2,
+            };
+            hash = hash * 3 + square_value;
+        }
+        hash * 2 + (self.current_player == BreakthroughColor::Black) as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome};
+    use crate::boards::breakthrough::{BreakthroughBoard, BreakthroughColor, BreakthroughMove};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn step(from: u8, to: u8) -> BreakthroughMove {
+        BreakthroughMove { from, to }
+    }
+
+    fn board_with(rows: usize, columns: usize, white: &[u8], black: &[u8]) -> BreakthroughBoard {
+        let mut board = BreakthroughBoard::new(rows, columns);
+        board.squares = vec![None; rows * columns];
+        for x in white {
+            board.squares[*x as usize] = Some(BreakthroughColor::White);
+        }
+        for x in black {
+            board.squares[*x as usize] = Some(BreakthroughColor::Black);
+        }
+        board
+    }
+
+    #[test]
+    fn opening_moves_go_forward() {
+        // arrange
+        let board = BreakthroughBoard::default();
+
+        // act
+        let moves = board.get_available_moves();
+
+        // assert
+        assert_eq!(moves.len(), 22);
+        assert!(
+            moves
+                .iter()
+                .all(|x| (8..16).contains(&x.from) && (16..24).contains(&x.to))
+        );
+    }
+
+    #[test]
+    fn captures_only_diagonally() {
+        // arrange
+        let board = board_with(5, 3, &[4], &[7, 8]);
+
+        // act
+        let moves = board.get_available_moves();
+
+        // assert
+        assert_eq!(moves.to_vec(), vec![step(4, 6), step(4, 8)]);
+    }
+
+    #[test]
+    fn reaching_the_home_row_wins() {
+        // arrange
+        let mut board = board_with(5, 2, &[6], &[2, 9]);
+
+        // act
+        board.perform_move(&step(6, 8));
+
+        // assert
+        assert_eq!(board.get_winner(), Some(BreakthroughColor::White));
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn capturing_every_piece_wins() {
+        // arrange
+        let mut board = board_with(5, 2, &[2], &[5]);
+        board.set_root_player(BreakthroughColor::Black);
+
+        // act
+        board.perform_move(&step(2, 5));
+
+        // assert
+        assert_eq!(board.get_winner(), Some(BreakthroughColor::White));
+        assert_eq!(board.get_outcome(), GameOutcome::Lose);
+    }
+
+    #[test]
+    fn search_finds_the_winning_move() {
+        // arrange
+        let board = board_with(5, 3, &[0, 9], &[3, 12]);
+        let mut mcts = MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(200);
+
+        // assert
+        assert_eq!(mcts.get_most_perspective_move(), Some(step(9, 13)));
+    }
+}
@@ -0,0 +1,426 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+
+/// The number of consecutive plies without a capture or a man moving after which the game is a
+/// draw, i.e. forty moves by each player.
+pub const DRAW_PLIES: u32 = 80;
+
+/// The color of a piece, and of the player owning it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum CheckersColor {
+    /// The player moving first, starting on rows 0 to 2 and moving towards row 7.
+    Black,
+    /// The player moving second, starting on rows 5 to 7 and moving towards row 0.
+    White,
+}
+
+impl CheckersColor {
+    /// Returns the other color.
+    pub fn opponent(self) -> Self {
+        match self {
+            CheckersColor::Black => CheckersColor::White,
+            CheckersColor::White => CheckersColor::Black,
+        }
+    }
+
+    /// Returns the row direction men of this color move in.
+    fn forward(self) -> i8 {
+        match self {
+            CheckersColor::Black => 1,
+            CheckersColor::White => -1,
+        }
+    }
+
+    /// Returns the row on which men of this color are crowned.
+    fn king_row(self) -> i8 {
+        match self {
+            CheckersColor::Black => 7,
+            CheckersColor::White => 0,
+        }
+    }
+}
+
+/// A piece on the board.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct CheckersPiece {
+    /// The color of the piece.
+    pub color: CheckersColor,
+    /// Whether the piece has been crowned and may move backwards.
+    pub is_king: bool,
+}
+
+/// A move in checkers: a simple step, or a sequence of one or more jumps.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct CheckersMove {
+    /// The squares the piece visits, starting with the square it leaves. Squares are indexed from
+    /// 0 to 63, row by row.
+    pub path: Vec<u8>,
+    /// The squares of the pieces captured along the way.
+    pub captured: Vec<u8>,
+}
+
+/// An implementation of the `Board` trait for checkers (American draughts) on an 8x8 board.
+///
+/// Captures are mandatory and a capturing piece must keep jumping while it can, so a single move
+/// may consist of several jumps. Men move and capture diagonally forward and are crowned on the
+/// far row, which ends the move; kings move and capture one square in any diagonal direction. A
+/// player without legal moves loses, and the game is drawn after `DRAW_PLIES` plies without
+/// progress.
+#[derive(Debug, Clone)]
+pub struct CheckersBoard {
+    root_player: CheckersColor,
+    current_player: CheckersColor,
+    squares: [Option<CheckersPiece>; 64],
+    plies_without_progress: u32,
+}
+
+impl Default for CheckersBoard {
+    /// Creates the standard starting position with black to move, searching for black.
+    fn default() -> Self {
+        let mut squares = [None; 64];
+        for (index, square) in squares.iter_mut().enumerate() {
+            let row = index / 8;
+            if (row + index % 8) % 2 == 0 {
+                continue;
+            }
+
+            let color = match row {
+                0..=2 => CheckersColor::Black,
+                5..=7 => CheckersColor::White,
+                _ => continue,
+            };
+            *square = Some(CheckersPiece {
+                color,
+                is_king: false,
+            });
+        }
+
+        Self {
+            root_player: CheckersColor::Black,
+            current_player: CheckersColor::Black,
+            squares,
+            plies_without_progress: 0,
+        }
+    }
+}
+
+impl CheckersBoard {
+    /// Sets the color the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: CheckersColor) {
+        self.root_player = root_player;
+    }
+
+    /// Returns the color to move.
+    pub fn get_color_to_move(&self) -> CheckersColor {
+        self.current_player
+    }
+
+    /// Returns the piece on the square with the given index, if any.
+    pub fn get_piece(&self, index: u8) -> Option<CheckersPiece> {
+        self.squares[index as usize]
+    }
+
+    /// Returns the diagonal directions the given piece may move in.
+    fn directions(piece: CheckersPiece) -> Vec<(i8, i8)> {
+        let forward = piece.color.forward();
+        if piece.is_king {
+            vec![(forward, -1), (forward, 1), (-forward, -1), (-forward, 1)]
+        } else {
+            vec![(forward, -1), (forward, 1)]
+        }
+    }
+
+    /// Returns the square `distance` steps away in the given direction, if it is on the board.
+    fn step(index: u8, (row_step, column_step): (i8, i8), distance: i8) -> Option<u8> {
+        let row = (index / 8) as i8 + row_step * distance;
+        let column = (index % 8) as i8 + column_step * distance;
+        ((0..8).contains(&row) && (0..8).contains(&column)).then_some((row * 8 + column) as u8)
+    }
+
+    /// Collects the complete jump sequences continuing `path`, with the jumping piece already
+    /// moved to the last square of the path and the captured pieces removed.
+    fn collect_jumps(
+        &mut self,
+        piece: CheckersPiece,
+        path: &mut Vec<u8>,
+        captured: &mut Vec<u8>,
+        moves: &mut MoveList<CheckersMove>,
+    ) {
+        let from = *path.last().unwrap();
+        let is_crowned = !piece.is_king && (from / 8) as i8 == piece.color.king_row();
+        let mut has_jumped = false;
+        if !is_crowned {
+            for direction in CheckersBoard::directions(piece) {
+                let (Some(over), Some(to)) = (
+                    CheckersBoard::step(from, direction, 1),
+                    CheckersBoard::step(from, direction, 2),
+                ) else {
+                    continue;
+                };
+                let jumped = self.squares[over as usize];
+                if self.squares[to as usize].is_some()
+                    || jumped.is_none_or(|x| x.color == piece.color)
+                {
+                    continue;
+                }
+
+                has_jumped = true;
+                self.squares[over as usize] = None;
+                path.push(to);
+                captured.push(over);
+                self.collect_jumps(piece, path, captured, moves);
+                captured.pop();
+                path.pop();
+                self.squares[over as usize] = jumped;
+            }
+        }
+
+        if !has_jumped && !captured.is_empty() {
+            moves.push(CheckersMove {
+                path: path.clone(),
+                captured: captured.clone(),
+            });
+        }
+    }
+
+    fn get_legal_moves(&self) -> MoveList<CheckersMove> {
+        let mut board = self.clone();
+        let mut moves = MoveList::new();
+        let mut steps = MoveList::new();
+        for from in 0..64u8 {
+            let Some(piece) = self.squares[from as usize] else {
+                continue;
+            };
+            if piece.color != self.current_player {
+                continue;
+            }
+
+            board.squares[from as usize] = None;
+            board.collect_jumps(piece, &mut vec![from], &mut Vec::new(), &mut moves);
+            board.squares[from as usize] = Some(piece);
+
+            for direction in CheckersBoard::directions(piece) {
+                if let Some(to) = CheckersBoard::step(from, direction, 1)
+                    && self.squares[to as usize].is_none()
+                {
+                    steps.push(CheckersMove {
+                        path: vec![from, to],
+                        captured: Vec::new(),
+                    });
+                }
+            }
+        }
+
+        if moves.is_empty() { steps } else { moves }
+    }
+}
+
+impl Board for CheckersBoard {
+    type Move = CheckersMove;
+
+    fn get_current_player(&self) -> Player {
+        match self.current_player == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        if self.plies_without_progress >= DRAW_PLIES {
+            return GameOutcome::Draw;
+        }
+
+        if !self.get_legal_moves().is_empty() {
+            return GameOutcome::InProgress;
+        }
+
+        match self.current_player == self.root_player {
+            true => GameOutcome::Lose,
+            false => GameOutcome::Win,
+        }
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.plies_without_progress >= DRAW_PLIES {
+            return MoveList::new();
+        }
+
+        self.get_legal_moves()
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        let from = b_move.path[0] as usize;
+        let to = *b_move.path.last().unwrap();
+        let mut piece = self.squares[from].take().unwrap();
+        let is_progress = !piece.is_king || !b_move.captured.is_empty();
+        if (to / 8) as i8 == piece.color.king_row() {
+            piece.is_king = true;
+        }
+        self.squares[to as usize] = Some(piece);
+        for captured in &b_move.captured {
+            self.squares[*captured as usize] = None;
+        }
+
+        self.plies_without_progress = match is_progress {
+            true => 0,
+            false => self.plies_without_progress + 1,
+        };
+        self.current_player = self.current_player.opponent();
+    }
+
+    fn get_hash(&self) -> u128 {
+        let mut hash = 0;
+        for (index, square) in self.squares.iter().enumerate().rev() {
+            if (index / 8 + index % 8) % 2 == 0 {
+                continue;
+            }
+
+            let square_value = match square {
+                None => 0,
+                Some(x) => 1 + x.is_king as u128 * 2 + (x.color == CheckersColor::White) as u128,
+            };
+            hash = hash * 5 + square_value;
+        }
+        hash * 2 + (self.current_player == CheckersColor::White) as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome, Player};
+    use crate::boards::checkers::{
+        CheckersBoard, CheckersColor, CheckersMove, CheckersPiece, DRAW_PLIES,
+    };
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn board_with(pieces: &[(u8, CheckersColor, bool)], to_move: CheckersColor) -> CheckersBoard {
+        let mut squares = [None; 64];
+        for (index, color, is_king) in pieces {
+            squares[*index as usize] = Some(CheckersPiece {
+                color: *color,
+                is_king: *is_king,
+            });
+        }
+        CheckersBoard {
+            root_player: CheckersColor::Black,
+            current_player: to_move,
+            squares,
+            plies_without_progress: 0,
+        }
+    }
+
+    fn step(path: &[u8], captured: &[u8]) -> CheckersMove {
+        CheckersMove {
+            path: path.to_vec(),
+            captured: captured.to_vec(),
+        }
+    }
+
+    #[test]
+    fn opening_has_seven_moves() {
+        let board = CheckersBoard::default();
+        assert_eq!(board.get_available_moves().len(), 7);
+        assert_eq!(board.get_outcome(), GameOutcome::InProgress);
+    }
+
+    #[test]
+    fn captures_are_forced_and_chained() {
+        // arrange
+        let black = CheckersColor::Black;
+        let white = CheckersColor::White;
+        let board = board_with(
+            &[
+                (1, black, false),
+                (10, white, false),
+                (26, white, false),
+                (7, black, false),
+            ],
+            black,
+        );
+
+        // act
+        let moves = board.get_available_moves();
+
+        // assert
+        assert_eq!(moves.to_vec(), vec![step(&[1, 19, 33], &[10, 26])]);
+    }
+
+    #[test]
+    fn man_is_crowned_and_stops_on_the_far_row() {
+        // arrange
+        let black = CheckersColor::Black;
+        let white = CheckersColor::White;
+        let mut board = board_with(
+            &[(42, black, false), (51, white, false), (53, white, false)],
+            black,
+        );
+
+        // act
+        let moves = board.get_available_moves();
+        board.perform_move(&moves[0]);
+
+        // assert
+        assert_eq!(moves.to_vec(), vec![step(&[42, 60], &[51])]);
+        let king = CheckersPiece {
+            color: black,
+            is_king: true,
+        };
+        assert_eq!(board.get_piece(60), Some(king));
+        assert_eq!(board.get_piece(53).map(|x| x.color), Some(white));
+    }
+
+    #[test]
+    fn player_without_moves_loses() {
+        // arrange
+        let black = CheckersColor::Black;
+        let white = CheckersColor::White;
+        let mut board = board_with(&[(1, black, false), (10, white, false)], black);
+
+        // act
+        board.perform_move(&step(&[1, 19], &[10]));
+
+        // assert
+        assert_eq!(board.get_current_player(), Player::Other);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn kings_shuffling_draw_the_game() {
+        // arrange
+        let black = CheckersColor::Black;
+        let white = CheckersColor::White;
+        let mut board = board_with(&[(1, black, true), (62, white, true)], black);
+
+        // act
+        for ply in 0..DRAW_PLIES {
+            let b_move = match ply % 4 {
+                0 => step(&[1, 10], &[]),
+                1 => step(&[62, 53], &[]),
+                2 => step(&[10, 1], &[]),
+                _ => step(&[53, 62], &[]),
+            };
+            board.perform_move(&b_move);
+        }
+
+        // assert
+        assert_eq!(board.get_outcome(), GameOutcome::Draw);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn search_returns_a_legal_opening_move() {
+        // arrange
+        let board = CheckersBoard::default();
+        let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(500);
+
+        // assert
+        let best_move = mcts.get_most_perspective_move().unwrap();
+        assert!(board.get_available_moves().contains(&best_move));
+    }
+}
@@ -0,0 +1,284 @@
+use crate::board::{Board, GameOutcome, GamePhase, MoveList, Player};
+use crate::notation::BoardNotation;
+use crate::protocols::uci::UciBoard;
+use shakmaty::fen::Fen;
+use shakmaty::uci::UciMove;
+use shakmaty::zobrist::Zobrist128;
+use shakmaty::{CastlingMode, Chess, Color, EnPassantMode, KnownOutcome, Move, Outcome, Position};
+
+/// The number of plies without a capture or pawn move after which the game is drawn.
+pub const FIFTY_MOVE_PLIES: u32 = 100;
+
+/// The number of times a position has to occur for the game to be drawn by repetition.
+pub const REPETITIONS: usize = 3;
+
+/// An implementation of the `Board` trait for chess, backed by the `shakmaty` crate.
+///
+/// Besides checkmate, stalemate and insufficient material, the game is drawn by the fifty-move
+/// rule and by threefold repetition. Both are claimable in over-the-board chess, but here they end
+/// the game at once, which keeps the search from shuffling pieces forever.
+#[derive(Debug, Clone)]
+pub struct ChessBoard {
+    root_player: Color,
+    position: Chess,
+    hash: u128,
+    /// The hashes of the positions since the last capture or pawn move, including the current one.
+    history: Vec<u128>,
+    outcome: Option<KnownOutcome>,
+}
+
+impl ChessBoard {
+    /// Creates a board from the given position, searching for the side to move.
+    pub fn new(position: Chess) -> Self {
+        let hash = position.zobrist_hash::<Zobrist128>(EnPassantMode::Legal).0;
+        let mut board = Self {
+            root_player: position.turn(),
+            position,
+            hash,
+            history: vec![hash],
+            outcome: None,
+        };
+        board.outcome = board.compute_outcome();
+        board
+    }
+
+    /// Sets the color the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: Color) {
+        self.root_player = root_player;
+    }
+
+    /// Returns the color to move.
+    pub fn get_color_to_move(&self) -> Color {
+        self.position.turn()
+    }
+
+    /// Returns the underlying position.
+    pub fn get_position(&self) -> &Chess {
+        &self.position
+    }
+
+    /// Returns how often the current position has occurred since the last capture or pawn move.
+    pub fn get_repetitions(&self) -> usize {
+        self.history.iter().filter(|x| **x == self.hash).count()
+    }
+
+    fn compute_outcome(&self) -> Option<KnownOutcome> {
+        if let Outcome::Known(outcome) = self.position.outcome() {
+            return Some(outcome);
+        }
+
+        let is_drawn =
+            self.position.halfmoves() >= FIFTY_MOVE_PLIES || self.get_repetitions() >= REPETITIONS;
+        is_drawn.then_some(KnownOutcome::Draw)
+    }
+}
+
+impl Default for ChessBoard {
+    /// Creates the standard starting position.
+    fn default() -> Self {
+        ChessBoard::new(Chess::default())
+    }
+}
+
+impl Board for ChessBoard {
+    type Move = Move;
+
+    fn get_current_player(&self) -> Player {
+        match self.position.turn() == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        match self.outcome {
+            None => GameOutcome::InProgress,
+            Some(KnownOutcome::Draw) => GameOutcome::Draw,
+            Some(KnownOutcome::Decisive { winner }) if winner == self.root_player => {
+                GameOutcome::Win
+            }
+            Some(KnownOutcome::Decisive { .. }) => GameOutcome::Lose,
+        }
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.outcome.is_some() {
+            return MoveList::new();
+        }
+
+        self.position.legal_moves().into_iter().collect()
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        if self.position.is_irreversible(*b_move) {
+            self.history.clear();
+        }
+        self.position.play_unchecked(*b_move);
+        self.hash = self
+            .position
+            .zobrist_hash::<Zobrist128>(EnPassantMode::Legal)
+            .0;
+        self.history.push(self.hash);
+        self.outcome = self.compute_outcome();
+    }
+
+    fn get_hash(&self) -> u128 {
+        self.hash
+    }
+
+    /// Returns the opening for the first ten moves, and the endgame once at most four pieces
+    /// besides the kings and the pawns are left.
+    fn get_phase(&self) -> GamePhase {
+        let board = self.position.board();
+        let pieces = board.occupied() ^ board.kings() ^ board.pawns();
+        if pieces.count() <= 4 {
+            GamePhase::Endgame
+        } else if self.position.fullmoves().get() <= 10 {
+            GamePhase::Opening
+        } else {
+            GamePhase::Midgame
+        }
+    }
+}
+
+/// Writes positions in Forsyth-Edwards Notation and moves in the notation of the UCI protocol,
+/// e.g. `e2e4`.
+impl BoardNotation for ChessBoard {
+    fn parse_position(notation: &str) -> Option<Self> {
+        let position = notation
+            .parse::<Fen>()
+            .ok()?
+            .into_position(CastlingMode::Standard)
+            .ok()?;
+        Some(ChessBoard::new(position))
+    }
+
+    fn format_position(&self) -> String {
+        Fen::from_position(&self.position, EnPassantMode::Legal).to_string()
+    }
+
+    fn parse_move(&self, notation: &str) -> Option<Self::Move> {
+        notation
+            .parse::<UciMove>()
+            .ok()?
+            .to_move(&self.position)
+            .ok()
+    }
+
+    fn format_move(&self, b_move: &Self::Move) -> String {
+        b_move.to_uci(CastlingMode::Standard).to_string()
+    }
+}
+
+impl UciBoard for ChessBoard {
+    fn start_position() -> Self {
+        ChessBoard::default()
+    }
+
+    fn is_white_to_move(&self) -> bool {
+        self.position.turn() == Color::White
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome, GamePhase};
+    use crate::boards::chess::ChessBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::notation::BoardNotation;
+    use crate::random::CustomNumberGenerator;
+    use shakmaty::Color;
+
+    fn play(board: &mut ChessBoard, moves: &[&str]) {
+        for notation in moves {
+            let b_move = board.parse_move(notation).unwrap();
+            board.perform_move(&b_move);
+        }
+    }
+
+    #[test]
+    fn start_position_has_twenty_moves() {
+        let board = ChessBoard::default();
+        assert_eq!(board.get_available_moves().len(), 20);
+        assert_eq!(board.get_outcome(), GameOutcome::InProgress);
+    }
+
+    #[test]
+    fn checkmate_ends_the_game() {
+        // arrange
+        let mut board = ChessBoard::default();
+
+        // act
+        play(&mut board, &["f2f3", "e7e5", "g2g4", "d8h4"]);
+
+        // assert
+        assert_eq!(board.get_outcome(), GameOutcome::Lose);
+        assert!(board.get_available_moves().is_empty());
+        board.set_root_player(Color::Black);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+    }
+
+    #[test]
+    fn threefold_repetition_is_a_draw() {
+        // arrange
+        let mut board = ChessBoard::default();
+        let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+
+        // act
+        play(&mut board, &shuffle);
+        let after_one_cycle = board.get_outcome();
+        play(&mut board, &shuffle);
+
+        // assert
+        assert_eq!(after_one_cycle, GameOutcome::InProgress);
+        assert_eq!(board.get_repetitions(), 3);
+        assert_eq!(board.get_outcome(), GameOutcome::Draw);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn fifty_quiet_moves_are_a_draw() {
+        // arrange
+        let mut board = ChessBoard::parse_position("4k3/8/8/8/8/8/4P3/R3K3 w - - 99 80").unwrap();
+
+        // act
+        play(&mut board, &["a1a2"]);
+
+        // assert
+        assert_eq!(board.get_outcome(), GameOutcome::Draw);
+    }
+
+    #[test]
+    fn phase_follows_the_moves_and_the_material() {
+        let phase = |fen: &str| ChessBoard::parse_position(fen).unwrap().get_phase();
+        assert_eq!(ChessBoard::default().get_phase(), GamePhase::Opening);
+        assert_eq!(
+            phase("r1bqkb1r/pppp1ppp/2n2n2/4p3/4P3/2N2N2/PPPP1PPP/R1BQKB1R w KQkq - 4 20"),
+            GamePhase::Midgame
+        );
+        assert_eq!(
+            phase("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1"),
+            GamePhase::Endgame
+        );
+    }
+
+    #[test]
+    fn search_finds_mate_in_one() {
+        // arrange
+        let board = ChessBoard::parse_position("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+        let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(1000);
+
+        // assert
+        let best_move = mcts.get_most_perspective_move().unwrap();
+        assert_eq!(board.format_move(&best_move), "a1a8");
+        assert_eq!(
+            board.format_position(),
+            "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1"
+        );
+    }
+}
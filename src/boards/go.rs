@@ -0,0 +1,586 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+use crate::notation::BoardNotation;
+use crate::protocols::gtp::{GtpBoard, GtpColor, format_vertex, parse_vertex};
+use crate::random::{RandomGenerator, SplitMix64};
+
+/// The komi of a new board, the usual value under area scoring.
+pub const DEFAULT_KOMI: f64 = 7.5;
+
+/// The number of moves per point after which a game is scored as it stands, which bounds games
+/// prolonged by repeating kos.
+const PLIES_PER_POINT: u32 = 3;
+
+/// The color of a stone, and of the player owning it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum GoColor {
+    /// The player moving first.
+    Black,
+    /// The player moving second, who receives the komi.
+    White,
+}
+
+impl GoColor {
+    /// Returns the other color.
+    pub fn opponent(self) -> Self {
+        match self {
+            GoColor::Black => GoColor::White,
+            GoColor::White => GoColor::Black,
+        }
+    }
+}
+
+/// A move in Go.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum GoMove {
+    /// Places a stone on the point with the given index.
+    Place(u16),
+    /// Passes the turn.
+    Pass,
+}
+
+/// An implementation of the `Board` trait for the game of Go on a square board of configurable
+/// size, using area scoring.
+///
+/// Points are indexed row by row from the bottom left corner, matching the vertices of the Go Text
+/// Protocol. Suicide is illegal, and the simple ko rule forbids retaking a single stone at once;
+/// longer cycles are not detected, but a game is scored as it stands after three moves per point.
+/// The game ends after two passes in a row, and the player with more stones and surrounded empty
+/// points wins, white receiving the komi.
+///
+/// The available moves leave out filling one's own single-point eyes. Such moves are legal but
+/// almost never useful, and without them random playouts end in reasonable positions instead of
+/// going on until every group has been captured.
+#[derive(Debug, Clone)]
+pub struct GoBoard {
+    size: usize,
+    komi: f64,
+    root_player: GoColor,
+    current_player: GoColor,
+    points: Vec<Option<GoColor>>,
+    /// The point where the simple ko rule forbids playing this turn.
+    ko_point: Option<u16>,
+    consecutive_passes: u8,
+    plies: u32,
+    hash: u128,
+    outcome: GameOutcome,
+}
+
+impl GoBoard {
+    /// Creates an empty board with `DEFAULT_KOMI`, black to move and searching for black.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not between 2 and 19.
+    pub fn new(size: usize) -> Self {
+        assert!((2..=19).contains(&size), "size must be between 2 and 19");
+        Self {
+            size,
+            komi: DEFAULT_KOMI,
+            root_player: GoColor::Black,
+            current_player: GoColor::Black,
+            points: vec![None; size * size],
+            ko_point: None,
+            consecutive_passes: 0,
+            plies: 0,
+            hash: 0,
+            outcome: GameOutcome::InProgress,
+        }
+    }
+
+    /// Sets the points added to the score of white.
+    pub fn with_komi(mut self, komi: f64) -> Self {
+        self.komi = komi;
+        self
+    }
+
+    /// Sets the color the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: GoColor) {
+        self.root_player = root_player;
+        self.outcome = self.compute_outcome();
+    }
+
+    /// Returns the length of a side of the board.
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the color to move.
+    pub fn get_color_to_move(&self) -> GoColor {
+        self.current_player
+    }
+
+    /// Returns the stone on the point with the given index, if any.
+    pub fn get_point(&self, index: u16) -> Option<GoColor> {
+        self.points[index as usize]
+    }
+
+    /// Returns the area score of the given color: its stones, the empty points only it surrounds
+    /// and, for white, the komi.
+    pub fn get_score(&self, color: GoColor) -> f64 {
+        let mut region_owners = vec![None; self.points.len()];
+        let mut is_visited = vec![false; self.points.len()];
+        for start in 0..self.points.len() {
+            if self.points[start].is_some() || is_visited[start] {
+                continue;
+            }
+
+            let mut region = vec![start];
+            let mut borders = [false, false];
+            let mut stack = vec![start];
+            is_visited[start] = true;
+            while let Some(point) = stack.pop() {
+                for neighbor in self.neighbors(point) {
+                    match self.points[neighbor] {
+                        Some(x) => borders[x as usize] = true,
+                        None if !is_visited[neighbor] => {
+                            is_visited[neighbor] = true;
+                            region.push(neighbor);
+                            stack.push(neighbor);
+                        }
+                        None => {}
+                    }
+                }
+            }
+
+            let owner = match borders {
+                [true, false] => Some(GoColor::Black),
+                [false, true] => Some(GoColor::White),
+                _ => None,
+            };
+            for point in region {
+                region_owners[point] = owner;
+            }
+        }
+
+        let area = (0..self.points.len())
+            .filter(|x| self.points[*x].or(region_owners[*x]) == Some(color))
+            .count() as f64;
+        match color {
+            GoColor::Black => area,
+            GoColor::White => area + self.komi,
+        }
+    }
+
+    /// Checks whether the color to move may place a stone on the point with the given index.
+    pub fn is_legal(&self, index: u16) -> bool {
+        let index = index as usize;
+        if self.points[index].is_some() || self.ko_point == Some(index as u16) {
+            return false;
+        }
+
+        // A stone is not a suicide if it has a liberty, joins a group with another liberty, or
+        // captures.
+        let color = self.current_player;
+        self.neighbors(index).any(|x| match self.points[x] {
+            None => true,
+            Some(c) if c == color => self.count_liberties(x) > 1,
+            Some(_) => self.count_liberties(x) == 1,
+        })
+    }
+
+    /// Returns the stones of the group on the given point.
+    fn group(&self, index: usize) -> Vec<usize> {
+        let color = self.points[index];
+        let mut group = vec![index];
+        let mut stack = vec![index];
+        while let Some(point) = stack.pop() {
+            for neighbor in self.neighbors(point) {
+                if self.points[neighbor] == color && !group.contains(&neighbor) {
+                    group.push(neighbor);
+                    stack.push(neighbor);
+                }
+            }
+        }
+        group
+    }
+
+    fn count_liberties(&self, index: usize) -> usize {
+        let mut liberties = Vec::new();
+        for point in self.group(index) {
+            for neighbor in self.neighbors(point) {
+                if self.points[neighbor].is_none() && !liberties.contains(&neighbor) {
+                    liberties.push(neighbor);
+                }
+            }
+        }
+        liberties.len()
+    }
+
+    /// Checks whether the point is a single-point eye of the color to move, which it would only
+    /// fill by playing there.
+    fn is_own_eye(&self, index: usize) -> bool {
+        let color = Some(self.current_player);
+        self.neighbors(index)
+            .all(|x| self.points[x] == color && self.count_liberties(x) > 1)
+    }
+
+    /// Returns the points orthogonally adjacent to the given one.
+    fn neighbors(&self, index: usize) -> impl Iterator<Item = usize> + use<> {
+        let size = self.size as isize;
+        let (row, column) = (index as isize / size, index as isize % size);
+        [(-1, 0), (0, -1), (0, 1), (1, 0)]
+            .into_iter()
+            .map(move |(r, c)| (row + r, column + c))
+            .filter(move |(r, c)| (0..size).contains(r) && (0..size).contains(c))
+            .map(move |(r, c)| (r * size + c) as usize)
+    }
+
+    fn compute_outcome(&self) -> GameOutcome {
+        let max_plies = PLIES_PER_POINT * self.points.len() as u32;
+        if self.consecutive_passes < 2 && self.plies < max_plies {
+            return GameOutcome::InProgress;
+        }
+
+        let mine = self.get_score(self.root_player);
+        let theirs = self.get_score(self.root_player.opponent());
+        match mine.partial_cmp(&theirs) {
+            Some(std::cmp::Ordering::Greater) => GameOutcome::Win,
+            Some(std::cmp::Ordering::Less) => GameOutcome::Lose,
+            _ => GameOutcome::Draw,
+        }
+    }
+
+    /// Returns the Zobrist key of a stone of the given color on the given point.
+    fn stone_key(index: usize, color: GoColor) -> u128 {
+        let mut random = SplitMix64::new(index as u64 * 2 + (color == GoColor::White) as u64);
+        ((random.next_u64() as u128) << 64) | random.next_u64() as u128
+    }
+}
+
+impl Default for GoBoard {
+    /// Creates an empty 9x9 board.
+    fn default() -> Self {
+        GoBoard::new(9)
+    }
+}
+
+impl Board for GoBoard {
+    type Move = GoMove;
+
+    fn get_current_player(&self) -> Player {
+        match self.current_player == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        self.outcome
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.outcome != GameOutcome::InProgress {
+            return MoveList::new();
+        }
+
+        let mut moves: MoveList<GoMove> = (0..self.points.len())
+            .filter(|x| self.is_legal(*x as u16) && !self.is_own_eye(*x))
+            .map(|x| GoMove::Place(x as u16))
+            .collect();
+        moves.push(GoMove::Pass);
+        moves
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        let color = self.current_player;
+        self.ko_point = None;
+        match *b_move {
+            GoMove::Pass => self.consecutive_passes += 1,
+            GoMove::Place(index) => {
+                let index = index as usize;
+                self.consecutive_passes = 0;
+                self.points[index] = Some(color);
+                self.hash ^= GoBoard::stone_key(index, color);
+
+                let mut captured = Vec::new();
+                for neighbor in self.neighbors(index) {
+                    if self.points[neighbor] == Some(color.opponent())
+                        && self.count_liberties(neighbor) == 0
+                    {
+                        for point in self.group(neighbor) {
+                            self.points[point] = None;
+                            self.hash ^= GoBoard::stone_key(point, color.opponent());
+                            captured.push(point);
+                        }
+                    }
+                }
+
+                // Capturing one stone with a lone stone left in atari is a ko.
+                if let [point] = captured[..]
+                    && self.group(index).len() == 1
+                    && self.count_liberties(index) == 1
+                {
+                    self.ko_point = Some(point as u16);
+                }
+            }
+        }
+
+        self.plies += 1;
+        self.current_player = color.opponent();
+        self.outcome = self.compute_outcome();
+    }
+
+    fn get_hash(&self) -> u128 {
+        let ko = self.ko_point.map_or(0, |x| x as u128 + 1);
+        let state = (ko << 3)
+            | ((self.consecutive_passes as u128) << 1)
+            | (self.current_player == GoColor::White) as u128;
+        self.hash ^ state
+    }
+}
+
+/// Names moves by their GTP vertices, e.g. `D4` or `pass`, and writes positions as rows of `X` for
+/// black, `O` for white and `.` separated by `/`, from the top row down, followed by `b` or `w` for
+/// the color to move, e.g. `.../.X./... w`. The komi, the ko and the passes are not part of a
+/// position.
+impl BoardNotation for GoBoard {
+    fn parse_position(notation: &str) -> Option<Self> {
+        let (rows, color) = notation.split_once(' ')?;
+        let rows: Vec<_> = rows.split('/').rev().collect();
+        let size = rows.len();
+        if !(2..=19).contains(&size) || rows.iter().any(|x| x.len() != size) {
+            return None;
+        }
+
+        let mut board = GoBoard::new(size);
+        board.current_player = match color.trim() {
+            "b" => GoColor::Black,
+            "w" => GoColor::White,
+            _ => return None,
+        };
+        for (index, point) in rows.concat().chars().enumerate() {
+            let color = match point {
+                'X' => GoColor::Black,
+                'O' => GoColor::White,
+                '.' => continue,
+                _ => return None,
+            };
+            board.points[index] = Some(color);
+            board.hash ^= GoBoard::stone_key(index, color);
+        }
+        let has_liberties = (0..board.points.len())
+            .all(|x| board.points[x].is_none() || board.count_liberties(x) > 0);
+        board.outcome = board.compute_outcome();
+        has_liberties.then_some(board)
+    }
+
+    fn format_position(&self) -> String {
+        let rows: Vec<String> = self
+            .points
+            .chunks(self.size)
+            .rev()
+            .map(|row| {
+                row.iter()
+                    .map(|x| match x {
+                        None => '.',
+                        Some(GoColor::Black) => 'X',
+                        Some(GoColor::White) => 'O',
+                    })
+                    .collect()
+            })
+            .collect();
+        let color = match self.current_player {
+            GoColor::Black => 'b',
+            GoColor::White => 'w',
+        };
+        format!("{} {color}", rows.join("/"))
+    }
+
+    fn parse_move(&self, vertex: &str) -> Option<Self::Move> {
+        if vertex.eq_ignore_ascii_case("pass") {
+            return Some(GoMove::Pass);
+        }
+
+        let (column, row) = parse_vertex(vertex, self.size)?;
+        let index = (row * self.size + column) as u16;
+        self.is_legal(index).then_some(GoMove::Place(index))
+    }
+
+    fn format_move(&self, b_move: &Self::Move) -> String {
+        match *b_move {
+            GoMove::Pass => "pass".to_string(),
+            GoMove::Place(index) => {
+                format_vertex(index as usize % self.size, index as usize / self.size)
+            }
+        }
+    }
+}
+
+impl GtpBoard for GoBoard {
+    fn with_size(size: usize) -> Option<Self> {
+        (2..=19).contains(&size).then(|| GoBoard::new(size))
+    }
+
+    fn get_color_to_move(&self) -> GtpColor {
+        match self.current_player {
+            GoColor::Black => GtpColor::Black,
+            GoColor::White => GtpColor::White,
+        }
+    }
+
+    fn set_komi(&mut self, komi: f64) {
+        self.komi = komi;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome};
+    use crate::boards::go::{GoBoard, GoColor, GoMove};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::notation::BoardNotation;
+    use crate::protocols::gtp::GtpEngine;
+    use crate::random::CustomNumberGenerator;
+    use std::io::Cursor;
+
+    fn play(board: &mut GoBoard, moves: &[Option<u16>]) {
+        for b_move in moves {
+            let b_move = b_move.map_or(GoMove::Pass, GoMove::Place);
+            board.perform_move(&b_move);
+        }
+    }
+
+    #[test]
+    fn surrounded_stones_are_captured() {
+        // arrange
+        let mut board = GoBoard::default();
+
+        // act
+        play(
+            &mut board,
+            &[Some(1), Some(10), Some(9), None, Some(11), None, Some(19)],
+        );
+
+        // assert
+        assert_eq!(board.get_point(10), None);
+        assert_eq!(board.get_point(19), Some(GoColor::Black));
+    }
+
+    #[test]
+    fn suicide_is_illegal() {
+        // arrange
+        let mut board = GoBoard::default();
+
+        // act
+        play(&mut board, &[Some(1), None, Some(9)]);
+
+        // assert
+        assert!(!board.is_legal(0));
+        assert!(!board.get_available_moves().contains(&GoMove::Place(0)));
+    }
+
+    #[test]
+    fn own_eyes_are_not_filled() {
+        // arrange
+        let mut board = GoBoard::default();
+
+        // act
+        play(&mut board, &[Some(1), None, Some(9), None]);
+
+        // assert
+        assert!(board.is_legal(0));
+        assert!(!board.get_available_moves().contains(&GoMove::Place(0)));
+    }
+
+    #[test]
+    fn ko_cannot_be_retaken_at_once() {
+        // arrange
+        let mut board = GoBoard::default();
+        play(
+            &mut board,
+            &[
+                Some(1),
+                Some(2),
+                Some(9),
+                Some(12),
+                Some(19),
+                Some(20),
+                None,
+                Some(10),
+            ],
+        );
+
+        // act
+        play(&mut board, &[Some(11)]);
+        let is_retake_legal = board.is_legal(10);
+        play(&mut board, &[Some(80), Some(40)]);
+
+        // assert
+        assert_eq!(board.get_point(10), None);
+        assert!(!is_retake_legal);
+        assert!(board.is_legal(10));
+    }
+
+    #[test]
+    fn two_passes_end_the_game_with_area_scoring() {
+        // arrange
+        let mut board = GoBoard::new(3).with_komi(0.5);
+
+        // act
+        play(
+            &mut board,
+            &[Some(1), Some(2), Some(4), Some(5), Some(7), Some(8), None],
+        );
+        let after_one_pass = board.get_outcome();
+        play(&mut board, &[None]);
+
+        // assert
+        assert_eq!(after_one_pass, GameOutcome::InProgress);
+        assert_eq!(board.get_score(GoColor::Black), 6.0);
+        assert_eq!(board.get_score(GoColor::White), 3.5);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert!(board.get_available_moves().is_empty());
+        board.set_root_player(GoColor::White);
+        assert_eq!(board.get_outcome(), GameOutcome::Lose);
+    }
+
+    #[test]
+    fn gtp_engine_generates_a_move() {
+        // arrange
+        let mut engine = GtpEngine::new("mcts-lib", 9)
+            .with_iterations(300)
+            .with_search_factory(|mut board: GoBoard| {
+                board.set_root_player(board.get_color_to_move());
+                MonteCarloTreeSearch::builder(board)
+                    .with_random_generator(CustomNumberGenerator::default())
+                    .build()
+            });
+        let mut output = Vec::new();
+
+        // act
+        engine
+            .run(
+                Cursor::new("boardsize 5\nkomi 0.5\nplay b C3\ngenmove w\n"),
+                &mut output,
+            )
+            .unwrap();
+
+        // assert
+        let output = String::from_utf8(output).unwrap();
+        let response = output.split("\n\n").nth(3).unwrap();
+        assert!(response.starts_with("= "));
+        assert_ne!(response, "= C3");
+        assert_ne!(response, "= resign");
+        assert_eq!(engine.get_board().get_point(12), Some(GoColor::Black));
+    }
+
+    #[test]
+    fn positions_round_trip_through_the_notation() {
+        // arrange
+        let mut board = GoBoard::new(3);
+        play(&mut board, &[Some(4), Some(6)]);
+
+        // act
+        let notation = board.format_position();
+        let parsed = GoBoard::parse_position(&notation).unwrap();
+
+        // assert
+        assert_eq!(notation, "O../.X./... b");
+        assert_eq!(parsed.get_point(6), Some(GoColor::White));
+        assert_eq!(parsed.get_color_to_move(), GoColor::Black);
+        assert_eq!(parsed.get_hash(), board.get_hash());
+        assert_eq!(board.parse_move("a3"), None);
+        assert_eq!(board.format_move(&GoMove::Place(2)), "C1");
+        assert!(GoBoard::parse_position("XO/O. w").is_none());
+    }
+}
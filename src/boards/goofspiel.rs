@@ -0,0 +1,249 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+
+/// One of the two players.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum GoofspielPlayer {
+    /// The player bidding first in the sequential representation.
+    One,
+    /// The player bidding second in the sequential representation.
+    Two,
+}
+
+impl GoofspielPlayer {
+    /// Returns the other player.
+    pub fn opponent(self) -> Self {
+        match self {
+            GoofspielPlayer::One => GoofspielPlayer::Two,
+            GoofspielPlayer::Two => GoofspielPlayer::One,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// An implementation of the `Board` trait for Goofspiel (the Game of Pure Strategy).
+///
+/// Each player holds the cards 1 to N. Every round a prize card is revealed, both players bid one
+/// of their cards at the same time, and the higher bid wins the prize's value; on a tie the prize
+/// is discarded. The bid cards are gone, and after N rounds the player with more points wins. A
+/// move is the value of the card bid.
+///
+/// Goofspiel is a simultaneous-move game, which the search does not support yet. Here the bids
+/// are made one after the other, player one first, so a search for player two sees the bid it
+/// answers. Use `has_pending_bid` to tell the two halves of a round apart, e.g. to search only for
+/// player one, or to hide the pending bid once simultaneous moves are supported.
+#[derive(Debug, Clone)]
+pub struct GoofspielBoard {
+    root_player: GoofspielPlayer,
+    /// The prize cards, in the order they are revealed.
+    prizes: Vec<u8>,
+    round: usize,
+    /// The cards left in each hand, as bitmasks with bit `n` set for the card `n`.
+    hands: [u32; 2],
+    pending_bid: Option<u8>,
+    scores: [u32; 2],
+}
+
+impl GoofspielBoard {
+    /// Creates a game with the cards 1 to `cards` and the prizes revealed in ascending order,
+    /// searching for player one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cards` is not between 1 and 30.
+    pub fn new(cards: u8) -> Self {
+        Self::with_prize_order((1..=cards).collect())
+    }
+
+    /// Creates a game where the prizes are revealed in the given order. The hands hold the cards
+    /// 1 to the number of prizes, which should be a permutation of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are not between 1 and 30 prizes.
+    pub fn with_prize_order(prizes: Vec<u8>) -> Self {
+        assert!(
+            (1..=30).contains(&prizes.len()),
+            "the game needs 1 to 30 cards"
+        );
+        let hand = (1..=prizes.len()).fold(0, |acc, x| acc | 1 << x);
+        Self {
+            root_player: GoofspielPlayer::One,
+            prizes,
+            round: 0,
+            hands: [hand, hand],
+            pending_bid: None,
+            scores: [0, 0],
+        }
+    }
+
+    /// Sets the player the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: GoofspielPlayer) {
+        self.root_player = root_player;
+    }
+
+    /// Returns the player to bid.
+    pub fn get_player_to_move(&self) -> GoofspielPlayer {
+        match self.pending_bid {
+            None => GoofspielPlayer::One,
+            Some(_) => GoofspielPlayer::Two,
+        }
+    }
+
+    /// Returns the prize of the current round, or `None` once all rounds are played.
+    pub fn get_prize(&self) -> Option<u8> {
+        self.prizes.get(self.round).copied()
+    }
+
+    /// Returns the points won by the given player.
+    pub fn get_score(&self, player: GoofspielPlayer) -> u32 {
+        self.scores[player.index()]
+    }
+
+    /// Returns the cards left in the hand of the given player, in ascending order.
+    pub fn get_hand(&self, player: GoofspielPlayer) -> Vec<u8> {
+        let hand = self.hands[player.index()];
+        (1..=self.prizes.len() as u8)
+            .filter(|x| hand & 1 << x != 0)
+            .collect()
+    }
+
+    /// Checks whether player one has bid in the current round and player two has not.
+    pub fn has_pending_bid(&self) -> bool {
+        self.pending_bid.is_some()
+    }
+}
+
+impl Default for GoofspielBoard {
+    /// Creates a game with the thirteen cards of a suit.
+    fn default() -> Self {
+        GoofspielBoard::new(13)
+    }
+}
+
+impl Board for GoofspielBoard {
+    type Move = u8;
+
+    fn get_current_player(&self) -> Player {
+        match self.get_player_to_move() == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        if self.round < self.prizes.len() {
+            return GameOutcome::InProgress;
+        }
+
+        let mine = self.scores[self.root_player.index()];
+        let theirs = self.scores[self.root_player.opponent().index()];
+        match mine.cmp(&theirs) {
+            std::cmp::Ordering::Greater => GameOutcome::Win,
+            std::cmp::Ordering::Less => GameOutcome::Lose,
+            std::cmp::Ordering::Equal => GameOutcome::Draw,
+        }
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.round >= self.prizes.len() {
+            return MoveList::new();
+        }
+
+        self.get_hand(self.get_player_to_move()).into()
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        let player = self.get_player_to_move();
+        self.hands[player.index()] &= !(1 << b_move);
+        let Some(first_bid) = self.pending_bid.take() else {
+            self.pending_bid = Some(*b_move);
+            return;
+        };
+
+        let prize = self.prizes[self.round] as u32;
+        match first_bid.cmp(b_move) {
+            std::cmp::Ordering::Greater => self.scores[GoofspielPlayer::One.index()] += prize,
+            std::cmp::Ordering::Less => self.scores[GoofspielPlayer::Two.index()] += prize,
+            std::cmp::Ordering::Equal => {}
+        }
+        self.round += 1;
+    }
+
+    fn get_hash(&self) -> u128 {
+        // The round follows from the size of the hands, so it needs no bits of its own.
+        ((self.hands[0] as u128) << 96)
+            | ((self.hands[1] as u128) << 64)
+            | ((self.scores[0] as u128) << 40)
+            | ((self.scores[1] as u128) << 16)
+            | self.pending_bid.map_or(0, |x| x as u128 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome, Player};
+    use crate::boards::goofspiel::{GoofspielBoard, GoofspielPlayer};
+    use crate::mcts::{MctsAction, MonteCarloTreeSearch};
+    use crate::random::CustomNumberGenerator;
+
+    #[test]
+    fn higher_bid_wins_the_prize() {
+        // arrange
+        let mut board = GoofspielBoard::with_prize_order(vec![3, 1, 2]);
+
+        // act
+        board.perform_move(&1);
+        let is_pending = board.has_pending_bid();
+        let second_bidder = board.get_current_player();
+        board.perform_move(&2);
+
+        // assert
+        assert!(is_pending);
+        assert_eq!(second_bidder, Player::Other);
+        assert_eq!(board.get_score(GoofspielPlayer::Two), 3);
+        assert_eq!(board.get_prize(), Some(1));
+        assert_eq!(board.get_hand(GoofspielPlayer::One), vec![2, 3]);
+        assert_eq!(board.get_hand(GoofspielPlayer::Two), vec![1, 3]);
+    }
+
+    #[test]
+    fn tied_bids_discard_the_prize() {
+        // arrange
+        let mut board = GoofspielBoard::new(3);
+
+        // act
+        for b_move in [1, 1, 2, 3, 3, 2] {
+            board.perform_move(&b_move);
+        }
+
+        // assert
+        assert_eq!(board.get_score(GoofspielPlayer::One), 3);
+        assert_eq!(board.get_score(GoofspielPlayer::Two), 2);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn search_solves_a_small_game() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(GoofspielBoard::new(3))
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        while *mcts.get_next_mcts_action() != MctsAction::EverythingIsCalculated {
+            mcts.iterate_n_times(100);
+        }
+
+        // assert
+        let best_move = mcts.get_most_perspective_move().unwrap();
+        assert!(
+            GoofspielBoard::new(3)
+                .get_available_moves()
+                .contains(&best_move)
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+use crate::random::{RandomGenerator, SplitMix64};
+
+/// The color of a stone, and of the player owning it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum HexColor {
+    /// The player moving first, connecting the top and bottom edges.
+    Red,
+    /// The player moving second, connecting the left and right edges.
+    Blue,
+}
+
+impl HexColor {
+    /// Returns the other color.
+    pub fn opponent(self) -> Self {
+        match self {
+            HexColor::Red => HexColor::Blue,
+            HexColor::Blue => HexColor::Red,
+        }
+    }
+}
+
+/// An implementation of the `Board` trait for the game of Hex on a rhombus of configurable size.
+///
+/// A move is the index of a cell, row by row from the top left corner. Red wins by connecting the
+/// top and bottom edges, blue by connecting the left and right edges; a game of Hex cannot end in a
+/// draw. Connectivity is tracked with a union-find structure, so detecting a win after a move takes
+/// logarithmic time.
+#[derive(Clone)]
+pub struct HexBoard {
+    size: usize,
+    root_player: HexColor,
+    current_player: HexColor,
+    cells: Vec<Option<HexColor>>,
+    /// The union-find parents of the cells, followed by the top, bottom, left and right edges.
+    parents: Vec<u16>,
+    /// The union-find ranks, in the same order as `parents`.
+    ranks: Vec<u8>,
+    hash: u128,
+    outcome: GameOutcome,
+}
+
+impl HexBoard {
+    /// Creates an empty board with red to move, searching for red.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not between 1 and 25.
+    pub fn new(size: usize) -> Self {
+        assert!((1..=25).contains(&size), "size must be between 1 and 25");
+        let cell_count = size * size;
+        Self {
+            size,
+            root_player: HexColor::Red,
+            current_player: HexColor::Red,
+            cells: vec![None; cell_count],
+            parents: (0..cell_count as u16 + 4).collect(),
+            ranks: vec![0; cell_count + 4],
+            hash: 0,
+            outcome: GameOutcome::InProgress,
+        }
+    }
+
+    /// Sets the color the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: HexColor) {
+        self.root_player = root_player;
+        self.outcome = self.compute_outcome();
+    }
+
+    /// Returns the length of a side of the board.
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the color to move.
+    pub fn get_color_to_move(&self) -> HexColor {
+        self.current_player
+    }
+
+    /// Returns the stone on the cell with the given index, if any.
+    pub fn get_cell(&self, index: u16) -> Option<HexColor> {
+        self.cells[index as usize]
+    }
+
+    /// Returns the color that has connected its edges, if any.
+    pub fn get_winner(&self) -> Option<HexColor> {
+        let cell_count = self.cells.len() as u16;
+        let (top, bottom, left, right) =
+            (cell_count, cell_count + 1, cell_count + 2, cell_count + 3);
+        if self.find(top) == self.find(bottom) {
+            Some(HexColor::Red)
+        } else if self.find(left) == self.find(right) {
+            Some(HexColor::Blue)
+        } else {
+            None
+        }
+    }
+
+    fn compute_outcome(&self) -> GameOutcome {
+        match self.get_winner() {
+            None => GameOutcome::InProgress,
+            Some(x) if x == self.root_player => GameOutcome::Win,
+            Some(_) => GameOutcome::Lose,
+        }
+    }
+
+    fn find(&self, mut node: u16) -> u16 {
+        while self.parents[node as usize] != node {
+            node = self.parents[node as usize];
+        }
+        node
+    }
+
+    fn union(&mut self, a: u16, b: u16) {
+        let (root_a, root_b) = (self.find(a) as usize, self.find(b) as usize);
+        if root_a == root_b {
+            return;
+        }
+
+        // Union by rank keeps the trees logarithmically shallow without path compression, which
+        // would need `find` to take `&mut self`.
+        match self.ranks[root_a].cmp(&self.ranks[root_b]) {
+            std::cmp::Ordering::Less => self.parents[root_a] = root_b as u16,
+            std::cmp::Ordering::Greater => self.parents[root_b] = root_a as u16,
+            std::cmp::Ordering::Equal => {
+                self.parents[root_b] = root_a as u16;
+                self.ranks[root_a] += 1;
+            }
+        }
+    }
+
+    /// Returns the cells adjacent to the given one.
+    fn neighbors(&self, index: usize) -> impl Iterator<Item = usize> + use<> {
+        let size = self.size as isize;
+        let (row, column) = (index as isize / size, index as isize % size);
+        [(-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0)]
+            .into_iter()
+            .map(move |(r, c)| (row + r, column + c))
+            .filter(move |(r, c)| (0..size).contains(r) && (0..size).contains(c))
+            .map(move |(r, c)| (r * size + c) as usize)
+    }
+
+    /// Returns the Zobrist key of a stone of the given color on the given cell.
+    fn stone_key(index: usize, color: HexColor) -> u128 {
+        let mut random = SplitMix64::new(index as u64 * 2 + (color == HexColor::Blue) as u64);
+        ((random.next_u64() as u128) << 64) | random.next_u64() as u128
+    }
+}
+
+impl Default for HexBoard {
+    /// Creates an empty 11x11 board, the standard tournament size.
+    fn default() -> Self {
+        HexBoard::new(11)
+    }
+}
+
+impl Board for HexBoard {
+    type Move = u16;
+
+    fn get_current_player(&self) -> Player {
+        match self.current_player == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        self.outcome
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.outcome != GameOutcome::InProgress {
+            return MoveList::new();
+        }
+
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.is_none())
+            .map(|(i, _)| i as u16)
+            .collect()
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        let index = *b_move as usize;
+        let color = self.current_player;
+        self.cells[index] = Some(color);
+        self.hash ^= HexBoard::stone_key(index, color);
+
+        let cell_count = self.cells.len() as u16;
+        let (row, column) = (index / self.size, index % self.size);
+        let edges = match color {
+            HexColor::Red => [
+                (row == 0, cell_count),
+                (row == self.size - 1, cell_count + 1),
+            ],
+            HexColor::Blue => [
+                (column == 0, cell_count + 2),
+                (column == self.size - 1, cell_count + 3),
+            ],
+        };
+        for (is_on_edge, edge) in edges {
+            if is_on_edge {
+                self.union(*b_move, edge);
+            }
+        }
+        for neighbor in self.neighbors(index) {
+            if self.cells[neighbor] == Some(color) {
+                self.union(*b_move, neighbor as u16);
+            }
+        }
+
+        self.current_player = color.opponent();
+        self.outcome = self.compute_outcome();
+    }
+
+    fn get_hash(&self) -> u128 {
+        self.hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome};
+    use crate::boards::hex::{HexBoard, HexColor};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::{CustomNumberGenerator, RandomGenerator};
+
+    #[test]
+    fn diagonal_neighbors_connect() {
+        // arrange
+        let mut board = HexBoard::new(3);
+
+        // act
+        for b_move in [2, 0, 4, 1, 6] {
+            board.perform_move(&b_move);
+        }
+
+        // assert
+        assert_eq!(board.get_winner(), Some(HexColor::Red));
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn blue_connects_left_and_right() {
+        // arrange
+        let mut board = HexBoard::new(3);
+
+        // act
+        for b_move in [0, 3, 2, 4, 8, 5] {
+            board.perform_move(&b_move);
+        }
+
+        // assert
+        assert_eq!(board.get_winner(), Some(HexColor::Blue));
+        assert_eq!(board.get_outcome(), GameOutcome::Lose);
+    }
+
+    #[test]
+    fn full_board_always_has_a_winner() {
+        let mut random = CustomNumberGenerator::default();
+        for _ in 0..100 {
+            let mut board = HexBoard::new(5);
+            let mut moves = board.get_available_moves();
+            while board.get_winner().is_none() {
+                let b_move = moves.swap_remove(random.next_index(moves.len()));
+                board.perform_move(&b_move);
+            }
+            assert_ne!(board.get_outcome(), GameOutcome::Draw);
+        }
+    }
+
+    #[test]
+    fn hash_does_not_depend_on_move_order() {
+        // arrange
+        let mut first = HexBoard::new(4);
+        let mut second = HexBoard::new(4);
+
+        // act
+        for b_move in [0, 5, 10] {
+            first.perform_move(&b_move);
+        }
+        for b_move in [10, 5, 0] {
+            second.perform_move(&b_move);
+        }
+
+        // assert
+        assert_eq!(first.get_hash(), second.get_hash());
+        assert_ne!(first.get_hash(), HexBoard::new(4).get_hash());
+    }
+
+    #[test]
+    fn search_opens_in_the_center() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(HexBoard::new(3))
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(5000);
+
+        // assert
+        assert_eq!(mcts.get_most_perspective_move(), Some(4));
+    }
+}
@@ -0,0 +1,272 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+
+/// A side of the board, and the player owning it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum KalahSide {
+    /// The player moving first.
+    South,
+    /// The player moving second.
+    North,
+}
+
+impl KalahSide {
+    /// Returns the other side.
+    pub fn opponent(self) -> Self {
+        match self {
+            KalahSide::South => KalahSide::North,
+            KalahSide::North => KalahSide::South,
+        }
+    }
+}
+
+/// An implementation of the `Board` trait for the game of Kalah, a member of the Mancala family.
+///
+/// A move is the index of one of the pits of the player to move, counted from that player's left.
+/// The seeds of the pit are sown counterclockwise, one per pit, including the player's own store
+/// but skipping the opponent's. Ending in one's own store grants another move, so the same player
+/// may move several times in a row. Ending in one's own empty pit captures the seeds of the
+/// opposite pit. The game ends when one side runs out of seeds; the other side keeps the seeds
+/// left in its pits, and the fuller store wins.
+#[derive(Debug, Clone)]
+pub struct KalahBoard {
+    root_player: KalahSide,
+    current_player: KalahSide,
+    pits_per_side: usize,
+    /// The south pits, the south store, the north pits and the north store, counterclockwise.
+    seeds: Vec<u32>,
+}
+
+impl KalahBoard {
+    /// Creates a board with the given number of pits per side and seeds per pit, with south to
+    /// move and searching for south.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pits_per_side` is zero.
+    pub fn new(pits_per_side: usize, seeds_per_pit: u32) -> Self {
+        assert!(pits_per_side > 0, "a side needs at least one pit");
+        let mut seeds = vec![seeds_per_pit; 2 * pits_per_side + 2];
+        seeds[pits_per_side] = 0;
+        seeds[2 * pits_per_side + 1] = 0;
+        Self {
+            root_player: KalahSide::South,
+            current_player: KalahSide::South,
+            pits_per_side,
+            seeds,
+        }
+    }
+
+    /// Sets the side the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: KalahSide) {
+        self.root_player = root_player;
+    }
+
+    /// Returns the side to move.
+    pub fn get_side_to_move(&self) -> KalahSide {
+        self.current_player
+    }
+
+    /// Returns the seeds in the pits of the given side, from that side's left.
+    pub fn get_pits(&self, side: KalahSide) -> &[u32] {
+        let first_pit = self.first_pit(side);
+        &self.seeds[first_pit..first_pit + self.pits_per_side]
+    }
+
+    /// Returns the seeds in the store of the given side.
+    pub fn get_store(&self, side: KalahSide) -> u32 {
+        self.seeds[self.store(side)]
+    }
+
+    fn first_pit(&self, side: KalahSide) -> usize {
+        match side {
+            KalahSide::South => 0,
+            KalahSide::North => self.pits_per_side + 1,
+        }
+    }
+
+    fn store(&self, side: KalahSide) -> usize {
+        self.first_pit(side) + self.pits_per_side
+    }
+
+    fn is_side_empty(&self, side: KalahSide) -> bool {
+        self.get_pits(side).iter().all(|x| *x == 0)
+    }
+
+    fn is_game_over(&self) -> bool {
+        self.is_side_empty(KalahSide::South) || self.is_side_empty(KalahSide::North)
+    }
+
+    /// Returns the final score of the given side, counting the seeds it keeps at the end.
+    fn final_score(&self, side: KalahSide) -> u32 {
+        self.get_store(side) + self.get_pits(side).iter().sum::<u32>()
+    }
+}
+
+impl Default for KalahBoard {
+    /// Creates the standard board with six pits per side and four seeds per pit.
+    fn default() -> Self {
+        KalahBoard::new(6, 4)
+    }
+}
+
+impl Board for KalahBoard {
+    type Move = u8;
+
+    fn get_current_player(&self) -> Player {
+        match self.current_player == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        if !self.is_game_over() {
+            return GameOutcome::InProgress;
+        }
+
+        let mine = self.final_score(self.root_player);
+        let theirs = self.final_score(self.root_player.opponent());
+        match mine.cmp(&theirs) {
+            std::cmp::Ordering::Greater => GameOutcome::Win,
+            std::cmp::Ordering::Less => GameOutcome::Lose,
+            std::cmp::Ordering::Equal => GameOutcome::Draw,
+        }
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.is_game_over() {
+            return MoveList::new();
+        }
+
+        let pits = self.get_pits(self.current_player);
+        (0..pits.len() as u8)
+            .filter(|x| pits[*x as usize] > 0)
+            .collect()
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        let side = self.current_player;
+        let own_store = self.store(side);
+        let opponent_store = self.store(side.opponent());
+        let mut position = self.first_pit(side) + *b_move as usize;
+        let mut seeds = std::mem::take(&mut self.seeds[position]);
+        while seeds > 0 {
+            position = (position + 1) % self.seeds.len();
+            if position != opponent_store {
+                self.seeds[position] += 1;
+                seeds -= 1;
+            }
+        }
+
+        if position == own_store {
+            return;
+        }
+
+        let first_pit = self.first_pit(side);
+        let is_own_pit = (first_pit..own_store).contains(&position);
+        let opposite = 2 * self.pits_per_side - position;
+        if is_own_pit && self.seeds[position] == 1 && self.seeds[opposite] > 0 {
+            let captured = self.seeds[opposite] + 1;
+            self.seeds[position] = 0;
+            self.seeds[opposite] = 0;
+            self.seeds[own_store] += captured;
+        }
+        self.current_player = side.opponent();
+    }
+
+    fn get_hash(&self) -> u128 {
+        let hash = self.seeds.iter().fold(0u128, |acc, x| {
+            acc.wrapping_mul(0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c835)
+                .wrapping_add(*x as u128 + 1)
+        });
+        hash.wrapping_mul(2) + (self.current_player == KalahSide::North) as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome, Player};
+    use crate::boards::kalah::{KalahBoard, KalahSide};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    #[test]
+    fn ending_in_the_store_grants_another_move() {
+        // arrange
+        let mut board = KalahBoard::default();
+
+        // act
+        board.perform_move(&2);
+
+        // assert
+        assert_eq!(board.get_pits(KalahSide::South), [4, 4, 0, 5, 5, 5]);
+        assert_eq!(board.get_store(KalahSide::South), 1);
+        assert_eq!(board.get_side_to_move(), KalahSide::South);
+        assert_eq!(board.get_current_player(), Player::Me);
+    }
+
+    #[test]
+    fn sowing_skips_the_opponent_store() {
+        // arrange
+        let mut board = KalahBoard::new(2, 0);
+        board.seeds = vec![0, 6, 0, 1, 1, 0];
+
+        // act
+        board.perform_move(&1);
+
+        // assert
+        assert_eq!(board.seeds, vec![1, 1, 2, 2, 2, 0]);
+        assert_eq!(board.get_side_to_move(), KalahSide::South);
+    }
+
+    #[test]
+    fn ending_in_an_empty_pit_captures_the_opposite_pit() {
+        // arrange
+        let mut board = KalahBoard::new(6, 0);
+        board.seeds = vec![1, 0, 4, 4, 4, 4, 0, 4, 4, 4, 4, 3, 4, 0];
+
+        // act
+        board.perform_move(&0);
+
+        // assert
+        assert_eq!(board.get_pits(KalahSide::South), [0, 0, 4, 4, 4, 4]);
+        assert_eq!(board.get_pits(KalahSide::North), [4, 4, 4, 4, 0, 4]);
+        assert_eq!(board.get_store(KalahSide::South), 4);
+    }
+
+    #[test]
+    fn remaining_seeds_count_for_their_owner() {
+        // arrange
+        let mut board = KalahBoard::new(2, 0);
+        board.seeds = vec![0, 1, 3, 4, 0, 2];
+
+        // act
+        board.perform_move(&1);
+
+        // assert
+        assert!(board.get_available_moves().is_empty());
+        assert_eq!(board.get_outcome(), GameOutcome::Lose);
+        board.set_root_player(KalahSide::North);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+    }
+
+    #[test]
+    fn search_takes_the_extra_move() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(KalahBoard::new(4, 3))
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(3000);
+
+        // assert
+        let root = mcts.get_root();
+        let extra_move = root
+            .children()
+            .find(|x| x.value().prev_move() == Some(&1))
+            .unwrap();
+        assert_eq!(extra_move.value().current_player, Player::Me);
+        assert_eq!(mcts.get_most_perspective_move(), Some(1));
+    }
+}
@@ -1,4 +1,30 @@
 //! Contains pre-made implementations of the `Board` trait for common games.
 
+/// A `Board` implementation for Breakthrough.
+pub mod breakthrough;
+/// A `Board` implementation for checkers (American draughts).
+pub mod checkers;
+/// A `Board` implementation for chess, backed by the `shakmaty` crate.
+#[cfg(feature = "chess")]
+pub mod chess;
+/// A `Board` implementation for the game of Go with area scoring.
+#[cfg(feature = "go")]
+pub mod go;
+/// A `Board` implementation for Goofspiel, with its simultaneous bids made in turn.
+pub mod goofspiel;
+/// A `Board` implementation for the game of Hex.
+pub mod hex;
+/// A `Board` implementation for the game of Kalah.
+pub mod kalah;
+/// A `Board` implementation for the game of Nim, in normal and misère play.
+pub mod nim;
+/// A `Board` implementation for the game of Nine Men's Morris.
+pub mod nine_mens_morris;
+/// A `Board` implementation for the game of Othello (Reversi).
+pub mod othello;
+/// A `Board` implementation for the dice game Pig, with die rolls as chance nodes.
+pub mod pig;
 /// A `Board` implementation for the game of Tic-Tac-Toe.
 pub mod tic_tac_toe;
+/// A `Board` implementation for the game of Ultimate Tic-Tac-Toe.
+pub mod ultimate_tic_tac_toe;
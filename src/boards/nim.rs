@@ -0,0 +1,190 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+
+/// A move in Nim.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct NimMove {
+    /// The index of the pile to take objects from.
+    pub pile: usize,
+    /// The number of objects to take, at least one.
+    pub count: u32,
+}
+
+/// An implementation of the `Board` trait for the game of Nim with any number of piles.
+///
+/// Players take turns removing any positive number of objects from a single pile. In normal play
+/// the player taking the last object wins; in misère play that player loses. Nim is solved by
+/// `is_winning_position`, which makes it a convenient reference for checking the bounds the search
+/// proves.
+#[derive(Debug, Clone)]
+pub struct NimBoard {
+    piles: Vec<u32>,
+    is_misere: bool,
+    is_root_to_move: bool,
+}
+
+impl NimBoard {
+    /// Creates a normal-play game with the given piles, searching for the player to move.
+    pub fn new(piles: &[u32]) -> Self {
+        Self {
+            piles: piles.to_vec(),
+            is_misere: false,
+            is_root_to_move: true,
+        }
+    }
+
+    /// Switches between normal play and misère play.
+    pub fn with_misere_play(mut self, is_misere: bool) -> Self {
+        self.is_misere = is_misere;
+        self
+    }
+
+    /// Returns the number of objects left in each pile.
+    pub fn get_piles(&self) -> &[u32] {
+        &self.piles
+    }
+
+    /// Checks whether the game is played under the misère rule.
+    pub fn is_misere(&self) -> bool {
+        self.is_misere
+    }
+
+    /// Checks whether the player to move wins with perfect play.
+    ///
+    /// In normal play, this is the case exactly when the bitwise XOR of the piles is not zero. In
+    /// misère play the same holds as long as some pile has more than one object; otherwise the
+    /// player to move wins when an even number of piles is left.
+    pub fn is_winning_position(&self) -> bool {
+        let nim_sum = self.piles.iter().fold(0, |acc, x| acc ^ x);
+        if self.is_misere && self.piles.iter().all(|x| *x <= 1) {
+            self.piles.iter().sum::<u32>() % 2 == 0
+        } else {
+            nim_sum != 0
+        }
+    }
+}
+
+impl Board for NimBoard {
+    type Move = NimMove;
+
+    fn get_current_player(&self) -> Player {
+        match self.is_root_to_move {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        if self.piles.iter().any(|x| *x > 0) {
+            return GameOutcome::InProgress;
+        }
+
+        // The player to move did not take the last object.
+        let has_root_won = self.is_root_to_move == self.is_misere;
+        match has_root_won {
+            true => GameOutcome::Win,
+            false => GameOutcome::Lose,
+        }
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        self.piles
+            .iter()
+            .enumerate()
+            .flat_map(|(pile, size)| (1..=*size).map(move |count| NimMove { pile, count }))
+            .collect()
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        self.piles[b_move.pile] -= b_move.count;
+        self.is_root_to_move = !self.is_root_to_move;
+    }
+
+    fn get_hash(&self) -> u128 {
+        let hash = self.piles.iter().fold(0u128, |acc, x| {
+            acc.wrapping_mul(0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c835)
+                .wrapping_add(*x as u128 + 1)
+        });
+        hash.wrapping_mul(2) + self.is_root_to_move as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Bound, GameOutcome};
+    use crate::boards::nim::{NimBoard, NimMove};
+    use crate::mcts::{MctsAction, MonteCarloTreeSearch};
+    use crate::random::CustomNumberGenerator;
+
+    fn solve(board: NimBoard) -> MonteCarloTreeSearch<NimBoard, CustomNumberGenerator> {
+        let mut mcts = MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        while *mcts.get_next_mcts_action() != MctsAction::EverythingIsCalculated {
+            mcts.iterate_n_times(100);
+        }
+        mcts
+    }
+
+    #[test]
+    fn last_object_decides_the_game() {
+        // arrange
+        let mut normal = NimBoard::new(&[1]);
+        let mut misere = NimBoard::new(&[1]).with_misere_play(true);
+
+        // act
+        normal.perform_move(&NimMove { pile: 0, count: 1 });
+        misere.perform_move(&NimMove { pile: 0, count: 1 });
+
+        // assert
+        assert_eq!(normal.get_outcome(), GameOutcome::Win);
+        assert_eq!(misere.get_outcome(), GameOutcome::Lose);
+        assert!(normal.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn search_proves_the_theoretical_result() {
+        let positions: [&[u32]; 6] = [
+            &[1, 2, 3],
+            &[1, 2, 4],
+            &[3, 4, 5],
+            &[2, 2],
+            &[1, 1, 1],
+            &[1, 1],
+        ];
+        for piles in positions {
+            for is_misere in [false, true] {
+                // arrange
+                let board = NimBoard::new(piles).with_misere_play(is_misere);
+                let expected = match board.is_winning_position() {
+                    true => Bound::DefoWin,
+                    false => Bound::DefoLose,
+                };
+
+                // act
+                let mcts = solve(board);
+
+                // assert
+                let root = mcts.get_root();
+                assert_eq!(
+                    root.value().stats.bound,
+                    expected,
+                    "{piles:?}, misère: {is_misere}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn search_finds_a_winning_move() {
+        // arrange
+        let board = NimBoard::new(&[3, 4, 6]);
+
+        // act
+        let mcts = solve(board.clone());
+
+        // assert
+        let mut next = board;
+        next.perform_move(&mcts.get_most_perspective_move().unwrap());
+        assert!(!next.is_winning_position());
+    }
+}
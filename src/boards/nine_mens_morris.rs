@@ -0,0 +1,415 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+
+/// The number of consecutive plies without a capture after which the game is a draw, i.e. fifty
+/// moves by each player.
+pub const DRAW_PLIES: u32 = 100;
+
+/// The number of pieces each player starts with in hand.
+const PIECES_PER_PLAYER: u8 = 9;
+
+/// The lines of three points forming a mill. Points are numbered from 0 to 23, row by row from the
+/// top left corner of the outer square, and two points are adjacent exactly when they are next to
+/// each other in one of these lines.
+const MILLS: [[u8; 3]; 16] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [9, 10, 11],
+    [12, 13, 14],
+    [15, 16, 17],
+    [18, 19, 20],
+    [21, 22, 23],
+    [0, 9, 21],
+    [3, 10, 18],
+    [6, 11, 15],
+    [1, 4, 7],
+    [16, 19, 22],
+    [8, 12, 17],
+    [5, 13, 20],
+    [2, 14, 23],
+];
+
+/// The color of a piece, and of the player owning it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MorrisColor {
+    /// The player moving first.
+    White,
+    /// The player moving second.
+    Black,
+}
+
+impl MorrisColor {
+    /// Returns the other color.
+    pub fn opponent(self) -> Self {
+        match self {
+            MorrisColor::White => MorrisColor::Black,
+            MorrisColor::Black => MorrisColor::White,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The phase a player is in, which decides the kind of moves available.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MorrisPhase {
+    /// The player still has pieces in hand and places one per move.
+    Placing,
+    /// The player moves a piece to an adjacent empty point.
+    Moving,
+    /// The player is down to three pieces and may move a piece to any empty point.
+    Flying,
+}
+
+/// A move in Nine Men's Morris.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct MorrisMove {
+    /// The point the piece leaves, or `None` when placing a piece from hand.
+    pub from: Option<u8>,
+    /// The point the piece arrives on.
+    pub to: u8,
+    /// The opponent's piece removed because the move closed a mill, if any.
+    pub remove: Option<u8>,
+}
+
+/// An implementation of the `Board` trait for the game of Nine Men's Morris.
+///
+/// The game runs through three phases per player: placing the nine pieces in hand, moving pieces
+/// along the lines, and flying anywhere once reduced to three pieces. Closing a mill removes one
+/// opponent piece, which must not be part of a mill unless all of them are; the removal is part of
+/// the move that closed the mill. A player reduced to two pieces or left without legal moves loses,
+/// and the game is drawn after `DRAW_PLIES` plies without a capture.
+#[derive(Debug, Clone)]
+pub struct NineMensMorrisBoard {
+    root_player: MorrisColor,
+    current_player: MorrisColor,
+    points: [Option<MorrisColor>; 24],
+    in_hand: [u8; 2],
+    plies_without_capture: u32,
+    outcome: GameOutcome,
+}
+
+impl Default for NineMensMorrisBoard {
+    /// Creates an empty board with white to move, searching for white.
+    fn default() -> Self {
+        Self {
+            root_player: MorrisColor::White,
+            current_player: MorrisColor::White,
+            points: [None; 24],
+            in_hand: [PIECES_PER_PLAYER; 2],
+            plies_without_capture: 0,
+            outcome: GameOutcome::InProgress,
+        }
+    }
+}
+
+impl NineMensMorrisBoard {
+    /// Sets the color the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: MorrisColor) {
+        self.root_player = root_player;
+        self.outcome = self.compute_outcome();
+    }
+
+    /// Returns the color to move.
+    pub fn get_color_to_move(&self) -> MorrisColor {
+        self.current_player
+    }
+
+    /// Returns the piece on the given point, if any.
+    pub fn get_point(&self, point: u8) -> Option<MorrisColor> {
+        self.points[point as usize]
+    }
+
+    /// Returns the number of pieces the given player has yet to place.
+    pub fn get_pieces_in_hand(&self, color: MorrisColor) -> u8 {
+        self.in_hand[color.index()]
+    }
+
+    /// Returns the number of pieces the given player has on the board.
+    pub fn count_pieces(&self, color: MorrisColor) -> usize {
+        self.points.iter().filter(|x| **x == Some(color)).count()
+    }
+
+    /// Returns the phase the given player is in.
+    pub fn get_phase(&self, color: MorrisColor) -> MorrisPhase {
+        if self.in_hand[color.index()] > 0 {
+            MorrisPhase::Placing
+        } else if self.count_pieces(color) <= 3 {
+            MorrisPhase::Flying
+        } else {
+            MorrisPhase::Moving
+        }
+    }
+
+    /// Returns the points adjacent to the given one.
+    fn neighbors(point: u8) -> impl Iterator<Item = u8> {
+        MILLS.iter().flat_map(move |mill| {
+            let position = mill.iter().position(|x| *x == point);
+            let before = position.and_then(|x| x.checked_sub(1)).map(|x| mill[x]);
+            let after = position.and_then(|x| mill.get(x + 1)).copied();
+            before.into_iter().chain(after)
+        })
+    }
+
+    fn is_in_mill(&self, point: u8) -> bool {
+        let color = self.points[point as usize];
+        color.is_some()
+            && MILLS
+                .iter()
+                .filter(|x| x.contains(&point))
+                .any(|x| x.iter().all(|y| self.points[*y as usize] == color))
+    }
+
+    fn get_legal_moves(&self) -> MoveList<MorrisMove> {
+        let color = self.current_player;
+        let empty_points: Vec<u8> = (0..24)
+            .filter(|x| self.points[*x as usize].is_none())
+            .collect();
+        let own_points = (0..24).filter(|x| self.points[*x as usize] == Some(color));
+        let steps: Vec<(Option<u8>, u8)> = match self.get_phase(color) {
+            MorrisPhase::Placing => empty_points.iter().map(|x| (None, *x)).collect(),
+            MorrisPhase::Flying => own_points
+                .flat_map(|from| empty_points.iter().map(move |to| (Some(from), *to)))
+                .collect(),
+            MorrisPhase::Moving => own_points
+                .flat_map(|from| {
+                    NineMensMorrisBoard::neighbors(from)
+                        .filter(|to| self.points[*to as usize].is_none())
+                        .map(move |to| (Some(from), to))
+                })
+                .collect(),
+        };
+
+        let mut moves = MoveList::new();
+        for (from, to) in steps {
+            let mut board = self.clone();
+            if let Some(from) = from {
+                board.points[from as usize] = None;
+            }
+            board.points[to as usize] = Some(color);
+            if !board.is_in_mill(to) {
+                moves.push(MorrisMove {
+                    from,
+                    to,
+                    remove: None,
+                });
+                continue;
+            }
+
+            let opponent_points: Vec<u8> = (0..24)
+                .filter(|x| board.points[*x as usize] == Some(color.opponent()))
+                .collect();
+            let free_points: Vec<u8> = opponent_points
+                .iter()
+                .copied()
+                .filter(|x| !board.is_in_mill(*x))
+                .collect();
+            let removable = if free_points.is_empty() {
+                opponent_points
+            } else {
+                free_points
+            };
+            if removable.is_empty() {
+                moves.push(MorrisMove {
+                    from,
+                    to,
+                    remove: None,
+                });
+            }
+            for remove in removable {
+                moves.push(MorrisMove {
+                    from,
+                    to,
+                    remove: Some(remove),
+                });
+            }
+        }
+        moves
+    }
+
+    fn compute_outcome(&self) -> GameOutcome {
+        let color = self.current_player;
+        let has_lost = self.in_hand[color.index()] == 0 && self.count_pieces(color) < 3
+            || self.get_legal_moves().is_empty();
+        if has_lost {
+            return match color == self.root_player {
+                true => GameOutcome::Lose,
+                false => GameOutcome::Win,
+            };
+        }
+
+        match self.plies_without_capture >= DRAW_PLIES {
+            true => GameOutcome::Draw,
+            false => GameOutcome::InProgress,
+        }
+    }
+}
+
+impl Board for NineMensMorrisBoard {
+    type Move = MorrisMove;
+
+    fn get_current_player(&self) -> Player {
+        match self.current_player == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        self.outcome
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.outcome != GameOutcome::InProgress {
+            return MoveList::new();
+        }
+
+        self.get_legal_moves()
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        let color = self.current_player;
+        match b_move.from {
+            Some(from) => self.points[from as usize] = None,
+            None => self.in_hand[color.index()] -= 1,
+        }
+        self.points[b_move.to as usize] = Some(color);
+
+        self.plies_without_capture += 1;
+        if let Some(remove) = b_move.remove {
+            self.points[remove as usize] = None;
+            self.plies_without_capture = 0;
+        }
+
+        self.current_player = color.opponent();
+        self.outcome = self.compute_outcome();
+    }
+
+    fn get_hash(&self) -> u128 {
+        let mut hash = 0;
+        for point in self.points.iter().rev() {
+            let point_value = match point {
+                None => 0,
+                Some(MorrisColor::White) => 1,
+                Some(MorrisColor::Black) => 2,
+            };
+            hash = hash * 3 + point_value;
+        }
+        hash = hash * 10 + self.in_hand[0] as u128;
+        hash = hash * 10 + self.in_hand[1] as u128;
+        hash * 2 + self.current_player.index() as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome};
+    use crate::boards::nine_mens_morris::{
+        MorrisColor, MorrisMove, MorrisPhase, NineMensMorrisBoard,
+    };
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn board_with(white: &[u8], black: &[u8], to_move: MorrisColor) -> NineMensMorrisBoard {
+        let mut points = [None; 24];
+        for point in white {
+            points[*point as usize] = Some(MorrisColor::White);
+        }
+        for point in black {
+            points[*point as usize] = Some(MorrisColor::Black);
+        }
+        let mut board = NineMensMorrisBoard {
+            root_player: MorrisColor::White,
+            current_player: to_move,
+            points,
+            in_hand: [0, 0],
+            plies_without_capture: 0,
+            outcome: GameOutcome::InProgress,
+        };
+        board.outcome = board.compute_outcome();
+        board
+    }
+
+    #[test]
+    fn placing_phase_allows_every_empty_point() {
+        let board = NineMensMorrisBoard::default();
+        assert_eq!(board.get_phase(MorrisColor::White), MorrisPhase::Placing);
+        assert_eq!(board.get_available_moves().len(), 24);
+    }
+
+    #[test]
+    fn closing_a_mill_removes_a_piece_outside_mills() {
+        // arrange
+        let board = board_with(&[0, 1, 10, 14, 18], &[3, 6, 7, 8, 22], MorrisColor::White);
+
+        // act
+        let mills: Vec<MorrisMove> = board
+            .get_available_moves()
+            .into_iter()
+            .filter(|x| x.remove.is_some())
+            .collect();
+
+        // assert
+        let removed: Vec<_> = mills.iter().map(|x| x.remove.unwrap()).collect();
+        assert_eq!(removed, vec![3, 22]);
+        assert!(mills.iter().all(|x| x.from == Some(14) && x.to == 2));
+    }
+
+    #[test]
+    fn three_pieces_may_fly() {
+        // arrange
+        let board = board_with(&[0, 12, 23], &[3, 6, 7, 8], MorrisColor::White);
+
+        // act
+        let moves = board.get_available_moves();
+
+        // assert
+        assert_eq!(board.get_phase(MorrisColor::White), MorrisPhase::Flying);
+        assert_eq!(board.get_phase(MorrisColor::Black), MorrisPhase::Moving);
+        assert_eq!(moves.len(), 3 * 17);
+    }
+
+    #[test]
+    fn player_with_two_pieces_loses() {
+        // arrange
+        let mut board = board_with(&[0, 1, 14, 20], &[6, 7, 18], MorrisColor::White);
+
+        // act
+        board.perform_move(&MorrisMove {
+            from: Some(14),
+            to: 2,
+            remove: Some(6),
+        });
+
+        // assert
+        assert_eq!(board.count_pieces(MorrisColor::Black), 2);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn blocked_player_loses() {
+        let board = board_with(&[1, 3, 5, 7, 9, 14, 22], &[0, 2, 4, 21], MorrisColor::Black);
+        assert_eq!(board.get_phase(MorrisColor::Black), MorrisPhase::Moving);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn search_returns_a_legal_move() {
+        // arrange
+        let board = NineMensMorrisBoard::default();
+        let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(300);
+
+        // assert
+        let best_move = mcts.get_most_perspective_move().unwrap();
+        assert!(board.get_available_moves().contains(&best_move));
+    }
+}
@@ -0,0 +1,307 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+use smallvec::smallvec;
+
+/// The directions a line of discs can be flipped in, as `(row, column)` steps.
+const DIRECTIONS: [(i8, i8); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// The color of a disc, and of the player owning it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum OthelloColor {
+    /// The player moving first.
+    Black,
+    /// The player moving second.
+    White,
+}
+
+impl OthelloColor {
+    /// Returns the other color.
+    pub fn opponent(self) -> Self {
+        match self {
+            OthelloColor::Black => OthelloColor::White,
+            OthelloColor::White => OthelloColor::Black,
+        }
+    }
+}
+
+/// A move in Othello.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum OthelloMove {
+    /// Places a disc on the cell with the given index, from 0 (a1) to 63 (h8), row by row.
+    Place(u8),
+    /// Passes the turn. Only legal, and then forced, when no disc can be placed.
+    Pass,
+}
+
+/// An implementation of the `Board` trait for the game of Othello (Reversi) on an 8x8 board.
+///
+/// A player who cannot flip any disc must pass. The game ends when neither player can place a
+/// disc, and the player with more discs wins.
+#[derive(Clone)]
+pub struct OthelloBoard {
+    root_player: OthelloColor,
+    current_player: OthelloColor,
+    cells: [Option<OthelloColor>; 64],
+    outcome: GameOutcome,
+}
+
+impl Default for OthelloBoard {
+    /// Creates the standard starting position with black to move, searching for black.
+    fn default() -> Self {
+        let mut cells = [None; 64];
+        cells[27] = Some(OthelloColor::White);
+        cells[28] = Some(OthelloColor::Black);
+        cells[35] = Some(OthelloColor::Black);
+        cells[36] = Some(OthelloColor::White);
+        Self {
+            root_player: OthelloColor::Black,
+            current_player: OthelloColor::Black,
+            cells,
+            outcome: GameOutcome::InProgress,
+        }
+    }
+}
+
+impl OthelloBoard {
+    /// Sets the color the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: OthelloColor) {
+        self.root_player = root_player;
+        self.outcome = self.compute_outcome();
+    }
+
+    /// Returns the color to move.
+    pub fn get_color_to_move(&self) -> OthelloColor {
+        self.current_player
+    }
+
+    /// Returns the disc on the cell with the given index, if any.
+    pub fn get_cell(&self, index: u8) -> Option<OthelloColor> {
+        self.cells[index as usize]
+    }
+
+    /// Returns the number of discs of the given color.
+    pub fn count_discs(&self, color: OthelloColor) -> usize {
+        self.cells.iter().filter(|x| **x == Some(color)).count()
+    }
+
+    /// Returns the cells whose discs would be flipped if `color` placed a disc on `index`.
+    fn flips(&self, color: OthelloColor, index: u8) -> Vec<u8> {
+        let mut flips = Vec::new();
+        if self.cells[index as usize].is_some() {
+            return flips;
+        }
+
+        let (row, column) = ((index / 8) as i8, (index % 8) as i8);
+        for (row_step, column_step) in DIRECTIONS {
+            let mut line = Vec::new();
+            let (mut r, mut c) = (row + row_step, column + column_step);
+            while (0..8).contains(&r) && (0..8).contains(&c) {
+                let cell = (r * 8 + c) as u8;
+                match self.cells[cell as usize] {
+                    Some(x) if x == color => {
+                        flips.append(&mut line);
+                        break;
+                    }
+                    Some(_) => line.push(cell),
+                    None => break,
+                }
+                r += row_step;
+                c += column_step;
+            }
+        }
+        flips
+    }
+
+    fn has_placement(&self, color: OthelloColor) -> bool {
+        (0..64).any(|x| !self.flips(color, x).is_empty())
+    }
+
+    fn compute_outcome(&self) -> GameOutcome {
+        if self.has_placement(self.current_player)
+            || self.has_placement(self.current_player.opponent())
+        {
+            return GameOutcome::InProgress;
+        }
+
+        let mine = self.count_discs(self.root_player);
+        let theirs = self.count_discs(self.root_player.opponent());
+        match mine.cmp(&theirs) {
+            std::cmp::Ordering::Greater => GameOutcome::Win,
+            std::cmp::Ordering::Less => GameOutcome::Lose,
+            std::cmp::Ordering::Equal => GameOutcome::Draw,
+        }
+    }
+}
+
+impl Board for OthelloBoard {
+    type Move = OthelloMove;
+
+    fn get_current_player(&self) -> Player {
+        match self.current_player == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        self.outcome
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.outcome != GameOutcome::InProgress {
+            return MoveList::new();
+        }
+
+        let placements: MoveList<_> = (0..64)
+            .filter(|x| !self.flips(self.current_player, *x).is_empty())
+            .map(OthelloMove::Place)
+            .collect();
+        if placements.is_empty() {
+            smallvec![OthelloMove::Pass]
+        } else {
+            placements
+        }
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        if let OthelloMove::Place(index) = *b_move {
+            for cell in self.flips(self.current_player, index) {
+                self.cells[cell as usize] = Some(self.current_player);
+            }
+            self.cells[index as usize] = Some(self.current_player);
+        }
+        self.current_player = self.current_player.opponent();
+        self.outcome = self.compute_outcome();
+    }
+
+    fn get_hash(&self) -> u128 {
+        let mut hash = 0;
+        for cell in self.cells.iter().rev() {
+            let cell_value = match cell {
+                None => 0,
+                Some(OthelloColor::Black) => 1,
+                Some(OthelloColor::White) => 2,
+            };
+            hash = hash * 3 + cell_value;
+        }
+        // Passing changes only the player to move, so it must be part of the hash.
+        hash * 2 + (self.current_player == OthelloColor::White) as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome, Player};
+    use crate::boards::othello::{OthelloBoard, OthelloColor, OthelloMove};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn board_with(discs: &[(u8, OthelloColor)], to_move: OthelloColor) -> OthelloBoard {
+        let mut cells = [None; 64];
+        for (index, color) in discs {
+            cells[*index as usize] = Some(*color);
+        }
+        let mut board = OthelloBoard {
+            root_player: OthelloColor::Black,
+            current_player: to_move,
+            cells,
+            outcome: GameOutcome::InProgress,
+        };
+        board.outcome = board.compute_outcome();
+        board
+    }
+
+    #[test]
+    fn placing_flips_the_enclosed_discs() {
+        // arrange
+        let mut board = OthelloBoard::default();
+
+        // act
+        let moves = board.get_available_moves();
+        board.perform_move(&OthelloMove::Place(19));
+
+        // assert
+        let expected = [19, 26, 37, 44].map(OthelloMove::Place);
+        assert_eq!(moves[..], expected);
+        assert_eq!(board.get_cell(27), Some(OthelloColor::Black));
+        assert_eq!(board.count_discs(OthelloColor::Black), 4);
+        assert_eq!(board.count_discs(OthelloColor::White), 1);
+        assert_eq!(board.get_current_player(), Player::Other);
+    }
+
+    #[test]
+    fn player_without_placements_must_pass() {
+        // arrange
+        let black = OthelloColor::Black;
+        let white = OthelloColor::White;
+        let mut board = board_with(&[(0, white), (1, black)], black);
+
+        // act
+        let moves = board.get_available_moves();
+        let hash_before_pass = board.get_hash();
+        board.perform_move(&OthelloMove::Pass);
+
+        // assert
+        assert_eq!(moves.to_vec(), vec![OthelloMove::Pass]);
+        assert_ne!(board.get_hash(), hash_before_pass);
+        assert_eq!(
+            board.get_available_moves().to_vec(),
+            vec![OthelloMove::Place(2)]
+        );
+    }
+
+    #[test]
+    fn game_ends_when_nobody_can_place() {
+        // arrange
+        let mut board = board_with(
+            &[(0, OthelloColor::White), (1, OthelloColor::Black)],
+            OthelloColor::White,
+        );
+
+        // act
+        board.perform_move(&OthelloMove::Place(2));
+
+        // assert
+        assert_eq!(board.get_outcome(), GameOutcome::Lose);
+        assert!(board.get_available_moves().is_empty());
+        board.set_root_player(OthelloColor::White);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+    }
+
+    #[test]
+    fn search_takes_a_corner() {
+        // arrange
+        let black = OthelloColor::Black;
+        let white = OthelloColor::White;
+        let board = board_with(
+            &[
+                (9, white),
+                (18, black),
+                (10, white),
+                (11, black),
+                (27, white),
+            ],
+            black,
+        );
+        let mut mcts = MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(2000);
+
+        // assert
+        assert_eq!(
+            mcts.get_most_perspective_move(),
+            Some(OthelloMove::Place(0))
+        );
+    }
+}
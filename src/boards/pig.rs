@@ -0,0 +1,278 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+use smallvec::smallvec;
+
+/// One of the two players.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum PigPlayer {
+    /// The player moving first.
+    First,
+    /// The player moving second.
+    Second,
+}
+
+impl PigPlayer {
+    /// Returns the other player.
+    pub fn opponent(self) -> Self {
+        match self {
+            PigPlayer::First => PigPlayer::Second,
+            PigPlayer::Second => PigPlayer::First,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// A move in Pig.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum PigMove {
+    /// Rolls the die, risking the points gathered this turn.
+    Roll,
+    /// Banks the points gathered this turn and passes the die.
+    Hold,
+    /// The face the die shows after a roll, from 1 to 6.
+    Face(u8),
+}
+
+/// An implementation of the `Board` trait for the dice game Pig.
+///
+/// On their turn, a player rolls a die as often as they like, adding each face to a turn total,
+/// and may hold at any point to add the turn total to their score. Rolling a 1 loses the turn
+/// total and ends the turn. The first player to reach the target score wins.
+///
+/// Die rolls are chance nodes: after `PigMove::Roll`, `is_chance_node` returns `true` and the
+/// available moves are the six `PigMove::Face`s, each equally likely. The search has no notion of
+/// chance, so it picks faces like the moves of the player who rolled and is therefore optimistic
+/// about rolling; a search handling chance nodes should sample the faces uniformly instead.
+#[derive(Debug, Clone)]
+pub struct PigBoard {
+    target: u32,
+    root_player: PigPlayer,
+    current_player: PigPlayer,
+    scores: [u32; 2],
+    turn_total: u32,
+    is_rolling: bool,
+}
+
+impl PigBoard {
+    /// Creates a game played to the given score, with the first player to move and searching for
+    /// the first player.
+    pub fn new(target: u32) -> Self {
+        Self {
+            target,
+            root_player: PigPlayer::First,
+            current_player: PigPlayer::First,
+            scores: [0, 0],
+            turn_total: 0,
+            is_rolling: false,
+        }
+    }
+
+    /// Sets the player the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: PigPlayer) {
+        self.root_player = root_player;
+    }
+
+    /// Returns the player whose turn it is.
+    pub fn get_player_to_move(&self) -> PigPlayer {
+        self.current_player
+    }
+
+    /// Returns the banked score of the given player.
+    pub fn get_score(&self, player: PigPlayer) -> u32 {
+        self.scores[player.index()]
+    }
+
+    /// Returns the points gathered in the current turn.
+    pub fn get_turn_total(&self) -> u32 {
+        self.turn_total
+    }
+
+    /// Checks whether the die has been rolled and the next move is the face it shows.
+    pub fn is_chance_node(&self) -> bool {
+        self.is_rolling
+    }
+
+    fn get_winner(&self) -> Option<PigPlayer> {
+        [PigPlayer::First, PigPlayer::Second]
+            .into_iter()
+            .find(|x| self.scores[x.index()] >= self.target)
+    }
+}
+
+impl Default for PigBoard {
+    /// Creates a game played to 100 points, the usual target.
+    fn default() -> Self {
+        PigBoard::new(100)
+    }
+}
+
+impl Board for PigBoard {
+    type Move = PigMove;
+
+    fn get_current_player(&self) -> Player {
+        match self.current_player == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        match self.get_winner() {
+            None => GameOutcome::InProgress,
+            Some(x) if x == self.root_player => GameOutcome::Win,
+            Some(_) => GameOutcome::Lose,
+        }
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.get_winner().is_some() {
+            MoveList::new()
+        } else if self.is_rolling {
+            (1..=6).map(PigMove::Face).collect()
+        } else if self.turn_total == 0 {
+            // Holding without points would only pass the die back and forth.
+            smallvec![PigMove::Roll]
+        } else {
+            smallvec![PigMove::Roll, PigMove::Hold]
+        }
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        match *b_move {
+            PigMove::Roll => self.is_rolling = true,
+            PigMove::Hold => {
+                self.scores[self.current_player.index()] += self.turn_total;
+                self.turn_total = 0;
+                self.current_player = self.current_player.opponent();
+            }
+            PigMove::Face(1) => {
+                self.is_rolling = false;
+                self.turn_total = 0;
+                self.current_player = self.current_player.opponent();
+            }
+            PigMove::Face(face) => {
+                self.is_rolling = false;
+                self.turn_total += face as u32;
+            }
+        }
+    }
+
+    fn get_hash(&self) -> u128 {
+        ((self.scores[0] as u128) << 96)
+            | ((self.scores[1] as u128) << 64)
+            | ((self.turn_total as u128) << 2)
+            | ((self.is_rolling as u128) << 1)
+            | self.current_player.index() as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Bound, GameOutcome, Player};
+    use crate::boards::pig::{PigBoard, PigMove, PigPlayer};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn play(board: &mut PigBoard, moves: &[PigMove]) {
+        for b_move in moves {
+            assert!(
+                board.get_available_moves().contains(b_move),
+                "illegal move {b_move:?}"
+            );
+            board.perform_move(b_move);
+        }
+    }
+
+    #[test]
+    fn rolls_are_chance_nodes() {
+        // arrange
+        let mut board = PigBoard::default();
+
+        // act
+        play(&mut board, &[PigMove::Roll]);
+
+        // assert
+        assert!(board.is_chance_node());
+        assert_eq!(board.get_available_moves().len(), 6);
+        assert_eq!(board.get_current_player(), Player::Me);
+    }
+
+    #[test]
+    fn rolling_a_one_loses_the_turn_total() {
+        // arrange
+        let mut board = PigBoard::default();
+
+        // act
+        play(
+            &mut board,
+            &[
+                PigMove::Roll,
+                PigMove::Face(5),
+                PigMove::Roll,
+                PigMove::Face(1),
+            ],
+        );
+
+        // assert
+        assert_eq!(board.get_turn_total(), 0);
+        assert_eq!(board.get_score(PigPlayer::First), 0);
+        assert_eq!(board.get_player_to_move(), PigPlayer::Second);
+        assert_eq!(board.get_available_moves().to_vec(), vec![PigMove::Roll]);
+    }
+
+    #[test]
+    fn holding_banks_the_turn_total() {
+        // arrange
+        let mut board = PigBoard::new(10);
+
+        // act
+        play(
+            &mut board,
+            &[
+                PigMove::Roll,
+                PigMove::Face(6),
+                PigMove::Roll,
+                PigMove::Face(4),
+            ],
+        );
+        let before_hold = board.get_outcome();
+        play(&mut board, &[PigMove::Hold]);
+
+        // assert
+        assert_eq!(before_hold, GameOutcome::InProgress);
+        assert_eq!(board.get_score(PigPlayer::First), 10);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn search_proves_that_holding_wins() {
+        // arrange
+        let mut board = PigBoard::new(10);
+        play(
+            &mut board,
+            &[PigMove::Roll, PigMove::Face(6), PigMove::Hold],
+        );
+        play(
+            &mut board,
+            &[PigMove::Roll, PigMove::Face(6), PigMove::Hold],
+        );
+        play(&mut board, &[PigMove::Roll, PigMove::Face(4)]);
+        let mut mcts = MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(200);
+
+        // assert
+        let root = mcts.get_root();
+        let hold = root
+            .children()
+            .find(|x| x.value().prev_move() == Some(&PigMove::Hold))
+            .unwrap();
+        assert_eq!(hold.value().stats.bound, Bound::DefoWin);
+    }
+}
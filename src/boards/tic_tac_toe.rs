@@ -1,45 +1,218 @@
-use crate::board::{Board, GameOutcome, Player};
-use std::fmt::Debug;
+use crate::board::{Board, GameOutcome, MoveList, Player};
+use crate::notation::BoardNotation;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
 
-/// An implementation of the `Board` trait for the game of Tic-Tac-Toe.
+/// An implementation of the `Board` trait for Tic-Tac-Toe and its generalization to an N×N board
+/// where K marks in a row win.
 ///
-/// The board is represented by a 9-element array, where each element corresponds to a cell.
-/// A move is represented by a `u8` from 0 to 8.
+/// The board is represented by two bitmasks, one per player, where bit `i` is set if the player
+/// occupies cell `i`. The masks of the winning lines through every cell are computed once per board
+/// size and shared between clones, so detecting a win after a move only tests a few masks, and the
+/// hash is computed in constant time. This makes the board a good starting point for writing a fast
+/// `Board`.
+/// A move is the index of a cell, row by row from the top left corner, from 0 to 8 on the classic
+/// 3×3 board.
+#[derive(Debug, Clone)]
 pub struct TicTacToeBoard {
+    geometry: Arc<Geometry>,
     root_player: TTTPlayer,
     current_player: TTTPlayer,
-    field: [Option<TTTPlayer>; 9],
+    masks: [u128; 2],
     outcome: GameOutcome,
 }
 
+/// The layout of a board size, shared by all boards of that size.
+#[derive(Debug)]
+struct Geometry {
+    size: usize,
+    win_length: usize,
+    /// The mask of all cells.
+    full_mask: u128,
+    /// The masks of all winning lines.
+    lines: Vec<u128>,
+    /// For every cell, the masks of the winning lines through it.
+    cell_lines: Vec<Vec<u128>>,
+}
+
+impl Geometry {
+    fn new(size: usize, win_length: usize) -> Self {
+        let cell_count = size * size;
+        let mut lines = Vec::new();
+        for row in 0..size {
+            for column in 0..size {
+                for (row_step, column_step) in [(0, 1), (1, 0), (1, 1), (1, -1)] {
+                    let end_row = row as isize + row_step * (win_length as isize - 1);
+                    let end_column = column as isize + column_step * (win_length as isize - 1);
+                    if !(0..size as isize).contains(&end_row)
+                        || !(0..size as isize).contains(&end_column)
+                    {
+                        continue;
+                    }
+
+                    let line = (0..win_length as isize)
+                        .map(|x| {
+                            let cell_row = row as isize + row_step * x;
+                            let cell_column = column as isize + column_step * x;
+                            1u128 << (cell_row as usize * size + cell_column as usize)
+                        })
+                        .fold(0, |mask, cell| mask | cell);
+                    lines.push(line);
+                }
+            }
+        }
+        let cell_lines = (0..cell_count)
+            .map(|cell| {
+                lines
+                    .iter()
+                    .copied()
+                    .filter(|line| line & 1 << cell != 0)
+                    .collect()
+            })
+            .collect();
+        Self {
+            size,
+            win_length,
+            full_mask: u128::MAX >> (128 - cell_count),
+            lines,
+            cell_lines,
+        }
+    }
+}
+
 impl TicTacToeBoard {
-    fn new(root_player: TTTPlayer) -> Self {
+    /// Creates an empty `size`×`size` board where `win_length` marks in a row win, with X to move,
+    /// searching for X.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not between 1 and 11, or `win_length` is not between 1 and `size`.
+    pub fn new(size: usize, win_length: usize) -> Self {
+        assert!((1..=11).contains(&size), "size must be between 1 and 11");
+        assert!(
+            (1..=size).contains(&win_length),
+            "win length must be between 1 and the size"
+        );
         Self {
-            root_player,
+            geometry: Arc::new(Geometry::new(size, win_length)),
+            root_player: TTTPlayer::X,
             current_player: TTTPlayer::X,
-            field: [None; 9],
+            masks: [0; 2],
             outcome: GameOutcome::InProgress,
         }
     }
-}
 
-impl Default for TicTacToeBoard {
-    /// Creates a new Tic-Tac-Toe board with player 'X' starting.
-    fn default() -> Self {
-        TicTacToeBoard::new(TTTPlayer::X)
+    /// Creates the position reached by playing `moves` from the empty 3×3 board, searching for X.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a move is out of range, targets an occupied cell or is played after the game
+    /// has ended.
+    pub fn from_moves(moves: &[u8]) -> Self {
+        let mut board = Self::default();
+        for b_move in moves {
+            assert!(
+                (*b_move as usize) < board.geometry.size * board.geometry.size,
+                "move {b_move} is out of range"
+            );
+            assert!(
+                board.get_cell(*b_move).is_none(),
+                "cell {b_move} is already occupied"
+            );
+            assert_eq!(
+                board.outcome,
+                GameOutcome::InProgress,
+                "move {b_move} is played after the game has ended"
+            );
+            board.perform_move(b_move);
+        }
+        board
     }
-}
 
-impl Clone for TicTacToeBoard {
-    fn clone(&self) -> Self {
-        let mut copied_field = [None; 9];
-        copied_field.copy_from_slice(&self.field);
-        Self {
-            root_player: self.root_player,
-            current_player: self.current_player,
-            field: copied_field,
-            outcome: self.outcome,
+    /// Sets the mark on a cell, e.g. to set up a position.
+    ///
+    /// The player to move is derived from the number of marks, X moving whenever both players
+    /// have placed as many marks, and the outcome is recomputed.
+    pub fn set_cell(&mut self, index: u8, player: Option<TTTPlayer>) {
+        let cell = 1 << index;
+        self.masks[0] &= !cell;
+        self.masks[1] &= !cell;
+        if let Some(player) = player {
+            self.masks[player as usize] |= cell;
         }
+        self.current_player = match self.masks[0].count_ones() > self.masks[1].count_ones() {
+            true => TTTPlayer::O,
+            false => TTTPlayer::X,
+        };
+        self.outcome = self.compute_outcome();
+    }
+
+    /// Sets the player the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: TTTPlayer) {
+        self.root_player = root_player;
+        self.outcome = self.compute_outcome();
+    }
+
+    /// Returns the length of a side of the board.
+    pub fn get_size(&self) -> usize {
+        self.geometry.size
+    }
+
+    /// Returns the number of marks in a row needed to win.
+    pub fn get_win_length(&self) -> usize {
+        self.geometry.win_length
+    }
+
+    /// Returns the player to move.
+    pub fn get_player_to_move(&self) -> TTTPlayer {
+        self.current_player
+    }
+
+    /// Returns the mark on the cell with the given index, if any.
+    pub fn get_cell(&self, index: u8) -> Option<TTTPlayer> {
+        [TTTPlayer::X, TTTPlayer::O]
+            .into_iter()
+            .find(|x| self.masks[*x as usize] & 1 << index != 0)
+    }
+
+    /// Returns the player with a winning line, if any.
+    pub fn get_winner(&self) -> Option<TTTPlayer> {
+        [TTTPlayer::X, TTTPlayer::O].into_iter().find(|x| {
+            let mask = self.masks[*x as usize];
+            self.geometry.lines.iter().any(|line| mask & line == *line)
+        })
+    }
+
+    /// Computes the outcome of an arbitrary position.
+    fn compute_outcome(&self) -> GameOutcome {
+        self.outcome_of(self.get_winner())
+    }
+
+    /// Computes the outcome after a move of `player` to `cell`, which can only have completed
+    /// one of the lines through it.
+    fn outcome_after(&self, player: TTTPlayer, cell: usize) -> GameOutcome {
+        let mask = self.masks[player as usize];
+        let is_won = self.geometry.cell_lines[cell]
+            .iter()
+            .any(|line| mask & line == *line);
+        self.outcome_of(is_won.then_some(player))
+    }
+
+    /// Returns the outcome of a position with the given winner.
+    fn outcome_of(&self, winner: Option<TTTPlayer>) -> GameOutcome {
+        match winner {
+            Some(winner) if winner == self.root_player => GameOutcome::Win,
+            Some(_) => GameOutcome::Lose,
+            None if self.masks[0] | self.masks[1] == self.geometry.full_mask => GameOutcome::Draw,
+            None => GameOutcome::InProgress,
+        }
+    }
+}
+
+impl Default for TicTacToeBoard {
+    /// Creates a new 3×3 Tic-Tac-Toe board with player 'X' starting.
+    fn default() -> Self {
+        TicTacToeBoard::new(3, 3)
     }
 }
 
@@ -54,93 +227,187 @@ impl Board for TicTacToeBoard {
     }
 
     fn get_outcome(&self) -> GameOutcome {
-        if self.field[0].is_some()
-            && (self.field[0] == self.field[1] && self.field[0] == self.field[2]
-                || self.field[0] == self.field[3] && self.field[0] == self.field[6])
-        {
-            return if self.field[0].unwrap() == self.root_player {
-                GameOutcome::Win
-            } else {
-                GameOutcome::Lose
-            };
+        self.outcome
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.outcome != GameOutcome::InProgress {
+            return MoveList::new();
         }
 
-        if self.field[8].is_some()
-            && (self.field[8] == self.field[2] && self.field[8] == self.field[5]
-                || self.field[8] == self.field[6] && self.field[8] == self.field[7])
-        {
-            return if self.field[8].unwrap() == self.root_player {
-                GameOutcome::Win
-            } else {
-                GameOutcome::Lose
-            };
+        let mut empty = !(self.masks[0] | self.masks[1]) & self.geometry.full_mask;
+        let mut moves = MoveList::new();
+        while empty != 0 {
+            moves.push(empty.trailing_zeros() as u8);
+            empty &= empty - 1;
+        }
+        moves
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        let player = self.current_player;
+        self.masks[player as usize] |= 1 << b_move;
+        self.current_player = player.opponent();
+        self.outcome = self.outcome_after(player, *b_move as usize);
+    }
+
+    fn outcome_after_move(&mut self, b_move: &Self::Move) -> GameOutcome {
+        self.perform_move(b_move);
+        self.outcome
+    }
+
+    /// Returns the two masks side by side, which is exact for boards of up to 8×8 cells. Larger
+    /// boards mix the masks instead.
+    fn get_hash(&self) -> u128 {
+        let [x, o] = self.masks;
+        if self.geometry.size <= 8 {
+            return x | o << 64;
         }
 
-        if self.field[4].is_some()
-            && (self.field[4] == self.field[1] && self.field[4] == self.field[7]
-                || self.field[4] == self.field[3] && self.field[4] == self.field[5]
-                || self.field[4] == self.field[0] && self.field[4] == self.field[8]
-                || self.field[4] == self.field[2] && self.field[4] == self.field[6])
+        let mixed = (x ^ x >> 64).wrapping_mul(0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c835)
+            ^ (o ^ o >> 64).wrapping_mul(0xbf58_476d_1ce4_e5b9_94d0_49bb_1331_11eb);
+        mixed ^ mixed >> 67
+    }
+}
+
+/// Names the cells by a column letter and a row number, `a1` being the top left cell, and writes
+/// positions as rows of `x`, `o` and `.` separated by `/`, e.g. `x../.o./...`. The win length
+/// follows the rows if it differs from the size, e.g. `...../...../...../...../..... 4`.
+impl BoardNotation for TicTacToeBoard {
+    fn parse_position(notation: &str) -> Option<Self> {
+        let mut parts = notation.split_whitespace();
+        let rows: Vec<_> = parts.next()?.split('/').collect();
+        let size = rows.len();
+        let win_length = match parts.next() {
+            Some(win_length) => win_length.parse().ok()?,
+            None => size,
+        };
+        if parts.next().is_some()
+            || !(1..=11).contains(&size)
+            || !(1..=size).contains(&win_length)
+            || rows.iter().any(|x| x.len() != size)
         {
-            return if self.field[4].unwrap() == self.root_player {
-                GameOutcome::Win
-            } else {
-                GameOutcome::Lose
+            return None;
+        }
+
+        let mut board = TicTacToeBoard::new(size, win_length);
+        for (index, cell) in rows.concat().chars().enumerate() {
+            let player = match cell {
+                'x' => Some(TTTPlayer::X),
+                'o' => Some(TTTPlayer::O),
+                '.' => None,
+                _ => return None,
             };
+            board.set_cell(index as u8, player);
         }
+        let [x, o] = board.masks.map(u128::count_ones);
+        (x == o || x == o + 1).then_some(board)
+    }
 
-        if self.field.iter().any(|x| x.is_none()) {
-            GameOutcome::InProgress
-        } else {
-            GameOutcome::Draw
+    fn format_position(&self) -> String {
+        let size = self.geometry.size as u8;
+        let rows: Vec<String> = (0..size)
+            .map(|row| {
+                (row * size..row * size + size)
+                    .map(|x| match self.get_cell(x) {
+                        None => '.',
+                        Some(TTTPlayer::X) => 'x',
+                        Some(TTTPlayer::O) => 'o',
+                    })
+                    .collect()
+            })
+            .collect();
+        match self.geometry.win_length == self.geometry.size {
+            true => rows.join("/"),
+            false => format!("{} {}", rows.join("/"), self.geometry.win_length),
         }
     }
 
-    fn get_available_moves(&self) -> Vec<Self::Move> {
-        if self.outcome != GameOutcome::InProgress {
-            return Vec::new();
+    fn parse_move(&self, notation: &str) -> Option<u8> {
+        let mut chars = notation.chars();
+        let column = (chars.next()?.to_ascii_lowercase() as u8).checked_sub(b'a')? as usize;
+        let row = chars.as_str().parse::<usize>().ok()?.checked_sub(1)?;
+        let size = self.geometry.size;
+        if column >= size || row >= size {
+            return None;
         }
 
-        self.field
-            .iter()
-            .enumerate()
-            .filter(|(_, x)| x.is_none())
-            .map(|(i, _)| i as u8)
+        let b_move = (row * size + column) as u8;
+        self.get_available_moves()
+            .contains(&b_move)
+            .then_some(b_move)
+    }
+
+    fn format_move(&self, b_move: &u8) -> String {
+        let size = self.geometry.size as u8;
+        format!("{}{}", (b'a' + b_move % size) as char, b_move / size + 1)
+    }
+}
+
+/// Encodes positions as two planes of `size×size` cells, the marks of the player to move followed by
+/// those of the opponent, with moves indexed by their cell.
+#[cfg(feature = "onnx")]
+impl crate::onnx::TensorEncoding for TicTacToeBoard {
+    fn get_tensor_shape(&self) -> Vec<usize> {
+        vec![2, self.get_size(), self.get_size()]
+    }
+
+    fn encode_tensor(&self) -> Vec<f32> {
+        let cells = self.get_size() * self.get_size();
+        [self.current_player, self.current_player.opponent()]
+            .into_iter()
+            .flat_map(|player| {
+                (0..cells).map(move |x| (self.get_cell(x as u8) == Some(player)) as u8 as f32)
+            })
             .collect()
     }
 
-    fn perform_move(&mut self, b_move: &Self::Move) {
-        self.field[*b_move as usize] = Some(self.current_player);
-        self.current_player = match self.current_player {
-            TTTPlayer::X => TTTPlayer::O,
-            TTTPlayer::O => TTTPlayer::X,
-        };
-        self.outcome = self.get_outcome();
+    fn get_policy_index(&self, b_move: &u8) -> usize {
+        *b_move as usize
     }
+}
 
-    fn get_hash(&self) -> u128 {
-        let mut hash = 0;
-        for (i, &cell) in self.field.iter().enumerate() {
-            let cell_value = match cell {
-                None => 0,
-                Some(TTTPlayer::X) => 1,
-                Some(TTTPlayer::O) => 2,
-            };
-            hash += cell_value * 3u128.pow(i as u32);
+impl Display for TicTacToeBoard {
+    /// Draws the board row by row, with `X`, `O` and `.` for empty cells.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let size = self.geometry.size as u8;
+        for row in 0..size {
+            let cells: Vec<_> = (row * size..row * size + size)
+                .map(|x| match self.get_cell(x) {
+                    None => ".",
+                    Some(TTTPlayer::X) => "X",
+                    Some(TTTPlayer::O) => "O",
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
         }
-        hash
+        Ok(())
     }
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-enum TTTPlayer {
+/// A mark on the board, and the player placing it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TTTPlayer {
+    /// The player moving first.
     X,
+    /// The player moving second.
     O,
 }
 
+impl TTTPlayer {
+    /// Returns the other player.
+    pub fn opponent(self) -> Self {
+        match self {
+            TTTPlayer::X => TTTPlayer::O,
+            TTTPlayer::O => TTTPlayer::X,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::board::{Board, GameOutcome, Player};
+    use crate::boards::tic_tac_toe::{TTTPlayer, TicTacToeBoard};
     use crate::mcts::MonteCarloTreeSearch;
     use crate::random::CustomNumberGenerator;
 
@@ -158,12 +425,12 @@ mod tests {
 
         // assert
         let best_node = &mcts.get_root().get_best_child().unwrap().value();
-        assert_eq!(best_node.prev_move.unwrap(), 4);
+        assert_eq!(*best_node.prev_move().unwrap(), 4);
         let root = &mcts.get_root().value();
-        assert_eq!(root.wins, 13867);
-        assert_eq!(root.draws, 2104);
-        assert_eq!(root.visits, 20000);
-        assert!(!root.is_fully_calculated);
+        assert_eq!(root.stats.wins, 13859);
+        assert_eq!(root.stats.draws, 2175);
+        assert_eq!(root.stats.visits, 20000);
+        assert!(!root.stats.is_fully_calculated);
     }
 
     #[test]
@@ -179,12 +446,12 @@ mod tests {
 
         // assert
         let best_node = &mcts.get_root().get_best_child().unwrap().value();
-        assert_eq!(best_node.prev_move.unwrap(), 4);
+        assert_eq!(*best_node.prev_move().unwrap(), 4);
         let root = &mcts.get_root().value();
-        assert_eq!(root.wins, 10758);
-        assert_eq!(root.draws, 3808);
-        assert_eq!(root.visits, 20000);
-        assert!(!root.is_fully_calculated);
+        assert_eq!(root.stats.wins, 10721);
+        assert_eq!(root.stats.draws, 3830);
+        assert_eq!(root.stats.visits, 20000);
+        assert!(!root.stats.is_fully_calculated);
     }
 
     #[test]
@@ -200,11 +467,116 @@ mod tests {
 
         // assert
         let best_node = &mcts.get_root().get_best_child().unwrap().value();
-        assert_eq!(best_node.prev_move.unwrap(), 4);
+        assert_eq!(*best_node.prev_move().unwrap(), 4);
         let root = &mcts.get_root().value();
-        assert_eq!(root.wins, 18225);
-        assert_eq!(root.draws, 10342);
-        assert_eq!(root.visits, 37432);
-        assert!(root.is_fully_calculated);
+        assert_eq!(root.stats.wins, 18388);
+        assert_eq!(root.stats.draws, 10486);
+        assert_eq!(root.stats.visits, 37659);
+        assert!(root.stats.is_fully_calculated);
+    }
+
+    #[test]
+    fn test4_bitboard_rules() {
+        // arrange
+        let mut board = TicTacToeBoard::default();
+
+        // act
+        let outcomes: Vec<_> = [0, 3, 1, 4]
+            .iter()
+            .map(|x| board.outcome_after_move(x))
+            .collect();
+        let hash = board.get_hash();
+        let moves = board.get_available_moves();
+        board.perform_move(&2);
+
+        // assert
+        assert_eq!(outcomes, vec![GameOutcome::InProgress; 4]);
+        assert_eq!(hash, 0b11 | 0b11000 << 64);
+        assert_eq!(moves.to_vec(), vec![2, 5, 6, 7, 8]);
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert!(board.get_available_moves().is_empty());
+    }
+
+    #[test]
+    fn test5_set_up_positions() {
+        // arrange
+        let mut board = TicTacToeBoard::from_moves(&[4, 0, 8]);
+
+        // act
+        board.set_cell(2, Some(TTTPlayer::O));
+        board.set_cell(8, None);
+        board.set_root_player(TTTPlayer::O);
+
+        // assert
+        assert_eq!(board.get_cell(0), Some(TTTPlayer::O));
+        assert_eq!(board.get_cell(8), None);
+        assert_eq!(board.get_player_to_move(), TTTPlayer::X);
+        assert_eq!(board.get_current_player(), Player::Other);
+        assert_eq!(board.to_string(), "O . O\n. X .\n. . .\n");
+        board.perform_move(&6);
+        board.perform_move(&1);
+        assert_eq!(board.get_winner(), Some(TTTPlayer::O));
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+    }
+
+    #[test]
+    #[should_panic(expected = "cell 4 is already occupied")]
+    fn test6_from_moves_rejects_occupied_cells() {
+        TicTacToeBoard::from_moves(&[4, 4]);
+    }
+
+    #[test]
+    fn test7_generalized_board() {
+        // arrange
+        let mut board = TicTacToeBoard::new(4, 3);
+
+        // act
+        for b_move in [5, 0, 10, 3] {
+            board.perform_move(&b_move);
+        }
+        let moves = board.get_available_moves();
+        board.perform_move(&15);
+
+        // assert
+        assert_eq!(moves.len(), 12);
+        assert_eq!(board.get_winner(), Some(TTTPlayer::X));
+        assert_eq!(board.get_outcome(), GameOutcome::Win);
+        assert_eq!(board.to_string(), "O . . O\n. X . .\n. . X .\n. . . X\n");
+    }
+
+    #[test]
+    fn test8_large_board_search() {
+        // arrange
+        let board = TicTacToeBoard::new(11, 5);
+        let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let mut other = board.clone();
+        other.perform_move(&120);
+
+        // act
+        mcts.iterate_n_times(200);
+
+        // assert
+        assert_eq!(mcts.get_root().children().count(), 121);
+        assert_ne!(board.get_hash(), other.get_hash());
+        assert_eq!(other.get_cell(120), Some(TTTPlayer::X));
+    }
+
+    #[cfg(feature = "onnx")]
+    #[test]
+    fn test9_tensor_encoding() {
+        use crate::onnx::TensorEncoding;
+
+        let board = TicTacToeBoard::from_moves(&[4, 0, 8]);
+        let planes = board.encode_tensor();
+        assert_eq!(board.get_tensor_shape(), vec![2, 3, 3]);
+        assert_eq!(planes.len(), 18);
+        // O is to move, so its mark on a1 comes first.
+        assert_eq!(planes[0], 1.0);
+        assert_eq!(planes[9 + 4], 1.0);
+        assert_eq!(planes[9 + 8], 1.0);
+        assert_eq!(planes.iter().sum::<f32>(), 3.0);
+        assert_eq!(board.get_policy_index(&7), 7);
     }
 }
@@ -0,0 +1,313 @@
+use crate::board::{Board, GameOutcome, MoveList, Player};
+use crate::random::{RandomGenerator, SplitMix64};
+
+/// The rows, columns and diagonals of a 3x3 grid.
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+/// A mark on the board, and the player placing it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum UltimateMark {
+    /// The player moving first.
+    X,
+    /// The player moving second.
+    O,
+}
+
+impl UltimateMark {
+    /// Returns the other mark.
+    pub fn opponent(self) -> Self {
+        match self {
+            UltimateMark::X => UltimateMark::O,
+            UltimateMark::O => UltimateMark::X,
+        }
+    }
+}
+
+/// The state of one of the nine small boards.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SubBoardState {
+    /// Cells are still free and nobody has three in a row.
+    Open,
+    /// The given player has three in a row.
+    Won(UltimateMark),
+    /// All cells are taken and nobody has three in a row.
+    Full,
+}
+
+/// An implementation of the `Board` trait for Ultimate Tic-Tac-Toe.
+///
+/// The board consists of nine small Tic-Tac-Toe boards. A move is a `u8` from 0 to 80, the index of
+/// the small board times nine plus the index of the cell within it. The cell a player picks sends
+/// the opponent to the small board with the same index; if that board is already decided, the
+/// opponent may play on any open board. Winning three small boards in a row wins the game.
+#[derive(Debug, Clone)]
+pub struct UltimateTicTacToeBoard {
+    root_player: UltimateMark,
+    current_player: UltimateMark,
+    cells: [Option<UltimateMark>; 81],
+    sub_boards: [SubBoardState; 9],
+    /// The small board the player to move is sent to, or `None` if any open board may be played.
+    active_sub_board: Option<usize>,
+    hash: u128,
+    outcome: GameOutcome,
+}
+
+impl Default for UltimateTicTacToeBoard {
+    /// Creates an empty board with X to move, searching for X.
+    fn default() -> Self {
+        Self {
+            root_player: UltimateMark::X,
+            current_player: UltimateMark::X,
+            cells: [None; 81],
+            sub_boards: [SubBoardState::Open; 9],
+            active_sub_board: None,
+            hash: 0,
+            outcome: GameOutcome::InProgress,
+        }
+    }
+}
+
+impl UltimateTicTacToeBoard {
+    /// Sets the mark the search plays for, which is reported as `Player::Me`.
+    pub fn set_root_player(&mut self, root_player: UltimateMark) {
+        self.root_player = root_player;
+        self.outcome = self.compute_outcome();
+    }
+
+    /// Returns the mark to move.
+    pub fn get_mark_to_move(&self) -> UltimateMark {
+        self.current_player
+    }
+
+    /// Returns the mark on the cell with the given move index, if any.
+    pub fn get_cell(&self, index: u8) -> Option<UltimateMark> {
+        self.cells[index as usize]
+    }
+
+    /// Returns the state of the small board with the given index.
+    pub fn get_sub_board(&self, index: usize) -> SubBoardState {
+        self.sub_boards[index]
+    }
+
+    /// Returns the small board the player to move must play on, or `None` if any open board may
+    /// be played.
+    pub fn get_active_sub_board(&self) -> Option<usize> {
+        self.active_sub_board
+    }
+
+    fn sub_board_state(&self, sub_board: usize) -> SubBoardState {
+        let cells = &self.cells[sub_board * 9..sub_board * 9 + 9];
+        for [a, b, c] in LINES {
+            if let Some(mark) = cells[a]
+                && cells[b] == Some(mark)
+                && cells[c] == Some(mark)
+            {
+                return SubBoardState::Won(mark);
+            }
+        }
+
+        match cells.iter().all(|x| x.is_some()) {
+            true => SubBoardState::Full,
+            false => SubBoardState::Open,
+        }
+    }
+
+    fn compute_outcome(&self) -> GameOutcome {
+        for [a, b, c] in LINES {
+            if let SubBoardState::Won(mark) = self.sub_boards[a]
+                && self.sub_boards[b] == SubBoardState::Won(mark)
+                && self.sub_boards[c] == SubBoardState::Won(mark)
+            {
+                return match mark == self.root_player {
+                    true => GameOutcome::Win,
+                    false => GameOutcome::Lose,
+                };
+            }
+        }
+
+        match self.sub_boards.contains(&SubBoardState::Open) {
+            true => GameOutcome::InProgress,
+            false => GameOutcome::Draw,
+        }
+    }
+
+    /// Returns the Zobrist key of a mark on the given cell, or of the active small board when
+    /// `index` is 81 or more.
+    fn key(index: usize, mark: UltimateMark) -> u128 {
+        let mut random = SplitMix64::new(index as u64 * 2 + (mark == UltimateMark::O) as u64);
+        ((random.next_u64() as u128) << 64) | random.next_u64() as u128
+    }
+
+    fn active_sub_board_key(&self) -> u128 {
+        match self.active_sub_board {
+            Some(x) => UltimateTicTacToeBoard::key(81 + x, UltimateMark::X),
+            None => 0,
+        }
+    }
+}
+
+impl Board for UltimateTicTacToeBoard {
+    type Move = u8;
+
+    fn get_current_player(&self) -> Player {
+        match self.current_player == self.root_player {
+            true => Player::Me,
+            false => Player::Other,
+        }
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        self.outcome
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        if self.outcome != GameOutcome::InProgress {
+            return MoveList::new();
+        }
+
+        (0..81u8)
+            .filter(|x| {
+                let sub_board = *x as usize / 9;
+                self.active_sub_board
+                    .is_none_or(|active| active == sub_board)
+                    && self.sub_boards[sub_board] == SubBoardState::Open
+                    && self.cells[*x as usize].is_none()
+            })
+            .collect()
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        let index = *b_move as usize;
+        let sub_board = index / 9;
+        self.cells[index] = Some(self.current_player);
+        self.hash ^= UltimateTicTacToeBoard::key(index, self.current_player);
+        self.sub_boards[sub_board] = self.sub_board_state(sub_board);
+
+        self.hash ^= self.active_sub_board_key();
+        let next_sub_board = index % 9;
+        self.active_sub_board = match self.sub_boards[next_sub_board] {
+            SubBoardState::Open => Some(next_sub_board),
+            _ => None,
+        };
+        self.hash ^= self.active_sub_board_key();
+
+        self.current_player = self.current_player.opponent();
+        self.outcome = self.compute_outcome();
+    }
+
+    fn get_hash(&self) -> u128 {
+        self.hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome};
+    use crate::boards::ultimate_tic_tac_toe::{
+        SubBoardState, UltimateMark, UltimateTicTacToeBoard,
+    };
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::{CustomNumberGenerator, RandomGenerator};
+
+    fn play(moves: &[u8]) -> UltimateTicTacToeBoard {
+        let mut board = UltimateTicTacToeBoard::default();
+        for b_move in moves {
+            assert!(
+                board.get_available_moves().contains(b_move),
+                "illegal move {b_move}"
+            );
+            board.perform_move(b_move);
+        }
+        board
+    }
+
+    #[test]
+    fn cell_sends_the_opponent_to_a_sub_board() {
+        // act
+        let board = play(&[4 * 9 + 2]);
+
+        // assert
+        assert_eq!(board.get_active_sub_board(), Some(2));
+        let expected: Vec<u8> = (18..27).collect();
+        assert_eq!(board.get_available_moves().to_vec(), expected);
+    }
+
+    #[test]
+    fn decided_sub_board_frees_the_next_move() {
+        // act: X takes the top row of the centre board while O answers elsewhere.
+        let board = play(&[36, 4, 37, 13, 38, 22]);
+
+        // assert
+        assert_eq!(board.get_sub_board(4), SubBoardState::Won(UltimateMark::X));
+        assert_eq!(board.get_active_sub_board(), None);
+        let moves = board.get_available_moves();
+        assert_eq!(moves.len(), 69);
+        assert!(moves.iter().all(|x| !(36..45).contains(x)));
+    }
+
+    #[test]
+    fn three_sub_boards_in_a_row_win_the_game() {
+        // arrange
+        let mut board = UltimateTicTacToeBoard::default();
+        for sub_board in [0, 1, 2] {
+            for cell in [0, 1, 2] {
+                board.cells[sub_board * 9 + cell] = Some(UltimateMark::X);
+            }
+            board.sub_boards[sub_board] = board.sub_board_state(sub_board);
+        }
+
+        // act
+        let outcome = board.compute_outcome();
+
+        // assert
+        assert_eq!(outcome, GameOutcome::Win);
+        board.set_root_player(UltimateMark::O);
+        assert_eq!(board.get_outcome(), GameOutcome::Lose);
+    }
+
+    #[test]
+    fn transpositions_share_a_hash() {
+        let first = play(&[1, 9, 2, 18]);
+        let second = play(&[2, 18, 1, 9]);
+        assert_eq!(first.get_hash(), second.get_hash());
+        assert_ne!(first.get_hash(), play(&[1, 9, 2]).get_hash());
+    }
+
+    #[test]
+    fn random_games_end() {
+        let mut random = CustomNumberGenerator::default();
+        for _ in 0..50 {
+            let mut board = UltimateTicTacToeBoard::default();
+            while board.get_outcome() == GameOutcome::InProgress {
+                let moves = board.get_available_moves();
+                board.perform_move(&moves[random.next_index(moves.len())]);
+            }
+            assert!(board.get_available_moves().is_empty());
+        }
+    }
+
+    #[test]
+    fn search_returns_a_legal_move() {
+        // arrange
+        let board = play(&[40]);
+        let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(1000);
+
+        // assert
+        let best_move = mcts.get_most_perspective_move().unwrap();
+        assert!(board.get_available_moves().contains(&best_move));
+    }
+}
@@ -0,0 +1,361 @@
+use crate::agent::GameRecord;
+use crate::board::{Board, GameOutcome, Player};
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::RandomGenerator;
+use crate::warm_start::RootEstimate;
+use std::collections::HashMap;
+#[cfg(feature = "json")]
+use std::io::{self, Read, Write};
+
+/// The statistics of a move played from a book position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BookMove<M> {
+    /// The move.
+    pub b_move: M,
+    /// The number of games in which the move was played.
+    pub games: u32,
+    /// The number of those games won by the side that played the move.
+    pub wins: u32,
+    /// The number of those games that ended in a draw.
+    pub draws: u32,
+}
+
+/// Decides how an `MctsAgent` uses its opening book.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum BookUsage {
+    /// Plays the book move directly, without searching.
+    #[default]
+    PlayMove,
+    /// Searches as usual, with the children of the root seeded from the book statistics.
+    SeedRoot,
+}
+
+/// A source of known moves for positions near the start of the game.
+pub trait OpeningBook<T: Board> {
+    /// Returns the book moves of the position, or an empty list if the book does not know it.
+    fn get_moves(&self, board: &T) -> Vec<BookMove<T::Move>>;
+
+    /// Returns the most played book move of the position, preferring the first one on ties.
+    fn get_book_move(&self, board: &T) -> Option<T::Move> {
+        self.get_moves(board)
+            .into_iter()
+            .reduce(|best, x| if x.games > best.games { x } else { best })
+            .map(|x| x.b_move)
+    }
+}
+
+/// An `OpeningBook` keyed by `Board::get_hash`, usually built from self-play games.
+///
+/// With the `serde` feature enabled the book serializes with stable field names, and with the
+/// `json` feature it can be saved to and loaded from JSON directly.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HashBook<M> {
+    max_plies: Option<usize>,
+    positions: HashMap<u128, Vec<BookMove<M>>>,
+}
+
+impl<M> Default for HashBook<M> {
+    fn default() -> Self {
+        Self {
+            max_plies: None,
+            positions: HashMap::new(),
+        }
+    }
+}
+
+impl<M: Clone + PartialEq> HashBook<M> {
+    /// Creates an empty book recording every ply of the games added to it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of plies from the start of a game that are recorded.
+    pub fn with_max_plies(mut self, max_plies: usize) -> Self {
+        self.max_plies = Some(max_plies);
+        self
+    }
+
+    /// Returns the number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns `true` if the book contains no positions.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Records the moves of a game played from `board`, crediting its outcome to every move.
+    ///
+    /// Games cut off before their end are ignored.
+    pub fn add_game<T: Board<Move = M>>(&mut self, board: &T, record: &GameRecord<M>) {
+        if record.outcome == GameOutcome::InProgress {
+            return;
+        }
+
+        let mut board = board.clone();
+        let plies = self.max_plies.unwrap_or(usize::MAX);
+        for b_move in record.moves.iter().take(plies) {
+            let is_win = matches!(
+                (board.get_current_player(), record.outcome),
+                (Player::Me, GameOutcome::Win) | (Player::Other, GameOutcome::Lose)
+            );
+            let moves = self.positions.entry(board.get_hash()).or_default();
+            let index = match moves.iter().position(|x| x.b_move == *b_move) {
+                Some(index) => index,
+                None => {
+                    moves.push(BookMove {
+                        b_move: b_move.clone(),
+                        games: 0,
+                        wins: 0,
+                        draws: 0,
+                    });
+                    moves.len() - 1
+                }
+            };
+            moves[index].games += 1;
+            moves[index].wins += is_win as u32;
+            moves[index].draws += (record.outcome == GameOutcome::Draw) as u32;
+            board.perform_move(b_move);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl<M: serde::Serialize + serde::de::DeserializeOwned> HashBook<M> {
+    /// Writes the book as JSON.
+    pub fn save_json<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Reads a book written by `save_json`.
+    ///
+    /// Fails with `io::ErrorKind::InvalidData` if a move has more wins and draws than games.
+    pub fn load_json<R: Read>(reader: R) -> io::Result<Self> {
+        let book: Self = serde_json::from_reader(reader)?;
+        let is_consistent = book.positions.values().flatten().all(|x| {
+            x.wins
+                .checked_add(x.draws)
+                .is_some_and(|results| results <= x.games)
+        });
+        if !is_consistent {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "a book move has more wins and draws than games",
+            ));
+        }
+        Ok(book)
+    }
+}
+
+impl<T: Board> OpeningBook<T> for HashBook<T::Move>
+where
+    T::Move: Clone,
+{
+    fn get_moves(&self, board: &T) -> Vec<BookMove<T::Move>> {
+        self.positions
+            .get(&board.get_hash())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: PartialEq,
+{
+    /// Adds the statistics of the book moves of the root position to the children of the root, as
+    /// if every game in the book had been a simulation through them, see `warm_start`.
+    ///
+    /// Book moves with no games or with more wins and draws than games are ignored. The root is
+    /// expanded first if needed, so this is best called before the first iteration. Returns
+    /// `false`, leaving the tree untouched, if the book does not know the position or the root
+    /// cannot be expanded.
+    pub fn seed_from_book(&mut self, book: &dyn OpeningBook<T>) -> bool {
+        let book_moves = book.get_moves(&self.get_board(self.get_tree().root().id()));
+        if book_moves.is_empty() || !self.expand_root() {
+            return false;
+        }
+
+        let draw_value = self.outcome_value(GameOutcome::Draw);
+        let is_me_to_move = self.get_tree().root().value().current_player == Player::Me;
+        // The estimates are taken for the player to move, who values a draw the other way round.
+        let draw_value = if is_me_to_move {
+            draw_value
+        } else {
+            1.0 - draw_value
+        };
+        let estimates = book_moves.into_iter().filter_map(|x| {
+            let results = x.wins.checked_add(x.draws)?;
+            (x.games > 0 && results <= x.games).then(|| RootEstimate {
+                value: (x.wins as f64 + x.draws as f64 * draw_value) / x.games as f64,
+                visits: x.games,
+                b_move: x.b_move,
+            })
+        });
+        self.warm_start(estimates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::agent::{Agent, GameRunner, MctsAgent};
+    use crate::board::Board;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::book::{BookMove, BookUsage, HashBook, OpeningBook};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn first_move(board: &TicTacToeBoard) -> u8 {
+        board.get_available_moves()[0]
+    }
+
+    fn last_move(board: &TicTacToeBoard) -> u8 {
+        *board.get_available_moves().last().unwrap()
+    }
+
+    fn self_play_book() -> HashBook<u8> {
+        let runner = GameRunner::new(TicTacToeBoard::default());
+        let mut book = HashBook::new().with_max_plies(2);
+        for _ in 0..3 {
+            let record = runner.play(&mut first_move, &mut first_move);
+            book.add_game(&TicTacToeBoard::default(), &record);
+        }
+        let record = runner.play(&mut last_move, &mut last_move);
+        book.add_game(&TicTacToeBoard::default(), &record);
+        book
+    }
+
+    #[test]
+    fn records_self_play_games() {
+        // act
+        let book = self_play_book();
+
+        // assert
+        assert_eq!(book.len(), 3);
+        let moves = book.get_moves(&TicTacToeBoard::default());
+        assert_eq!(
+            moves,
+            vec![
+                BookMove {
+                    b_move: 0,
+                    games: 3,
+                    wins: 3,
+                    draws: 0
+                },
+                BookMove {
+                    b_move: 8,
+                    games: 1,
+                    wins: 1,
+                    draws: 0
+                },
+            ]
+        );
+        let mut board = TicTacToeBoard::default();
+        board.perform_move(&0);
+        assert_eq!(book.get_moves(&board)[0].wins, 0);
+        assert_eq!(book.get_book_move(&TicTacToeBoard::default()), Some(0));
+    }
+
+    #[test]
+    fn seeds_the_root_children() {
+        // arrange
+        let book = self_play_book();
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        let is_seeded = mcts.seed_from_book(&book);
+        mcts.iterate_n_times(100);
+
+        // assert
+        assert!(is_seeded);
+        let root = mcts.get_root();
+        assert_eq!(root.value().stats.visits, 104);
+        assert_eq!(root.children().count(), 9);
+        let corner = root.children().find(|x| x.value().prev_move() == Some(&0));
+        assert!(corner.unwrap().value().stats.wins >= 3);
+        assert!(!mcts.seed_from_book(&HashBook::new()));
+    }
+
+    #[test]
+    fn agent_plays_the_book_move() {
+        // arrange
+        let factory = |board| {
+            MonteCarloTreeSearch::builder(board)
+                .with_random_generator(CustomNumberGenerator::default())
+                .build()
+        };
+        let mut book = HashBook::new();
+        let record =
+            GameRunner::new(TicTacToeBoard::default()).play(&mut last_move, &mut last_move);
+        book.add_game(&TicTacToeBoard::default(), &record);
+        let mut agent = MctsAgent::new(factory)
+            .with_iterations(10)
+            .with_opening_book(book, BookUsage::PlayMove);
+
+        // act
+        let b_move = agent.choose_move(&TicTacToeBoard::default());
+
+        // assert
+        assert_eq!(b_move, 8);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trips() {
+        // arrange
+        let book = self_play_book();
+
+        // act
+        let mut output = Vec::new();
+        book.save_json(&mut output).unwrap();
+        let loaded = HashBook::<u8>::load_json(output.as_slice()).unwrap();
+
+        // assert
+        assert_eq!(loaded, book);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn rejects_moves_with_more_results_than_games() {
+        // arrange
+        let mut book = self_play_book();
+        let hash = TicTacToeBoard::default().get_hash();
+        book.positions.get_mut(&hash).unwrap()[1].draws = 1;
+        let mut output = Vec::new();
+        book.save_json(&mut output).unwrap();
+
+        // act
+        let loaded = HashBook::<u8>::load_json(output.as_slice());
+
+        // assert
+        assert_eq!(loaded.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn seeding_ignores_moves_with_more_results_than_games() {
+        // arrange
+        let mut book = self_play_book();
+        let hash = TicTacToeBoard::default().get_hash();
+        book.positions.get_mut(&hash).unwrap()[1].draws = 1;
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        let is_seeded = mcts.seed_from_book(&book);
+
+        // assert
+        assert!(is_seeded);
+        let root = mcts.get_root();
+        assert_eq!(root.value().stats.visits, 3);
+        assert_eq!(root.value().stats.value_sum, 3.0);
+        let edge = root.children().find(|x| x.value().prev_move() == Some(&8));
+        assert_eq!(edge.unwrap().value().stats.visits, 0);
+    }
+}
@@ -0,0 +1,204 @@
+use ego_tree::NodeId;
+use std::time::Duration;
+
+/// The share of the budget above which a single root child is considered to starve its siblings.
+pub const DEFAULT_STARVATION_SHARE: f64 = 0.9;
+
+/// The limits of a single call to `MonteCarloTreeSearch::search`.
+///
+/// The search stops at whichever limit is reached first. Without any limit it runs until the tree
+/// is fully calculated or the stop condition holds.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct SearchBudget {
+    /// The number of iterations to run.
+    pub iterations: Option<u64>,
+    /// The time after which the search stops.
+    pub time: Option<Duration>,
+}
+
+impl SearchBudget {
+    /// Creates a budget of the given number of iterations.
+    pub fn iterations(iterations: u64) -> Self {
+        Self::default().with_iterations(iterations)
+    }
+
+    /// Creates a budget of the given time.
+    pub fn time(time: Duration) -> Self {
+        Self::default().with_time(time)
+    }
+
+    /// Sets the number of iterations to run.
+    pub fn with_iterations(mut self, iterations: u64) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Sets the time after which the search stops.
+    pub fn with_time(mut self, time: Duration) -> Self {
+        self.time = Some(time);
+        self
+    }
+}
+
+/// The amount of search budget spent below a single root child.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubtreeBudget {
+    /// The number of iterations whose selected path went through the child.
+    pub iterations: u64,
+    /// The number of moves played in simulations started below the child.
+    pub playout_moves: u64,
+}
+
+/// The budget consumed by one root child, as reported by `BudgetReport`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChildBudget {
+    /// The root child this entry describes.
+    pub node_id: NodeId,
+    /// The budget spent below the child.
+    pub budget: SubtreeBudget,
+    /// The fraction of all iterations that went through the child.
+    pub share: f64,
+}
+
+/// Describes how the search budget was distributed among the children of the root.
+///
+/// A search that converges too early spends nearly everything on one child and leaves the others
+/// with a handful of iterations, which is easy to spot here but not from win rates alone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetReport {
+    /// One entry per root child, in the order of the children in the tree.
+    pub children: Vec<ChildBudget>,
+    /// The budget spent below all root children combined.
+    pub total: SubtreeBudget,
+}
+
+impl BudgetReport {
+    /// Creates a report from the budget spent below each root child.
+    pub fn new(children: impl IntoIterator<Item = (NodeId, SubtreeBudget)>) -> Self {
+        let children: Vec<_> = children.into_iter().collect();
+        let total = children
+            .iter()
+            .fold(SubtreeBudget::default(), |total, (_, budget)| {
+                SubtreeBudget {
+                    iterations: total.iterations + budget.iterations,
+                    playout_moves: total.playout_moves + budget.playout_moves,
+                }
+            });
+
+        let children = children
+            .into_iter()
+            .map(|(node_id, budget)| ChildBudget {
+                node_id,
+                budget,
+                share: if total.iterations == 0 {
+                    0.0
+                } else {
+                    budget.iterations as f64 / total.iterations as f64
+                },
+            })
+            .collect();
+
+        Self { children, total }
+    }
+
+    /// Returns the root child that received the largest share of the iterations.
+    pub fn most_expensive_child(&self) -> Option<&ChildBudget> {
+        self.children
+            .iter()
+            .max_by(|a, b| a.budget.iterations.cmp(&b.budget.iterations))
+    }
+
+    /// Returns Jain's fairness index of the iteration counts.
+    ///
+    /// The index is `1.0` when every root child received the same number of iterations and
+    /// approaches `1 / n` when a single child received all of them.
+    pub fn fairness_index(&self) -> f64 {
+        let sum_of_squares: f64 = self
+            .children
+            .iter()
+            .map(|x| (x.budget.iterations as f64).powi(2))
+            .sum();
+        if sum_of_squares == 0.0 {
+            return 1.0;
+        }
+
+        (self.total.iterations as f64).powi(2) / (self.children.len() as f64 * sum_of_squares)
+    }
+
+    /// Returns the root child that consumed at least `min_share` of the iterations, if there is
+    /// more than one child to share the budget with.
+    pub fn dominant_child(&self, min_share: f64) -> Option<&ChildBudget> {
+        if self.children.len() < 2 {
+            return None;
+        }
+
+        self.most_expensive_child()
+            .filter(|x| x.budget.iterations > 0 && x.share >= min_share)
+    }
+
+    /// Checks whether a single root child starved its siblings, using `DEFAULT_STARVATION_SHARE`.
+    pub fn is_starving(&self) -> bool {
+        self.dominant_child(DEFAULT_STARVATION_SHARE).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    #[test]
+    fn accounts_every_iteration_to_a_root_child() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(3000);
+
+        // assert
+        let report = mcts.get_budget_report();
+        assert_eq!(report.children.len(), 9);
+        assert_eq!(report.total.iterations, 3000);
+        assert!(report.total.playout_moves > 0);
+        for (child, node) in report.children.iter().zip(mcts.get_root().children()) {
+            assert_eq!(child.node_id, node.id());
+            assert_eq!(child.budget.iterations, node.value().stats.visits as u64);
+        }
+
+        let share_sum: f64 = report.children.iter().map(|x| x.share).sum();
+        assert!((share_sum - 1.0).abs() < 1e-9);
+        assert!(report.fairness_index() > 1.0 / 9.0 && report.fairness_index() <= 1.0);
+        assert!(!report.is_starving());
+        assert_eq!(
+            report.most_expensive_child().unwrap().node_id,
+            mcts.get_root().children().nth(4).unwrap().id()
+        );
+    }
+
+    #[test]
+    fn detects_a_dominant_child() {
+        use crate::budget::{BudgetReport, SubtreeBudget};
+
+        let mcts =
+            MonteCarloTreeSearch::<_, CustomNumberGenerator>::builder(TicTacToeBoard::default())
+                .build();
+        let root_id = mcts.get_root().id();
+        let budget = |iterations| SubtreeBudget {
+            iterations,
+            playout_moves: 0,
+        };
+
+        let report = BudgetReport::new([(root_id, budget(95)), (root_id, budget(5))]);
+        assert!(report.is_starving());
+        assert!(report.dominant_child(0.96).is_none());
+
+        let balanced = BudgetReport::new([(root_id, budget(50)), (root_id, budget(50))]);
+        assert!(!balanced.is_starving());
+        assert_eq!(balanced.fairness_index(), 1.0);
+    }
+}
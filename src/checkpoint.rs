@@ -0,0 +1,372 @@
+//! Saving a running search to disk and resuming it later, e.g. across restarts of a long solver
+//! run.
+//!
+//! A checkpoint holds the whole tree in the interchange format, the state of the random
+//! generator, the configuration and counters of the search and the action it executes next, so a
+//! resumed search continues exactly like the original one would have. The hooks of the search,
+//! such as observers, stop conditions, move filters, oracles and metrics, are not saved; set them
+//! again on the search that loads the checkpoint. Node annotations are not saved either.
+//!
+//! # Format
+//!
+//! The file starts with the magic bytes `MCTSCKPT` and the format version as a little-endian
+//! `u32`, followed by the checkpoint encoded with `bincode`. Files with any other version are
+//! rejected.
+
+use crate::board::{Board, GameOutcome};
+use crate::budget::SubtreeBudget;
+use crate::interchange::TreeSnapshot;
+use crate::mcts::{MctsAction, MonteCarloTreeSearch, SearchState, SelectionStep};
+use crate::random::RandomState;
+use ego_tree::NodeId;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// The version of the checkpoint format produced by this release.
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+const MAGIC: &[u8; 8] = b"MCTSCKPT";
+
+/// The next action of the search, with nodes given by their index in the snapshot.
+#[derive(serde::Serialize, serde::Deserialize)]
+enum ActionRecord {
+    Selection {
+        root: usize,
+        path: Vec<usize>,
+    },
+    Expansion {
+        leaf: usize,
+        path: Vec<(usize, f64)>,
+    },
+    Simulation {
+        child: usize,
+        created: Vec<usize>,
+    },
+    Backpropagation {
+        child: usize,
+        result: GameOutcome,
+    },
+    EverythingIsCalculated,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Checkpoint<M> {
+    root_hash: u128,
+    tree: TreeSnapshot<M>,
+    random_state: Vec<u64>,
+    state: SearchState,
+    next_action: ActionRecord,
+    selected_leaf: usize,
+    subtree_budgets: Vec<(usize, SubtreeBudget)>,
+}
+
+impl<T: Board, K: RandomState> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Clone + Serialize + DeserializeOwned,
+{
+    /// Saves the search to a file, see the module documentation.
+    ///
+    /// The checkpoint is written to a temporary file next to `path` first, which then replaces
+    /// `path`, so an interrupted save leaves the previous checkpoint intact.
+    pub fn save_checkpoint(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        self.write_checkpoint(&mut writer)?;
+        writer.into_inner()?.sync_all()?;
+        std::fs::rename(temporary, path)
+    }
+
+    /// Resumes the search saved to a file by `save_checkpoint`.
+    ///
+    /// See `read_checkpoint` for the requirements on this search.
+    pub fn load_checkpoint(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.read_checkpoint(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the search as a checkpoint.
+    pub fn write_checkpoint<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let indices: HashMap<NodeId, usize> = self
+            .get_tree()
+            .root()
+            .descendants()
+            .enumerate()
+            .map(|(index, x)| (x.id(), index))
+            .collect();
+        let index = |node_id: &NodeId| {
+            indices.get(node_id).copied().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "node outside of the tree")
+            })
+        };
+
+        let next_action = match self.get_next_mcts_action() {
+            MctsAction::Selection { R, RP } => ActionRecord::Selection {
+                root: index(R)?,
+                // Nodes on the last path may have been pruned since, and are only informational.
+                path: RP.iter().filter_map(|x| indices.get(x).copied()).collect(),
+            },
+            MctsAction::Expansion { L, SP } => ActionRecord::Expansion {
+                leaf: index(L)?,
+                path: SP
+                    .iter()
+                    .map(|x| Ok((index(&x.node)?, x.ucb)))
+                    .collect::<io::Result<_>>()?,
+            },
+            MctsAction::Simulation { C, AC } => ActionRecord::Simulation {
+                child: index(C)?,
+                created: AC.iter().map(index).collect::<io::Result<_>>()?,
+            },
+            MctsAction::Backpropagation { C, result } => ActionRecord::Backpropagation {
+                child: index(C)?,
+                result: *result,
+            },
+            MctsAction::EverythingIsCalculated => ActionRecord::EverythingIsCalculated,
+        };
+
+        let checkpoint = Checkpoint {
+            root_hash: self.get_root_board().get_hash(),
+            tree: self.to_snapshot(),
+            random_state: self.get_random_generator().save_state(),
+            state: self.get_search_state(),
+            next_action,
+            selected_leaf: indices.get(&self.get_selected_leaf()).copied().unwrap_or(0),
+            subtree_budgets: self
+                .get_subtree_budgets()
+                .iter()
+                .filter_map(|(node_id, x)| Some((indices.get(node_id).copied()?, *x)))
+                .collect(),
+        };
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&CHECKPOINT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, &checkpoint).map_err(|x| into_io_error(*x))?;
+        writer.flush()
+    }
+
+    /// Resumes the search written by `write_checkpoint`.
+    ///
+    /// This search must be over the same root position as the saved one. Its tree, random
+    /// generator, configuration and counters are replaced, while its hooks and its board storage
+    /// are kept. On error the search is left untouched.
+    pub fn read_checkpoint<R: Read>(&mut self, mut reader: R) -> io::Result<()> {
+        let mut header = [0; 12];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid_data("not a checkpoint"));
+        }
+        let version = u32::from_le_bytes(header[8..].try_into().unwrap());
+        if version != CHECKPOINT_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported checkpoint version {version}, expected {CHECKPOINT_VERSION}"
+            )));
+        }
+
+        let checkpoint: Checkpoint<T::Move> =
+            bincode::deserialize_from(reader).map_err(|x| into_io_error(*x))?;
+        if checkpoint.root_hash != self.get_root_board().get_hash() {
+            return Err(invalid_data("checkpoint of another root position"));
+        }
+        let random = K::restore_state(&checkpoint.random_state)
+            .ok_or_else(|| invalid_data("invalid random generator state"))?;
+        let len = checkpoint.tree.nodes.len();
+        let action_indices: Vec<usize> = match &checkpoint.next_action {
+            ActionRecord::Selection { root, path } => [*root].iter().chain(path).copied().collect(),
+            ActionRecord::Expansion { leaf, path } => [*leaf]
+                .into_iter()
+                .chain(path.iter().map(|x| x.0))
+                .collect(),
+            ActionRecord::Simulation { child, created } => {
+                [*child].iter().chain(created).copied().collect()
+            }
+            ActionRecord::Backpropagation { child, result: _ } => vec![*child],
+            ActionRecord::EverythingIsCalculated => vec![],
+        };
+        let is_valid = checkpoint.selected_leaf < len
+            && action_indices.iter().all(|x| *x < len)
+            && checkpoint.subtree_budgets.iter().all(|x| x.0 < len);
+        if !is_valid {
+            return Err(invalid_data("node index out of range"));
+        }
+
+        self.load_snapshot(checkpoint.tree).map_err(invalid_data)?;
+
+        let node_ids: Vec<NodeId> = self
+            .get_tree()
+            .root()
+            .descendants()
+            .map(|x| x.id())
+            .collect();
+        let next_action = match checkpoint.next_action {
+            ActionRecord::Selection { root, path } => MctsAction::Selection {
+                R: node_ids[root],
+                RP: path.into_iter().map(|x| node_ids[x]).collect(),
+            },
+            ActionRecord::Expansion { leaf, path } => MctsAction::Expansion {
+                L: node_ids[leaf],
+                SP: path
+                    .into_iter()
+                    .map(|(node, ucb)| SelectionStep {
+                        node: node_ids[node],
+                        ucb,
+                    })
+                    .collect(),
+            },
+            ActionRecord::Simulation { child, created } => MctsAction::Simulation {
+                C: node_ids[child],
+                AC: created.into_iter().map(|x| node_ids[x]).collect(),
+            },
+            ActionRecord::Backpropagation { child, result } => MctsAction::Backpropagation {
+                C: node_ids[child],
+                result,
+            },
+            ActionRecord::EverythingIsCalculated => MctsAction::EverythingIsCalculated,
+        };
+        let subtree_budgets = checkpoint
+            .subtree_budgets
+            .into_iter()
+            .map(|(index, x)| (node_ids[index], x))
+            .collect();
+        self.restore_search_state(
+            checkpoint.state,
+            next_action,
+            node_ids[checkpoint.selected_leaf],
+            subtree_budgets,
+        );
+        self.set_random_generator(random);
+        Ok(())
+    }
+}
+
+impl<T: Board, K: RandomState> MonteCarloTreeSearch<T, K> {
+    fn get_root_board(&self) -> T {
+        self.get_board(self.get_tree().root().id())
+    }
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn into_io_error(error: bincode::ErrorKind) -> io::Error {
+    match error {
+        bincode::ErrorKind::Io(error) => error,
+        error => invalid_data(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::criterion::BestChildCriterion;
+    use crate::mcts::{BackupOperator, MonteCarloTreeSearch};
+    use crate::random::Xoshiro256PlusPlus;
+    use crate::stop::StopCondition;
+    use std::io::ErrorKind;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn search() -> MonteCarloTreeSearch<TicTacToeBoard, Xoshiro256PlusPlus> {
+        MonteCarloTreeSearch::builder(TicTacToeBoard::new(4, 3))
+            .with_random_generator(Xoshiro256PlusPlus::new(7))
+            .with_best_child_criterion(BestChildCriterion::MaxVisits)
+            .with_implicit_minimax(0.3)
+            .with_backup_operator(BackupOperator::Mixed { threshold: 20.0 })
+            .build()
+    }
+
+    #[test]
+    fn resumed_search_continues_like_the_original() {
+        // arrange
+        let mut original = search();
+        original.iterate_n_times(500);
+        let path = std::env::temp_dir().join(format!("mcts-{}.ckpt", std::process::id()));
+
+        // act
+        original.save_checkpoint(&path).unwrap();
+        let mut resumed = MonteCarloTreeSearch::builder(TicTacToeBoard::new(4, 3))
+            .with_random_generator(Xoshiro256PlusPlus::new(1))
+            .build();
+        resumed.load_checkpoint(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        original.iterate_n_times(500);
+        resumed.iterate_n_times(500);
+
+        // assert
+        assert_eq!(resumed.to_snapshot(), original.to_snapshot());
+        assert_eq!(
+            resumed.get_statistics().iterations,
+            original.get_statistics().iterations
+        );
+        assert_eq!(
+            resumed.get_best_child_criterion(),
+            BestChildCriterion::MaxVisits
+        );
+    }
+
+    #[test]
+    fn resumes_an_unfinished_iteration() {
+        // arrange
+        struct StopAfter(AtomicU32);
+        impl StopCondition for StopAfter {
+            fn should_stop(&self) -> bool {
+                self.0.fetch_sub(1, Ordering::Relaxed) <= 1
+            }
+        }
+        let mut original = search();
+        original.iterate_n_times(100);
+        original.set_stop_condition(StopAfter(AtomicU32::new(3)));
+        original.do_iteration();
+        original.take_stop_condition();
+        let mut checkpoint = Vec::new();
+
+        // act
+        original.write_checkpoint(&mut checkpoint).unwrap();
+        let mut resumed = search();
+        resumed.read_checkpoint(checkpoint.as_slice()).unwrap();
+
+        // assert
+        assert_eq!(
+            resumed.get_next_mcts_action().get_name(),
+            original.get_next_mcts_action().get_name()
+        );
+        original.iterate_n_times(100);
+        resumed.iterate_n_times(100);
+        assert_eq!(resumed.to_snapshot(), original.to_snapshot());
+    }
+
+    #[test]
+    fn rejects_other_versions_and_positions() {
+        // arrange
+        let mut original = search();
+        original.iterate_n_times(100);
+        let mut checkpoint = Vec::new();
+        original.write_checkpoint(&mut checkpoint).unwrap();
+        let mut other_version = checkpoint.clone();
+        other_version[8] = 99;
+        let mut board = TicTacToeBoard::new(4, 3);
+        board.perform_move(&0);
+        let mut other_position = MonteCarloTreeSearch::builder(board)
+            .with_random_generator(Xoshiro256PlusPlus::new(1))
+            .build();
+
+        // act
+        let version_error = search().read_checkpoint(other_version.as_slice());
+        let position_error = other_position.read_checkpoint(checkpoint.as_slice());
+        let truncated_error = search().read_checkpoint(&checkpoint[..checkpoint.len() / 2]);
+
+        // assert
+        assert_eq!(version_error.unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(position_error.unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            truncated_error.unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+        assert_eq!(other_position.get_statistics().iterations, 0);
+    }
+}
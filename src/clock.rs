@@ -0,0 +1,27 @@
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+/// Measures the time spent in the search.
+///
+/// `wasm32-unknown-unknown` has no clock in the standard library, so there the time is read from
+/// `performance.now()` of the JavaScript host.
+pub(crate) struct Stopwatch {
+    started_at: Instant,
+}
+
+impl Stopwatch {
+    /// Starts measuring.
+    pub(crate) fn start() -> Self {
+        Self {
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Returns the time since the stopwatch was started.
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+}
@@ -0,0 +1,326 @@
+use crate::board::GamePhase;
+use crate::criterion::BestChildCriterion;
+use crate::mcts::{
+    BackupOperator, BoardStorage, CyclePolicy, DEFAULT_EXPLORATION_CONSTANT, ExpansionMode,
+    GarbageCollection, HashWidth, ProgressiveUnpruning, SelectionPolicy,
+};
+use crate::proof_number::ProofNumberSearch;
+#[cfg(any(feature = "json", feature = "toml"))]
+use std::io::{self, Read};
+
+/// The tuning knobs of a search gathered in one value, applied with
+/// `MonteCarloTreeSearchBuilder::with_config` and read back with `MonteCarloTreeSearch::get_config`.
+///
+/// With the `serde` feature the configuration can be deserialized, and fields missing from the
+/// input keep their default, so experiments can be driven by config files listing only what they
+/// change. `load_json` and `load_toml` read such files behind the `json` and `toml` features:
+///
+/// ```toml
+/// exploration_constant = 1.0
+/// draw_reward = 0.5
+/// selection_policy = "Ucb1Tuned"
+/// backup_operator = { Mixed = { threshold = 20.0 } }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct MctsConfig {
+    /// Whether alpha-beta pruning is enabled.
+    pub use_alpha_beta_pruning: bool,
+    /// The weight of the exploration term of UCB1.
+    pub exploration_constant: f64,
+    /// How selection rates the children of a node.
+    pub selection_policy: SelectionPolicy,
+    /// How the values of simulations are backed up for selection.
+    pub backup_operator: BackupOperator,
+    /// How the best move is chosen once the search is over.
+    pub best_child_criterion: BestChildCriterion,
+    /// The progressive unpruning of the children of a node, if enabled.
+    pub progressive_unpruning: Option<ProgressiveUnpruning>,
+    /// The proof-number search run on promising nodes, if enabled.
+    pub proof_number_search: Option<ProofNumberSearch>,
+    /// The value a drawn simulation backs up.
+    pub draw_reward: f64,
+    /// How much less than the draw reward a draw is worth to the search.
+    pub contempt: f64,
+    /// The weight of implicit minimax backups, `0.0` to disable them.
+    pub implicit_minimax_weight: f64,
+    /// The factor by which backed-up values are discounted for every move.
+    pub reward_discount: f64,
+    /// The cap on the number of nodes in the tree, if any.
+    pub max_nodes: Option<usize>,
+    /// The temperature the final move is sampled with, `0.0` to play the best child.
+    pub temperature: f64,
+    /// The periodic garbage collection of never-visited children, if any.
+    pub garbage_collection: Option<GarbageCollection>,
+    /// How simulations deal with positions repeating within a playout, and how long they may be.
+    pub cycle_policy: CyclePolicy,
+    /// Which nodes keep their own copy of the game state.
+    pub board_storage: BoardStorage,
+    /// How many children a leaf gets when selection reaches it.
+    pub expansion_mode: ExpansionMode,
+    /// Which hash of the board identifies the positions seen in a playout.
+    pub hash_width: HashWidth,
+    /// Whether hash hits in playouts are verified with `Board::get_fingerprint`.
+    pub verify_hashes: bool,
+    /// Whether the subtrees of fully calculated nodes are dropped.
+    pub prune_proven_subtrees: bool,
+}
+
+impl Default for MctsConfig {
+    /// Returns the defaults of `MonteCarloTreeSearchBuilder`.
+    fn default() -> Self {
+        Self {
+            use_alpha_beta_pruning: true,
+            exploration_constant: DEFAULT_EXPLORATION_CONSTANT,
+            selection_policy: SelectionPolicy::default(),
+            backup_operator: BackupOperator::default(),
+            best_child_criterion: BestChildCriterion::default(),
+            progressive_unpruning: None,
+            proof_number_search: None,
+            max_nodes: None,
+            temperature: 0.0,
+            garbage_collection: None,
+            draw_reward: 0.0,
+            contempt: 0.0,
+            implicit_minimax_weight: 0.0,
+            reward_discount: 1.0,
+            cycle_policy: CyclePolicy::default(),
+            board_storage: BoardStorage::default(),
+            expansion_mode: ExpansionMode::default(),
+            hash_width: HashWidth::default(),
+            verify_hashes: false,
+            prune_proven_subtrees: false,
+        }
+    }
+}
+
+/// The configurations a search switches between with the phase of the game, see
+/// `Board::get_phase` and `MctsAgent::with_phase_configs`.
+///
+/// A phase without a configuration keeps the one the search was created with. With the `serde`
+/// feature, missing phases are left empty, e.g. to only enable the solver in the endgame:
+///
+/// ```toml
+/// [endgame]
+/// proof_number_search = { trigger_visits = 10, node_budget = 10000 }
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct PhaseConfigs {
+    /// The configuration of the opening, if any.
+    pub opening: Option<MctsConfig>,
+    /// The configuration of the midgame, if any.
+    pub midgame: Option<MctsConfig>,
+    /// The configuration of the endgame, if any.
+    pub endgame: Option<MctsConfig>,
+}
+
+impl PhaseConfigs {
+    /// Returns the configuration of `phase`, if any.
+    pub fn get(&self, phase: GamePhase) -> Option<MctsConfig> {
+        match phase {
+            GamePhase::Opening => self.opening,
+            GamePhase::Midgame => self.midgame,
+            GamePhase::Endgame => self.endgame,
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl MctsConfig {
+    /// Reads a configuration written as JSON.
+    pub fn load_json<R: Read>(reader: R) -> io::Result<Self> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl MctsConfig {
+    /// Reads a configuration written as TOML.
+    pub fn load_toml<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        toml::from_str(&text).map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::config::MctsConfig;
+    use crate::mcts::{MonteCarloTreeSearch, SelectionPolicy};
+    use crate::proof_number::ProofNumberSearch;
+    use crate::random::CustomNumberGenerator;
+
+    #[test]
+    fn builder_applies_the_config() {
+        // arrange
+        let config = MctsConfig {
+            use_alpha_beta_pruning: false,
+            exploration_constant: 0.5,
+            selection_policy: SelectionPolicy::Ucb1Tuned,
+            proof_number_search: Some(ProofNumberSearch::default()),
+            draw_reward: 0.5,
+            ..MctsConfig::default()
+        };
+
+        // act
+        let mcts: MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator> =
+            MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_config(config)
+                .build();
+
+        // assert
+        assert_eq!(mcts.get_config(), config);
+        let default: MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator> =
+            MonteCarloTreeSearch::builder(TicTacToeBoard::default()).build();
+        assert_eq!(default.get_config(), MctsConfig::default());
+    }
+
+    #[test]
+    fn set_config_keeps_the_tree() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(500);
+        let node_count = mcts.get_tree().nodes().len();
+        let config = MctsConfig {
+            exploration_constant: 0.5,
+            proof_number_search: Some(ProofNumberSearch::default()),
+            ..MctsConfig::default()
+        };
+
+        // act
+        mcts.set_config(config);
+
+        // assert
+        assert_eq!(mcts.get_config(), config);
+        assert_eq!(mcts.get_tree().nodes().len(), node_count);
+        mcts.iterate_n_times(500);
+        assert_eq!(mcts.get_root().value().stats.visits, 1000);
+    }
+
+    #[test]
+    fn set_config_enables_the_solver_when_the_endgame_starts() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_exploration_constant(0.5)
+            .build();
+        mcts.iterate_n_times(200);
+        let visits: Vec<_> = mcts
+            .get_tree()
+            .nodes()
+            .map(|x| (x.id(), x.value().stats.visits))
+            .collect();
+        let endgame = MctsConfig {
+            proof_number_search: Some(ProofNumberSearch {
+                trigger_visits: 1,
+                node_budget: 1000,
+            }),
+            ..mcts.get_config()
+        };
+
+        // act
+        mcts.set_config(endgame);
+        mcts.iterate_n_times(200);
+
+        // assert
+        assert_eq!(mcts.get_config(), endgame);
+        assert_eq!(mcts.get_config().exploration_constant, 0.5);
+        for (node_id, node_visits) in visits {
+            let node = mcts.get_tree().get(node_id).unwrap();
+            assert!(node.value().stats.visits >= node_visits);
+        }
+        assert_eq!(mcts.get_root().value().stats.visits, 400);
+    }
+
+    #[test]
+    fn exploration_constant_spreads_the_visits() {
+        // arrange
+        let search = |exploration_constant: f64| {
+            let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_alpha_beta_pruning(false)
+                .with_exploration_constant(exploration_constant)
+                .build();
+            mcts.iterate_n_times(2000);
+            let root = mcts.get_root();
+            root.children()
+                .map(|x| x.value().stats.visits)
+                .max()
+                .unwrap()
+        };
+
+        // act
+        let greedy = search(0.2);
+        let exploring = search(5.0);
+
+        // assert
+        assert!(greedy > exploring * 2);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn loads_partial_json() {
+        let json =
+            r#"{"exploration_constant": 1.0, "backup_operator": {"Mixed": {"threshold": 20.0}}}"#;
+        let config = MctsConfig::load_json(json.as_bytes()).unwrap();
+        assert_eq!(config.exploration_constant, 1.0);
+        assert_eq!(
+            config.backup_operator,
+            crate::mcts::BackupOperator::Mixed { threshold: 20.0 }
+        );
+        assert_eq!(config.draw_reward, MctsConfig::default().draw_reward);
+        assert!(MctsConfig::load_json("{\"draw_reward\": \"high\"}".as_bytes()).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn loads_partial_toml() {
+        let toml = "
+            draw_reward = 0.5
+            selection_policy = \"Thompson\"
+            [proof_number_search]
+            trigger_visits = 10
+            node_budget = 500
+        ";
+        let config = MctsConfig::load_toml(toml.as_bytes()).unwrap();
+        assert_eq!(config.draw_reward, 0.5);
+        assert_eq!(config.selection_policy, SelectionPolicy::Thompson);
+        assert_eq!(
+            config.proof_number_search,
+            Some(ProofNumberSearch {
+                trigger_visits: 10,
+                node_budget: 500
+            })
+        );
+        assert!(config.use_alpha_beta_pruning);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn loads_phase_configs_from_toml() {
+        use crate::board::GamePhase;
+        use crate::config::PhaseConfigs;
+
+        let toml = "
+            [endgame]
+            proof_number_search = { trigger_visits = 10, node_budget = 500 }
+        ";
+        let phase_configs: PhaseConfigs = toml::from_str(toml).unwrap();
+        assert_eq!(phase_configs.get(GamePhase::Opening), None);
+        let endgame = phase_configs.get(GamePhase::Endgame).unwrap();
+        assert!(endgame.proof_number_search.is_some());
+        assert_eq!(endgame.draw_reward, MctsConfig::default().draw_reward);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::board::{Board, Bound};
+use crate::mcts::MctsTreeNode;
+use crate::mcts_node::MctsNode;
+use ego_tree::NodeRef;
+
+/// Decides which child of a node is reported as the best move once the search is over.
+///
+/// The value of a child is the mean of the values backed up through it, which counts draws with
+/// the draw reward of the search.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BestChildCriterion {
+    /// Prefers children proven to be wins, then picks the highest value.
+    #[default]
+    ProvenWinFirst,
+    /// Picks the highest value. Unstable when some children have only a few visits.
+    MaxValue,
+    /// Picks the most visited child.
+    MaxVisits,
+    /// Picks the child that has both the most visits and the highest value, falling back to the
+    /// child with the largest total value when no child leads in both.
+    RobustChild,
+    /// Picks the child with the highest lower bound of the 95% Wilson interval of the value,
+    /// penalizing children with few visits.
+    SecureChild,
+}
+
+impl BestChildCriterion {
+    /// Returns the best child of `node` according to this criterion.
+    pub fn select<'a, T: Board>(self, node: &MctsTreeNode<'a, T>) -> Option<MctsTreeNode<'a, T>> {
+        let best_child = match self {
+            BestChildCriterion::ProvenWinFirst => node
+                .children()
+                .filter(|x| x.value().stats.bound == Bound::DefoWin)
+                .fold(None, |best, x| max_by(best, x, |x| x.value_mean()))
+                .or_else(|| max_by_key(node, MctsNode::value_mean)),
+            BestChildCriterion::MaxValue => max_by_key(node, MctsNode::value_mean),
+            BestChildCriterion::MaxVisits => max_by_key(node, |x| x.stats.visits as f64),
+            BestChildCriterion::RobustChild => {
+                let most_visited = max_by_key(node, |x| x.stats.visits as f64)?;
+                let most_valuable = max_by_key(node, MctsNode::value_mean)?;
+                if most_visited.value().value_mean() >= most_valuable.value().value_mean() {
+                    Some(most_visited)
+                } else {
+                    max_by_key(node, |x| x.stats.value_sum)
+                }
+            }
+            BestChildCriterion::SecureChild => max_by_key(node, secure_value),
+        };
+        best_child.map(|x| x.into())
+    }
+}
+
+/// Returns the lower bound of the 95% Wilson interval of the value of a node.
+fn secure_value<T: Board>(node: &MctsNode<T>) -> f64 {
+    if node.stats.visits == 0 {
+        f64::MIN
+    } else {
+        node.wilson_interval(1.96).0
+    }
+}
+
+/// Returns the first child with the highest key.
+fn max_by_key<'a, T: Board>(
+    node: &MctsTreeNode<'a, T>,
+    key: impl Fn(&MctsNode<T>) -> f64,
+) -> Option<NodeRef<'a, MctsNode<T>>> {
+    node.children().fold(None, |best, x| max_by(best, x, &key))
+}
+
+fn max_by<'a, T: Board>(
+    best: Option<NodeRef<'a, MctsNode<T>>>,
+    candidate: NodeRef<'a, MctsNode<T>>,
+    key: impl Fn(&MctsNode<T>) -> f64,
+) -> Option<NodeRef<'a, MctsNode<T>>> {
+    match best {
+        Some(best) if key(best.value()) >= key(candidate.value()) => Some(best),
+        _ => Some(candidate),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::criterion::BestChildCriterion;
+    use crate::mcts::{MctsTreeNode, MonteCarloTreeSearch};
+    use crate::mcts_node::MctsNode;
+    use crate::random::CustomNumberGenerator;
+    use ego_tree::Tree;
+
+    fn tree_with_children(children: &[(u32, u32)]) -> Tree<MctsNode<TicTacToeBoard>> {
+        let mut tree = Tree::new(MctsNode::new(0, TicTacToeBoard::default()));
+        for (index, (visits, wins)) in children.iter().enumerate() {
+            let mut node = MctsNode::with_move(0, TicTacToeBoard::default(), Some(index as u8), 1);
+            node.stats.visits = *visits;
+            node.stats.wins = *wins;
+            node.stats.value_sum = *wins as f64;
+            tree.root_mut().append(node);
+        }
+        tree
+    }
+
+    fn best_move(tree: &Tree<MctsNode<TicTacToeBoard>>, criterion: BestChildCriterion) -> u8 {
+        let root = MctsTreeNode::from(tree.root());
+        let best_child = root.get_best_child_by(criterion).unwrap();
+        *best_child.value().prev_move().unwrap()
+    }
+
+    #[test]
+    fn criteria_weigh_visits_and_value_differently() {
+        // arrange
+        let tree = tree_with_children(&[(100, 60), (3, 3), (60, 36)]);
+
+        // act & assert
+        assert_eq!(best_move(&tree, BestChildCriterion::MaxValue), 1);
+        assert_eq!(best_move(&tree, BestChildCriterion::MaxVisits), 0);
+        assert_eq!(best_move(&tree, BestChildCriterion::RobustChild), 0);
+        assert_eq!(best_move(&tree, BestChildCriterion::SecureChild), 0);
+    }
+
+    #[test]
+    fn robust_child_falls_back_to_most_wins() {
+        // arrange
+        let tree = tree_with_children(&[(100, 50), (90, 54), (3, 3)]);
+
+        // act & assert
+        assert_eq!(best_move(&tree, BestChildCriterion::RobustChild), 1);
+        assert_eq!(best_move(&tree, BestChildCriterion::MaxVisits), 0);
+    }
+
+    #[test]
+    fn secure_child_penalizes_few_visits() {
+        // arrange
+        let tree = tree_with_children(&[(400, 240), (16, 11)]);
+
+        // act & assert
+        assert_eq!(best_move(&tree, BestChildCriterion::MaxValue), 1);
+        assert_eq!(best_move(&tree, BestChildCriterion::SecureChild), 0);
+    }
+
+    #[test]
+    fn most_perspective_move_uses_the_configured_criterion() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_best_child_criterion(BestChildCriterion::MaxVisits)
+            .build();
+
+        // act
+        mcts.iterate_n_times(2000);
+
+        // assert
+        let most_visited = mcts
+            .get_root()
+            .children()
+            .max_by_key(|x| x.value().stats.visits)
+            .unwrap();
+        assert_eq!(
+            mcts.get_most_perspective_move(),
+            most_visited.value().prev_move().copied()
+        );
+        assert_eq!(
+            mcts.get_search_result().best_move,
+            mcts.get_most_perspective_move()
+        );
+    }
+}
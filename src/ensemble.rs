@@ -0,0 +1,202 @@
+use crate::board::Board;
+use crate::budget::SearchBudget;
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::RandomGenerator;
+
+/// Decides how the recommendations of the members of an `Ensemble` are combined.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Aggregation {
+    /// Picks the move recommended by the most members, breaking ties by visits.
+    #[default]
+    Votes,
+    /// Picks the move with the most visits summed over all members, breaking ties by votes.
+    VisitSum,
+}
+
+/// The combined statistics of a root move in an `EnsembleResult`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnsembleMove<M> {
+    /// The move.
+    pub b_move: M,
+    /// The number of members recommending the move.
+    pub votes: u32,
+    /// The visits of the move summed over all members.
+    pub visits: u64,
+}
+
+/// The combined recommendation of the members of an `Ensemble`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnsembleResult<M> {
+    /// The move chosen by the aggregation, if any member has root moves.
+    pub best_move: Option<M>,
+    /// Every root move of any member, ranked by the aggregation.
+    pub moves: Vec<EnsembleMove<M>>,
+}
+
+/// Runs several independent searches of the same position and combines their recommendations.
+///
+/// The members differ by their random generators or, in games with hidden information, by the
+/// determinization of the hidden state their root board was sampled with. Combining them reduces
+/// the variance of short searches and lets the search handle hidden information without a
+/// dedicated algorithm. Moves are matched across members by equality, so a move must compare
+/// equal no matter which determinization it was generated from.
+pub struct Ensemble<T: Board, K: RandomGenerator> {
+    members: Vec<MonteCarloTreeSearch<T, K>>,
+    aggregation: Aggregation,
+}
+
+impl<T: Board, K: RandomGenerator> Ensemble<T, K>
+where
+    T::Move: Clone + PartialEq,
+{
+    /// Creates an ensemble of `size` searches, each created by the factory from its index, e.g. to
+    /// seed its generator or to sample its determinization.
+    pub fn new(size: usize, factory: impl FnMut(usize) -> MonteCarloTreeSearch<T, K>) -> Self {
+        Self {
+            members: (0..size).map(factory).collect(),
+            aggregation: Aggregation::default(),
+        }
+    }
+
+    /// Sets how the recommendations of the members are combined.
+    pub fn with_aggregation(mut self, aggregation: Aggregation) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+
+    /// Returns the searches of the ensemble.
+    pub fn get_members(&self) -> &[MonteCarloTreeSearch<T, K>] {
+        &self.members
+    }
+
+    /// Runs every member within the budget, one after another, and combines their
+    /// recommendations.
+    pub fn search(&mut self, budget: SearchBudget) -> EnsembleResult<T::Move> {
+        for member in &mut self.members {
+            member.search(budget);
+        }
+        self.get_result()
+    }
+
+    /// Runs every member within the budget on a thread of its own, and combines their
+    /// recommendations.
+    ///
+    /// The members share no state and are combined in their order, so with an iteration budget
+    /// and generators seeded by the factory the result is exactly the one of `search`, however the
+    /// threads are scheduled. This makes parallel runs reproducible, e.g. in regression tests that
+    /// check exact statistics. Time budgets end after a different number of iterations on every
+    /// run.
+    pub fn search_parallel(&mut self, budget: SearchBudget) -> EnsembleResult<T::Move>
+    where
+        T: Send,
+        T::Move: Send,
+        K: Send,
+    {
+        std::thread::scope(|scope| {
+            for member in &mut self.members {
+                scope.spawn(move || {
+                    member.search(budget);
+                });
+            }
+        });
+        self.get_result()
+    }
+
+    /// Combines the current recommendations of the members.
+    pub fn get_result(&self) -> EnsembleResult<T::Move> {
+        let mut moves: Vec<EnsembleMove<T::Move>> = Vec::new();
+        for member in &self.members {
+            let result = member.get_search_result();
+            for stats in result.moves {
+                let is_vote = result.best_move.as_ref() == Some(&stats.b_move);
+                let index = match moves.iter().position(|x| x.b_move == stats.b_move) {
+                    Some(index) => index,
+                    None => {
+                        moves.push(EnsembleMove {
+                            b_move: stats.b_move,
+                            votes: 0,
+                            visits: 0,
+                        });
+                        moves.len() - 1
+                    }
+                };
+                moves[index].votes += is_vote as u32;
+                moves[index].visits += stats.visits as u64;
+            }
+        }
+
+        match self.aggregation {
+            Aggregation::Votes => moves.sort_by_key(|x| std::cmp::Reverse((x.votes, x.visits))),
+            Aggregation::VisitSum => moves.sort_by_key(|x| std::cmp::Reverse((x.visits, x.votes))),
+        }
+        EnsembleResult {
+            best_move: moves.first().map(|x| x.b_move.clone()),
+            moves,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::budget::SearchBudget;
+    use crate::ensemble::{Aggregation, Ensemble};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::Xoshiro256PlusPlus;
+
+    fn ensemble(size: usize) -> Ensemble<TicTacToeBoard, Xoshiro256PlusPlus> {
+        Ensemble::new(size, |index| {
+            MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(Xoshiro256PlusPlus::new(index as u64))
+                .with_alpha_beta_pruning(false)
+                .build()
+        })
+    }
+
+    #[test]
+    fn votes_and_visits_add_up_over_members() {
+        // arrange
+        let mut ensemble = ensemble(5);
+
+        // act
+        let result = ensemble.search(SearchBudget::iterations(300));
+
+        // assert
+        assert_eq!(result.moves.len(), 9);
+        assert_eq!(result.moves.iter().map(|x| x.votes).sum::<u32>(), 5);
+        assert_eq!(result.moves.iter().map(|x| x.visits).sum::<u64>(), 5 * 300);
+        assert_eq!(result.best_move, Some(result.moves[0].b_move));
+        assert!(result.moves.windows(2).all(|x| x[0].votes >= x[1].votes));
+    }
+
+    #[test]
+    fn parallel_search_matches_sequential_search() {
+        // arrange
+        let mut sequential = ensemble(4).with_aggregation(Aggregation::VisitSum);
+        let mut parallel = ensemble(4).with_aggregation(Aggregation::VisitSum);
+
+        // act
+        let expected = sequential.search(SearchBudget::iterations(500));
+        let result = parallel.search_parallel(SearchBudget::iterations(500));
+
+        // assert
+        assert_eq!(result, expected);
+        for (a, b) in parallel.get_members().iter().zip(sequential.get_members()) {
+            assert_eq!(a.to_snapshot(), b.to_snapshot());
+        }
+    }
+
+    #[test]
+    fn visit_sum_ranks_by_total_visits() {
+        // arrange
+        let mut ensemble = ensemble(3).with_aggregation(Aggregation::VisitSum);
+
+        // act
+        let result = ensemble.search(SearchBudget::iterations(3000));
+
+        // assert
+        assert!(result.moves.windows(2).all(|x| x[0].visits >= x[1].visits));
+        assert_eq!(result.best_move, Some(4));
+        assert_eq!(ensemble.get_members().len(), 3);
+    }
+}
@@ -0,0 +1,415 @@
+use crate::board::{Board, GameOutcome, GamePhase, MoveList, Player};
+use crate::clock::Stopwatch;
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// The estimates of an `Evaluator` for a position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    /// The prior of every available move, in the order of `Board::get_available_moves`, higher
+    /// being better, e.g. the logits of a policy network.
+    pub priors: Vec<f64>,
+    /// The estimated value of the position for the player to move, from `0.0` (a sure loss) to
+    /// `1.0` (a sure win).
+    pub value: f64,
+}
+
+/// A source of move priors and position values, e.g. a trained policy/value network.
+///
+/// Any `FnMut(&T) -> Evaluation` closure is an evaluator. Wrap a board in an `EvaluatedBoard` to
+/// feed an evaluator to the search.
+pub trait Evaluator<T: Board> {
+    /// Estimates the priors of the moves and the value of a position that is not over.
+    fn evaluate(&mut self, board: &T) -> Evaluation;
+
+    /// Estimates several positions at once, returning their evaluations in order.
+    ///
+    /// Evaluates the positions one by one by default; evaluators running a model override it to
+    /// evaluate them in one call.
+    fn evaluate_batch(&mut self, boards: &[T]) -> Vec<Evaluation> {
+        boards.iter().map(|x| self.evaluate(x)).collect()
+    }
+}
+
+impl<T: Board, F: FnMut(&T) -> Evaluation> Evaluator<T> for F {
+    fn evaluate(&mut self, board: &T) -> Evaluation {
+        self(board)
+    }
+}
+
+/// How often a request whose batch is under evaluation checks that the evaluation goes on.
+const POISON_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An evaluator shared by the searches of several threads, batching their requests.
+///
+/// Every clone is a handle to the same evaluator. A request waits until `batch_size` requests are
+/// pending, or until it has waited `max_wait`, and the pending requests are then evaluated in one
+/// `Evaluator::evaluate_batch` call on the thread that completed the batch. This keeps a model
+/// busy with full batches while every thread runs its own search, e.g. in
+/// `selfplay::play_games_parallel`.
+pub struct SharedEvaluator<T: Board, E> {
+    shared: Arc<Shared<T, E>>,
+}
+
+struct Shared<T: Board, E> {
+    queue: Mutex<Queue<T>>,
+    evaluated: Condvar,
+    evaluator: Mutex<E>,
+    batch_size: usize,
+    max_wait: Duration,
+}
+
+/// The requests waiting for a batch and the evaluations not yet picked up by their requester.
+struct Queue<T> {
+    pending: Vec<(u64, T)>,
+    results: HashMap<u64, Evaluation>,
+    next_ticket: u64,
+}
+
+impl<T: Board, E: Evaluator<T>> SharedEvaluator<T, E> {
+    /// Shares `evaluator`, evaluating batches of up to `batch_size` positions and waiting at most
+    /// `max_wait` for a batch to fill.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn new(evaluator: E, batch_size: usize, max_wait: Duration) -> Self {
+        assert!(batch_size > 0, "the batch size must be positive");
+        Self {
+            shared: Arc::new(Shared {
+                queue: Mutex::new(Queue {
+                    pending: Vec::new(),
+                    results: HashMap::new(),
+                    next_ticket: 0,
+                }),
+                evaluated: Condvar::new(),
+                evaluator: Mutex::new(evaluator),
+                batch_size,
+                max_wait,
+            }),
+        }
+    }
+}
+
+impl<T: Board, E> Clone for SharedEvaluator<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T: Board, E: Evaluator<T>> Evaluator<T> for SharedEvaluator<T, E> {
+    /// Queues the position and returns its evaluation once its batch has been evaluated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shared evaluator panicked on another thread.
+    fn evaluate(&mut self, board: &T) -> Evaluation {
+        let shared = &*self.shared;
+        let stopwatch = Stopwatch::start();
+        let mut queue = shared.queue.lock().unwrap();
+        let ticket = queue.next_ticket;
+        queue.next_ticket += 1;
+        queue.pending.push((ticket, board.clone()));
+        loop {
+            if let Some(evaluation) = queue.results.remove(&ticket) {
+                return evaluation;
+            }
+            assert!(
+                !shared.evaluator.is_poisoned(),
+                "the shared evaluator panicked on another thread"
+            );
+
+            let waited = stopwatch.elapsed();
+            let is_pending = queue.pending.iter().any(|x| x.0 == ticket);
+            if is_pending && (queue.pending.len() >= shared.batch_size || waited >= shared.max_wait)
+            {
+                let count = queue.pending.len().min(shared.batch_size);
+                let batch: Vec<_> = queue.pending.drain(..count).collect();
+                drop(queue);
+                let (tickets, boards): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+                let evaluations = shared.evaluator.lock().unwrap().evaluate_batch(&boards);
+                queue = shared.queue.lock().unwrap();
+                queue.results.extend(tickets.into_iter().zip(evaluations));
+                shared.evaluated.notify_all();
+                continue;
+            }
+
+            // A request in a batch under evaluation waits for it, checking now and then that the
+            // evaluating thread has not panicked.
+            let timeout = match is_pending {
+                true => shared.max_wait - waited,
+                false => POISON_CHECK_INTERVAL,
+            };
+            queue = shared.evaluated.wait_timeout(queue, timeout).unwrap().0;
+        }
+    }
+}
+
+/// A board answering `Board::get_move_prior` and `Board::get_heuristic_value` with the estimates
+/// of an `Evaluator`, delegating everything else to the wrapped board.
+///
+/// The priors then drive progressive unpruning and `MonteCarloTreeSearch::search_gumbel`, and the
+/// values drive implicit minimax backups, see `MonteCarloTreeSearchBuilder::with_implicit_minimax`.
+/// A position is evaluated at most once, and only when the search asks for an estimate, so
+/// playouts do not pay for evaluations. Clones share the evaluator.
+pub struct EvaluatedBoard<T: Board, E> {
+    board: T,
+    evaluator: Rc<RefCell<E>>,
+    evaluation: OnceCell<Rc<Evaluation>>,
+}
+
+impl<T: Board, E: Evaluator<T>> EvaluatedBoard<T, E> {
+    /// Wraps `board`, estimating its positions with `evaluator`.
+    pub fn new(board: T, evaluator: E) -> Self {
+        Self {
+            board,
+            evaluator: Rc::new(RefCell::new(evaluator)),
+            evaluation: OnceCell::new(),
+        }
+    }
+
+    /// Returns the wrapped board.
+    pub fn get_board(&self) -> &T {
+        &self.board
+    }
+
+    /// Returns the estimates of the position, evaluating it on the first call.
+    pub fn get_evaluation(&self) -> &Evaluation {
+        self.evaluation
+            .get_or_init(|| Rc::new(self.evaluator.borrow_mut().evaluate(&self.board)))
+    }
+}
+
+impl<T: Board, E> Clone for EvaluatedBoard<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            board: self.board.clone(),
+            evaluator: self.evaluator.clone(),
+            evaluation: self.evaluation.clone(),
+        }
+    }
+}
+
+impl<T: Board, E: Evaluator<T>> Board for EvaluatedBoard<T, E>
+where
+    T::Move: PartialEq,
+{
+    type Move = T::Move;
+
+    fn get_current_player(&self) -> Player {
+        self.board.get_current_player()
+    }
+
+    fn get_outcome(&self) -> GameOutcome {
+        self.board.get_outcome()
+    }
+
+    fn get_available_moves(&self) -> MoveList<Self::Move> {
+        self.board.get_available_moves()
+    }
+
+    fn perform_move(&mut self, b_move: &Self::Move) {
+        self.board.perform_move(b_move);
+        self.evaluation = OnceCell::new();
+    }
+
+    fn outcome_after_move(&mut self, b_move: &Self::Move) -> GameOutcome {
+        self.evaluation = OnceCell::new();
+        self.board.outcome_after_move(b_move)
+    }
+
+    fn get_hash(&self) -> u128 {
+        self.board.get_hash()
+    }
+
+    fn get_hash64(&self) -> u64 {
+        self.board.get_hash64()
+    }
+
+    fn get_fingerprint(&self) -> Option<u64> {
+        self.board.get_fingerprint()
+    }
+
+    fn is_hopeless(&self) -> bool {
+        self.board.is_hopeless()
+    }
+
+    fn get_phase(&self) -> GamePhase {
+        self.board.get_phase()
+    }
+
+    /// Returns the value of the evaluation, seen from `Player::Me`, or `None` if the game is over.
+    fn get_heuristic_value(&self) -> Option<f64> {
+        if self.board.get_outcome() != GameOutcome::InProgress {
+            return None;
+        }
+        let value = self.get_evaluation().value;
+        match self.board.get_current_player() {
+            Player::Me => Some(value),
+            Player::Other => Some(1.0 - value),
+        }
+    }
+
+    /// Returns the prior of the evaluation, or `0.0` for moves the evaluation has no prior for.
+    fn get_move_prior(&self, b_move: &Self::Move) -> f64 {
+        let moves = self.board.get_available_moves();
+        moves
+            .iter()
+            .position(|x| x == b_move)
+            .and_then(|x| self.get_evaluation().priors.get(x).copied())
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Player};
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::evaluator::{EvaluatedBoard, Evaluation, Evaluator, SharedEvaluator};
+    use crate::mcts::{MonteCarloTreeSearch, ProgressiveUnpruning};
+    use crate::random::CustomNumberGenerator;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Rates the centre highest and every position as slightly good for the player to move,
+    /// counting its calls.
+    fn centre_evaluator(calls: Rc<Cell<u32>>) -> impl FnMut(&TicTacToeBoard) -> Evaluation {
+        move |board| {
+            calls.set(calls.get() + 1);
+            Evaluation {
+                priors: board
+                    .get_available_moves()
+                    .iter()
+                    .map(|x| if *x == 4 { 1.0 } else { 0.0 })
+                    .collect(),
+                value: 0.6,
+            }
+        }
+    }
+
+    #[test]
+    fn answers_priors_and_values_from_the_evaluator() {
+        // arrange
+        let calls = Rc::new(Cell::new(0));
+        let mut board =
+            EvaluatedBoard::new(TicTacToeBoard::default(), centre_evaluator(calls.clone()));
+
+        // act
+        let priors = (board.get_move_prior(&4), board.get_move_prior(&0));
+        let value = board.get_heuristic_value();
+        board.perform_move(&0);
+        let value_after_move = board.get_heuristic_value();
+
+        // assert
+        assert_eq!(priors, (1.0, 0.0));
+        assert_eq!(value, Some(0.6));
+        assert_eq!(board.get_current_player(), Player::Other);
+        assert_eq!(value_after_move.map(|x| (x * 10.0).round()), Some(4.0));
+        assert_eq!(calls.get(), 2);
+        assert_eq!(
+            board.get_hash(),
+            TicTacToeBoard::from_moves(&[0]).get_hash()
+        );
+    }
+
+    #[test]
+    fn search_follows_the_priors() {
+        // arrange
+        let calls = Rc::new(Cell::new(0));
+        let board = EvaluatedBoard::new(TicTacToeBoard::default(), centre_evaluator(calls.clone()));
+        let mut mcts = MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_alpha_beta_pruning(false)
+            .with_progressive_unpruning(ProgressiveUnpruning {
+                initial_children: 1,
+                threshold: 1000.0,
+                growth: 2.0,
+            })
+            .build();
+
+        // act
+        mcts.iterate_n_times(100);
+
+        // assert
+        assert_eq!(mcts.get_most_perspective_move(), Some(4));
+        assert!(calls.get() > 0);
+        assert!(calls.get() <= mcts.get_tree().nodes().len() as u32);
+    }
+
+    /// Values a position by its number of moves, recording the size of every batch.
+    struct BatchRecorder {
+        batch_sizes: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl Evaluator<TicTacToeBoard> for BatchRecorder {
+        fn evaluate(&mut self, board: &TicTacToeBoard) -> Evaluation {
+            Evaluation {
+                priors: vec![1.0; board.get_available_moves().len()],
+                value: board.get_available_moves().len() as f64 / 10.0,
+            }
+        }
+
+        fn evaluate_batch(&mut self, boards: &[TicTacToeBoard]) -> Vec<Evaluation> {
+            self.batch_sizes.lock().unwrap().push(boards.len());
+            boards.iter().map(|x| self.evaluate(x)).collect()
+        }
+    }
+
+    #[test]
+    fn shared_evaluator_batches_the_requests_of_all_threads() {
+        // arrange
+        let batch_sizes = Arc::new(Mutex::new(Vec::new()));
+        let evaluator = SharedEvaluator::new(
+            BatchRecorder {
+                batch_sizes: batch_sizes.clone(),
+            },
+            4,
+            Duration::from_secs(60),
+        );
+
+        // act
+        let values: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|x| {
+                    let mut evaluator = evaluator.clone();
+                    scope.spawn(move || {
+                        let board = TicTacToeBoard::from_moves(&(0..x).collect::<Vec<_>>());
+                        evaluator.evaluate(&board).value
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|x| x.join().unwrap()).collect()
+        });
+
+        // assert
+        assert_eq!(*batch_sizes.lock().unwrap(), vec![4]);
+        assert_eq!(values, vec![0.9, 0.8, 0.7, 0.6]);
+    }
+
+    #[test]
+    fn shared_evaluator_evaluates_a_partial_batch_after_the_wait() {
+        // arrange
+        let batch_sizes = Arc::new(Mutex::new(Vec::new()));
+        let mut evaluator = SharedEvaluator::new(
+            BatchRecorder {
+                batch_sizes: batch_sizes.clone(),
+            },
+            8,
+            Duration::from_millis(10),
+        );
+
+        // act
+        let evaluation = evaluator.evaluate(&TicTacToeBoard::default());
+
+        // assert
+        assert_eq!(evaluation.value, 0.9);
+        assert_eq!(*batch_sizes.lock().unwrap(), vec![1]);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::board::Board;
+#[cfg(feature = "json")]
+use crate::interchange::SnapshotFilter;
+use crate::mcts::MonteCarloTreeSearch;
+use crate::notation::BoardNotation;
+use crate::random::RandomGenerator;
+use ego_tree::NodeId;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::io::{self, Write};
+
+const PV_STYLE: &str = "color=red, penwidth=2";
+
+#[cfg(feature = "json")]
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Clone + serde::Serialize,
+{
+    /// Writes the part of the search tree selected by `filter` as JSON.
+    ///
+    /// The document is a `TreeSnapshot` of the interchange format: a version number and a flat
+    /// list of nodes in pre-order, each with its move, statistics and the indices of its children.
+    pub fn export_json<W: Write>(&self, writer: W, filter: &SnapshotFilter) -> io::Result<()> {
+        serde_json::to_writer(writer, &self.to_filtered_snapshot(filter))?;
+        Ok(())
+    }
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Debug,
+{
+    /// Writes the search tree in Graphviz DOT format.
+    ///
+    /// Nodes deeper than `max_depth` below the root are omitted. Every node is labeled with its
+    /// statistics and every edge with its move; the principal variation, i.e. the chain of best
+    /// children starting at the root, is highlighted.
+    pub fn export_dot<W: Write>(&self, writer: W, max_depth: usize) -> io::Result<()> {
+        self.write_dot(writer, max_depth, |_| None, |_, x| format!("{x:?}"))
+    }
+}
+
+impl<T: BoardNotation, K: RandomGenerator> MonteCarloTreeSearch<T, K> {
+    /// Writes the search tree in Graphviz DOT format like `export_dot`, with every node also
+    /// labeled with its position and every edge with its move in the notation of the board.
+    pub fn export_dot_notated<W: Write>(&self, writer: W, max_depth: usize) -> io::Result<()> {
+        self.write_dot(
+            writer,
+            max_depth,
+            |id| Some(self.get_board(id).format_position()),
+            |parent_id, x| self.get_board(parent_id).format_move(x),
+        )
+    }
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K> {
+    /// Writes the search tree in Graphviz DOT format, with the given labels for the positions of
+    /// the nodes and for the moves from a parent.
+    fn write_dot<W: Write>(
+        &self,
+        mut writer: W,
+        max_depth: usize,
+        position_label: impl Fn(NodeId) -> Option<String>,
+        move_label: impl Fn(NodeId, &T::Move) -> String,
+    ) -> io::Result<()> {
+        let principal_variation = self.principal_variation_ids();
+
+        writeln!(writer, "digraph mcts {{")?;
+        writeln!(writer, "    node [shape=box, fontname=\"monospace\"];")?;
+
+        let root = self.get_tree().root();
+        let mut stack = vec![(root, 0usize, 0usize)];
+        let mut next_index = 1;
+        while let Some((node, depth, index)) = stack.pop() {
+            let mcts_node = node.value();
+            let style = if principal_variation.contains(&node.id()) {
+                format!(", {PV_STYLE}")
+            } else {
+                String::new()
+            };
+            let position = position_label(node.id())
+                .map(|x| format!("{}\\n", escape(&x)))
+                .unwrap_or_default();
+            writeln!(
+                writer,
+                "    n{index} [label=\"{position}visits: {}\\nwins: {}\\ndraws: {}\\nbound: {:?}\"{style}];",
+                mcts_node.stats.visits,
+                mcts_node.stats.wins,
+                mcts_node.stats.draws,
+                mcts_node.stats.bound,
+            )?;
+
+            if depth >= max_depth {
+                continue;
+            }
+
+            let mut children = Vec::new();
+            for child in node.children() {
+                let child_index = next_index;
+                next_index += 1;
+                let label = escape(&move_label(node.id(), child.value().prev_move().unwrap()));
+                let style = if principal_variation.contains(&child.id()) {
+                    format!(", {PV_STYLE}")
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    writer,
+                    "    n{index} -> n{child_index} [label=\"{label}\"{style}];"
+                )?;
+                children.push((child, depth + 1, child_index));
+            }
+            stack.extend(children.into_iter().rev());
+        }
+
+        writeln!(writer, "}}")
+    }
+
+    /// Returns the nodes on the chain of best children starting at the root.
+    fn principal_variation_ids(&self) -> HashSet<NodeId> {
+        let mut ids = HashSet::new();
+        let mut node = self.get_root();
+        ids.insert(node.id());
+        while let Some(child) = node.get_best_child_by(self.get_best_child_criterion()) {
+            ids.insert(child.id());
+            node = child;
+        }
+        ids
+    }
+}
+
+/// Escapes a string for use inside a quoted DOT label.
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    #[test]
+    fn exports_limited_depth_with_principal_variation() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(2000);
+
+        // act
+        let mut output = Vec::new();
+        mcts.export_dot(&mut output, 1).unwrap();
+        let dot = String::from_utf8(output).unwrap();
+
+        // assert
+        assert!(dot.starts_with("digraph mcts {"));
+        assert!(dot.trim_end().ends_with('}'));
+        assert_eq!(dot.matches(" -> ").count(), 9);
+        assert_eq!(dot.matches("[label=\"visits: ").count(), 10);
+        assert!(dot.contains("n0 [label=\"visits: 2000\\nwins: "));
+        assert!(dot.contains("n0 -> n5 [label=\"4\", color=red, penwidth=2];"));
+        assert_eq!(dot.matches("color=red").count(), 3);
+    }
+
+    #[test]
+    fn exports_positions_and_moves_in_notation() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(100);
+
+        // act
+        let mut output = Vec::new();
+        mcts.export_dot_notated(&mut output, 1).unwrap();
+        let dot = String::from_utf8(output).unwrap();
+
+        // assert
+        assert!(dot.contains("n0 [label=\".../.../...\\nvisits: 100\\n"));
+        assert!(dot.contains("[label=\".../.x./...\\nvisits: "));
+        assert!(dot.contains(" -> n5 [label=\"b2\""));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn exports_filtered_json() {
+        use crate::interchange::{FORMAT_VERSION, SnapshotFilter};
+
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(1000);
+
+        // act
+        let mut output = Vec::new();
+        let filter = SnapshotFilter {
+            max_depth: Some(1),
+            min_visits: 0,
+        };
+        mcts.export_json(&mut output, &filter).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        // assert
+        let nodes = json["nodes"].as_array().unwrap();
+        assert_eq!(json["version"], FORMAT_VERSION);
+        assert_eq!(nodes.len(), 10);
+        assert_eq!(nodes[0]["visits"], 1000);
+        assert_eq!(nodes[0]["children"].as_array().unwrap().len(), 9);
+        assert_eq!(nodes[1]["prev_move"], 0);
+        assert_eq!(nodes[1]["bound"], "None");
+    }
+}
@@ -0,0 +1,92 @@
+use crate::board::Board;
+
+/// Decides which moves of the root position the search may consider.
+///
+/// The filter is consulted once, when the root is expanded. If it rejects every move, it is
+/// ignored and all moves are searched.
+///
+/// Any closure taking the board and a move implements this trait.
+pub trait MoveFilter<T: Board>: Send {
+    /// Returns `true` if `b_move` may be searched from `board`.
+    fn allows(&self, board: &T, b_move: &T::Move) -> bool;
+}
+
+impl<T: Board, F: Fn(&T, &T::Move) -> bool + Send> MoveFilter<T> for F {
+    fn allows(&self, board: &T, b_move: &T::Move) -> bool {
+        self(board, b_move)
+    }
+}
+
+/// A `MoveFilter` allowing only the listed moves, like the `searchmoves` option of UCI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMoves<M>(pub Vec<M>);
+
+impl<T: Board> MoveFilter<T> for SearchMoves<T::Move>
+where
+    T::Move: PartialEq + Send,
+{
+    fn allows(&self, _board: &T, b_move: &T::Move) -> bool {
+        self.0.contains(b_move)
+    }
+}
+
+/// A `MoveFilter` rejecting the listed moves, e.g. moves known to be bad.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExcludedMoves<M>(pub Vec<M>);
+
+impl<T: Board> MoveFilter<T> for ExcludedMoves<T::Move>
+where
+    T::Move: PartialEq + Send,
+{
+    fn allows(&self, _board: &T, b_move: &T::Move) -> bool {
+        !self.0.contains(b_move)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::filter::{ExcludedMoves, SearchMoves};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn root_moves(mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>) -> Vec<u8> {
+        let mut moves: Vec<_> = mcts
+            .get_root()
+            .children()
+            .filter_map(|x| x.value().prev_move().copied())
+            .collect();
+        moves.sort();
+        moves
+    }
+
+    #[test]
+    fn search_moves_restrict_the_root() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_root_move_filter(SearchMoves(vec![8, 0]))
+            .build();
+
+        // act
+        mcts.iterate_n_times(100);
+
+        // assert
+        assert_eq!(root_moves(&mcts), vec![0, 8]);
+    }
+
+    #[test]
+    fn excluded_moves_are_not_searched() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_root_move_filter(ExcludedMoves(vec![4]))
+            .build();
+
+        // act
+        mcts.iterate_n_times(100);
+
+        // assert
+        assert_eq!(root_moves(&mcts), vec![0, 1, 2, 3, 5, 6, 7, 8]);
+    }
+}
@@ -0,0 +1,200 @@
+//! Helpers for fuzzing boards through the real search loop with the `arbitrary` crate, e.g. from
+//! a `cargo fuzz` target:
+//!
+//! ```ignore
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+//!     let mut u = arbitrary::Unstructured::new(data);
+//!     let _ = mcts_lib::fuzz::fuzz_search(MyBoard::default(), &mut u);
+//! });
+//! ```
+//!
+//! Every bundled board implements `Arbitrary`, generating positions reached by a game of moves
+//! picked from the fuzz input.
+
+use crate::board::{Board, GameOutcome};
+use crate::boards::breakthrough::BreakthroughBoard;
+use crate::boards::checkers::CheckersBoard;
+use crate::boards::goofspiel::GoofspielBoard;
+use crate::boards::hex::HexBoard;
+use crate::boards::kalah::KalahBoard;
+use crate::boards::nim::NimBoard;
+use crate::boards::nine_mens_morris::NineMensMorrisBoard;
+use crate::boards::othello::OthelloBoard;
+use crate::boards::pig::PigBoard;
+use crate::boards::tic_tac_toe::TicTacToeBoard;
+use crate::boards::ultimate_tic_tac_toe::UltimateTicTacToeBoard;
+use crate::mcts::{
+    BackupOperator, BoardStorage, ExpansionMode, MonteCarloTreeSearch, SelectionPolicy,
+};
+use crate::random::CustomNumberGenerator;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::fmt::Debug;
+
+/// The maximum number of iterations `fuzz_search` runs per move.
+const MAX_ITERATIONS: u32 = 200;
+
+/// Plays moves picked from the fuzz input from `board` until the input runs out, the game ends or
+/// the input picks to stop, returning the position reached.
+pub fn arbitrary_position<T: Board>(board: T, u: &mut Unstructured) -> Result<T> {
+    let mut board = board;
+    while !u.is_empty() && board.get_outcome() == GameOutcome::InProgress {
+        let moves = board.get_available_moves();
+        if moves.is_empty() || !u.arbitrary::<bool>()? {
+            break;
+        }
+        board.perform_move(&moves[u.choose_index(moves.len())?]);
+    }
+    Ok(board)
+}
+
+/// Plays a game from `board` in which every move is chosen by a search configured from the fuzz
+/// input, panicking if the engine or the board does.
+///
+/// The seed, the number of iterations, alpha-beta pruning, the board storage, the expansion mode,
+/// the selection policy and the backup operator of every search are taken from the input. Besides the panics of
+/// the engine and the board, the driver panics if a search picks a move that is not available.
+pub fn fuzz_search<T: Board>(board: T, u: &mut Unstructured) -> Result<()>
+where
+    T::Move: Clone + PartialEq + Debug,
+{
+    let mut board = board;
+    while !u.is_empty() && board.get_outcome() == GameOutcome::InProgress {
+        let seed = u.arbitrary()?;
+        let iterations = u.int_in_range(1..=MAX_ITERATIONS)?;
+        let board_storage = match u.arbitrary()? {
+            true => BoardStorage::RootOnly,
+            false => BoardStorage::EveryNode,
+        };
+        let expansion_mode = match u.arbitrary()? {
+            true => ExpansionMode::SingleChild,
+            false => ExpansionMode::AllChildren,
+        };
+        let selection_policy = *u.choose(&[
+            SelectionPolicy::Ucb1,
+            SelectionPolicy::Ucb1Tuned,
+            SelectionPolicy::Thompson,
+        ])?;
+        let backup_operator = match u.int_in_range(0..=2)? {
+            0 => BackupOperator::Average,
+            1 => BackupOperator::Max,
+            _ => BackupOperator::Mixed {
+                threshold: u.int_in_range(1..=100)? as f64,
+            },
+        };
+        let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+            .with_random_generator(CustomNumberGenerator::new(seed))
+            .with_alpha_beta_pruning(u.arbitrary()?)
+            .with_board_storage(board_storage)
+            .with_expansion_mode(expansion_mode)
+            .with_selection_policy(selection_policy)
+            .with_backup_operator(backup_operator)
+            .build();
+        mcts.iterate_n_times(iterations);
+
+        let Some(b_move) = mcts.get_most_perspective_move() else {
+            break;
+        };
+        assert!(
+            board.get_available_moves().contains(&b_move),
+            "the search picked the unavailable move {b_move:?}"
+        );
+        board.perform_move(&b_move);
+    }
+    Ok(())
+}
+
+/// Implements `Arbitrary` for bundled boards by playing moves from their default position.
+macro_rules! impl_arbitrary_from_default {
+    ($($board:ty),* $(,)?) => {
+        $(
+            impl<'a> Arbitrary<'a> for $board {
+                fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                    arbitrary_position(<$board>::default(), u)
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary_from_default!(
+    BreakthroughBoard,
+    CheckersBoard,
+    GoofspielBoard,
+    HexBoard,
+    KalahBoard,
+    NineMensMorrisBoard,
+    OthelloBoard,
+    PigBoard,
+    TicTacToeBoard,
+    UltimateTicTacToeBoard,
+);
+
+#[cfg(feature = "chess")]
+impl_arbitrary_from_default!(crate::boards::chess::ChessBoard);
+
+#[cfg(feature = "go")]
+impl_arbitrary_from_default!(crate::boards::go::GoBoard);
+
+impl<'a> Arbitrary<'a> for NimBoard {
+    /// Generates up to four piles of up to seven tokens, in normal or misère play.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let pile_count = u.int_in_range(1..=4)?;
+        let piles = (0..pile_count)
+            .map(|_| u.int_in_range(0..=7))
+            .collect::<Result<Vec<u32>>>()?;
+        let board = NimBoard::new(&piles).with_misere_play(u.arbitrary()?);
+        arbitrary_position(board, u)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::boards::nim::NimBoard;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::fuzz::fuzz_search;
+    use crate::random::{CustomNumberGenerator, RandomGenerator};
+    use crate::testing::{BoardSuiteConfig, check_board};
+    use arbitrary::{Arbitrary, Unstructured};
+    use std::collections::HashSet;
+
+    fn fuzz_input(seed: i64, len: usize) -> Vec<u8> {
+        let mut rng = CustomNumberGenerator::new(seed);
+        (0..len).map(|_| rng.next_u64() as u8).collect()
+    }
+
+    #[test]
+    fn generates_varied_valid_positions() {
+        // arrange
+        let mut hashes = HashSet::new();
+
+        // act
+        for seed in 0..20 {
+            let data = fuzz_input(seed, 64);
+            let board = TicTacToeBoard::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            hashes.insert(board.get_hash());
+            let nim = NimBoard::arbitrary(&mut Unstructured::new(&data)).unwrap();
+
+            // assert
+            let config = BoardSuiteConfig {
+                games: 1,
+                ..BoardSuiteConfig::default()
+            };
+            check_board(board, &config);
+            check_board(nim, &config);
+        }
+        assert!(hashes.len() > 10);
+    }
+
+    #[test]
+    fn fuzzes_the_search_without_panics() {
+        for seed in 0..10 {
+            let data = fuzz_input(seed, 256);
+            let mut u = Unstructured::new(&data);
+            let board = TicTacToeBoard::arbitrary(&mut u).unwrap();
+            fuzz_search(board, &mut u).unwrap();
+            let nim = NimBoard::arbitrary(&mut u).unwrap();
+            fuzz_search(nim, &mut u).unwrap();
+        }
+    }
+}
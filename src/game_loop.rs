@@ -0,0 +1,210 @@
+//! A turn-based loop for playing a game against an agent in a terminal.
+//!
+//! `GameLoop` shows the position, reads the moves of a human from a text input in the notation of
+//! the board, checks them, and lets an `Agent`, e.g. an `MctsAgent`, answer. Besides moves, the
+//! human can type `undo` to take back their last move together with the answer to it, and `resign`
+//! to give up. See `examples/play_tic_tac_toe.rs`.
+
+use crate::agent::{Agent, GameRecord};
+use crate::board::{GameOutcome, Player};
+use crate::notation::BoardNotation;
+use std::io::{self, BufRead, Write};
+
+/// A line typed by the human.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command<M> {
+    /// Plays the move.
+    Move(M),
+    /// Takes back the last move of the human and the answer to it.
+    Undo,
+    /// Gives up the game.
+    Resign,
+}
+
+/// Parses a line typed by the human in the given position.
+///
+/// Returns an error message if the line is neither a command nor a legal move.
+pub fn parse_command<T: BoardNotation>(board: &T, line: &str) -> Result<Command<T::Move>, String> {
+    match line.trim() {
+        "undo" => Ok(Command::Undo),
+        "resign" => Ok(Command::Resign),
+        "" => Err("enter a move, undo or resign".to_string()),
+        notation => board
+            .parse_move(notation)
+            .map(Command::Move)
+            .ok_or_else(|| format!("illegal move: {notation}")),
+    }
+}
+
+/// Plays a game between a human at a text terminal and an agent.
+pub struct GameLoop<T: BoardNotation> {
+    board: T,
+    human: Player,
+    renderer: Box<dyn Fn(&T) -> String>,
+}
+
+impl<T: BoardNotation> GameLoop<T>
+where
+    T::Move: Clone,
+{
+    /// Creates a loop playing from the given board, with the human moving for `human` as the board
+    /// reports it.
+    ///
+    /// Positions are shown with `BoardNotation::format_position` unless a renderer is set.
+    pub fn new(board: T, human: Player) -> Self {
+        Self {
+            board,
+            human,
+            renderer: Box::new(|board| board.format_position()),
+        }
+    }
+
+    /// Sets the function drawing the position before every move, e.g. the `Display`
+    /// implementation of the board.
+    pub fn with_renderer(mut self, renderer: impl Fn(&T) -> String + 'static) -> Self {
+        self.renderer = Box::new(renderer);
+        self
+    }
+
+    /// Plays a game, reading the commands of the human from `input` and writing the positions,
+    /// the moves of the agent and any error messages to `output`.
+    ///
+    /// The game ends when it is over, when the human resigns or when the input ends, which counts
+    /// as resigning. The outcome of the record is given for the side reported as `Player::Me` by
+    /// the starting board, like `GameRunner` does.
+    pub fn play<R: BufRead, W: Write>(
+        &self,
+        agent: &mut dyn Agent<T>,
+        mut input: R,
+        mut output: W,
+    ) -> io::Result<GameRecord<T::Move>> {
+        let mut history = Vec::new();
+        let mut moves = Vec::new();
+        let mut board = self.board.clone();
+        loop {
+            writeln!(output, "{}", (self.renderer)(&board))?;
+            let outcome = board.get_outcome();
+            if outcome != GameOutcome::InProgress || board.get_available_moves().is_empty() {
+                let outcome = match outcome {
+                    GameOutcome::InProgress => GameOutcome::Draw,
+                    outcome => outcome,
+                };
+                writeln!(output, "game over: {outcome:?}")?;
+                return Ok(GameRecord { moves, outcome });
+            }
+
+            if board.get_current_player() != self.human {
+                let b_move = agent.choose_move(&board);
+                writeln!(output, "agent plays {}", board.format_move(&b_move))?;
+                history.push(board.clone());
+                board.perform_move(&b_move);
+                moves.push(b_move);
+                continue;
+            }
+
+            let command = loop {
+                write!(output, "> ")?;
+                output.flush()?;
+                let mut line = String::new();
+                if input.read_line(&mut line)? == 0 {
+                    break Command::Resign;
+                }
+                match parse_command(&board, &line) {
+                    Ok(command) => break command,
+                    Err(message) => writeln!(output, "{message}")?,
+                }
+            };
+
+            match command {
+                Command::Move(b_move) => {
+                    history.push(board.clone());
+                    board.perform_move(&b_move);
+                    moves.push(b_move);
+                }
+                Command::Undo => {
+                    // Go back to the last position in which the human was to move.
+                    let index = history
+                        .iter()
+                        .rposition(|x: &T| x.get_current_player() == self.human);
+                    match index {
+                        Some(index) => {
+                            board = history[index].clone();
+                            history.truncate(index);
+                            moves.truncate(index);
+                        }
+                        None => writeln!(output, "nothing to undo")?,
+                    }
+                }
+                Command::Resign => {
+                    let outcome = match self.human {
+                        Player::Me => GameOutcome::Lose,
+                        Player::Other => GameOutcome::Win,
+                    };
+                    writeln!(output, "game over: resigned")?;
+                    return Ok(GameRecord { moves, outcome });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome, Player};
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::game_loop::{Command, GameLoop, parse_command};
+    use std::io::Cursor;
+
+    fn first_move(board: &TicTacToeBoard) -> u8 {
+        board.get_available_moves()[0]
+    }
+
+    fn play(input: &str, human: Player) -> (String, Vec<u8>, GameOutcome) {
+        let game_loop = GameLoop::new(TicTacToeBoard::default(), human);
+        let mut output = Vec::new();
+        let record = game_loop
+            .play(&mut first_move, Cursor::new(input.to_string()), &mut output)
+            .unwrap();
+        (
+            String::from_utf8(output).unwrap(),
+            record.moves,
+            record.outcome,
+        )
+    }
+
+    #[test]
+    fn parses_moves_and_commands() {
+        let board = TicTacToeBoard::from_moves(&[4]);
+        assert_eq!(parse_command(&board, "a1\n"), Ok(Command::Move(0)));
+        assert_eq!(parse_command(&board, " undo "), Ok(Command::Undo));
+        assert_eq!(parse_command(&board, "resign"), Ok(Command::Resign));
+        assert_eq!(
+            parse_command(&board, "b2"),
+            Err("illegal move: b2".to_string())
+        );
+    }
+
+    #[test]
+    fn plays_a_game_against_the_agent() {
+        // act
+        let (output, moves, outcome) = play("b2\nc1\na3\n", Player::Me);
+
+        // assert
+        assert_eq!(moves, vec![4, 0, 2, 1, 6]);
+        assert_eq!(outcome, GameOutcome::Win);
+        assert!(output.contains("agent plays a1\n"));
+        assert!(output.ends_with("game over: Win\n"));
+    }
+
+    #[test]
+    fn retries_illegal_moves_and_undoes_whole_turns() {
+        // act
+        let (output, moves, outcome) = play("b2\nb2\nundo\nc3\n", Player::Other);
+
+        // assert
+        assert_eq!(moves, vec![0, 8, 1]);
+        assert_eq!(outcome, GameOutcome::Win);
+        assert!(output.contains("illegal move: b2\n"));
+        assert!(output.ends_with("game over: resigned\n"));
+    }
+}
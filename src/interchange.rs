@@ -0,0 +1,588 @@
+//! A versioned, serialization-independent description of a search tree.
+//!
+//! External tools should depend on the types in this module rather than on the layout of
+//! `MctsNode`, which is free to change between releases.
+//!
+//! # Format (version 4)
+//!
+//! A [`TreeSnapshot`] is a `version` number and a flat list of [`NodeRecord`]s:
+//!
+//! - `nodes[0]` is the root of the tree, and it is the only node without a `prev_move`.
+//! - Nodes are stored in pre-order: every index listed in `children` is greater than the index
+//!   of the node that lists it, and siblings appear in the order the engine created them.
+//! - Every node except the root is listed as a child exactly once.
+//! - Game states are not stored. They are reconstructed by replaying `prev_move` from the root
+//!   position, so a snapshot can only be loaded into a search over the same root position.
+//! - `current_player` and `outcome` are informational copies of what the replayed board reports,
+//!   and are verified on import.
+//! - A node expanded one child at a time, see `ExpansionMode::SingleChild`, records the index of
+//!   its next untried move in `untried_moves`; the moves from that index on are searched after
+//!   import as they would have been without the export.
+//! - A node whose children are not all in the snapshot, because garbage collection dropped the
+//!   unvisited ones or a [`SnapshotFilter`] left them out, lists the indices of the missing
+//!   children among the moves it is expanded with in `missing_children`. The search creates them
+//!   again after import when it next selects the node.
+//! - `minimax_value` and `max_value` carry the state of implicit minimax and max backups, so a
+//!   search using them continues after import as it would have without the export.
+//!
+//! # Guarantees
+//!
+//! Loading a snapshot with [`MonteCarloTreeSearch::load_snapshot`] and exporting it again with
+//! [`MonteCarloTreeSearch::to_snapshot`] yields an identical snapshot. With the `serde` feature
+//! enabled the types serialize with stable field names, so the same holds for any self-describing
+//! format such as JSON.
+//!
+//! Any change to the meaning or the set of fields bumps [`FORMAT_VERSION`]; snapshots with any
+//! other version are rejected.
+
+use crate::board::{Board, Bound, GameOutcome, Player};
+use crate::mcts::{BoardStorage, MonteCarloTreeSearch};
+use crate::mcts_node::{MctsNode, NodeStats};
+use crate::random::RandomGenerator;
+use ego_tree::{NodeId, Tree};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// The version of the interchange format produced by this release.
+pub const FORMAT_VERSION: u32 = 4;
+
+/// Selects the part of the search tree included in a snapshot.
+///
+/// A node is included if it passes the filter and all of its ancestors are included.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct SnapshotFilter {
+    /// The maximum depth below the root of included nodes, or `None` for no limit.
+    pub max_depth: Option<usize>,
+    /// The minimum number of visits of included nodes. The root is always included.
+    pub min_visits: u32,
+}
+
+/// A snapshot of a whole search tree. See the module documentation for the format rules.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeSnapshot<M> {
+    /// The version of the format, always [`FORMAT_VERSION`] for snapshots created by this release.
+    pub version: u32,
+    /// All nodes of the tree in pre-order, starting with the root.
+    pub nodes: Vec<NodeRecord<M>>,
+}
+
+/// A single node of a [`TreeSnapshot`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeRecord<M> {
+    /// The identifier of the node.
+    pub id: i32,
+    /// The move leading to this node from its parent. `None` only for the root.
+    pub prev_move: Option<M>,
+    /// The depth of the node, `0` for the root.
+    pub height: i32,
+    /// The player to move in this node's game state.
+    pub current_player: Player,
+    /// The outcome of the game in this node's game state.
+    pub outcome: GameOutcome,
+    /// The number of times this node has been visited.
+    pub visits: u32,
+    /// The number of simulations through this node that ended in a win.
+    pub wins: u32,
+    /// The number of simulations through this node that ended in a draw.
+    pub draws: u32,
+    /// The sum of the values backed up through this node.
+    pub value_sum: f64,
+    /// The sum of the squares of the values backed up through this node.
+    pub value_sum_sq: f64,
+    /// The proven bound of the node.
+    pub bound: Bound,
+    /// Whether the outcome of this node is definitively known.
+    pub is_fully_calculated: bool,
+    /// The minimax value of the heuristic estimates below this node, if implicit minimax backups
+    /// computed one.
+    pub minimax_value: Option<f64>,
+    /// The value of the best visited child of this node, if max or mixed backups computed one.
+    pub max_value: Option<f64>,
+    /// Indices into [`TreeSnapshot::nodes`] of the children of this node.
+    pub children: Vec<usize>,
+    /// For a partially expanded node, the index of its next untried move among the moves the
+    /// node is expanded with; `None` once every move has a child.
+    pub untried_moves: Option<usize>,
+    /// The indices, in ascending order, of the children missing from the snapshot among the
+    /// moves the node is expanded with.
+    pub missing_children: Vec<usize>,
+}
+
+/// The reasons a snapshot can be rejected on import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterchangeError {
+    /// The snapshot was written with a different version of the format.
+    UnsupportedVersion(u32),
+    /// The snapshot contains no nodes.
+    EmptyTree,
+    /// A node lists a child index that is out of range, not after its own index, or already used.
+    InvalidChildIndex {
+        /// The index of the node listing the child.
+        parent: usize,
+        /// The offending child index.
+        child: usize,
+    },
+    /// The node at this index is not reachable from the root.
+    UnreachableNode(usize),
+    /// The root has a `prev_move`, or a non-root node at this index has none.
+    InvalidMove(usize),
+    /// The state reconstructed for the node at this index does not match the record.
+    StateMismatch(usize),
+}
+
+impl Display for InterchangeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InterchangeError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported snapshot version {version}, expected {FORMAT_VERSION}"
+            ),
+            InterchangeError::EmptyTree => write!(f, "snapshot contains no nodes"),
+            InterchangeError::InvalidChildIndex { parent, child } => {
+                write!(f, "node {parent} lists invalid child index {child}")
+            }
+            InterchangeError::UnreachableNode(index) => {
+                write!(f, "node {index} is not reachable from the root")
+            }
+            InterchangeError::InvalidMove(index) => {
+                write!(f, "node {index} has an invalid previous move")
+            }
+            InterchangeError::StateMismatch(index) => {
+                write!(f, "node {index} does not match the replayed game state")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InterchangeError {}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Clone,
+{
+    /// Exports the whole search tree in the interchange format.
+    pub fn to_snapshot(&self) -> TreeSnapshot<T::Move> {
+        self.to_filtered_snapshot(&SnapshotFilter::default())
+    }
+
+    /// Exports the part of the search tree selected by `filter` in the interchange format.
+    ///
+    /// The result is a valid snapshot of the retained nodes, which always include the root. The
+    /// children left out are listed as missing, so a search importing the snapshot creates them
+    /// again, with fresh statistics, when it next selects their parent.
+    pub fn to_filtered_snapshot(&self, filter: &SnapshotFilter) -> TreeSnapshot<T::Move> {
+        let mut nodes: Vec<NodeRecord<T::Move>> = Vec::new();
+        let mut stack = vec![(self.get_tree().root(), None::<usize>, 0)];
+        while let Some((node, parent_index, depth)) = stack.pop() {
+            let index = nodes.len();
+            if let Some(parent_index) = parent_index {
+                nodes[parent_index].children.push(index);
+            }
+
+            let mcts_node = node.value();
+            nodes.push(NodeRecord {
+                id: mcts_node.id,
+                prev_move: mcts_node.prev_move().cloned(),
+                height: mcts_node.height,
+                current_player: mcts_node.current_player,
+                outcome: mcts_node.outcome,
+                visits: mcts_node.stats.visits,
+                wins: mcts_node.stats.wins,
+                draws: mcts_node.stats.draws,
+                value_sum: mcts_node.stats.value_sum,
+                value_sum_sq: mcts_node.stats.value_sum_sq,
+                bound: mcts_node.stats.bound,
+                is_fully_calculated: mcts_node.stats.is_fully_calculated,
+                minimax_value: mcts_node.stats.minimax_value,
+                max_value: mcts_node.stats.max_value,
+                children: Vec::new(),
+                untried_moves: self.get_untried_moves().get(&node.id()).copied(),
+                missing_children: Vec::new(),
+            });
+
+            // Children left out by the filter are missing just like collected ones, at their
+            // index among the moves the node is expanded with.
+            let collected = self.get_collected_nodes().get(&node.id());
+            let mut missing_children = collected.cloned().unwrap_or_default();
+            let move_indices = (0..).filter(|x| !collected.is_some_and(|c| c.contains(x)));
+            let is_at_max_depth = filter.max_depth.is_some_and(|x| depth >= x);
+            let mut children = Vec::new();
+            for (child, move_index) in node.children().zip(move_indices) {
+                if is_at_max_depth || child.value().stats.visits < filter.min_visits {
+                    missing_children.push(move_index);
+                } else {
+                    children.push((child, Some(index), depth + 1));
+                }
+            }
+            missing_children.sort_unstable();
+            nodes[index].missing_children = missing_children;
+            stack.extend(children.into_iter().rev());
+        }
+
+        TreeSnapshot {
+            version: FORMAT_VERSION,
+            nodes,
+        }
+    }
+
+    /// Replaces the search tree with the one described by `snapshot`.
+    ///
+    /// The snapshot must have been taken from a search over the same root position. On error the
+    /// current tree is left untouched.
+    pub fn load_snapshot(
+        &mut self,
+        snapshot: TreeSnapshot<T::Move>,
+    ) -> Result<(), InterchangeError> {
+        validate_structure(&snapshot)?;
+
+        let mut records: Vec<_> = snapshot.nodes.into_iter().map(Some).collect();
+        let root_record = records[0].take().unwrap();
+        if root_record.prev_move.is_some() {
+            return Err(InterchangeError::InvalidMove(0));
+        }
+
+        let root_board = self.get_board(self.get_tree().root().id());
+        let root_node = restore_node(0, root_record.id, root_board.clone(), None, &root_record)?;
+        let mut tree = Tree::with_capacity(root_node, records.len());
+        let root_id = tree.root().id();
+        let mut expansion_state = ExpansionState::default();
+        expansion_state.record(root_id, &root_record);
+        let stores_boards = self.get_board_storage() == BoardStorage::EveryNode;
+
+        let mut stack: Vec<(NodeId, T, Vec<usize>)> =
+            vec![(root_id, root_board, root_record.children)];
+        while let Some((parent_id, parent_board, children)) = stack.pop() {
+            let parent_height = tree.get(parent_id).unwrap().value().height;
+            for child_index in children {
+                let record = records[child_index].take().unwrap();
+                let prev_move = record
+                    .prev_move
+                    .clone()
+                    .ok_or(InterchangeError::InvalidMove(child_index))?;
+                if record.height != parent_height + 1 {
+                    return Err(InterchangeError::StateMismatch(child_index));
+                }
+
+                let mut board = parent_board.clone();
+                board.perform_move(&prev_move);
+                let mut node = restore_node(
+                    child_index,
+                    record.id,
+                    board.clone(),
+                    Some(prev_move),
+                    &record,
+                )?;
+                if !stores_boards {
+                    node.drop_board();
+                }
+                let child_id = tree.get_mut(parent_id).unwrap().append(node).id();
+                expansion_state.record(child_id, &record);
+                stack.push((child_id, board, record.children));
+            }
+        }
+
+        self.replace_tree(tree);
+        self.set_untried_moves(expansion_state.untried_moves);
+        self.set_collected_nodes(expansion_state.missing_children);
+        Ok(())
+    }
+}
+
+/// The children still to be created for the nodes of an imported tree.
+#[derive(Default)]
+struct ExpansionState {
+    untried_moves: HashMap<NodeId, usize>,
+    missing_children: HashMap<NodeId, Vec<usize>>,
+}
+
+impl ExpansionState {
+    fn record<M>(&mut self, node_id: NodeId, record: &NodeRecord<M>) {
+        if let Some(index) = record.untried_moves {
+            self.untried_moves.insert(node_id, index);
+        }
+        if !record.missing_children.is_empty() {
+            self.missing_children
+                .insert(node_id, record.missing_children.clone());
+        }
+    }
+}
+
+/// Checks the index rules of the format without looking at the game states.
+fn validate_structure<M>(snapshot: &TreeSnapshot<M>) -> Result<(), InterchangeError> {
+    if snapshot.version != FORMAT_VERSION {
+        return Err(InterchangeError::UnsupportedVersion(snapshot.version));
+    }
+
+    if snapshot.nodes.is_empty() {
+        return Err(InterchangeError::EmptyTree);
+    }
+
+    let mut is_referenced = vec![false; snapshot.nodes.len()];
+    is_referenced[0] = true;
+    for (parent, record) in snapshot.nodes.iter().enumerate() {
+        for &child in &record.children {
+            if child <= parent || child >= snapshot.nodes.len() || is_referenced[child] {
+                return Err(InterchangeError::InvalidChildIndex { parent, child });
+            }
+            is_referenced[child] = true;
+        }
+    }
+
+    match is_referenced.iter().position(|x| !x) {
+        Some(index) => Err(InterchangeError::UnreachableNode(index)),
+        None => Ok(()),
+    }
+}
+
+/// Creates a tree node for a record, checking it against the replayed board.
+fn restore_node<T: Board>(
+    index: usize,
+    id: i32,
+    board: T,
+    prev_move: Option<T::Move>,
+    record: &NodeRecord<T::Move>,
+) -> Result<MctsNode<T>, InterchangeError> {
+    let mut node = MctsNode::with_move(id, board, prev_move, record.height);
+    if node.current_player != record.current_player || node.outcome != record.outcome {
+        return Err(InterchangeError::StateMismatch(index));
+    }
+
+    node.stats = NodeStats {
+        visits: record.visits,
+        wins: record.wins,
+        draws: record.draws,
+        value_sum: record.value_sum,
+        value_sum_sq: record.value_sum_sq,
+        bound: record.bound,
+        is_fully_calculated: record.is_fully_calculated,
+        minimax_value: record.minimax_value,
+        max_value: record.max_value,
+    };
+    Ok(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Player};
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::interchange::{FORMAT_VERSION, InterchangeError, SnapshotFilter, TreeSnapshot};
+    use crate::mcts::{BackupOperator, ExpansionMode, MonteCarloTreeSearch};
+    use crate::random::CustomNumberGenerator;
+
+    /// Returns the moves leading from the root to the node at `index` of the snapshot.
+    fn snapshot_line(snapshot: &TreeSnapshot<u8>, index: usize) -> Vec<u8> {
+        let mut line = Vec::new();
+        let mut index = index;
+        while index != 0 {
+            line.push(snapshot.nodes[index].prev_move.unwrap());
+            index = snapshot
+                .nodes
+                .iter()
+                .position(|x| x.children.contains(&index))
+                .unwrap();
+        }
+        line.reverse();
+        line
+    }
+
+    fn searched(iterations: u32) -> MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator> {
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(iterations);
+        mcts
+    }
+
+    #[test]
+    fn snapshot_round_trips() {
+        let snapshot = searched(2000).to_snapshot();
+        assert_eq!(snapshot.version, FORMAT_VERSION);
+        assert_eq!(snapshot.nodes[0].visits, 2000);
+
+        let mut restored = searched(0);
+        restored.load_snapshot(snapshot.clone()).unwrap();
+        assert_eq!(restored.to_snapshot(), snapshot);
+        assert_eq!(
+            restored.get_tree().root().descendants().count(),
+            snapshot.nodes.len()
+        );
+    }
+
+    #[test]
+    fn restored_search_can_continue() {
+        let mut restored = searched(0);
+        restored.load_snapshot(searched(500).to_snapshot()).unwrap();
+
+        restored.iterate_n_times(100);
+        assert_eq!(restored.get_root().value().stats.visits, 600);
+    }
+
+    #[test]
+    fn rejects_invalid_snapshots() {
+        let snapshot = searched(100).to_snapshot();
+        let mut target = searched(0);
+
+        let mut wrong_version = snapshot.clone();
+        wrong_version.version = FORMAT_VERSION + 1;
+        assert_eq!(
+            target.load_snapshot(wrong_version),
+            Err(InterchangeError::UnsupportedVersion(FORMAT_VERSION + 1))
+        );
+
+        let mut cyclic = snapshot.clone();
+        cyclic.nodes[1].children.push(0);
+        assert_eq!(
+            target.load_snapshot(cyclic),
+            Err(InterchangeError::InvalidChildIndex {
+                parent: 1,
+                child: 0
+            })
+        );
+
+        let mut wrong_state = snapshot.clone();
+        let first_child = wrong_state.nodes[0].children[0];
+        wrong_state.nodes[first_child].current_player = Player::Me;
+        assert_eq!(
+            target.load_snapshot(wrong_state),
+            Err(InterchangeError::StateMismatch(first_child))
+        );
+
+        assert_eq!(target.get_tree().root().descendants().count(), 1);
+    }
+
+    #[test]
+    fn filtered_snapshot_is_a_valid_prefix() {
+        let mcts = searched(2000);
+        let filter = SnapshotFilter {
+            max_depth: Some(2),
+            min_visits: 20,
+        };
+        let snapshot = mcts.to_filtered_snapshot(&filter);
+
+        assert_eq!(snapshot.nodes[0].visits, 2000);
+        assert!(snapshot.nodes.len() < mcts.to_snapshot().nodes.len());
+        assert!(snapshot.nodes.iter().all(|x| x.height <= 2));
+        assert!(snapshot.nodes.iter().all(|x| x.visits >= 20));
+        assert!(snapshot.nodes.iter().any(|x| x.height == 2));
+        assert!(
+            snapshot
+                .nodes
+                .iter()
+                .any(|x| !x.missing_children.is_empty())
+        );
+
+        let mut restored = searched(0);
+        restored.load_snapshot(snapshot.clone()).unwrap();
+        assert_eq!(restored.to_snapshot(), snapshot);
+    }
+
+    #[test]
+    fn restored_search_reaches_the_filtered_out_moves() {
+        let filter = SnapshotFilter {
+            max_depth: None,
+            min_visits: 200,
+        };
+        let snapshot = searched(2000).to_filtered_snapshot(&filter);
+        assert!(snapshot.nodes[0].children.len() < 9);
+
+        let mut restored = searched(0);
+        restored.load_snapshot(snapshot).unwrap();
+        restored.iterate_n_times(20000);
+
+        let root = restored.get_root();
+        let mut moves: Vec<_> = root
+            .children()
+            .map(|x| *x.value().prev_move().unwrap())
+            .collect();
+        moves.sort();
+        assert_eq!(moves, (0..9).collect::<Vec<u8>>());
+        assert!(root.children().all(|x| x.value().stats.visits > 0));
+    }
+
+    #[test]
+    fn restored_search_tries_the_untried_moves() {
+        let search = || {
+            MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_expansion_mode(ExpansionMode::SingleChild)
+                .build()
+        };
+        let mut mcts = search();
+        mcts.iterate_n_times(3);
+        let snapshot = mcts.to_snapshot();
+        assert_eq!(snapshot.nodes[0].untried_moves, Some(3));
+
+        let mut restored = search();
+        restored.load_snapshot(snapshot.clone()).unwrap();
+        assert_eq!(restored.to_snapshot(), snapshot);
+
+        restored.iterate_n_times(20000);
+        let root = restored.get_root();
+        let mut moves: Vec<_> = root
+            .children()
+            .map(|x| *x.value().prev_move().unwrap())
+            .collect();
+        moves.sort();
+        assert_eq!(moves, (0..9).collect::<Vec<u8>>());
+        assert!(root.children().all(|x| x.value().stats.visits > 0));
+    }
+
+    #[test]
+    fn restored_search_keeps_the_max_values() {
+        let search = || {
+            MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_backup_operator(BackupOperator::Max)
+                .build()
+        };
+        let mut mcts = search();
+        mcts.iterate_n_times(300);
+        let snapshot = mcts.to_snapshot();
+        assert!(snapshot.nodes[0].max_value.is_some());
+
+        let mut restored = search();
+        restored.load_snapshot(snapshot.clone()).unwrap();
+        assert_eq!(restored.to_snapshot(), snapshot);
+        let stats = |mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>| {
+            let root = mcts.get_root();
+            root.children()
+                .map(|x| x.value().stats.max_value)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(stats(&restored), stats(&mcts));
+    }
+
+    #[test]
+    fn restored_search_recreates_collected_children() {
+        let mut mcts = searched(300);
+        assert!(mcts.collect_garbage(5) > 0);
+        let snapshot = mcts.to_snapshot();
+        let collected = snapshot
+            .nodes
+            .iter()
+            .position(|x| !x.missing_children.is_empty())
+            .unwrap();
+        let line = snapshot_line(&snapshot, collected);
+
+        let mut restored = searched(0);
+        restored.load_snapshot(snapshot.clone()).unwrap();
+        assert_eq!(restored.to_snapshot(), snapshot);
+
+        restored.iterate_n_times(20000);
+        let node = restored.find_node(&line).unwrap();
+        let node = restored.get_tree().get(node).unwrap();
+        let expected = node.value().board().unwrap().get_available_moves().len();
+        assert_eq!(node.children().count(), expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trips() {
+        let snapshot = searched(300).to_snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let parsed: crate::interchange::TreeSnapshot<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, snapshot);
+    }
+}
@@ -4,13 +4,107 @@
 //! MCTS is a heuristic search algorithm used in decision-making processes, most notably in game AI.
 //! The library is designed to be flexible and adaptable to various turn-based games.
 
+/// Contains the `Agent` trait for players and the `GameRunner` playing games between them.
+pub mod agent;
+/// Contains the per-move statistics of a search and their export as CSV or TSV tables.
+pub mod analysis;
+/// Contains the `Arena` playing matches between differently configured searches.
+pub mod arena;
+/// Contains the `search_async` wrapper running the search on a background task.
+#[cfg(feature = "async")]
+pub mod async_search;
+/// Contains the batch analysis of many independent positions, optionally on several threads.
+pub mod batch;
+/// Contains the `bench` method measuring search throughput; `wasm32-unknown-unknown` lacks a clock.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub mod bench;
 /// Contains the `Board` trait and related enums that define the interface for a game.
 pub mod board;
 /// Contains pre-made implementations of the `Board` trait for common games.
 pub mod boards;
+/// Contains the `OpeningBook` trait and a hash-keyed book built from self-play games.
+pub mod book;
+/// Contains types describing how the search budget is spread over the root's subtrees.
+pub mod budget;
+/// Contains the binary checkpoints saving a search to disk and resuming it.
+#[cfg(feature = "checkpoint")]
+pub mod checkpoint;
+/// Contains the stopwatch used to measure the time spent in the search.
+mod clock;
+/// Contains the `MctsConfig` gathering the tuning knobs of a search, loadable from config files.
+pub mod config;
+/// Contains the `BestChildCriterion` strategies for choosing the final move.
+pub mod criterion;
+/// Contains the `Ensemble` combining the recommendations of several independent searches.
+pub mod ensemble;
+/// Contains the `Evaluator` trait supplying move priors and position values, e.g. from a network.
+pub mod evaluator;
+/// Contains exporters that render the search tree in formats understood by external tools.
+pub mod export;
+/// Contains the `MoveFilter` trait used to restrict the moves searched from the root.
+pub mod filter;
+/// Contains `Arbitrary` implementations of the bundled boards and a driver fuzzing the search
+/// against any board.
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+/// Contains a loop for playing a game against an agent in a terminal.
+pub mod game_loop;
+/// Contains the Gumbel root selection with Sequential Halving for searches with tiny budgets.
+pub mod gumbel;
+/// Contains the versioned interchange format for exporting and importing search trees.
+pub mod interchange;
 /// The core module of the library, containing the `MonteCarloTreeSearch` implementation.
 pub mod mcts;
 /// Contains the `MctsNode` struct, which represents a node in the search tree.
 pub mod mcts_node;
+/// Contains the `Metrics` trait receiving the counters of a search for export to monitoring.
+pub mod metrics;
+/// Contains the `BoardNotation` trait for reading and writing positions and moves as text.
+pub mod notation;
+/// Contains the callback type and report used to observe the progress of a search.
+pub mod observer;
+/// Contains an `Evaluator` running ONNX policy/value models with ONNX Runtime.
+#[cfg(feature = "onnx")]
+pub mod onnx;
+/// Contains the `TerminalOracle` trait declaring positions exactly solved, e.g. by a tablebase.
+pub mod oracle;
+/// Re-exports the types most integrations need, for use with a glob import.
+pub mod prelude;
+/// Contains a proof-number search proving wins and losses of promising nodes during a search.
+pub mod proof_number;
+/// Contains adapters exposing the search through standard engine protocols such as UCI and GTP.
+pub mod protocols;
 /// Contains traits and implementations for random number generation.
 pub mod random;
+/// Contains Elo estimates and the sequential probability ratio test for arena results.
+pub mod rating;
+/// Contains the `SearchResult` summary of a search and the comparison of two results.
+pub mod result;
+/// Contains the temperature-based sampling of root moves from their visit counts.
+pub mod sampling;
+/// Contains move filters written in the embedded Rhai scripting language.
+#[cfg(feature = "scripting")]
+pub mod scripting;
+/// Contains the self-play generation of training data and its compact file format.
+pub mod selfplay;
+/// Contains the exact solver computing the game-theoretic value and an optimal strategy.
+pub mod solver;
+/// Contains the `SearchStatistics` struct describing the size and progress of a search.
+pub mod statistics;
+/// Contains the `StopCondition` trait used to interrupt a running search.
+pub mod stop;
+/// Contains a WebSocket feed streaming the growth of the search tree as JSON.
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+/// Contains helpers for writing deterministic regression tests of the search on custom boards.
+pub mod testing;
+/// Contains the `ActionTrace` recording the actions executed by the search.
+pub mod trace;
+/// Contains a ratatui viewer drawing the top of the search tree live during a search.
+#[cfg(feature = "tui")]
+pub mod tui;
+/// Contains an exhaustive alpha-beta search solving tiny games exactly, for cross-checking the
+/// search.
+pub mod verify;
+/// Contains the `RootEstimate` seeding the children of the root with external estimates.
+pub mod warm_start;
@@ -1,9 +1,31 @@
-use crate::board::{Board, Bound, GameOutcome, Player};
-use crate::mcts_node::MctsNode;
-use crate::random::{RandomGenerator, StandardRandomGenerator};
+use crate::board::{Board, Bound, GameOutcome, MoveList, Player};
+use crate::budget::{BudgetReport, SubtreeBudget};
+use crate::clock::Stopwatch;
+use crate::config::MctsConfig;
+use crate::criterion::BestChildCriterion;
+use crate::evaluator::Evaluator;
+use crate::filter::MoveFilter;
+use crate::mcts_node::{MctsNode, NodeStats};
+use crate::metrics::{IterationMetrics, Metrics};
+use crate::observer::{IterationObserver, IterationReport};
+use crate::oracle::TerminalOracle;
+use crate::proof_number::{self, ProofNumberSearch};
+use crate::random::RandomGenerator;
+#[cfg(feature = "rand")]
+use crate::random::StandardRandomGenerator;
+use crate::statistics::{MemoryReport, SearchStatistics};
+use crate::stop::StopCondition;
+use crate::trace::ActionTrace;
 use ego_tree::{NodeId, NodeRef, Tree};
-use std::collections::HashSet;
+use std::any::Any;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+/// The weight of the exploration term of UCB1 unless configured otherwise.
+pub const DEFAULT_EXPLORATION_CONSTANT: f64 = std::f64::consts::SQRT_2;
 
 /// The main struct for running the Monte Carlo Tree Search algorithm.
 ///
@@ -11,9 +33,242 @@ use std::ops::{Deref, DerefMut};
 pub struct MonteCarloTreeSearch<T: Board, K: RandomGenerator> {
     tree: Tree<MctsNode<T>>,
     root_id: NodeId,
+    next_node_id: i32,
     random: K,
     use_alpha_beta_pruning: bool,
+    board_storage: BoardStorage,
     next_action: MctsAction,
+    subtree_budgets: HashMap<NodeId, SubtreeBudget>,
+    last_playout_length: u64,
+    visited_states: HashMap<u128, u64>,
+    hash_width: HashWidth,
+    verify_hashes: bool,
+    hash_collisions: u64,
+    iterations: u64,
+    elapsed: Duration,
+    selected_leaf: NodeId,
+    iteration_observer: Option<IterationObserver>,
+    stop_condition: Option<Box<dyn StopCondition>>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    root_move_filter: Option<Box<dyn MoveFilter<T>>>,
+    terminal_oracle: Option<Box<dyn TerminalOracle<T>>>,
+    best_child_criterion: BestChildCriterion,
+    prune_proven_subtrees: bool,
+    pruned_nodes: usize,
+    draw_reward: f64,
+    contempt: f64,
+    action_trace: Option<ActionTrace>,
+    metrics: Option<Box<dyn Metrics>>,
+    implicit_minimax_weight: f64,
+    reward_discount: f64,
+    progressive_unpruning: Option<ProgressiveUnpruning>,
+    exploration_constant: f64,
+    selection_policy: SelectionPolicy,
+    backup_operator: BackupOperator,
+    proof_number_search: Option<ProofNumberSearch>,
+    max_nodes: Option<usize>,
+    temperature: f64,
+    garbage_collection: Option<GarbageCollection>,
+    collected_nodes: HashMap<NodeId, Vec<usize>>,
+    expansion_mode: ExpansionMode,
+    untried_moves: HashMap<NodeId, usize>,
+    cycle_policy: CyclePolicy,
+    last_nodes_created: usize,
+    newly_proven_nodes: usize,
+    forced_root_child: Option<NodeId>,
+}
+
+/// The configuration and counters of a search, saved in checkpoints alongside its tree.
+///
+/// The board storage is left out, as it only decides how much memory the tree takes.
+#[cfg(feature = "checkpoint")]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct SearchState {
+    use_alpha_beta_pruning: bool,
+    hash_width: HashWidth,
+    verify_hashes: bool,
+    best_child_criterion: BestChildCriterion,
+    prune_proven_subtrees: bool,
+    draw_reward: f64,
+    contempt: f64,
+    implicit_minimax_weight: f64,
+    reward_discount: f64,
+    progressive_unpruning: Option<ProgressiveUnpruning>,
+    exploration_constant: f64,
+    selection_policy: SelectionPolicy,
+    backup_operator: BackupOperator,
+    proof_number_search: Option<ProofNumberSearch>,
+    max_nodes: Option<usize>,
+    temperature: f64,
+    garbage_collection: Option<GarbageCollection>,
+    expansion_mode: ExpansionMode,
+    cycle_policy: CyclePolicy,
+    next_node_id: i32,
+    iterations: u64,
+    elapsed: Duration,
+    hash_collisions: u64,
+    pruned_nodes: usize,
+}
+
+/// Controls which nodes of the search tree keep their own copy of the game state.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoardStorage {
+    /// Every node stores its board. Fastest, but memory grows with the size of the board type.
+    #[default]
+    EveryNode,
+    /// Only the root stores its board. The state of any other node is rebuilt by replaying the
+    /// moves leading to it, trading time per iteration for memory.
+    RootOnly,
+}
+
+/// Controls how many children a leaf gets when selection reaches it.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExpansionMode {
+    /// A leaf gets a child for every available move at once.
+    #[default]
+    AllChildren,
+    /// A node gets one child per visit, for its next untried move, and selection descends into
+    /// its children only once every move has been tried, as in classic UCT. Avoids creating
+    /// hundreds of nodes at once in wide games, most of which would never be visited.
+    SingleChild,
+}
+
+/// Selects the hash of the board used to identify positions.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HashWidth {
+    /// Uses `Board::get_hash`.
+    #[default]
+    Bits128,
+    /// Uses `Board::get_hash64`, which is cheaper for some boards but collides more often.
+    Bits64,
+}
+
+/// Controls how simulations deal with positions repeating within a playout.
+///
+/// The policies that avoid repetitions call `Board::get_hash` after every move of a playout; the
+/// others never hash, which suits boards that cannot hash cheaply.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CyclePolicy {
+    /// Plays random moves until the game ends, so games that can cycle forever never finish.
+    Off,
+    /// Never moves to a position already seen in the playout, and ends it as a draw once every
+    /// move leads to such a position.
+    #[default]
+    TreatAsDraw,
+    /// Like `TreatAsDraw`, but ends the playout as a loss.
+    TreatAsLoss,
+    /// Plays random moves without tracking positions, ending the playout as a draw after the
+    /// given number of moves.
+    MaxPlayoutLength(u64),
+}
+
+/// Controls how selection rates the children of a node.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelectionPolicy {
+    /// Picks the child with the highest UCB1 value, trying every child once first.
+    #[default]
+    Ucb1,
+    /// Like `Ucb1`, but scales the exploration term of every child by an upper bound of the
+    /// variance of its values, so children whose values barely vary are explored less.
+    Ucb1Tuned,
+    /// Keeps a Beta posterior over the win probability of every child, starting from a uniform
+    /// prior and counting backed-up values as successes, and picks the child with the highest
+    /// win probability sampled from its posterior. Explores in proportion to how likely a child
+    /// is to be the best, which can beat UCB1 when rewards are noisy, e.g. in stochastic games.
+    Thompson,
+}
+
+/// Controls how the values of simulations are backed up through the tree for selection.
+///
+/// Every node keeps the mean of the values backed up through it whatever the operator, and the
+/// best move is still chosen with the `BestChildCriterion`; the operator only changes the value
+/// selection rates a node by.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BackupOperator {
+    /// Rates a node by the mean of the values backed up through it, the classic rule.
+    #[default]
+    Average,
+    /// Rates a node by the value of its best visited child, the highest when the searching
+    /// player is to move and the lowest otherwise, and a node without visited children by its
+    /// mean. Reacts at once to a refutation found deep in the tree, which helps in tactical
+    /// games, but is noisy while the children have few visits.
+    Max,
+    /// Blends the two, weighting the max backup of a node with `n` visits by
+    /// `n / (n + threshold)`, so nodes are rated by their mean while they have few visits and
+    /// increasingly by their best child as they gather more.
+    Mixed {
+        /// The number of visits at which the mean and the max backup weigh the same.
+        threshold: f64,
+    },
+}
+
+/// Controls how many children of a node selection considers with progressive unpruning.
+///
+/// A node with `n` visits has `initial_children` unpruned children, plus one more for every
+/// threshold `threshold * growth^i` that `n` has reached. Children that are fully calculated do
+/// not count, so solving an unpruned child also reintroduces the next one. This makes games with
+/// a huge branching factor, such as 19x19 Go, searchable by focusing on the moves with the best
+/// priors first.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProgressiveUnpruning {
+    /// The number of children considered before the first threshold is reached.
+    pub initial_children: usize,
+    /// The number of visits at which the first pruned child is reintroduced.
+    pub threshold: f64,
+    /// The factor by which every following threshold grows.
+    pub growth: f64,
+}
+
+impl Default for ProgressiveUnpruning {
+    /// Returns 5 initial children, with thresholds at `40 * 1.4^i` visits.
+    fn default() -> Self {
+        Self {
+            initial_children: 5,
+            threshold: 40.0,
+            growth: 1.4,
+        }
+    }
+}
+
+impl ProgressiveUnpruning {
+    /// Returns the number of children considered by selection at a node with the given visits.
+    pub fn unpruned_children(&self, visits: u32) -> usize {
+        let visits = visits as f64;
+        if visits < self.threshold {
+            return self.initial_children;
+        }
+
+        let reached = (visits / self.threshold).ln() / self.growth.ln();
+        self.initial_children + reached.floor() as usize + 1
+    }
+}
+
+/// Configures the periodic garbage collection dropping the never visited children of heavily
+/// visited nodes, see `MonteCarloTreeSearchBuilder::with_garbage_collection`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GarbageCollection {
+    /// The number of visits from which the unvisited children of a node are dropped.
+    pub min_visits: u32,
+    /// The number of iterations between two collections.
+    pub interval: u64,
+}
+
+impl Default for GarbageCollection {
+    /// Returns a collection every 10000 iterations, of the nodes with at least 1000 visits.
+    fn default() -> Self {
+        Self {
+            min_visits: 1000,
+            interval: 10000,
+        }
+    }
 }
 
 /// A builder for creating instances of `MonteCarloTreeSearch`.
@@ -23,6 +278,31 @@ pub struct MonteCarloTreeSearchBuilder<T: Board, K: RandomGenerator> {
     board: T,
     random_generator: K,
     use_alpha_beta_pruning: bool,
+    board_storage: BoardStorage,
+    iteration_observer: Option<IterationObserver>,
+    stop_condition: Option<Box<dyn StopCondition>>,
+    root_move_filter: Option<Box<dyn MoveFilter<T>>>,
+    terminal_oracle: Option<Box<dyn TerminalOracle<T>>>,
+    best_child_criterion: BestChildCriterion,
+    prune_proven_subtrees: bool,
+    draw_reward: f64,
+    contempt: f64,
+    record_actions: bool,
+    metrics: Option<Box<dyn Metrics>>,
+    implicit_minimax_weight: f64,
+    reward_discount: f64,
+    progressive_unpruning: Option<ProgressiveUnpruning>,
+    exploration_constant: f64,
+    selection_policy: SelectionPolicy,
+    backup_operator: BackupOperator,
+    proof_number_search: Option<ProofNumberSearch>,
+    max_nodes: Option<usize>,
+    temperature: f64,
+    garbage_collection: Option<GarbageCollection>,
+    expansion_mode: ExpansionMode,
+    cycle_policy: CyclePolicy,
+    hash_width: HashWidth,
+    verify_hashes: bool,
 }
 
 impl<T: Board, K: RandomGenerator> MonteCarloTreeSearchBuilder<T, K> {
@@ -32,6 +312,31 @@ impl<T: Board, K: RandomGenerator> MonteCarloTreeSearchBuilder<T, K> {
             board,
             random_generator: K::default(),
             use_alpha_beta_pruning: true,
+            board_storage: BoardStorage::default(),
+            iteration_observer: None,
+            stop_condition: None,
+            root_move_filter: None,
+            terminal_oracle: None,
+            best_child_criterion: BestChildCriterion::default(),
+            prune_proven_subtrees: false,
+            draw_reward: 0.0,
+            contempt: 0.0,
+            record_actions: false,
+            metrics: None,
+            implicit_minimax_weight: 0.0,
+            reward_discount: 1.0,
+            progressive_unpruning: None,
+            exploration_constant: DEFAULT_EXPLORATION_CONSTANT,
+            selection_policy: SelectionPolicy::default(),
+            backup_operator: BackupOperator::default(),
+            proof_number_search: None,
+            max_nodes: None,
+            temperature: 0.0,
+            garbage_collection: None,
+            expansion_mode: ExpansionMode::default(),
+            cycle_policy: CyclePolicy::default(),
+            hash_width: HashWidth::default(),
+            verify_hashes: false,
         }
     }
 
@@ -47,13 +352,265 @@ impl<T: Board, K: RandomGenerator> MonteCarloTreeSearchBuilder<T, K> {
         self
     }
 
+    /// Sets which nodes keep their own copy of the game state.
+    pub fn with_board_storage(mut self, board_storage: BoardStorage) -> Self {
+        self.board_storage = board_storage;
+        self
+    }
+
+    /// Sets how many children a leaf gets when selection reaches it.
+    pub fn with_expansion_mode(mut self, expansion_mode: ExpansionMode) -> Self {
+        self.expansion_mode = expansion_mode;
+        self
+    }
+
+    /// Sets how simulations deal with positions repeating within a playout.
+    pub fn with_cycle_policy(mut self, cycle_policy: CyclePolicy) -> Self {
+        self.cycle_policy = cycle_policy;
+        self
+    }
+
+    /// Sets which hash of the board identifies the positions seen in a playout.
+    pub fn with_hash_width(mut self, hash_width: HashWidth) -> Self {
+        self.hash_width = hash_width;
+        self
+    }
+
+    /// Enables or disables verifying hash hits in playouts with `Board::get_fingerprint`.
+    ///
+    /// A position whose hash was seen before but whose fingerprint differs is counted as a
+    /// collision, see `SearchStatistics::hash_collisions`, and treated as a new position. Meant for
+    /// debugging hash functions, as it computes a fingerprint for every position of a playout.
+    pub fn with_hash_verification(mut self, verify_hashes: bool) -> Self {
+        self.verify_hashes = verify_hashes;
+        self
+    }
+
+    /// Sets a callback invoked after every completed iteration.
+    pub fn with_iteration_observer(
+        mut self,
+        observer: impl FnMut(&IterationReport) + Send + 'static,
+    ) -> Self {
+        self.iteration_observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Sets a sink receiving the counters of every completed iteration.
+    pub fn with_metrics(mut self, metrics: impl Metrics + 'static) -> Self {
+        self.metrics = Some(Box::new(metrics));
+        self
+    }
+
+    /// Enables or disables recording every executed action into an `ActionTrace`, see
+    /// `MonteCarloTreeSearch::get_action_trace`.
+    pub fn with_action_trace(mut self, record_actions: bool) -> Self {
+        self.record_actions = record_actions;
+        self
+    }
+
+    /// Sets a condition that interrupts the search when it holds, e.g. an `Arc<AtomicBool>`.
+    pub fn with_stop_condition(mut self, stop_condition: impl StopCondition + 'static) -> Self {
+        self.stop_condition = Some(Box::new(stop_condition));
+        self
+    }
+
+    /// Sets a filter restricting the moves searched from the root.
+    pub fn with_root_move_filter(mut self, filter: impl MoveFilter<T> + 'static) -> Self {
+        self.root_move_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Sets an oracle declaring positions exactly solved, see `TerminalOracle`.
+    pub fn with_terminal_oracle(mut self, oracle: impl TerminalOracle<T> + 'static) -> Self {
+        self.terminal_oracle = Some(Box::new(oracle));
+        self
+    }
+
+    /// Sets how the best move is chosen once the search is over.
+    pub fn with_best_child_criterion(mut self, criterion: BestChildCriterion) -> Self {
+        self.best_child_criterion = criterion;
+        self
+    }
+
+    /// Sets the value a drawn simulation backs up, from `0.0` (a draw is worth as much as a loss,
+    /// the default) to `1.0` (as much as a win).
+    ///
+    /// The value is used both during selection and when choosing the best move, so a value such as
+    /// `0.5` lets the search prefer a safe draw to a risky line in games like Tic-Tac-Toe or chess.
+    pub fn with_draw_reward(mut self, draw_reward: f64) -> Self {
+        self.draw_reward = draw_reward;
+        self
+    }
+
+    /// Sets how much less than the draw reward a draw is worth to the search, e.g. `0.2` when it
+    /// plays a weaker opponent and should avoid draws, or a negative value when it should seek
+    /// them. The resulting value of a draw is clamped to `[0, 1]`.
+    pub fn with_contempt(mut self, contempt: f64) -> Self {
+        self.contempt = contempt;
+        self
+    }
+
+    /// Enables implicit minimax backups, blending the minimax value of heuristic estimates into
+    /// the value of every child during selection with the given weight.
+    ///
+    /// Newly expanded nodes are valued with `Board::get_heuristic_value`, or with their outcome
+    /// if the game is over, and these values are backed up with minimax alongside the averages of
+    /// the simulations. Selection then uses `(1 - weight) * average + weight * minimax`, which
+    /// helps in games with sharp tactical lines that averaging misses. A weight of `0.0`, the
+    /// default, disables it.
+    pub fn with_implicit_minimax(mut self, weight: f64) -> Self {
+        self.implicit_minimax_weight = weight;
+        self
+    }
+
+    /// Sets the factor by which backed-up values are discounted for every move between the root
+    /// and the end of the game, from `0.0` to `1.0` (no discount, the default).
+    ///
+    /// A value reached `d` moves below the root is backed up as `0.5 + (value - 0.5) * discount^d`,
+    /// so among otherwise equal lines the search prefers faster wins and slower losses.
+    pub fn with_reward_discount(mut self, discount: f64) -> Self {
+        self.reward_discount = discount;
+        self
+    }
+
+    /// Enables progressive unpruning: the children of a node are ordered by
+    /// `Board::get_move_prior`, and selection only considers the best few of them, reintroducing
+    /// the others as the node gathers visits. See `ProgressiveUnpruning`.
+    pub fn with_progressive_unpruning(mut self, unpruning: ProgressiveUnpruning) -> Self {
+        self.progressive_unpruning = Some(unpruning);
+        self
+    }
+
+    /// Sets the weight of the exploration term of UCB1, `sqrt(2)` by default. Larger values
+    /// spread the visits more evenly over the children, smaller ones focus them on the best.
+    pub fn with_exploration_constant(mut self, exploration_constant: f64) -> Self {
+        self.exploration_constant = exploration_constant;
+        self
+    }
+
+    /// Sets how selection rates the children of a node, see `SelectionPolicy`.
+    pub fn with_selection_policy(mut self, selection_policy: SelectionPolicy) -> Self {
+        self.selection_policy = selection_policy;
+        self
+    }
+
+    /// Sets how the values of simulations are backed up for selection, see `BackupOperator`.
+    pub fn with_backup_operator(mut self, backup_operator: BackupOperator) -> Self {
+        self.backup_operator = backup_operator;
+        self
+    }
+
+    /// Runs a proof-number search on every node reaching the configured number of visits, turning
+    /// proven wins and losses into bounds. Has no effect without alpha-beta pruning. See
+    /// `ProofNumberSearch`.
+    pub fn with_proof_number_search(mut self, proof_number_search: ProofNumberSearch) -> Self {
+        self.proof_number_search = Some(proof_number_search);
+        self
+    }
+
+    /// Caps the number of nodes in the tree. Once the tree holds `max_nodes` nodes, leaves are no
+    /// longer expanded and iterations simulate from the selected leaf instead, so the search goes
+    /// on within a fixed amount of memory. The expansion reaching the cap may exceed it by the
+    /// children of one node.
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Sets the temperature `MonteCarloTreeSearch::choose_final_move` samples the played move
+    /// with. The default of `0.0` plays the best child by the best child criterion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `temperature` is negative or NaN.
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        assert!(temperature >= 0.0, "temperature must not be negative");
+        self.temperature = temperature;
+        self
+    }
+
+    /// Enables a periodic garbage collection dropping the unvisited children of nodes with many
+    /// visits, see `MonteCarloTreeSearch::collect_garbage`.
+    ///
+    /// In wide games most children of a node are never visited again once a sibling is proven or
+    /// clearly best, so collecting them shrinks the tree without changing the search.
+    pub fn with_garbage_collection(mut self, garbage_collection: GarbageCollection) -> Self {
+        self.garbage_collection = Some(garbage_collection);
+        self
+    }
+
+    /// Enables or disables dropping the subtrees of fully calculated nodes.
+    ///
+    /// A fully calculated node is never selected again, so only its own statistics and bound are
+    /// needed. With pruning enabled its children are detached from the tree, and the tree is
+    /// rebuilt without them once they make up half of its storage, which keeps the memory of long
+    /// solver-style searches proportional to the unsolved part of the tree. The children of the
+    /// root are never dropped. Rebuilding the tree changes the ids of its nodes.
+    pub fn with_proven_subtree_pruning(mut self, prune_proven_subtrees: bool) -> Self {
+        self.prune_proven_subtrees = prune_proven_subtrees;
+        self
+    }
+
+    /// Applies every tuning knob of `config`, replacing the values set so far, see `MctsConfig`.
+    pub fn with_config(mut self, config: MctsConfig) -> Self {
+        self.use_alpha_beta_pruning = config.use_alpha_beta_pruning;
+        self.exploration_constant = config.exploration_constant;
+        self.selection_policy = config.selection_policy;
+        self.backup_operator = config.backup_operator;
+        self.best_child_criterion = config.best_child_criterion;
+        self.progressive_unpruning = config.progressive_unpruning;
+        self.proof_number_search = config.proof_number_search;
+        self.max_nodes = config.max_nodes;
+        self.temperature = config.temperature;
+        self.garbage_collection = config.garbage_collection;
+        self.draw_reward = config.draw_reward;
+        self.contempt = config.contempt;
+        self.implicit_minimax_weight = config.implicit_minimax_weight;
+        self.reward_discount = config.reward_discount;
+        self.cycle_policy = config.cycle_policy;
+        self.board_storage = config.board_storage;
+        self.expansion_mode = config.expansion_mode;
+        self.hash_width = config.hash_width;
+        self.verify_hashes = config.verify_hashes;
+        self.prune_proven_subtrees = config.prune_proven_subtrees;
+        self
+    }
+
     /// Builds the `MonteCarloTreeSearch` instance with the configured parameters.
     pub fn build(self) -> MonteCarloTreeSearch<T, K> {
-        MonteCarloTreeSearch::new(
+        let mut mcts = MonteCarloTreeSearch::new(
             self.board,
             self.random_generator,
             self.use_alpha_beta_pruning,
-        )
+        );
+        mcts.board_storage = self.board_storage;
+        mcts.expansion_mode = self.expansion_mode;
+        mcts.iteration_observer = self.iteration_observer;
+        mcts.stop_condition = self.stop_condition;
+        mcts.root_move_filter = self.root_move_filter;
+        mcts.terminal_oracle = self.terminal_oracle;
+        mcts.best_child_criterion = self.best_child_criterion;
+        mcts.prune_proven_subtrees = self.prune_proven_subtrees;
+        mcts.draw_reward = self.draw_reward;
+        mcts.contempt = self.contempt;
+        mcts.metrics = self.metrics;
+        mcts.implicit_minimax_weight = self.implicit_minimax_weight;
+        mcts.reward_discount = self.reward_discount;
+        mcts.progressive_unpruning = self.progressive_unpruning;
+        mcts.exploration_constant = self.exploration_constant;
+        mcts.selection_policy = self.selection_policy;
+        mcts.backup_operator = self.backup_operator;
+        mcts.proof_number_search = self.proof_number_search;
+        mcts.max_nodes = self.max_nodes;
+        mcts.temperature = self.temperature;
+        mcts.garbage_collection = self.garbage_collection;
+        mcts.cycle_policy = self.cycle_policy;
+        mcts.hash_width = self.hash_width;
+        mcts.verify_hashes = self.verify_hashes;
+        if self.record_actions {
+            mcts.set_action_trace(ActionTrace::new());
+        }
+        mcts
     }
 }
 
@@ -67,19 +624,59 @@ impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K> {
     ///
     /// It is recommended to use the builder pattern via `MonteCarloTreeSearch::builder()` instead.
     pub fn new(board: T, rg: K, use_alpha_beta_pruning: bool) -> Self {
-        let root_mcts_node = MctsNode::new(0, Box::new(board));
+        let root_mcts_node = MctsNode::new(0, board);
         let tree: Tree<MctsNode<T>> = Tree::new(root_mcts_node);
         let root_id = tree.root().id();
 
         Self {
             tree,
-            root_id: root_id.clone(),
+            root_id,
+            next_node_id: 1,
             random: rg,
             use_alpha_beta_pruning,
+            board_storage: BoardStorage::default(),
             next_action: MctsAction::Selection {
-                R: root_id.clone(),
+                R: root_id,
                 RP: vec![],
             },
+            subtree_budgets: HashMap::new(),
+            last_playout_length: 0,
+            visited_states: HashMap::new(),
+            hash_width: HashWidth::default(),
+            verify_hashes: false,
+            hash_collisions: 0,
+            iterations: 0,
+            elapsed: Duration::ZERO,
+            selected_leaf: root_id,
+            iteration_observer: None,
+            stop_condition: None,
+            cancel_flag: None,
+            root_move_filter: None,
+            terminal_oracle: None,
+            best_child_criterion: BestChildCriterion::default(),
+            prune_proven_subtrees: false,
+            pruned_nodes: 0,
+            draw_reward: 0.0,
+            contempt: 0.0,
+            action_trace: None,
+            metrics: None,
+            implicit_minimax_weight: 0.0,
+            reward_discount: 1.0,
+            progressive_unpruning: None,
+            exploration_constant: DEFAULT_EXPLORATION_CONSTANT,
+            selection_policy: SelectionPolicy::default(),
+            backup_operator: BackupOperator::default(),
+            proof_number_search: None,
+            max_nodes: None,
+            temperature: 0.0,
+            garbage_collection: None,
+            collected_nodes: HashMap::new(),
+            expansion_mode: ExpansionMode::default(),
+            untried_moves: HashMap::new(),
+            cycle_policy: CyclePolicy::default(),
+            last_nodes_created: 0,
+            newly_proven_nodes: 0,
+            forced_root_child: None,
         }
     }
 
@@ -88,430 +685,3905 @@ impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K> {
         &self.tree
     }
 
-    /// Returns the next MCTS action to be performed. Useful for debugging and visualization.
-    pub fn get_next_mcts_action(&self) -> &MctsAction {
-        &self.next_action
+    /// Returns how the best move is chosen once the search is over.
+    pub fn get_best_child_criterion(&self) -> BestChildCriterion {
+        self.best_child_criterion
     }
 
-    /// Executes a single step of the MCTS algorithm (Selection, Expansion, Simulation, or Backpropagation).
-    pub fn execute_action(&mut self) {
-        match self.next_action.clone() {
-            MctsAction::Selection { R, RP: _cr } => {
-                let maybe_selected_node = self.select_next_node(R);
-                self.next_action = match maybe_selected_node {
-                    None => MctsAction::EverythingIsCalculated,
-                    Some(selected_node) => MctsAction::Expansion { L: selected_node },
-                };
-            }
-            MctsAction::Expansion { L } => {
-                let (children, selected_child) = self.expand_node(L);
-                self.next_action = MctsAction::Simulation {
-                    C: selected_child,
-                    AC: children,
-                };
-            }
-            MctsAction::Simulation { C, AC: _ac } => {
-                let outcome = self.simulate(C);
-                self.next_action = MctsAction::Backpropagation { C, result: outcome };
-            }
-            MctsAction::Backpropagation { C, result } => {
-                let affected_nodes = self.backpropagate(C, result);
-                self.next_action = MctsAction::Selection {
-                    R: self.root_id.clone(),
-                    RP: affected_nodes,
-                }
-            }
-            MctsAction::EverythingIsCalculated => {}
+    /// Returns the tuning knobs of the search, as accepted by
+    /// `MonteCarloTreeSearchBuilder::with_config`.
+    pub fn get_config(&self) -> MctsConfig {
+        MctsConfig {
+            use_alpha_beta_pruning: self.use_alpha_beta_pruning,
+            exploration_constant: self.exploration_constant,
+            selection_policy: self.selection_policy,
+            backup_operator: self.backup_operator,
+            best_child_criterion: self.best_child_criterion,
+            progressive_unpruning: self.progressive_unpruning,
+            proof_number_search: self.proof_number_search,
+            max_nodes: self.max_nodes,
+            temperature: self.temperature,
+            garbage_collection: self.garbage_collection,
+            draw_reward: self.draw_reward,
+            contempt: self.contempt,
+            implicit_minimax_weight: self.implicit_minimax_weight,
+            reward_discount: self.reward_discount,
+            cycle_policy: self.cycle_policy,
+            board_storage: self.board_storage,
+            expansion_mode: self.expansion_mode,
+            hash_width: self.hash_width,
+            verify_hashes: self.verify_hashes,
+            prune_proven_subtrees: self.prune_proven_subtrees,
         }
     }
 
-    /// Performs one full iteration of the MCTS algorithm (Selection, Expansion, Simulation, Backpropagation).
-    /// Returns the path of nodes that were updated during backpropagation.
-    pub fn do_iteration(&mut self) -> Vec<NodeId> {
-        self.execute_action();
-        let mut is_selection = matches!(self.next_action, MctsAction::Selection { R: _, RP: _ });
-        let mut is_fully_calculated =
-            matches!(self.next_action, MctsAction::EverythingIsCalculated);
-        while !is_selection && !is_fully_calculated {
-            self.execute_action();
-            is_selection = matches!(self.next_action, MctsAction::Selection { R: _, RP: _ });
-            is_fully_calculated = matches!(self.next_action, MctsAction::EverythingIsCalculated);
-        }
+    /// Applies every tuning knob of `config` to the following iterations, keeping the tree.
+    ///
+    /// The statistics, proofs and nodes gathered so far are kept as they are: a new draw reward
+    /// only changes the values backed up from now on, and a new board storage or expansion mode
+    /// only the nodes created from now on. Every knob is replaced, so a configuration changing
+    /// only a few of them is best built from `get_config`, e.g. to enable the proof-number search
+    /// once the game reaches its endgame.
+    pub fn set_config(&mut self, config: MctsConfig) {
+        self.use_alpha_beta_pruning = config.use_alpha_beta_pruning;
+        self.exploration_constant = config.exploration_constant;
+        self.selection_policy = config.selection_policy;
+        self.backup_operator = config.backup_operator;
+        self.best_child_criterion = config.best_child_criterion;
+        self.progressive_unpruning = config.progressive_unpruning;
+        self.proof_number_search = config.proof_number_search;
+        self.max_nodes = config.max_nodes;
+        self.temperature = config.temperature;
+        self.garbage_collection = config.garbage_collection;
+        self.draw_reward = config.draw_reward;
+        self.contempt = config.contempt;
+        self.implicit_minimax_weight = config.implicit_minimax_weight;
+        self.reward_discount = config.reward_discount;
+        self.cycle_policy = config.cycle_policy;
+        self.board_storage = config.board_storage;
+        self.expansion_mode = config.expansion_mode;
+        self.hash_width = config.hash_width;
+        self.verify_hashes = config.verify_hashes;
+        self.prune_proven_subtrees = config.prune_proven_subtrees;
+    }
 
-        match self.next_action.clone() {
-            MctsAction::Selection { R: _, RP: rp } => rp,
-            _ => vec![],
-        }
+    /// Returns the weight of the exploration term of UCB1.
+    pub fn get_exploration_constant(&self) -> f64 {
+        self.exploration_constant
     }
 
-    /// Runs the MCTS search for a specified number of iterations.
-    pub fn iterate_n_times(&mut self, n: u32) {
-        let mut iteration = 0;
-        while iteration < n {
-            self.do_iteration();
-            iteration += 1;
-        }
+    /// Returns how selection rates the children of a node.
+    pub fn get_selection_policy(&self) -> SelectionPolicy {
+        self.selection_policy
     }
 
-    /// Returns a reference to the root node of the search tree.
-    pub fn get_root(&self) -> MctsTreeNode<T> {
-        let root = self.tree.root();
-        root.into()
+    /// Returns the value a drawn simulation backs up.
+    pub fn get_draw_reward(&self) -> f64 {
+        self.draw_reward
     }
 
-    /// Selects the most promising node to expand, using the UCB1 formula.
-    fn select_next_node(&self, root_id: NodeId) -> Option<NodeId> {
-        let mut promising_node_id = root_id.clone();
-        let mut has_changed = false;
-        loop {
-            let mut best_child_id: Option<NodeId> = None;
-            let mut max_ucb = f64::MIN;
-            let node = self.tree.get(promising_node_id).unwrap();
-            for child in node.children() {
-                if child.value().is_fully_calculated {
-                    continue;
-                }
+    /// Returns how much less than the draw reward a draw is worth to the search.
+    pub fn get_contempt(&self) -> f64 {
+        self.contempt
+    }
 
-                let current_ucb = MonteCarloTreeSearch::<T, K>::ucb_value(
-                    node.value().visits,
-                    child.value().wins,
-                    child.value().visits,
-                );
-                if current_ucb > max_ucb {
-                    max_ucb = current_ucb;
-                    best_child_id = Some(child.id());
-                }
-            }
-            if best_child_id.is_none() {
-                break;
-            }
-            promising_node_id = best_child_id.unwrap();
-            has_changed = true;
-        }
+    /// Returns the factor by which backed-up values are discounted for every move.
+    pub fn get_reward_discount(&self) -> f64 {
+        self.reward_discount
+    }
 
-        if has_changed {
-            Some(promising_node_id.clone())
-        } else {
-            let root = self.tree.root();
-            if root.children().count() == 0 {
-                Some(root_id.clone())
-            } else {
-                None
-            }
-        }
+    /// Returns which nodes keep their own copy of the game state.
+    pub fn get_board_storage(&self) -> BoardStorage {
+        self.board_storage
     }
 
-    /// Expands a leaf node by creating its children, representing all possible moves from that state.
-    fn expand_node(&mut self, node_id: NodeId) -> (Vec<NodeId>, NodeId) {
-        let node = self.tree.get(node_id).unwrap();
-        if !node.children().count() == 0 {
-            panic!("BUG: expanding already expanded node");
-        }
-        if node.value().outcome != GameOutcome::InProgress {
-            return (vec![], node_id.clone());
-        }
+    /// Returns how many children a leaf gets when selection reaches it.
+    pub fn get_expansion_mode(&self) -> ExpansionMode {
+        self.expansion_mode
+    }
 
-        let children_height = node.value().height + 1;
-        let all_possible_moves = node.value().board.get_available_moves();
-        let mut new_mcts_nodes = Vec::with_capacity(all_possible_moves.len());
+    /// Returns how the values of simulations are backed up for selection.
+    pub fn get_backup_operator(&self) -> BackupOperator {
+        self.backup_operator
+    }
 
-        for possible_move in all_possible_moves {
-            let mut board_clone = node.value().board.clone();
-            board_clone.perform_move(&possible_move);
-            let new_node_id = self.random.next();
-            let mut mcts_node = MctsNode::new(new_node_id, board_clone);
-            mcts_node.prev_move = Some(possible_move);
-            mcts_node.height = children_height;
-            new_mcts_nodes.push(mcts_node);
-        }
+    /// Returns the proof-number search run on promising nodes, if any.
+    pub fn get_proof_number_search(&self) -> Option<ProofNumberSearch> {
+        self.proof_number_search
+    }
 
-        let mut new_node_ids = Vec::with_capacity(new_mcts_nodes.len());
-        for mcts_node in new_mcts_nodes {
-            let mut node = self.tree.get_mut(node_id).unwrap();
-            node.append(mcts_node);
-            new_node_ids.push(node_id.clone());
-        }
+    /// Returns how simulations deal with positions repeating within a playout.
+    pub fn get_cycle_policy(&self) -> CyclePolicy {
+        self.cycle_policy
+    }
 
-        let children: Vec<_> = self.tree.get(node_id).unwrap().children().collect();
-        let selected_child_index = self.random.next_range(0, children.len() as i32) as usize;
-        let selected_child = children[selected_child_index].id();
-        (new_node_ids, selected_child)
+    /// Returns the cap on the number of nodes in the tree, if any.
+    pub fn get_max_nodes(&self) -> Option<usize> {
+        self.max_nodes
     }
 
-    /// Simulates a random playout from a given node until the game ends.
-    fn simulate(&mut self, node_id: NodeId) -> GameOutcome {
-        let node = self.tree.get(node_id).unwrap();
-        let mut board = node.value().board.clone();
-        let mut outcome = board.get_outcome();
-        let mut visited_states = HashSet::new();
-        visited_states.insert(board.get_hash());
+    /// Returns the temperature the final move is sampled with.
+    pub fn get_temperature(&self) -> f64 {
+        self.temperature
+    }
 
-        while outcome == GameOutcome::InProgress {
-            let mut all_possible_moves = board.get_available_moves();
+    /// Returns the periodic garbage collection of unvisited children, if enabled.
+    pub fn get_garbage_collection(&self) -> Option<GarbageCollection> {
+        self.garbage_collection
+    }
 
-            while !all_possible_moves.is_empty() {
-                let random_move_index =
-                    self.random.next_range(0, all_possible_moves.len() as i32) as usize;
-                let random_move = all_possible_moves.get(random_move_index).unwrap();
-                let mut new_board = board.clone();
-                new_board.perform_move(random_move);
-                let new_board_hash = new_board.get_hash();
-                if visited_states.contains(&new_board_hash) {
-                    all_possible_moves.remove(random_move_index);
-                    continue;
-                } else {
-                    visited_states.insert(new_board_hash);
-                    board = new_board;
-                    break;
+    /// Returns a copy of the game state at the given node.
+    ///
+    /// If the node does not store its board, the state is rebuilt by replaying the moves from the
+    /// closest ancestor that does.
+    pub fn get_board(&self, node_id: NodeId) -> T {
+        let mut moves = Vec::new();
+        let mut node = self.tree.get(node_id).unwrap();
+        let mut board = loop {
+            match node.value().board() {
+                Some(board) => break board.clone(),
+                None => {
+                    moves.push(node.value().prev_move().unwrap());
+                    node = node.parent().unwrap();
                 }
             }
+        };
 
-            if all_possible_moves.is_empty() {
-                return GameOutcome::Draw;
-            }
-
-            outcome = board.get_outcome();
+        for b_move in moves.into_iter().rev() {
+            board.perform_move(b_move);
         }
-        outcome
+        board
     }
 
-    /// Propagates the result of a simulation back up the tree, updating node statistics.
-    fn backpropagate(&mut self, node_id: NodeId, outcome: GameOutcome) -> Vec<NodeId> {
-        let mut branch = vec![node_id.clone()];
+    /// Replaces the whole search tree, restarting the state machine at the new root.
+    pub(crate) fn replace_tree(&mut self, tree: Tree<MctsNode<T>>) {
+        let max_node_id = tree.values().map(|x| x.id).max().unwrap_or(0);
+        self.next_node_id = self.next_node_id.max(max_node_id + 1);
+        self.root_id = tree.root().id();
+        self.tree = tree;
+        self.next_action = MctsAction::Selection {
+            R: self.root_id,
+            RP: vec![],
+        };
+        self.subtree_budgets.clear();
+        self.collected_nodes.clear();
+        self.untried_moves.clear();
+        self.pruned_nodes = 0;
+    }
 
-        loop {
-            let temp_node = self.tree.get(*branch.last().unwrap()).unwrap();
-            match temp_node.parent() {
-                None => break,
-                Some(parent) => branch.push(parent.id()),
-            }
+    /// Returns the configuration and counters of the search, as saved in checkpoints.
+    #[cfg(feature = "checkpoint")]
+    pub(crate) fn get_search_state(&self) -> SearchState {
+        SearchState {
+            use_alpha_beta_pruning: self.use_alpha_beta_pruning,
+            hash_width: self.hash_width,
+            verify_hashes: self.verify_hashes,
+            best_child_criterion: self.best_child_criterion,
+            prune_proven_subtrees: self.prune_proven_subtrees,
+            draw_reward: self.draw_reward,
+            contempt: self.contempt,
+            implicit_minimax_weight: self.implicit_minimax_weight,
+            reward_discount: self.reward_discount,
+            progressive_unpruning: self.progressive_unpruning,
+            exploration_constant: self.exploration_constant,
+            selection_policy: self.selection_policy,
+            backup_operator: self.backup_operator,
+            proof_number_search: self.proof_number_search,
+            max_nodes: self.max_nodes,
+            temperature: self.temperature,
+            garbage_collection: self.garbage_collection,
+            expansion_mode: self.expansion_mode,
+            cycle_policy: self.cycle_policy,
+            next_node_id: self.next_node_id,
+            iterations: self.iterations,
+            elapsed: self.elapsed,
+            hash_collisions: self.hash_collisions,
+            pruned_nodes: self.pruned_nodes,
         }
+    }
 
-        let is_win = outcome == GameOutcome::Win;
-        let is_draw = outcome == GameOutcome::Draw;
+    /// Restores the configuration and counters of the search, and its progress through the tree
+    /// set with `replace_tree`.
+    #[cfg(feature = "checkpoint")]
+    pub(crate) fn restore_search_state(
+        &mut self,
+        state: SearchState,
+        next_action: MctsAction,
+        selected_leaf: NodeId,
+        subtree_budgets: HashMap<NodeId, SubtreeBudget>,
+    ) {
+        self.use_alpha_beta_pruning = state.use_alpha_beta_pruning;
+        self.hash_width = state.hash_width;
+        self.verify_hashes = state.verify_hashes;
+        self.best_child_criterion = state.best_child_criterion;
+        self.prune_proven_subtrees = state.prune_proven_subtrees;
+        self.draw_reward = state.draw_reward;
+        self.contempt = state.contempt;
+        self.implicit_minimax_weight = state.implicit_minimax_weight;
+        self.reward_discount = state.reward_discount;
+        self.progressive_unpruning = state.progressive_unpruning;
+        self.exploration_constant = state.exploration_constant;
+        self.selection_policy = state.selection_policy;
+        self.backup_operator = state.backup_operator;
+        self.proof_number_search = state.proof_number_search;
+        self.max_nodes = state.max_nodes;
+        self.temperature = state.temperature;
+        self.garbage_collection = state.garbage_collection;
+        self.expansion_mode = state.expansion_mode;
+        self.cycle_policy = state.cycle_policy;
+        self.next_node_id = state.next_node_id;
+        self.iterations = state.iterations;
+        self.elapsed = state.elapsed;
+        self.hash_collisions = state.hash_collisions;
+        self.pruned_nodes = state.pruned_nodes;
+        self.next_action = next_action;
+        self.selected_leaf = selected_leaf;
+        self.subtree_budgets = subtree_budgets;
+    }
 
-        for node_id in &branch {
-            let bound = self.get_bound(*node_id);
-            let is_fully_calculated = self.is_fully_calculated(*node_id, bound);
-            let mut temp_node = self.tree.get_mut(*node_id).unwrap();
-            let mcts_node = temp_node.value();
-            mcts_node.visits += 1;
-            if is_win {
-                mcts_node.wins += 1;
-            }
+    /// Returns the node selected by the last or current iteration.
+    #[cfg(feature = "checkpoint")]
+    pub(crate) fn get_selected_leaf(&self) -> NodeId {
+        self.selected_leaf
+    }
 
-            if is_draw {
-                mcts_node.draws += 1;
-            }
+    /// Returns the budget spent below every root child.
+    #[cfg(feature = "checkpoint")]
+    pub(crate) fn get_subtree_budgets(&self) -> &HashMap<NodeId, SubtreeBudget> {
+        &self.subtree_budgets
+    }
 
-            if is_fully_calculated {
-                mcts_node.is_fully_calculated = true;
-            }
+    /// Returns the nodes whose unvisited children were dropped by garbage collection, with the
+    /// indices of the dropped children among the children the node was expanded with.
+    pub(crate) fn get_collected_nodes(&self) -> &HashMap<NodeId, Vec<usize>> {
+        &self.collected_nodes
+    }
+
+    /// Records the nodes of a tree just set with `replace_tree` whose children are missing, with
+    /// the indices of the missing children among the children the node is expanded with.
+    pub(crate) fn set_collected_nodes(&mut self, collected_nodes: HashMap<NodeId, Vec<usize>>) {
+        self.collected_nodes = collected_nodes;
+    }
+
+    /// Returns the partially expanded nodes, with the index of their next untried move among the
+    /// moves they are expanded with.
+    pub(crate) fn get_untried_moves(&self) -> &HashMap<NodeId, usize> {
+        &self.untried_moves
+    }
+
+    /// Records the partially expanded nodes of a tree just set with `replace_tree`.
+    pub(crate) fn set_untried_moves(&mut self, untried_moves: HashMap<NodeId, usize>) {
+        self.untried_moves = untried_moves;
+    }
+
+    /// Returns the random generator of the search.
+    #[cfg(feature = "checkpoint")]
+    pub(crate) fn get_random_generator(&self) -> &K {
+        &self.random
+    }
+
+    /// Returns the random generator of the search for sampling outside of the search itself.
+    pub(crate) fn get_random_generator_mut(&mut self) -> &mut K {
+        &mut self.random
+    }
+
+    /// Replaces the random generator of the search.
+    #[cfg(feature = "checkpoint")]
+    pub(crate) fn set_random_generator(&mut self, random: K) {
+        self.random = random;
+    }
+
+    /// Makes selection descend from the root into the given child instead of the one with the
+    /// highest UCB1 value, until it is reset with `None`.
+    pub(crate) fn set_forced_root_child(&mut self, child_id: Option<NodeId>) {
+        self.forced_root_child = child_id;
+    }
+
+    /// Returns a mutable reference to the underlying search tree.
+    pub(crate) fn get_tree_mut(&mut self) -> &mut Tree<MctsNode<T>> {
+        &mut self.tree
+    }
+
+    /// Expands the root if no iteration has done so yet and none is under way, returning whether
+    /// the root has children. A partially expanded root gets its remaining children.
+    pub(crate) fn expand_root(&mut self) -> bool {
+        let is_selection = matches!(self.next_action, MctsAction::Selection { R: _, RP: _ });
+        if is_selection && !self.tree.root().has_children() {
+            self.expand_node(self.root_id);
+        }
+        if is_selection && self.untried_moves.contains_key(&self.root_id) {
+            self.create_children(self.root_id, false);
+        }
+        self.tree.root().has_children()
+    }
+
+    /// Sets a callback invoked after every completed iteration, replacing any previous one.
+    pub fn set_iteration_observer(
+        &mut self,
+        observer: impl FnMut(&IterationReport) + Send + 'static,
+    ) {
+        self.iteration_observer = Some(Box::new(observer));
+    }
+
+    /// Removes the callback set with `set_iteration_observer`, returning it.
+    pub fn take_iteration_observer(&mut self) -> Option<IterationObserver> {
+        self.iteration_observer.take()
+    }
+
+    /// Runs `f` with an extra observer receiving every iteration alongside the one already set,
+    /// which is restored afterwards.
+    #[cfg(any(feature = "telemetry", feature = "tui"))]
+    pub(crate) fn observe_during<R>(
+        &mut self,
+        mut observer: impl FnMut(&IterationReport) + Send + 'static,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let previous = Arc::new(std::sync::Mutex::new(self.iteration_observer.take()));
+        self.set_iteration_observer({
+            let previous = previous.clone();
+            move |report: &IterationReport| {
+                observer(report);
+                if let Some(observer) = previous.lock().unwrap().as_mut() {
+                    observer(report);
+                }
+            }
+        });
+
+        let result = f(self);
+
+        self.iteration_observer = previous.lock().unwrap().take();
+        result
+    }
+
+    /// Runs `f` with the settings of the exact solver, which are restored afterwards: alpha-beta
+    /// pruning enabled, proven subtrees kept, and playouts ending at once as draws, so no random
+    /// moves are played.
+    pub(crate) fn with_solver_settings<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let use_alpha_beta_pruning = std::mem::replace(&mut self.use_alpha_beta_pruning, true);
+        let prune_proven_subtrees = std::mem::replace(&mut self.prune_proven_subtrees, false);
+        let cycle_policy =
+            std::mem::replace(&mut self.cycle_policy, CyclePolicy::MaxPlayoutLength(0));
+
+        let result = f(self);
+
+        self.use_alpha_beta_pruning = use_alpha_beta_pruning;
+        self.prune_proven_subtrees = prune_proven_subtrees;
+        self.cycle_policy = cycle_policy;
+        result
+    }
+
+    /// Sets a sink receiving the counters of every completed iteration, replacing any previous one.
+    pub fn set_metrics(&mut self, metrics: impl Metrics + 'static) {
+        self.metrics = Some(Box::new(metrics));
+    }
+
+    /// Removes the sink set with `set_metrics`, returning it.
+    pub fn take_metrics(&mut self) -> Option<Box<dyn Metrics>> {
+        self.metrics.take()
+    }
+
+    /// Starts recording every executed action into the given trace, replacing any previous one.
+    ///
+    /// The pending action is recorded first, so the trace can be replayed from the current state.
+    pub fn set_action_trace(&mut self, mut trace: ActionTrace) {
+        trace.push(self.next_action.clone());
+        self.action_trace = Some(trace);
+    }
+
+    /// Returns the trace being recorded, if any.
+    pub fn get_action_trace(&self) -> Option<&ActionTrace> {
+        self.action_trace.as_ref()
+    }
+
+    /// Stops recording actions, returning the trace set with `set_action_trace`.
+    pub fn take_action_trace(&mut self) -> Option<ActionTrace> {
+        self.action_trace.take()
+    }
+
+    /// Sets a condition that interrupts the search when it holds, replacing any previous one.
+    pub fn set_stop_condition(&mut self, stop_condition: impl StopCondition + 'static) {
+        self.stop_condition = Some(Box::new(stop_condition));
+    }
+
+    /// Removes the condition set with `set_stop_condition`, returning it.
+    pub fn take_stop_condition(&mut self) -> Option<Box<dyn StopCondition>> {
+        self.stop_condition.take()
+    }
+
+    /// Sets the weight of the exploration term of UCB1 for the following iterations, e.g. to
+    /// explore less as the game approaches its end. The statistics of the tree are kept.
+    pub fn set_exploration_constant(&mut self, exploration_constant: f64) {
+        self.exploration_constant = exploration_constant;
+    }
+
+    /// Sets how selection rates the children of a node for the following iterations.
+    pub fn set_selection_policy(&mut self, selection_policy: SelectionPolicy) {
+        self.selection_policy = selection_policy;
+    }
+
+    /// Sets how the values of simulations are backed up for the following iterations. Nodes
+    /// visited before switching from `BackupOperator::Average` are rated by their average until
+    /// they are visited again.
+    pub fn set_backup_operator(&mut self, backup_operator: BackupOperator) {
+        self.backup_operator = backup_operator;
+    }
+
+    /// Sets how the best move is chosen once the search is over.
+    pub fn set_best_child_criterion(&mut self, criterion: BestChildCriterion) {
+        self.best_child_criterion = criterion;
+    }
+
+    /// Sets the value a drawn simulation backs up for the following iterations. The values
+    /// backed up so far are kept.
+    pub fn set_draw_reward(&mut self, draw_reward: f64) {
+        self.draw_reward = draw_reward;
+    }
+
+    /// Sets how much less than the draw reward a draw is worth for the following iterations.
+    pub fn set_contempt(&mut self, contempt: f64) {
+        self.contempt = contempt;
+    }
+
+    /// Sets the proof-number search run on promising nodes, or disables it with `None`.
+    pub fn set_proof_number_search(&mut self, proof_number_search: Option<ProofNumberSearch>) {
+        self.proof_number_search = proof_number_search;
+    }
+
+    /// Sets the cap on the number of nodes in the tree, or removes it with `None`. Lowering the
+    /// cap below the current size only stops further expansions; no nodes are dropped.
+    pub fn set_max_nodes(&mut self, max_nodes: Option<usize>) {
+        self.max_nodes = max_nodes;
+    }
+
+    /// Sets the temperature `choose_final_move` samples the played move with, e.g. to vary the
+    /// opening moves and play the best ones later on. The search itself is not affected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `temperature` is negative or NaN.
+    pub fn set_temperature(&mut self, temperature: f64) {
+        assert!(temperature >= 0.0, "temperature must not be negative");
+        self.temperature = temperature;
+    }
+
+    /// Sets the periodic garbage collection of unvisited children, or disables it with `None`.
+    /// Children collected so far are still restored when needed.
+    pub fn set_garbage_collection(&mut self, garbage_collection: Option<GarbageCollection>) {
+        self.garbage_collection = garbage_collection;
+    }
+
+    /// Sets a flag that interrupts the search in addition to the stop condition set by the user.
+    #[cfg(feature = "async")]
+    pub(crate) fn set_cancel_flag(&mut self, cancel_flag: Option<Arc<AtomicBool>>) {
+        self.cancel_flag = cancel_flag;
+    }
+
+    /// Checks whether the stop condition currently holds.
+    pub fn is_stop_requested(&self) -> bool {
+        self.cancel_flag.as_ref().is_some_and(|x| x.should_stop())
+            || self
+                .stop_condition
+                .as_ref()
+                .is_some_and(|x| x.should_stop())
+    }
+
+    /// Sets a filter restricting the moves searched from the root, replacing any previous one.
+    ///
+    /// The filter only takes effect when the root is expanded, so it should be set before the
+    /// first iteration.
+    pub fn set_root_move_filter(&mut self, filter: impl MoveFilter<T> + 'static) {
+        self.root_move_filter = Some(Box::new(filter));
+    }
+
+    /// Removes the filter set with `set_root_move_filter`, returning it.
+    pub fn take_root_move_filter(&mut self) -> Option<Box<dyn MoveFilter<T>>> {
+        self.root_move_filter.take()
+    }
+
+    /// Sets an oracle declaring positions exactly solved, replacing any previous one.
+    ///
+    /// Only nodes created and simulations run after this call consult the oracle.
+    pub fn set_terminal_oracle(&mut self, oracle: impl TerminalOracle<T> + 'static) {
+        self.terminal_oracle = Some(Box::new(oracle));
+    }
+
+    /// Removes the oracle set with `set_terminal_oracle`, returning it.
+    pub fn take_terminal_oracle(&mut self) -> Option<Box<dyn TerminalOracle<T>>> {
+        self.terminal_oracle.take()
+    }
+
+    /// Returns the next MCTS action to be performed. Useful for debugging and visualization.
+    pub fn get_next_mcts_action(&self) -> &MctsAction {
+        &self.next_action
+    }
+
+    /// Executes a single step of the MCTS algorithm (Selection, Expansion, Simulation, or Backpropagation).
+    pub fn execute_action(&mut self) {
+        let is_selection = matches!(self.next_action, MctsAction::Selection { R: _, RP: _ });
+        if is_selection && self.pruned_nodes > 0 && self.pruned_nodes * 2 >= self.tree.nodes().len()
+        {
+            self.compact_tree();
+        }
+
+        match self.next_action.clone() {
+            MctsAction::Selection { R, RP: _cr } => {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::trace_span!("selection").entered();
+                let maybe_selected_node = self.select_next_node(R);
+                #[cfg(feature = "tracing")]
+                match &maybe_selected_node {
+                    None => tracing::debug!("tree fully calculated"),
+                    Some((_, path)) => tracing::trace!(depth = path.len(), "selected leaf"),
+                }
+                self.next_action = match maybe_selected_node {
+                    None => MctsAction::EverythingIsCalculated,
+                    Some((selected_node, path)) => MctsAction::Expansion {
+                        L: selected_node,
+                        SP: path,
+                    },
+                };
+            }
+            MctsAction::Expansion { L, SP: _sp } => {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::trace_span!("expansion").entered();
+                self.selected_leaf = L;
+                let (children, selected_child) = self.expand_node(L);
+                self.last_nodes_created = children.len();
+                #[cfg(feature = "tracing")]
+                tracing::trace!(nodes_created = children.len(), "expanded leaf");
+                self.next_action = MctsAction::Simulation {
+                    C: selected_child,
+                    AC: children,
+                };
+            }
+            MctsAction::Simulation { C, AC: _ac } => {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::trace_span!("simulation").entered();
+                let outcome = self.simulate(C);
+                #[cfg(feature = "tracing")]
+                tracing::trace!(
+                    playout_length = self.last_playout_length,
+                    outcome = ?outcome,
+                    "simulated playout"
+                );
+                self.next_action = MctsAction::Backpropagation { C, result: outcome };
+            }
+            MctsAction::Backpropagation { C, result } => {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::trace_span!("backpropagation").entered();
+                let affected_nodes = self.backpropagate(C, result);
+                self.finish_iteration(C, result, affected_nodes);
+            }
+            MctsAction::EverythingIsCalculated => return,
+        }
+
+        if let Some(trace) = self.action_trace.as_mut() {
+            trace.push(self.next_action.clone());
+        }
+    }
+
+    /// Counts a completed iteration whose playout or evaluation started from `simulated_node`,
+    /// collecting garbage and reporting it if configured, and returns to the selection.
+    fn finish_iteration(
+        &mut self,
+        simulated_node: NodeId,
+        outcome: GameOutcome,
+        affected_nodes: Vec<NodeId>,
+    ) {
+        self.iterations += 1;
+        if let Some(x) = self.garbage_collection
+            && self.iterations.is_multiple_of(x.interval)
+        {
+            self.collect_garbage(x.min_visits);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            iteration = self.iterations,
+            path_length = affected_nodes.len(),
+            playout_length = self.last_playout_length,
+            outcome = ?outcome,
+            "completed iteration"
+        );
+        if let Some(observer) = self.iteration_observer.as_mut() {
+            observer(&IterationReport {
+                iteration: self.iterations,
+                selected_leaf: self.selected_leaf,
+                simulated_node,
+                outcome,
+                path: affected_nodes.clone(),
+            });
+        }
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.record(&IterationMetrics {
+                iteration: self.iterations,
+                nodes_created: self.last_nodes_created,
+                playout_length: self.last_playout_length,
+                tree_size: self.tree.nodes().len() - self.pruned_nodes,
+                proven_nodes: self.newly_proven_nodes,
+            });
+        }
+        self.newly_proven_nodes = 0;
+        self.next_action = MctsAction::Selection {
+            R: self.root_id,
+            RP: affected_nodes,
+        };
+    }
+
+    /// Performs one full iteration of the MCTS algorithm (Selection, Expansion, Simulation, Backpropagation).
+    /// Returns the path of nodes that were updated during backpropagation.
+    ///
+    /// The stop condition is checked before every phase. If it holds, the iteration is left
+    /// unfinished, an empty path is returned, and the next call continues where this one stopped.
+    pub fn do_iteration(&mut self) -> Vec<NodeId> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("iteration", iteration = self.iterations + 1).entered();
+        let stopwatch = Stopwatch::start();
+        let mut is_stopped = self.is_stop_requested();
+        if !is_stopped {
+            self.execute_action();
+        }
+        let mut is_selection = matches!(self.next_action, MctsAction::Selection { R: _, RP: _ });
+        let mut is_fully_calculated =
+            matches!(self.next_action, MctsAction::EverythingIsCalculated);
+        while !is_stopped && !is_selection && !is_fully_calculated {
+            is_stopped = self.is_stop_requested();
+            if is_stopped {
+                break;
+            }
+            self.execute_action();
+            is_selection = matches!(self.next_action, MctsAction::Selection { R: _, RP: _ });
+            is_fully_calculated = matches!(self.next_action, MctsAction::EverythingIsCalculated);
+        }
+        self.elapsed += stopwatch.elapsed();
+
+        match self.next_action.clone() {
+            MctsAction::Selection { R: _, RP: rp } if !is_stopped => rp,
+            _ => vec![],
+        }
+    }
+
+    /// Runs the MCTS search for a specified number of iterations.
+    ///
+    /// Returns early if the stop condition holds.
+    pub fn iterate_n_times(&mut self, n: u32) {
+        let mut iteration = 0;
+        while iteration < n && !self.is_stop_requested() {
+            self.do_iteration();
+            iteration += 1;
+        }
+    }
+
+    /// Runs `iterations` iterations that value their leaves with `evaluator` instead of random
+    /// playouts, evaluating up to `batch_size` leaves in one `Evaluator::evaluate_batch` call.
+    ///
+    /// Every batch selects and expands its leaves one after another, each adding a virtual loss,
+    /// a visit without value, along the path to its leaf so that the next selections turn to
+    /// other branches. The evaluations are then backed up in the order of the leaves, replacing
+    /// the virtual losses; leaves whose game is over back up their outcome instead. A leaf
+    /// selected several times in a batch, e.g. at the node cap, is evaluated once and backed up
+    /// every time. A leaf cut off by the proven subtree pruning of an earlier backup is dropped
+    /// and does not count as an iteration.
+    ///
+    /// Returns early if the stop condition holds or the tree is fully calculated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn iterate_batched<E: Evaluator<T>>(
+        &mut self,
+        evaluator: &mut E,
+        batch_size: usize,
+        iterations: u32,
+    ) {
+        assert!(batch_size > 0, "the batch size must be positive");
+        if !matches!(
+            self.next_action,
+            MctsAction::Selection { R: _, RP: _ } | MctsAction::EverythingIsCalculated
+        ) {
+            self.do_iteration();
+        }
+
+        let mut remaining = iterations as usize;
+        while remaining > 0
+            && !matches!(self.next_action, MctsAction::EverythingIsCalculated)
+            && !self.is_stop_requested()
+        {
+            let stopwatch = Stopwatch::start();
+            if self.pruned_nodes > 0 && self.pruned_nodes * 2 >= self.tree.nodes().len() {
+                self.compact_tree();
+            }
+            let mut leaves = Vec::new();
+            while leaves.len() < remaining.min(batch_size)
+                && let Some(leaf) = self.select_pending_leaf()
+            {
+                leaves.push(leaf);
+            }
+
+            let mut seen = HashSet::new();
+            let evaluated: Vec<_> = leaves
+                .iter()
+                .map(|x| x.path[0])
+                .filter(|x| self.tree.get(*x).unwrap().value().outcome == GameOutcome::InProgress)
+                .filter(|x| seen.insert(*x))
+                .collect();
+            let boards: Vec<_> = evaluated.iter().map(|x| self.get_board(*x)).collect();
+            let values: HashMap<_, _> = evaluated
+                .into_iter()
+                .zip(evaluator.evaluate_batch(&boards))
+                .map(|(node_id, evaluation)| {
+                    let value = match self.tree.get(node_id).unwrap().value().current_player {
+                        Player::Me => evaluation.value,
+                        Player::Other => 1.0 - evaluation.value,
+                    };
+                    (node_id, value)
+                })
+                .collect();
+            for leaf in leaves {
+                let value = values.get(&leaf.path[0]).copied();
+                if self.finish_pending_leaf(leaf, value) {
+                    remaining -= 1;
+                }
+            }
+            self.elapsed += stopwatch.elapsed();
+        }
+    }
+
+    /// Prepares the search for latency-sensitive use by running throwaway iterations.
+    ///
+    /// The iterations run on a scratch tree, so the search tree and its statistics are left as they
+    /// were, but the buffers used during simulations keep the capacity they grew to. If the tree
+    /// has not been expanded yet, it is also re-created with room for `node_capacity` nodes.
+    /// Note that the random generator is advanced by the throwaway iterations.
+    pub fn warm_up(&mut self, iterations: u32, node_capacity: usize) {
+        let root_board = self.get_board(self.root_id);
+        let root_id = self.tree.root().value().id;
+        let scratch_tree = Tree::new(MctsNode::new(root_id, root_board.clone()));
+
+        let tree = std::mem::replace(&mut self.tree, scratch_tree);
+        let saved_root_id = std::mem::replace(&mut self.root_id, self.tree.root().id());
+        let next_action = std::mem::replace(
+            &mut self.next_action,
+            MctsAction::Selection {
+                R: self.root_id,
+                RP: vec![],
+            },
+        );
+        let subtree_budgets = std::mem::take(&mut self.subtree_budgets);
+        let collected_nodes = std::mem::take(&mut self.collected_nodes);
+        let untried_moves = std::mem::take(&mut self.untried_moves);
+        let pruned_nodes = std::mem::take(&mut self.pruned_nodes);
+        let (iterations_run, elapsed) = (self.iterations, self.elapsed);
+        let (next_node_id, hash_collisions) = (self.next_node_id, self.hash_collisions);
+        let iteration_observer = self.iteration_observer.take();
+        let action_trace = self.action_trace.take();
+        let metrics = self.metrics.take();
+
+        self.iterate_n_times(iterations);
+
+        self.tree = tree;
+        self.root_id = saved_root_id;
+        self.next_action = next_action;
+        self.subtree_budgets = subtree_budgets;
+        self.collected_nodes = collected_nodes;
+        self.untried_moves = untried_moves;
+        self.pruned_nodes = pruned_nodes;
+        (self.iterations, self.elapsed) = (iterations_run, elapsed);
+        (self.next_node_id, self.hash_collisions) = (next_node_id, hash_collisions);
+        self.iteration_observer = iteration_observer;
+        self.action_trace = action_trace;
+        self.metrics = metrics;
+        self.newly_proven_nodes = 0;
+
+        if !self.tree.root().has_children() {
+            let placeholder = Tree::new(MctsNode::new(root_id, root_board));
+            let tree = std::mem::replace(&mut self.tree, placeholder);
+            let root = tree.into_iter().next().unwrap();
+            self.replace_tree(Tree::with_capacity(root, node_capacity));
+        }
+    }
+
+    /// Attaches an annotation to the node with the given id, returning the previous annotation of
+    /// the same type. See `MctsNode::set_annotation`.
+    pub fn set_annotation<A: Any + Clone + Send>(
+        &mut self,
+        node_id: NodeId,
+        annotation: A,
+    ) -> Option<A> {
+        let mut node = self.tree.get_mut(node_id).unwrap();
+        node.value().set_annotation(annotation)
+    }
+
+    /// Removes the annotation of type `A` from the node with the given id, returning it.
+    pub fn remove_annotation<A: Any>(&mut self, node_id: NodeId) -> Option<A> {
+        let mut node = self.tree.get_mut(node_id).unwrap();
+        node.value().remove_annotation()
+    }
+
+    /// Returns a reference to the root node of the search tree.
+    pub fn get_root(&self) -> MctsTreeNode<'_, T> {
+        let root = self.tree.root();
+        root.into()
+    }
+
+    /// Returns the node the iterations start from, the root of the tree unless set otherwise
+    /// with `set_search_root`.
+    pub fn get_search_root(&self) -> MctsTreeNode<'_, T> {
+        self.tree.get(self.root_id).unwrap().into()
+    }
+
+    /// Points the search at a node of the tree, e.g. to analyze a variation: the following
+    /// iterations select from that node instead of the root, reusing the statistics gathered
+    /// below it so far.
+    ///
+    /// Simulations still back up through the ancestors of the node up to the root, so the tree
+    /// stays consistent and its statistics are not lost once the root is restored with
+    /// `restore_search_root`. An iteration in progress finishes from where it stopped, and the
+    /// next one starts from the node. The search is done once the subtree of the node is fully
+    /// calculated. The root move filter keeps applying to the root of the tree, and rebuilding
+    /// the tree, e.g. for a checkpoint, resets the search root to the root of the tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node is not attached to the tree.
+    pub fn set_search_root(&mut self, node_id: NodeId) {
+        let node = self
+            .tree
+            .get(node_id)
+            .expect("the search root must be a node of the tree");
+        let tree_root = node.ancestors().last().unwrap_or(node);
+        assert!(
+            tree_root.id() == self.tree.root().id(),
+            "the search root must be attached to the tree"
+        );
+
+        self.root_id = node_id;
+        if matches!(
+            self.next_action,
+            MctsAction::Selection { R: _, RP: _ } | MctsAction::EverythingIsCalculated
+        ) {
+            self.next_action = MctsAction::Selection {
+                R: self.root_id,
+                RP: vec![],
+            };
+        }
+    }
+
+    /// Points the search back at the root of the tree after `set_search_root`.
+    pub fn restore_search_root(&mut self) {
+        self.set_search_root(self.tree.root().id());
+    }
+
+    /// Returns how the search budget spent so far was distributed among the children of the root.
+    pub fn get_budget_report(&self) -> BudgetReport {
+        BudgetReport::new(self.tree.root().children().map(|x| {
+            let budget = self.subtree_budgets.get(&x.id()).copied();
+            (x.id(), budget.unwrap_or_default())
+        }))
+    }
+
+    /// Returns the number of moves played in the most recent simulation.
+    pub(crate) fn get_last_playout_length(&self) -> u64 {
+        self.last_playout_length
+    }
+
+    /// Estimates the memory taken by the tree, the boards stored in it and the auxiliary tables
+    /// of the search, see `MemoryReport`.
+    ///
+    /// Walks every node, so polling it after every iteration slows large searches down.
+    pub fn memory_usage(&self) -> MemoryReport {
+        // The tree keeps the parent, the siblings and the first and last child of every node.
+        let node_size = size_of::<MctsNode<T>>() + 5 * size_of::<NodeId>();
+        let mut report = MemoryReport {
+            node_count: self.tree.nodes().len(),
+            nodes: self.tree.nodes().len() * node_size,
+            ..MemoryReport::default()
+        };
+        for node in self.tree.nodes() {
+            let (board, data) = node.value().get_data_size();
+            report.boards += board;
+            report.nodes += data;
+        }
+        // Hash tables keep a control byte per bucket besides the entry.
+        report.tables = self.visited_states.capacity() * (size_of::<(u128, u64)>() + 1)
+            + self.subtree_budgets.capacity() * (size_of::<(NodeId, SubtreeBudget)>() + 1)
+            + self.collected_nodes.capacity() * (size_of::<(NodeId, Vec<usize>)>() + 1)
+            + self
+                .collected_nodes
+                .values()
+                .map(|x| x.capacity())
+                .sum::<usize>()
+                * size_of::<usize>()
+            + self.untried_moves.capacity() * (size_of::<(NodeId, usize)>() + 1);
+        report
+    }
+
+    /// Returns the size of the tree and the amount of work done so far.
+    pub fn get_statistics(&self) -> SearchStatistics {
+        let root = self.tree.root();
+        let root_height = root.value().height;
+        let mut statistics = SearchStatistics {
+            iterations: self.iterations,
+            elapsed: self.elapsed,
+            hash_collisions: self.hash_collisions,
+            ..SearchStatistics::default()
+        };
+
+        let mut expanded_nodes = 0;
+        let mut children = 0;
+        for node in root.descendants() {
+            let mcts_node = node.value();
+            statistics.node_count += 1;
+            statistics.max_depth = statistics
+                .max_depth
+                .max((mcts_node.height - root_height) as usize);
+            if mcts_node.stats.bound != Bound::None {
+                statistics.proven_nodes += 1;
+            }
+            if node.has_children() {
+                expanded_nodes += 1;
+                children += node.children().count();
+            }
+        }
+
+        if expanded_nodes > 0 {
+            statistics.average_branching_factor = children as f64 / expanded_nodes as f64;
+        }
+        statistics
+    }
+
+    /// Selects the most promising node to expand, using the configured `SelectionPolicy`.
+    ///
+    /// Returns the selected node with the path leading to it, or `None` once the whole tree is
+    /// fully calculated. An interior node whose children are all fully calculated is a dead end:
+    /// it is marked as fully calculated itself, with the bound its children imply, and the
+    /// selection is repeated so a sibling is chosen instead.
+    fn select_next_node(&mut self, root_id: NodeId) -> Option<(NodeId, Vec<SelectionStep>)> {
+        loop {
+            let mut promising_node_id = root_id;
+            let mut path = Vec::new();
+            let is_dead_end = loop {
+                if self.collected_nodes.contains_key(&promising_node_id) {
+                    self.restore_collected_children(promising_node_id);
+                }
+                let node = self.tree.get(promising_node_id).unwrap();
+                if !node.has_children() {
+                    break node.value().stats.is_fully_calculated;
+                }
+                let is_forced = self.forced_root_child.is_some() && promising_node_id == root_id;
+                if !is_forced && self.is_expandable(promising_node_id) {
+                    break false;
+                }
+
+                let step = match self.forced_root_child {
+                    Some(child_id) if promising_node_id == root_id => Some(SelectionStep {
+                        node: child_id,
+                        ucb: self.get_child_ucb(child_id),
+                    }),
+                    _ => self.select_child(promising_node_id),
+                };
+                match step {
+                    Some(step) => {
+                        promising_node_id = step.node;
+                        path.push(step);
+                    }
+                    None => break true,
+                }
+            };
+
+            if !is_dead_end {
+                return Some((promising_node_id, path));
+            }
+            if promising_node_id == root_id {
+                return None;
+            }
+            self.resolve_dead_end(promising_node_id);
+        }
+    }
+
+    /// Returns whether selection stops at a partially expanded node to give it its next child:
+    /// while fewer of its children are open than progressive unpruning allows and the node limit
+    /// is not reached, or once all of them are fully calculated.
+    fn is_expandable(&self, node_id: NodeId) -> bool {
+        if !self.untried_moves.contains_key(&node_id) {
+            return false;
+        }
+        let node = self.tree.get(node_id).unwrap();
+        let open_children = node
+            .children()
+            .filter(|x| !x.value().stats.is_fully_calculated)
+            .count();
+        let unpruned_children = self.progressive_unpruning.map_or(usize::MAX, |x| {
+            x.unpruned_children(node.value().stats.visits)
+        });
+        open_children == 0 || (open_children < unpruned_children && !self.is_at_node_limit())
+    }
+
+    /// Returns the child rated highest by the selection policy among the children that are not
+    /// fully calculated yet.
+    fn select_child(&mut self, node_id: NodeId) -> Option<SelectionStep> {
+        let mut best_child: Option<SelectionStep> = None;
+        let mut max_ucb = f64::MIN;
+        let node = self.tree.get(node_id).unwrap();
+        let unpruned_children = self.progressive_unpruning.map_or(usize::MAX, |x| {
+            x.unpruned_children(node.value().stats.visits)
+        });
+        let children = node
+            .children()
+            .filter(|x| !x.value().stats.is_fully_calculated)
+            .take(unpruned_children);
+        for child in children {
+            let child_stats = &child.value().stats;
+            let current_ucb = match self.selection_policy {
+                SelectionPolicy::Ucb1 | SelectionPolicy::Ucb1Tuned => {
+                    self.child_ucb(node.value().stats.visits, child_stats)
+                }
+                SelectionPolicy::Thompson => {
+                    let value_sum = self.blended_value_sum(child_stats);
+                    let failures = (child_stats.visits as f64 - value_sum).max(0.0);
+                    self.random
+                        .next_beta(1.0 + value_sum.max(0.0), 1.0 + failures)
+                }
+            };
+            if current_ucb > max_ucb {
+                max_ucb = current_ucb;
+                best_child = Some(SelectionStep {
+                    node: child.id(),
+                    ucb: current_ucb,
+                });
+            }
+        }
+        best_child
+    }
+
+    /// Returns the UCB1 value selection currently assigns to the node with the given id, or its
+    /// UCB1-Tuned value with `SelectionPolicy::Ucb1Tuned`.
+    pub(crate) fn get_child_ucb(&self, child_id: NodeId) -> f64 {
+        let child = self.tree.get(child_id).unwrap();
+        let parent_visits = child.parent().map_or(0, |x| x.value().stats.visits);
+        self.child_ucb(parent_visits, &child.value().stats)
+    }
+
+    /// Calculates the UCB1 value of a child, or its UCB1-Tuned value with
+    /// `SelectionPolicy::Ucb1Tuned`, blending in its minimax value if enabled.
+    fn child_ucb(&self, parent_visits: u32, child_stats: &NodeStats) -> f64 {
+        let value_sum = self.blended_value_sum(child_stats);
+        match self.selection_policy {
+            SelectionPolicy::Ucb1Tuned if child_stats.visits > 0 => {
+                let visits = child_stats.visits as f64;
+                let mean = child_stats.value_sum / visits;
+                let variance = (child_stats.value_sum_sq / visits - mean * mean).max(0.0);
+                let log_ratio = (parent_visits as f64).ln() / visits;
+                let variance_bound = variance + (2.0 * log_ratio).sqrt();
+                value_sum / visits + (log_ratio * variance_bound.min(0.25)).sqrt()
+            }
+            _ => MonteCarloTreeSearch::<T, K>::ucb_value(
+                self.exploration_constant,
+                parent_visits,
+                value_sum,
+                child_stats.visits,
+            ),
+        }
+    }
+
+    /// Returns the value sum of a child, backed up with the backup operator and blending in its
+    /// minimax value if enabled.
+    fn blended_value_sum(&self, child_stats: &NodeStats) -> f64 {
+        let value_sum = self.backed_up_value_sum(child_stats);
+        match child_stats.minimax_value {
+            Some(minimax_value) if self.implicit_minimax_weight > 0.0 => {
+                let weight = self.implicit_minimax_weight;
+                (1.0 - weight) * value_sum + weight * minimax_value * child_stats.visits as f64
+            }
+            _ => value_sum,
+        }
+    }
+
+    /// Returns the value sum of a node as backed up by the backup operator, i.e. its visits times
+    /// the value selection rates it by.
+    fn backed_up_value_sum(&self, stats: &NodeStats) -> f64 {
+        let visits = stats.visits as f64;
+        match (self.backup_operator, stats.max_value) {
+            (BackupOperator::Average, _) | (_, None) => stats.value_sum,
+            (BackupOperator::Max, Some(max_value)) => max_value * visits,
+            (BackupOperator::Mixed { threshold }, Some(max_value)) => {
+                let weight = visits / (visits + threshold);
+                (1.0 - weight) * stats.value_sum + weight * max_value * visits
+            }
+        }
+    }
+
+    /// Marks an interior node whose children are all fully calculated as fully calculated.
+    fn resolve_dead_end(&mut self, node_id: NodeId) {
+        let bound = self.get_bound(node_id);
+        let mut node = self.tree.get_mut(node_id).unwrap();
+        let stats = &mut node.value().stats;
+        stats.is_fully_calculated = true;
+        if stats.bound == Bound::None && bound != Bound::None {
+            self.newly_proven_nodes += 1;
+        }
+        stats.bound = bound;
+        if self.prune_proven_subtrees {
+            self.prune_subtree(node_id);
+        }
+    }
+
+    /// Detaches the children of a node from the tree, leaving the node itself as it is.
+    fn prune_subtree(&mut self, node_id: NodeId) {
+        let children: Vec<_> = self
+            .tree
+            .get(node_id)
+            .unwrap()
+            .children()
+            .map(|x| x.id())
+            .collect();
+        for child_id in children {
+            self.pruned_nodes += self.tree.get(child_id).unwrap().descendants().count();
+            self.tree.get_mut(child_id).unwrap().detach();
+        }
+    }
+
+    /// Rebuilds the tree from the nodes still attached to it, dropping the pruned ones.
+    fn compact_tree(&mut self) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(pruned_nodes = self.pruned_nodes, "compacting tree");
+        let root_board = self.get_board(self.root_id);
+        let indices: HashMap<NodeId, usize> = self
+            .tree
+            .nodes()
+            .enumerate()
+            .map(|(index, node)| (node.id(), index))
+            .collect();
+        let order: Vec<_> = self
+            .tree
+            .root()
+            .descendants()
+            .map(|x| (indices[&x.id()], x.parent().map(|p| indices[&p.id()])))
+            .collect();
+
+        let scratch_tree = Tree::new(MctsNode::new(0, root_board));
+        let tree = std::mem::replace(&mut self.tree, scratch_tree);
+        let mut nodes: Vec<_> = tree.into_iter().map(Some).collect();
+        let mut new_ids = HashMap::with_capacity(order.len());
+        let mut order = order.into_iter();
+        let (root_index, _) = order.next().unwrap();
+        let mut tree = Tree::with_capacity(nodes[root_index].take().unwrap(), new_ids.capacity());
+        new_ids.insert(root_index, tree.root().id());
+        for (index, parent_index) in order {
+            let mut parent = tree.get_mut(new_ids[&parent_index.unwrap()]).unwrap();
+            let node_id = parent.append(nodes[index].take().unwrap()).id();
+            new_ids.insert(index, node_id);
+        }
+
+        let subtree_budgets = std::mem::take(&mut self.subtree_budgets);
+        let collected_nodes = std::mem::take(&mut self.collected_nodes);
+        let untried_moves = std::mem::take(&mut self.untried_moves);
+        let search_root = new_ids.get(&indices[&self.root_id]).copied();
+        self.replace_tree(tree);
+        self.subtree_budgets = subtree_budgets
+            .into_iter()
+            .filter_map(|(id, budget)| Some((*new_ids.get(&indices[&id])?, budget)))
+            .collect();
+        self.collected_nodes = collected_nodes
+            .into_iter()
+            .filter_map(|(id, x)| Some((*new_ids.get(&indices[&id])?, x)))
+            .collect();
+        self.untried_moves = untried_moves
+            .into_iter()
+            .filter_map(|(id, x)| Some((*new_ids.get(&indices[&id])?, x)))
+            .collect();
+        if let Some(search_root) = search_root {
+            self.set_search_root(search_root);
+        }
+        self.selected_leaf = self.root_id;
+    }
+
+    /// Returns the moves a node is expanded with: the moves available on its board, restricted by
+    /// the root move filter at the root, and sorted by their priors with progressive unpruning.
+    fn get_expansion_moves(&self, node_id: NodeId, board: &T) -> MoveList<T::Move> {
+        let mut all_possible_moves = board.get_available_moves();
+        if let Some(filter) = self
+            .root_move_filter
+            .as_ref()
+            .filter(|_| node_id == self.tree.root().id())
+        {
+            let (allowed_moves, rejected_moves): (MoveList<_>, MoveList<_>) = all_possible_moves
+                .into_iter()
+                .partition(|x| filter.allows(board, x));
+            all_possible_moves = if allowed_moves.is_empty() {
+                rejected_moves
+            } else {
+                allowed_moves
+            };
+        }
+        if self.progressive_unpruning.is_some() {
+            let mut prioritized: Vec<_> = all_possible_moves
+                .into_iter()
+                .map(|x| (board.get_move_prior(&x), x))
+                .collect();
+            prioritized.sort_by(|x, y| y.0.total_cmp(&x.0));
+            all_possible_moves = prioritized.into_iter().map(|(_, x)| x).collect();
+        }
+        all_possible_moves
+    }
+
+    /// Creates the node reached by playing a move on the board of its parent.
+    fn create_child(&mut self, board: &T, possible_move: T::Move, height: i32) -> MctsNode<T> {
+        let mut board_clone = board.clone();
+        let mut outcome = board_clone.outcome_after_move(&possible_move);
+        let new_node_id = self.next_node_id;
+        self.next_node_id += 1;
+        let mut mcts_node = MctsNode::with_outcome(
+            new_node_id,
+            board_clone,
+            Some(possible_move),
+            height,
+            outcome,
+        );
+        if outcome == GameOutcome::InProgress
+            && let Some(solved_outcome) = self.probe_oracle(mcts_node.board().unwrap())
+        {
+            self.mark_solved(&mut mcts_node, solved_outcome);
+            outcome = solved_outcome;
+        }
+        if self.implicit_minimax_weight > 0.0 {
+            mcts_node.stats.minimax_value = match outcome {
+                GameOutcome::InProgress => mcts_node.board().unwrap().get_heuristic_value(),
+                outcome => Some(self.outcome_value(outcome)),
+            };
+        }
+        if self.board_storage == BoardStorage::RootOnly {
+            mcts_node.drop_board();
+        }
+        mcts_node
+    }
+
+    /// Drops the unvisited and unsolved children of every node of the tree with at least
+    /// `min_visits` visits, returning the number of nodes dropped.
+    ///
+    /// The dropped children are created again, with fresh statistics and without annotations, as
+    /// soon as selection goes through their parent, so the search is unchanged; the tree only
+    /// shrinks below nodes the search has moved away from, such as nodes proven by a sibling.
+    /// The memory of the dropped nodes is reclaimed when the tree is next rebuilt, as with
+    /// `MonteCarloTreeSearchBuilder::with_proven_subtree_pruning`.
+    pub fn collect_garbage(&mut self, min_visits: u32) -> usize {
+        let mut protected = HashSet::new();
+        let mut node = self.tree.get(self.root_id);
+        while let Some(x) = node {
+            protected.insert(x.id());
+            node = x.parent();
+        }
+        let parents: Vec<_> = self
+            .tree
+            .root()
+            .descendants()
+            .filter(|x| {
+                x.value().stats.visits >= min_visits && !self.collected_nodes.contains_key(&x.id())
+            })
+            .map(|x| x.id())
+            .collect();
+
+        let mut dropped = 0;
+        for parent_id in parents {
+            let children: Vec<_> = self
+                .tree
+                .get(parent_id)
+                .unwrap()
+                .children()
+                .enumerate()
+                .filter(|(_, x)| {
+                    let stats = &x.value().stats;
+                    stats.visits == 0 && stats.bound == Bound::None && !protected.contains(&x.id())
+                })
+                .map(|(index, x)| (index, x.id()))
+                .collect();
+            if children.is_empty() {
+                continue;
+            }
+            for (_, child_id) in &children {
+                dropped += self.tree.get(*child_id).unwrap().descendants().count();
+                self.tree.get_mut(*child_id).unwrap().detach();
+            }
+            let indices = children.into_iter().map(|(index, _)| index).collect();
+            self.collected_nodes.insert(parent_id, indices);
+        }
+        self.pruned_nodes += dropped;
+        dropped
+    }
+
+    /// Creates again the children of a node dropped by garbage collection, at their places among
+    /// its children.
+    fn restore_collected_children(&mut self, node_id: NodeId) {
+        let Some(indices) = self.collected_nodes.remove(&node_id) else {
+            return;
+        };
+        let children_height = self.tree.get(node_id).unwrap().value().height + 1;
+        let board = self.get_board(node_id);
+        let mut moves: Vec<_> = self
+            .get_expansion_moves(node_id, &board)
+            .into_iter()
+            .map(Some)
+            .collect();
+        for index in indices {
+            let Some(b_move) = moves.get_mut(index).and_then(|x| x.take()) else {
+                continue;
+            };
+            let mcts_node = self.create_child(&board, b_move, children_height);
+            let next_sibling = self.tree.get(node_id).unwrap().children().nth(index);
+            match next_sibling.map(|x| x.id()) {
+                Some(sibling_id) => self
+                    .tree
+                    .get_mut(sibling_id)
+                    .unwrap()
+                    .insert_before(mcts_node),
+                None => self.tree.get_mut(node_id).unwrap().append(mcts_node),
+            };
+        }
+    }
+
+    /// Expands a leaf node by creating its children, representing all possible moves from that state,
+    /// or only the child of its next untried move with `ExpansionMode::SingleChild`.
+    fn expand_node(&mut self, node_id: NodeId) -> (Vec<NodeId>, NodeId) {
+        let is_single_child = self.expansion_mode == ExpansionMode::SingleChild;
+        let new_node_ids = self.create_children(node_id, is_single_child);
+        if new_node_ids.is_empty() {
+            return (vec![], node_id);
+        }
+        if is_single_child {
+            let selected_child = new_node_ids[0];
+            return (new_node_ids, selected_child);
+        }
+
+        let visits = self.tree.get(node_id).unwrap().value().stats.visits;
+        let unpruned_children = self
+            .progressive_unpruning
+            .map_or(usize::MAX, |x| x.unpruned_children(visits));
+        let selected_child_index = self
+            .random
+            .next_index(new_node_ids.len().min(unpruned_children));
+        let selected_child = new_node_ids[selected_child_index];
+        (new_node_ids, selected_child)
+    }
+
+    /// Creates the children of a node for the moves it has not tried yet, or only for the next
+    /// one with `single_child`, returning their ids.
+    ///
+    /// Moves are tried in the order of `get_expansion_moves`; a node with untried moves left is
+    /// recorded with the index of the next one.
+    fn create_children(&mut self, node_id: NodeId, single_child: bool) -> Vec<NodeId> {
+        let node = self.tree.get(node_id).unwrap();
+        if node.has_children() && !self.untried_moves.contains_key(&node_id) {
+            panic!("BUG: expanding already expanded node");
+        }
+        if node.value().outcome != GameOutcome::InProgress {
+            return vec![];
+        }
+
+        let children_height = node.value().height + 1;
+        let board = self.get_board(node_id);
+        let all_possible_moves = self.get_expansion_moves(node_id, &board);
+        if all_possible_moves.is_empty() || self.is_at_node_limit() {
+            return vec![];
+        }
+        let move_count = all_possible_moves.len();
+        let next_index = self.untried_moves.remove(&node_id).unwrap_or(0);
+        let new_mcts_nodes: Vec<_> = all_possible_moves
+            .into_iter()
+            .skip(next_index)
+            .take(if single_child { 1 } else { usize::MAX })
+            .map(|x| self.create_child(&board, x, children_height))
+            .collect();
+        if next_index + new_mcts_nodes.len() < move_count {
+            self.untried_moves
+                .insert(node_id, next_index + new_mcts_nodes.len());
+        }
+
+        let mut new_node_ids = Vec::with_capacity(new_mcts_nodes.len());
+        for mcts_node in new_mcts_nodes {
+            let mut node = self.tree.get_mut(node_id).unwrap();
+            new_node_ids.push(node.append(mcts_node).id());
+        }
+        new_node_ids
+    }
+
+    /// Selects and expands a leaf like an iteration does, and adds a virtual loss along the path
+    /// from the node to be valued up to the root. Returns `None` once the tree is fully
+    /// calculated.
+    fn select_pending_leaf(&mut self) -> Option<PendingLeaf> {
+        let Some((selected_leaf, _)) = self.select_next_node(self.root_id) else {
+            self.next_action = MctsAction::EverythingIsCalculated;
+            return None;
+        };
+        let (children, selected_child) = self.expand_node(selected_leaf);
+        let mut path = vec![selected_child];
+        let mut node = self.tree.get(selected_child).unwrap().parent();
+        while let Some(x) = node {
+            path.push(x.id());
+            node = x.parent();
+        }
+        for node_id in &path {
+            self.tree.get_mut(*node_id).unwrap().value().stats.visits += 1;
+        }
+        Some(PendingLeaf {
+            path,
+            selected_leaf,
+            nodes_created: children.len(),
+        })
+    }
+
+    /// Removes the virtual loss of a pending leaf and completes its iteration, backing up its
+    /// value, seen from `Player::Me`, or the outcome of a playout if it has none. Returns `false`
+    /// if the leaf was cut off from the tree meanwhile, leaving the iteration undone.
+    fn finish_pending_leaf(&mut self, leaf: PendingLeaf, value: Option<f64>) -> bool {
+        for node_id in &leaf.path {
+            self.tree.get_mut(*node_id).unwrap().value().stats.visits -= 1;
+        }
+        let node_id = leaf.path[0];
+        let node = self.tree.get(node_id).unwrap();
+        let top = node.ancestors().last().map_or(node_id, |x| x.id());
+        if top != self.tree.root().id() {
+            return false;
+        }
+
+        self.selected_leaf = leaf.selected_leaf;
+        self.last_nodes_created = leaf.nodes_created;
+        let (outcome, affected_nodes) = match value {
+            Some(value) => {
+                self.last_playout_length = 0;
+                let outcome = GameOutcome::InProgress;
+                (outcome, self.back_up_value(node_id, value, outcome))
+            }
+            None => {
+                let outcome = self.simulate(node_id);
+                (outcome, self.backpropagate(node_id, outcome))
+            }
+        };
+        self.finish_iteration(node_id, outcome, affected_nodes);
+        true
+    }
+
+    /// Simulates a random playout from a given node until the game ends.
+    ///
+    /// The playout starts from the outcome cached in the node, so the outcome of every state is
+    /// computed exactly once, when the move leading to it is performed.
+    fn simulate(&mut self, node_id: NodeId) -> GameOutcome {
+        let board = self.get_board(node_id);
+        let outcome = self.tree.get(node_id).unwrap().value().outcome;
+        let mut visited_states = std::mem::take(&mut self.visited_states);
+        visited_states.clear();
+        let outcome = self.play_out(board, outcome, &mut visited_states);
+        self.visited_states = visited_states;
+        outcome
+    }
+
+    /// Plays random moves from the given state, whose outcome is `outcome`, until the game ends.
+    fn play_out(
+        &mut self,
+        mut board: T,
+        mut outcome: GameOutcome,
+        visited_states: &mut HashMap<u128, u64>,
+    ) -> GameOutcome {
+        let repeated_outcome = match self.cycle_policy {
+            CyclePolicy::TreatAsDraw => Some(GameOutcome::Draw),
+            CyclePolicy::TreatAsLoss => Some(GameOutcome::Lose),
+            CyclePolicy::Off | CyclePolicy::MaxPlayoutLength(_) => None,
+        };
+        if repeated_outcome.is_some() {
+            let (hash, fingerprint) = self.state_key(&board);
+            visited_states.insert(hash, fingerprint);
+        }
+        self.last_playout_length = 0;
+
+        while outcome == GameOutcome::InProgress {
+            if let Some(outcome) = self.probe_oracle(&board) {
+                return outcome;
+            }
+
+            if board.is_hopeless() {
+                return GameOutcome::Lose;
+            }
+
+            if let CyclePolicy::MaxPlayoutLength(max_length) = self.cycle_policy
+                && self.last_playout_length >= max_length
+            {
+                return GameOutcome::Draw;
+            }
+
+            let mut all_possible_moves = board.get_available_moves();
+            if all_possible_moves.is_empty() {
+                return GameOutcome::Draw;
+            }
+
+            let Some(repeated_outcome) = repeated_outcome else {
+                let random_move_index = self.random.next_index(all_possible_moves.len());
+                outcome = board.outcome_after_move(&all_possible_moves[random_move_index]);
+                self.last_playout_length += 1;
+                continue;
+            };
+
+            while !all_possible_moves.is_empty() {
+                let random_move_index = self.random.next_index(all_possible_moves.len());
+                let random_move = all_possible_moves.get(random_move_index).unwrap();
+                let mut new_board = board.clone();
+                let new_outcome = new_board.outcome_after_move(random_move);
+                let (hash, fingerprint) = self.state_key(&new_board);
+                let is_repeated = match visited_states.insert(hash, fingerprint) {
+                    None => false,
+                    Some(seen) if seen == fingerprint => true,
+                    Some(_) => {
+                        self.hash_collisions += 1;
+                        false
+                    }
+                };
+                if is_repeated {
+                    all_possible_moves.remove(random_move_index);
+                    continue;
+                } else {
+                    board = new_board;
+                    outcome = new_outcome;
+                    self.last_playout_length += 1;
+                    break;
+                }
+            }
+
+            if all_possible_moves.is_empty() {
+                return repeated_outcome;
+            }
+        }
+        outcome
+    }
+
+    /// Returns the hash identifying a position, with its fingerprint if hashes are verified.
+    fn state_key(&self, board: &T) -> (u128, u64) {
+        let hash = match self.hash_width {
+            HashWidth::Bits128 => board.get_hash(),
+            HashWidth::Bits64 => board.get_hash64() as u128,
+        };
+        let fingerprint = match self.verify_hashes {
+            true => board.get_fingerprint().unwrap_or(0),
+            false => 0,
+        };
+        (hash, fingerprint)
+    }
+
+    /// Returns the exact outcome of a position if the terminal oracle knows it.
+    fn probe_oracle(&self, board: &T) -> Option<GameOutcome> {
+        let outcome = self.terminal_oracle.as_ref()?.probe(board)?;
+        (outcome != GameOutcome::InProgress).then_some(outcome)
+    }
+
+    /// Marks a node solved by the terminal oracle as fully calculated, with the bound implied by
+    /// its outcome.
+    fn mark_solved(&mut self, mcts_node: &mut MctsNode<T>, outcome: GameOutcome) {
+        mcts_node.stats.is_fully_calculated = true;
+        if !self.use_alpha_beta_pruning {
+            return;
+        }
+
+        mcts_node.stats.bound = match outcome {
+            GameOutcome::Win => Bound::DefoWin,
+            GameOutcome::Lose => Bound::DefoLose,
+            _ => Bound::None,
+        };
+        if mcts_node.stats.bound != Bound::None {
+            self.newly_proven_nodes += 1;
+        }
+    }
+
+    /// Returns `true` if the tree holds as many nodes as the configured cap allows.
+    fn is_at_node_limit(&self) -> bool {
+        self.max_nodes
+            .is_some_and(|x| self.tree.nodes().len() - self.pruned_nodes >= x)
+    }
+
+    /// Runs a proof-number search on a node, marking it solved if it is proven to be won or lost.
+    /// Its parent, next in the backpropagated branch, picks up the bound at once.
+    fn prove_node(&mut self, node_id: NodeId, node_budget: usize) {
+        let board = self.get_board(node_id);
+        let bound = match proof_number::prove_outcome(&board, node_budget) {
+            Some(GameOutcome::Win) => Bound::DefoWin,
+            Some(GameOutcome::Lose) => Bound::DefoLose,
+            _ => return,
+        };
+        let mut temp_node = self.tree.get_mut(node_id).unwrap();
+        let stats = &mut temp_node.value().stats;
+        stats.bound = bound;
+        stats.is_fully_calculated = true;
+        self.newly_proven_nodes += 1;
+    }
+
+    /// Propagates the result of a simulation back up the tree, updating node statistics.
+    fn backpropagate(&mut self, node_id: NodeId, outcome: GameOutcome) -> Vec<NodeId> {
+        let value = self.outcome_value(outcome);
+        self.back_up_value(node_id, value, outcome)
+    }
+
+    /// Propagates a value, seen from `Player::Me`, back up the tree, counting a win or a draw if
+    /// `outcome` is one.
+    fn back_up_value(
+        &mut self,
+        node_id: NodeId,
+        mut value: f64,
+        outcome: GameOutcome,
+    ) -> Vec<NodeId> {
+        let mut branch = vec![node_id];
+
+        loop {
+            let temp_node = self.tree.get(*branch.last().unwrap()).unwrap();
+            match temp_node.parent() {
+                None => break,
+                Some(parent) => branch.push(parent.id()),
+            }
+        }
+
+        if let [.., root_child, _root] = branch[..] {
+            let budget = self.subtree_budgets.entry(root_child).or_default();
+            budget.iterations += 1;
+            budget.playout_moves += self.last_playout_length;
+        }
+
+        let is_win = outcome == GameOutcome::Win;
+        let is_draw = outcome == GameOutcome::Draw;
+        if self.reward_discount != 1.0 {
+            let root_height = self.tree.root().value().height;
+            let node_height = self.tree.get(node_id).unwrap().value().height;
+            let depth = (node_height - root_height) as f64 + self.last_playout_length as f64;
+            value = 0.5 + (value - 0.5) * self.reward_discount.powf(depth);
+        }
+
+        for node_id in &branch {
+            let bound = self.get_bound(*node_id);
+            let is_fully_calculated = self.is_fully_calculated(*node_id, bound);
+            let mut temp_node = self.tree.get_mut(*node_id).unwrap();
+            let stats = &mut temp_node.value().stats;
+            stats.visits += 1;
+            stats.value_sum += value;
+            stats.value_sum_sq += value * value;
+            if is_win {
+                stats.wins += 1;
+            }
+
+            if is_draw {
+                stats.draws += 1;
+            }
+
+            if is_fully_calculated {
+                stats.is_fully_calculated = true;
+            }
 
             if bound != Bound::None {
-                mcts_node.bound = bound;
+                if stats.bound == Bound::None {
+                    self.newly_proven_nodes += 1;
+                }
+                stats.bound = bound;
+            }
+
+            if let Some(pn_search) = self.proof_number_search
+                && self.use_alpha_beta_pruning
+                && stats.visits == pn_search.trigger_visits
+                && stats.bound == Bound::None
+                && !stats.is_fully_calculated
+            {
+                self.prove_node(*node_id, pn_search.node_budget);
+            }
+
+            if self.implicit_minimax_weight > 0.0 {
+                self.back_up_minimax_value(*node_id);
+            }
+
+            if self.backup_operator != BackupOperator::Average {
+                self.back_up_max_value(*node_id);
+            }
+        }
+
+        if self.prune_proven_subtrees
+            && let Some(node_id) = branch.iter().rev().skip(1).copied().find(|x| {
+                let node = self.tree.get(*x).unwrap();
+                node.value().stats.is_fully_calculated && node.has_children()
+            })
+        {
+            self.prune_subtree(node_id);
+        }
+
+        branch
+    }
+
+    /// Sets the minimax value of an expanded node from the minimax values of its children.
+    fn back_up_minimax_value(&mut self, node_id: NodeId) {
+        let node = self.tree.get(node_id).unwrap();
+        let values = node
+            .children()
+            .filter_map(|x| x.value().stats.minimax_value);
+        let minimax_value = match node.value().current_player {
+            Player::Me => values.reduce(f64::max),
+            Player::Other => values.reduce(f64::min),
+        };
+        if minimax_value.is_some() {
+            self.tree
+                .get_mut(node_id)
+                .unwrap()
+                .value()
+                .stats
+                .minimax_value = minimax_value;
+        }
+    }
+
+    /// Sets the max value of a node from the values its visited children are rated by.
+    fn back_up_max_value(&mut self, node_id: NodeId) {
+        let node = self.tree.get(node_id).unwrap();
+        let values = node
+            .children()
+            .map(|x| &x.value().stats)
+            .filter(|x| x.visits > 0)
+            .map(|x| self.backed_up_value_sum(x) / x.visits as f64);
+        let max_value = match node.value().current_player {
+            Player::Me => values.reduce(f64::max),
+            Player::Other => values.reduce(f64::min),
+        };
+        if max_value.is_some() {
+            self.tree.get_mut(node_id).unwrap().value().stats.max_value = max_value;
+        }
+    }
+
+    /// Returns the value of a simulation outcome that is backed up through the tree.
+    pub(crate) fn outcome_value(&self, outcome: GameOutcome) -> f64 {
+        match outcome {
+            GameOutcome::Win => 1.0,
+            GameOutcome::Draw => (self.draw_reward - self.contempt).clamp(0.0, 1.0),
+            _ => 0.0,
+        }
+    }
+
+    /// Determines the bound of a node for alpha-beta pruning.
+    fn get_bound(&self, node_id: NodeId) -> Bound {
+        if !self.use_alpha_beta_pruning {
+            return Bound::None;
+        }
+
+        let node = self.tree.get(node_id).unwrap();
+        let mcts_node = node.value();
+        if mcts_node.stats.bound != Bound::None {
+            return mcts_node.stats.bound;
+        }
+
+        if mcts_node.outcome == GameOutcome::Win {
+            return Bound::DefoWin;
+        }
+
+        if mcts_node.outcome == GameOutcome::Lose {
+            return Bound::DefoLose;
+        }
+
+        if node.children().count() == 0 {
+            return Bound::None;
+        }
+
+        // Untried moves may still change a bound that needs all children.
+        let is_complete = !self.untried_moves.contains_key(&node_id);
+        match mcts_node.current_player {
+            Player::Me => {
+                if is_complete
+                    && node
+                        .children()
+                        .all(|x| x.value().stats.bound == Bound::DefoLose)
+                {
+                    return Bound::DefoLose;
+                }
+
+                if node
+                    .children()
+                    .any(|x| x.value().stats.bound == Bound::DefoWin)
+                {
+                    return Bound::DefoWin;
+                }
+            }
+            Player::Other => {
+                if is_complete
+                    && node
+                        .children()
+                        .all(|x| x.value().stats.bound == Bound::DefoWin)
+                {
+                    return Bound::DefoWin;
+                }
+
+                if node
+                    .children()
+                    .any(|x| x.value().stats.bound == Bound::DefoLose)
+                {
+                    return Bound::DefoLose;
+                }
+            }
+        }
+
+        Bound::None
+    }
+
+    /// Checks if a node can be considered fully calculated, meaning its outcome is certain.
+    fn is_fully_calculated(&self, node_id: NodeId, bound: Bound) -> bool {
+        if bound != Bound::None {
+            return true;
+        }
+
+        let node = self.tree.get(node_id).unwrap();
+        if node.value().outcome != GameOutcome::InProgress {
+            return true;
+        }
+
+        if node.children().count() == 0 || self.untried_moves.contains_key(&node_id) {
+            return false;
+        }
+
+        node.children().all(|x| x.value().stats.is_fully_calculated)
+    }
+
+    /// Calculates the UCB1 (Upper Confidence Bound 1) value for a node.
+    fn ucb_value(
+        exploration_constant: f64,
+        total_visits: u32,
+        node_value_sum: f64,
+        node_visit: u32,
+    ) -> f64 {
+        if node_visit == 0 {
+            i32::MAX.into()
+        } else {
+            (node_value_sum / (node_visit as f64))
+                + exploration_constant
+                    * f64::sqrt(f64::ln(total_visits as f64) / (node_visit as f64))
+        }
+    }
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Clone,
+{
+    /// Returns the move leading to the best child of the root, chosen with the configured
+    /// `BestChildCriterion`.
+    pub fn get_most_perspective_move(&self) -> Option<T::Move> {
+        let best_child = self
+            .get_root()
+            .get_best_child_by(self.best_child_criterion)?;
+        best_child.value().prev_move().cloned()
+    }
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: PartialEq,
+{
+    /// Returns the node reached by playing `moves` from the root of the tree, or `None` if the
+    /// tree does not contain that line. An empty slice returns the root.
+    pub fn find_node(&self, moves: &[T::Move]) -> Option<NodeId> {
+        let mut node = self.tree.root();
+        for b_move in moves {
+            node = node
+                .children()
+                .find(|x| x.value().prev_move() == Some(b_move))?;
+        }
+        Some(node.id())
+    }
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Clone + PartialEq,
+{
+    /// Merges the statistics of another search over the same root position into this one, e.g.
+    /// the searches of root-parallel or distributed workers, or a pondering search.
+    ///
+    /// Nodes reached by the same line of moves in both trees add up their visits, wins, draws and
+    /// backed-up values, and take the proven bound of the other node if theirs is unknown. Lines
+    /// only the other tree explored are copied with their statistics, except below fully
+    /// calculated nodes of this tree. The iterations of both searches add up too, while the
+    /// configuration and the hooks of this search are kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the roots of the two trees are different positions.
+    pub fn merge(&mut self, other: MonteCarloTreeSearch<T, K>) {
+        let root_id = self.tree.root().id();
+        let other_root = other.tree.root();
+        assert!(
+            self.get_board(root_id).get_hash() == other.get_board(other_root.id()).get_hash(),
+            "only searches of the same root position can be merged"
+        );
+
+        let mut pairs = vec![(root_id, other_root)];
+        while let Some((node_id, other_node)) = pairs.pop() {
+            let other_stats = &other_node.value().stats;
+            let mut node = self.tree.get_mut(node_id).unwrap();
+            let stats = &mut node.value().stats;
+            let is_fully_calculated = stats.is_fully_calculated;
+            stats.visits += other_stats.visits;
+            stats.wins += other_stats.wins;
+            stats.draws += other_stats.draws;
+            stats.value_sum += other_stats.value_sum;
+            stats.value_sum_sq += other_stats.value_sum_sq;
+            if stats.bound == Bound::None {
+                stats.bound = other_stats.bound;
+            }
+            stats.is_fully_calculated |= other_stats.is_fully_calculated;
+            stats.minimax_value = stats.minimax_value.or(other_stats.minimax_value);
+            stats.max_value = stats.max_value.or(other_stats.max_value);
+            if is_fully_calculated {
+                continue;
+            }
+
+            self.restore_collected_children(node_id);
+            let was_leaf = !self.tree.get(node_id).unwrap().has_children();
+            for other_child in other_node.children() {
+                let node = self.tree.get(node_id).unwrap();
+                let child_id = node
+                    .children()
+                    .find(|x| x.value().prev_move() == other_child.value().prev_move())
+                    .map(|x| x.id());
+                match child_id {
+                    Some(child_id) => pairs.push((child_id, other_child)),
+                    None => self.copy_subtree(&other, node_id, other_child),
+                }
+            }
+
+            // Both trees create children in the same order of moves, so the line with more tried
+            // moves has them all.
+            let other_untried_moves = other.untried_moves.get(&other_node.id()).copied();
+            if was_leaf {
+                self.copy_missing_children(&other, node_id, other_node.id());
+            } else if let Some(index) = self.untried_moves.get_mut(&node_id) {
+                match other_untried_moves {
+                    Some(other_index) => *index = (*index).max(other_index),
+                    None if other_node.has_children() => {
+                        self.untried_moves.remove(&node_id);
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        self.iterations += other.iterations;
+        if matches!(
+            self.next_action,
+            MctsAction::Selection { R: _, RP: _ } | MctsAction::EverythingIsCalculated
+        ) {
+            self.next_action = MctsAction::Selection {
+                R: self.root_id,
+                RP: vec![],
+            };
+        }
+    }
+
+    /// Appends a copy of a subtree of another search below a node, giving its nodes new ids and
+    /// storing their boards as the board storage of this search requires.
+    fn copy_subtree(
+        &mut self,
+        other: &MonteCarloTreeSearch<T, K>,
+        parent_id: NodeId,
+        subtree: NodeRef<MctsNode<T>>,
+    ) {
+        let mut queue = VecDeque::from([(parent_id, subtree)]);
+        while let Some((parent_id, other_node)) = queue.pop_front() {
+            let mut mcts_node = other_node.value().clone();
+            mcts_node.id = self.next_node_id;
+            self.next_node_id += 1;
+            mcts_node.drop_board();
+            let new_id = self.tree.get_mut(parent_id).unwrap().append(mcts_node).id();
+            if self.board_storage == BoardStorage::EveryNode {
+                let board = self.get_board(new_id);
+                self.tree.get_mut(new_id).unwrap().value().set_board(board);
             }
+            self.copy_missing_children(other, new_id, other_node.id());
+            queue.extend(other_node.children().map(|x| (new_id, x)));
+        }
+    }
+
+    /// Records the children another search has not created, or dropped, for one of its nodes as
+    /// missing in the copy of that node.
+    fn copy_missing_children(
+        &mut self,
+        other: &MonteCarloTreeSearch<T, K>,
+        node_id: NodeId,
+        other_id: NodeId,
+    ) {
+        if let Some(index) = other.untried_moves.get(&other_id) {
+            self.untried_moves.insert(node_id, *index);
+        }
+        if let Some(indices) = other.collected_nodes.get(&other_id) {
+            self.collected_nodes.insert(node_id, indices.clone());
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<T: Board> MonteCarloTreeSearch<T, StandardRandomGenerator> {
+    pub fn from_board(board: T) -> Self {
+        MonteCarloTreeSearchBuilder::new(board).build()
+    }
+}
+
+/// Represents the four main stages of the MCTS algorithm.
+///
+/// This enum is used to manage the state of the search process.
+#[allow(non_snake_case)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum MctsAction {
+    /// **Selection**: Start from the root `R` and select successive child nodes until a leaf node `L` is reached.
+    Selection {
+        /// The root of the current selection phase.
+        R: NodeId,
+        /// The path of nodes visited during the last backpropagation phase.
+        RP: Vec<NodeId>,
+    },
+    /// **Expansion**: Create one or more child nodes from the selected leaf node `L`.
+    Expansion {
+        /// The leaf node to be expanded.
+        L: NodeId,
+        /// The path the selection descended from the root to `L`, excluding the root.
+        SP: Vec<SelectionStep>,
+    },
+    /// **Simulation**: Run a random playout from a newly created child node `C`.
+    Simulation {
+        /// The child node from which the simulation will start.
+        C: NodeId,
+        /// All children created during the expansion phase.
+        AC: Vec<NodeId>,
+    },
+    /// **Backpropagation**: Update the statistics of the nodes on the path from `C` to the root `R`.
+    Backpropagation {
+        /// The child node from which the simulation was run.
+        C: NodeId,
+        /// The result of the simulation.
+        result: GameOutcome,
+    },
+    /// Represents a state where the entire tree has been explored and the outcome is certain.
+    EverythingIsCalculated,
+}
+
+/// A leaf selected for a batch of `MonteCarloTreeSearch::iterate_batched`, waiting for its value.
+struct PendingLeaf {
+    /// The node to be valued and its ancestors up to the root, which carry its virtual loss.
+    path: Vec<NodeId>,
+    /// The leaf chosen during selection, the parent of the node to be valued if it was expanded.
+    selected_leaf: NodeId,
+    nodes_created: usize,
+}
+
+/// A single step of the descent from the root to a leaf during selection.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SelectionStep {
+    /// The child the selection moved to.
+    pub node: NodeId,
+    /// The rating of the child at the time it was selected: its UCB1 value, or the win probability
+    /// sampled from its posterior with `SelectionPolicy::Thompson`.
+    pub ucb: f64,
+}
+
+impl MctsAction {
+    /// Returns the name of the current MCTS action as a string.
+    pub fn get_name(&self) -> String {
+        match self {
+            MctsAction::Selection { R: _, RP: _ } => "Selection".to_string(),
+            MctsAction::Expansion { L: _, SP: _ } => "Expansion".to_string(),
+            MctsAction::Simulation { C: _, AC: _ } => "Simulation".to_string(),
+            MctsAction::Backpropagation { C: _, result: _ } => "Backpropagation".to_string(),
+            MctsAction::EverythingIsCalculated => "EverythingIsCalculated".to_string(),
+        }
+    }
+}
+
+pub struct MctsTreeNode<'a, T: Board>(pub NodeRef<'a, MctsNode<T>>);
+
+impl<'a, T: Board> Clone for MctsTreeNode<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: Board> Copy for MctsTreeNode<'a, T> {}
+
+impl<'a, T: Board> Deref for MctsTreeNode<'a, T> {
+    type Target = NodeRef<'a, MctsNode<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T: Board> DerefMut for MctsTreeNode<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T: Board> From<MctsTreeNode<'a, T>> for NodeRef<'a, MctsNode<T>> {
+    fn from(node: MctsTreeNode<'a, T>) -> Self {
+        node.0
+    }
+}
+
+impl<'a, T: Board> From<NodeRef<'a, MctsNode<T>>> for MctsTreeNode<'a, T> {
+    fn from(node: NodeRef<'a, MctsNode<T>>) -> Self {
+        Self(node)
+    }
+}
+
+impl<'a, T: Board> MctsTreeNode<'a, T> {
+    /// Returns the child of the given node that is considered the most promising, based on win rate.
+    ///
+    /// Children proven to be wins are preferred, see `BestChildCriterion::ProvenWinFirst`.
+    pub fn get_best_child(&self) -> Option<MctsTreeNode<'a, T>> {
+        self.get_best_child_by(BestChildCriterion::ProvenWinFirst)
+    }
+
+    /// Returns the child of the given node that is the best according to `criterion`.
+    pub fn get_best_child_by(&self, criterion: BestChildCriterion) -> Option<MctsTreeNode<'a, T>> {
+        criterion.select(self)
+    }
+
+    /// Returns the children of the node, the most visited first. Children with equal visits keep
+    /// the order of their moves.
+    pub fn children_sorted_by_visits(&self) -> Vec<MctsTreeNode<'a, T>> {
+        let mut children: Vec<MctsTreeNode<'a, T>> = self.children().map(Into::into).collect();
+        children.sort_by_key(|x| std::cmp::Reverse(x.value().stats.visits));
+        children
+    }
+
+    /// Returns an iterator over the node and its descendants in breadth-first order, i.e. by
+    /// increasing depth, the children of every node in the order of their moves.
+    pub fn breadth_first(&self) -> BreadthFirst<'a, T> {
+        BreadthFirst {
+            queue: VecDeque::from([self.0]),
+        }
+    }
+
+    /// Returns an iterator over the descendants of the node, the node included, `depth` moves
+    /// below it.
+    pub fn nodes_at_depth(
+        &self,
+        depth: usize,
+    ) -> impl Iterator<Item = MctsTreeNode<'a, T>> + use<'a, T> {
+        let height = self.value().height + depth as i32;
+        self.breadth_first()
+            .skip_while(move |x| x.value().height < height)
+            .take_while(move |x| x.value().height == height)
+    }
+
+    /// Returns an iterator over the node and its descendants matching `predicate`, e.g. all nodes
+    /// with more than a number of visits, in breadth-first order.
+    pub fn filter_nodes<P: FnMut(&MctsNode<T>) -> bool>(
+        &self,
+        mut predicate: P,
+    ) -> impl Iterator<Item = MctsTreeNode<'a, T>> + use<'a, T, P> {
+        self.breadth_first().filter(move |x| predicate(x.value()))
+    }
+}
+
+/// An iterator over a node and its descendants in breadth-first order, returned by
+/// `MctsTreeNode::breadth_first`.
+pub struct BreadthFirst<'a, T: Board> {
+    queue: VecDeque<NodeRef<'a, MctsNode<T>>>,
+}
+
+impl<'a, T: Board> Iterator for BreadthFirst<'a, T> {
+    type Item = MctsTreeNode<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.children());
+        Some(node.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Bound, GameOutcome, MoveList, Player};
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::criterion::BestChildCriterion;
+    use crate::evaluator::{Evaluation, Evaluator};
+    use crate::mcts::{
+        BackupOperator, BoardStorage, CyclePolicy, ExpansionMode, GarbageCollection, HashWidth,
+        MctsAction, MonteCarloTreeSearch, ProgressiveUnpruning, SelectionPolicy,
+    };
+    use crate::mcts_node::MctsNode;
+    use crate::observer::IterationReport;
+    use crate::random::CustomNumberGenerator;
+    use smallvec::smallvec;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// A Tic-Tac-Toe board that gives up on every position.
+    #[derive(Clone, Default)]
+    struct HopelessBoard(TicTacToeBoard);
+
+    impl Board for HopelessBoard {
+        type Move = u8;
+
+        fn get_current_player(&self) -> Player {
+            self.0.get_current_player()
+        }
+
+        fn get_outcome(&self) -> GameOutcome {
+            self.0.get_outcome()
+        }
+
+        fn get_available_moves(&self) -> MoveList<Self::Move> {
+            self.0.get_available_moves()
+        }
+
+        fn perform_move(&mut self, b_move: &Self::Move) {
+            self.0.perform_move(b_move)
+        }
+
+        fn get_hash(&self) -> u128 {
+            self.0.get_hash()
+        }
+
+        fn is_hopeless(&self) -> bool {
+            true
+        }
+    }
+
+    /// A game walking around a ring of four cells that never ends.
+    #[derive(Clone, Default)]
+    struct RingBoard {
+        cell: u8,
+        player: u8,
+    }
+
+    impl Board for RingBoard {
+        type Move = u8;
+
+        fn get_current_player(&self) -> Player {
+            match self.player {
+                0 => Player::Me,
+                _ => Player::Other,
+            }
+        }
+
+        fn get_outcome(&self) -> GameOutcome {
+            GameOutcome::InProgress
+        }
+
+        fn get_available_moves(&self) -> MoveList<Self::Move> {
+            smallvec![1, 3]
+        }
+
+        fn perform_move(&mut self, b_move: &Self::Move) {
+            self.cell = (self.cell + b_move) % 4;
+            self.player = 1 - self.player;
+        }
+
+        fn get_hash(&self) -> u128 {
+            (self.cell * 2 + self.player) as u128
+        }
+    }
+
+    /// A Tic-Tac-Toe board with a 64-bit hash that often collides, counting the calls to the
+    /// 128-bit hash across all its clones.
+    #[derive(Clone, Default)]
+    struct CollidingBoard {
+        board: TicTacToeBoard,
+        hash_calls: Arc<AtomicUsize>,
+    }
+
+    impl Board for CollidingBoard {
+        type Move = u8;
+
+        fn get_current_player(&self) -> Player {
+            self.board.get_current_player()
+        }
+
+        fn get_outcome(&self) -> GameOutcome {
+            self.board.get_outcome()
+        }
+
+        fn get_available_moves(&self) -> MoveList<Self::Move> {
+            self.board.get_available_moves()
+        }
+
+        fn perform_move(&mut self, b_move: &Self::Move) {
+            self.board.perform_move(b_move)
+        }
+
+        fn get_hash(&self) -> u128 {
+            self.hash_calls.fetch_add(1, Ordering::Relaxed);
+            self.get_hash64() as u128
+        }
+
+        fn get_hash64(&self) -> u64 {
+            (self.board.get_hash() % 7) as u64
+        }
+
+        fn get_fingerprint(&self) -> Option<u64> {
+            Some(self.board.get_hash() as u64)
+        }
+    }
+
+    /// A Tic-Tac-Toe board counting the calls to `get_outcome` across all its clones.
+    #[derive(Clone, Default)]
+    struct CountingBoard {
+        board: TicTacToeBoard,
+        outcome_calls: Arc<AtomicUsize>,
+    }
+
+    impl Board for CountingBoard {
+        type Move = u8;
+
+        fn get_current_player(&self) -> Player {
+            self.board.get_current_player()
+        }
+
+        fn get_outcome(&self) -> GameOutcome {
+            self.outcome_calls.fetch_add(1, Ordering::Relaxed);
+            self.board.get_outcome()
+        }
+
+        fn get_available_moves(&self) -> MoveList<Self::Move> {
+            self.board.get_available_moves()
+        }
+
+        fn perform_move(&mut self, b_move: &Self::Move) {
+            self.board.perform_move(b_move)
+        }
+
+        fn outcome_after_move(&mut self, b_move: &Self::Move) -> GameOutcome {
+            self.board.outcome_after_move(b_move)
+        }
+
+        fn get_hash(&self) -> u128 {
+            self.board.get_hash()
+        }
+    }
+
+    /// A Tic-Tac-Toe board estimating positions only by whether the first move was a corner.
+    #[derive(Clone, Default)]
+    struct FirstMoveBoard {
+        board: TicTacToeBoard,
+        first_move: Option<u8>,
+    }
+
+    impl Board for FirstMoveBoard {
+        type Move = u8;
+
+        fn get_current_player(&self) -> Player {
+            self.board.get_current_player()
+        }
+
+        fn get_outcome(&self) -> GameOutcome {
+            self.board.get_outcome()
+        }
+
+        fn get_available_moves(&self) -> MoveList<Self::Move> {
+            self.board.get_available_moves()
+        }
+
+        fn perform_move(&mut self, b_move: &Self::Move) {
+            self.first_move.get_or_insert(*b_move);
+            self.board.perform_move(b_move)
+        }
+
+        fn get_hash(&self) -> u128 {
+            self.board.get_hash()
+        }
+
+        fn get_heuristic_value(&self) -> Option<f64> {
+            Some(if self.first_move == Some(8) { 0.9 } else { 0.1 })
+        }
+
+        fn get_move_prior(&self, b_move: &Self::Move) -> f64 {
+            *b_move as f64
+        }
+    }
+
+    #[test]
+    fn hopeless_positions_end_playouts_as_losses() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(HopelessBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(200);
+
+        // assert
+        let root = mcts.get_root();
+        assert_eq!(root.value().stats.visits, 200);
+        assert_eq!(root.value().stats.wins, 0);
+        assert_eq!(root.value().stats.draws, 0);
+        assert_eq!(mcts.get_budget_report().total.playout_moves, 0);
+    }
+
+    #[test]
+    fn statistics_describe_the_tree() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let initial = mcts.get_statistics();
+
+        // act
+        mcts.iterate_n_times(50000);
+        let statistics = mcts.get_statistics();
+
+        // assert
+        assert_eq!(initial.node_count, 1);
+        assert_eq!(initial.iterations, 0);
+        assert_eq!(initial.average_branching_factor, 0.0);
+        assert_eq!(
+            statistics.node_count,
+            mcts.get_tree().root().descendants().count()
+        );
+        assert_eq!(statistics.max_depth, 9);
+        assert!(statistics.average_branching_factor > 1.0);
+        assert!(statistics.average_branching_factor < 9.0);
+        assert!(statistics.proven_nodes > 0);
+        assert_eq!(
+            statistics.iterations,
+            mcts.get_root().value().stats.visits as u64
+        );
+        assert!(statistics.elapsed > initial.elapsed);
+    }
+
+    #[test]
+    fn memory_usage_follows_the_tree() {
+        // arrange
+        let search = |board_storage: BoardStorage| {
+            let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_board_storage(board_storage)
+                .build();
+            let initial = mcts.memory_usage();
+            mcts.iterate_n_times(500);
+            (initial, mcts.memory_usage(), mcts.get_tree().nodes().len())
+        };
+
+        // act
+        let (initial, every_node, node_count) = search(BoardStorage::EveryNode);
+        let (_, root_only, _) = search(BoardStorage::RootOnly);
+
+        // assert
+        let board_size = size_of::<Option<TicTacToeBoard>>();
+        assert_eq!(initial.node_count, 1);
+        assert_eq!(initial.boards, board_size);
+        assert_eq!(every_node.node_count, node_count);
+        assert_eq!(every_node.boards, node_count * board_size);
+        assert_eq!(root_only.boards, board_size);
+        assert!(every_node.nodes > initial.nodes * (node_count - 1));
+        assert_eq!(
+            every_node.total(),
+            every_node.nodes + every_node.boards + every_node.tables
+        );
+    }
+
+    #[test]
+    fn observer_sees_every_iteration() {
+        // arrange
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let observed = reports.clone();
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_iteration_observer(move |report: &IterationReport| {
+                observed.lock().unwrap().push(report.clone())
+            })
+            .build();
+
+        // act
+        mcts.warm_up(10, 0);
+        mcts.iterate_n_times(100);
+
+        // assert
+        let reports = reports.lock().unwrap();
+        let root_id = mcts.get_root().id();
+        assert_eq!(reports.len(), 100);
+        assert_eq!(reports[0].selected_leaf, root_id);
+        for (index, report) in reports.iter().enumerate() {
+            assert_eq!(report.iteration, index as u64 + 1);
+            assert_eq!(report.path.first(), Some(&report.simulated_node));
+            assert_eq!(report.path.last(), Some(&root_id));
+            assert!(report.path.contains(&report.selected_leaf));
+        }
+        assert!(mcts.take_iteration_observer().is_some());
+    }
+
+    #[test]
+    fn stop_flag_interrupts_the_search() {
+        // arrange
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_after_100 = stop.clone();
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_stop_condition(stop.clone())
+            .with_iteration_observer(move |report: &IterationReport| {
+                if report.iteration == 100 {
+                    stop_after_100.store(true, Ordering::Relaxed);
+                }
+            })
+            .build();
+
+        // act
+        mcts.iterate_n_times(1000);
+
+        // assert
+        assert!(mcts.is_stop_requested());
+        assert_eq!(mcts.get_statistics().iterations, 100);
+        assert_eq!(mcts.get_root().value().stats.visits, 100);
+        assert!(mcts.do_iteration().is_empty());
+        assert_eq!(mcts.get_statistics().iterations, 100);
+
+        stop.store(false, Ordering::Relaxed);
+        mcts.iterate_n_times(50);
+        assert_eq!(mcts.get_statistics().iterations, 150);
+    }
+
+    #[test]
+    fn interrupted_iteration_resumes_at_the_same_phase() {
+        // arrange
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.set_stop_condition(stop.clone());
+        mcts.iterate_n_times(10);
+        mcts.execute_action();
+        mcts.execute_action();
+
+        // act
+        stop.store(true, Ordering::Relaxed);
+        let interrupted_path = mcts.do_iteration();
+        stop.store(false, Ordering::Relaxed);
+        let resumed_path = mcts.do_iteration();
+
+        // assert
+        assert!(interrupted_path.is_empty());
+        assert!(!resumed_path.is_empty());
+        assert_eq!(mcts.get_root().value().stats.visits, 11);
+    }
+
+    #[test]
+    fn dead_end_is_resolved_instead_of_expanded_again() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(200);
+        let dead_end_id = mcts.get_root().children().nth(4).unwrap().id();
+        let grandchildren: Vec<_> = mcts
+            .tree
+            .get(dead_end_id)
+            .unwrap()
+            .children()
+            .map(|x| x.id())
+            .collect();
+        for child_id in &grandchildren {
+            let mut child = mcts.tree.get_mut(*child_id).unwrap();
+            child.value().stats.is_fully_calculated = true;
+        }
+
+        // act
+        let (selected_id, _) = mcts.select_next_node(mcts.root_id).unwrap();
+        mcts.iterate_n_times(100);
+
+        // assert
+        let selected = mcts.tree.get(selected_id).unwrap();
+        assert!(!selected.ancestors().any(|x| x.id() == dead_end_id));
+        let dead_end = mcts.tree.get(dead_end_id).unwrap();
+        assert!(dead_end.value().stats.is_fully_calculated);
+        assert_eq!(dead_end.children().count(), grandchildren.len());
+        assert_eq!(mcts.get_root().value().stats.visits, 300);
+    }
+
+    #[test]
+    fn search_ends_when_every_root_child_is_a_dead_end() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(100);
+        let grandchildren: Vec<_> = mcts
+            .get_root()
+            .children()
+            .flat_map(|x| x.children())
+            .map(|x| x.id())
+            .collect();
+        for child_id in grandchildren {
+            let mut child = mcts.tree.get_mut(child_id).unwrap();
+            child.value().stats.is_fully_calculated = true;
+        }
+
+        // act
+        mcts.do_iteration();
+
+        // assert
+        assert_eq!(
+            *mcts.get_next_mcts_action(),
+            MctsAction::EverythingIsCalculated
+        );
+        assert!(
+            mcts.get_root()
+                .children()
+                .all(|x| x.value().stats.is_fully_calculated)
+        );
+        assert_eq!(mcts.get_root().value().stats.visits, 100);
+    }
+
+    #[test]
+    fn finished_game_is_calculated_after_one_iteration() {
+        // arrange
+        let mut board = TicTacToeBoard::default();
+        for b_move in [0, 3, 1, 4, 2] {
+            board.perform_move(&b_move);
+        }
+        let mut mcts = MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(10);
+
+        // assert
+        assert_eq!(mcts.get_root().value().stats.visits, 1);
+        assert!(mcts.get_root().value().stats.is_fully_calculated);
+        assert_eq!(
+            *mcts.get_next_mcts_action(),
+            MctsAction::EverythingIsCalculated
+        );
+    }
+
+    #[test]
+    fn root_move_filter_restricts_root_children() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_root_move_filter(|_: &TicTacToeBoard, b_move: &u8| *b_move != 4)
+            .build();
+
+        // act
+        mcts.iterate_n_times(500);
+
+        // assert
+        let root_moves: Vec<_> = mcts
+            .get_root()
+            .children()
+            .map(|x| *x.value().prev_move().unwrap())
+            .collect();
+        assert_eq!(root_moves, vec![0, 1, 2, 3, 5, 6, 7, 8]);
+        assert!(mcts.get_root().children().any(|x| x.has_children()));
+        assert!(mcts.take_root_move_filter().is_some());
+    }
+
+    #[test]
+    fn root_move_filter_rejecting_everything_is_ignored() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.set_root_move_filter(|_: &TicTacToeBoard, _: &u8| false);
+
+        // act
+        mcts.iterate_n_times(10);
+
+        // assert
+        assert_eq!(mcts.get_root().children().count(), 9);
+    }
+
+    #[test]
+    fn tracks_value_variance() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(2000);
+
+        // assert
+        for node in mcts.get_tree().root().descendants() {
+            let mcts_node = node.value();
+            let wins_rate = mcts_node.wins_rate();
+            assert!((mcts_node.value_mean() - wins_rate).abs() < 1e-9);
+            assert!((mcts_node.value_variance() - wins_rate * (1.0 - wins_rate)).abs() < 1e-9);
         }
+    }
 
-        branch
+    #[test]
+    fn confidence_intervals_narrow_with_visits() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(2000);
+
+        // assert
+        let root = mcts.get_root();
+        let visits = root.value().stats.visits as f64;
+        let expected_error = (root.value().value_variance() / visits).sqrt();
+        assert!((root.value().value_standard_error() - expected_error).abs() < 1e-12);
+        let (low, high) = root.value().wilson_interval(1.96);
+        assert!(low < root.value().value_mean() && root.value().value_mean() < high);
+        assert!(high - low < 4.0 * 1.96 * expected_error);
+        for child in root.children() {
+            let (child_low, child_high) = child.value().wilson_interval(1.96);
+            assert!(0.0 <= child_low && child_high <= 1.0);
+            assert!(child_high - child_low > high - low);
+        }
+        let unvisited = MctsNode::new(0, TicTacToeBoard::default());
+        assert_eq!(unvisited.wilson_interval(1.96), (0.0, 1.0));
+        assert_eq!(unvisited.value_standard_error(), 0.0);
     }
 
-    /// Determines the bound of a node for alpha-beta pruning.
-    fn get_bound(&self, node_id: NodeId) -> Bound {
-        if !self.use_alpha_beta_pruning {
-            return Bound::None;
+    #[test]
+    fn ucb1_tuned_explores_less_than_ucb1() {
+        // arrange
+        let search = |selection_policy: SelectionPolicy| {
+            let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_alpha_beta_pruning(false)
+                .with_selection_policy(selection_policy)
+                .build();
+            mcts.iterate_n_times(3000);
+            mcts
+        };
+
+        // act
+        let ucb1 = search(SelectionPolicy::Ucb1);
+        let tuned = search(SelectionPolicy::Ucb1Tuned);
+
+        // assert
+        let most_visits = |mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>| {
+            let root = mcts.get_root();
+            root.children()
+                .map(|x| x.value().stats.visits)
+                .max()
+                .unwrap()
+        };
+        assert!(most_visits(&tuned) > most_visits(&ucb1));
+        assert_eq!(tuned.get_most_perspective_move(), Some(4));
+    }
+
+    #[test]
+    fn max_backups_rate_nodes_by_their_best_child() {
+        // arrange
+        let search = |backup_operator: BackupOperator| {
+            let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_alpha_beta_pruning(false)
+                .with_backup_operator(backup_operator)
+                .build();
+            mcts.iterate_n_times(1000);
+            mcts
+        };
+
+        // act
+        let average = search(BackupOperator::Average);
+        let max = search(BackupOperator::Max);
+        let mixed = search(BackupOperator::Mixed { threshold: 50.0 });
+
+        // assert
+        assert_eq!(max.get_backup_operator(), BackupOperator::Max);
+        assert!(average.get_root().value().stats.max_value.is_none());
+        for node in max.get_tree().root().descendants() {
+            let values = node
+                .children()
+                .filter(|x| x.value().stats.visits > 0)
+                .map(|x| x.value().stats.max_value.unwrap_or(x.value().value_mean()));
+            let expected = match node.value().current_player {
+                Player::Me => values.reduce(f64::max),
+                Player::Other => values.reduce(f64::min),
+            };
+            assert_eq!(node.value().stats.max_value, expected);
         }
+        let visits = |mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>| {
+            let root = mcts.get_root();
+            root.children()
+                .map(|x| x.value().stats.visits)
+                .collect::<Vec<_>>()
+        };
+        assert_ne!(visits(&average), visits(&max));
+        assert_ne!(visits(&mixed), visits(&max));
+        assert_eq!(mixed.get_root().value().stats.visits, 1000);
+    }
 
-        let node = self.tree.get(node_id).unwrap();
-        let mcts_node = node.value();
-        if mcts_node.bound != Bound::None {
-            return mcts_node.bound;
+    #[test]
+    fn warm_up_leaves_the_tree_untouched() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.warm_up(500, 10000);
+
+        // assert
+        assert_eq!(mcts.get_tree().root().descendants().count(), 1);
+        assert_eq!(mcts.get_root().value().stats.visits, 0);
+        assert_eq!(mcts.get_budget_report().total.iterations, 0);
+        assert_eq!(mcts.get_statistics().iterations, 0);
+
+        mcts.iterate_n_times(1000);
+        assert_eq!(mcts.get_root().value().stats.visits, 1000);
+        assert_eq!(mcts.get_budget_report().total.iterations, 1000);
+    }
+
+    #[test]
+    fn pruning_proven_subtrees_keeps_the_results() {
+        // arrange
+        let mut full = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let mut pruned = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_proven_subtree_pruning(true)
+            .build();
+
+        // act
+        full.iterate_n_times(20000);
+        pruned.iterate_n_times(20000);
+
+        // assert
+        let root_stats = |mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>| {
+            mcts.get_root()
+                .children()
+                .map(|x| (*x.value().prev_move().unwrap(), x.value().stats))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(root_stats(&pruned), root_stats(&full));
+        assert_eq!(
+            pruned.get_budget_report().total,
+            full.get_budget_report().total
+        );
+        assert_eq!(
+            pruned.get_most_perspective_move(),
+            full.get_most_perspective_move()
+        );
+        assert!(pruned.get_tree().nodes().len() * 2 < full.get_tree().nodes().len());
+        assert!(pruned.get_statistics().node_count < full.get_statistics().node_count);
+    }
+
+    #[test]
+    fn garbage_collection_keeps_the_search() {
+        // arrange
+        let mut full = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let mut collected = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        full.iterate_n_times(600);
+        collected.iterate_n_times(300);
+
+        // act
+        let dropped = collected.collect_garbage(5);
+        let node_count = collected.get_statistics().node_count;
+        collected.iterate_n_times(300);
+
+        // assert
+        let root_stats = |mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>| {
+            mcts.get_root()
+                .children()
+                .map(|x| (*x.value().prev_move().unwrap(), x.value().stats))
+                .collect::<Vec<_>>()
+        };
+        assert!(dropped > 0);
+        assert_eq!(collected.collect_garbage(u32::MAX), 0);
+        assert!(node_count < full.get_statistics().node_count);
+        assert_eq!(root_stats(&collected), root_stats(&full));
+        assert_eq!(
+            collected.get_most_perspective_move(),
+            full.get_most_perspective_move()
+        );
+    }
+
+    #[test]
+    fn periodic_garbage_collection_shrinks_the_tree() {
+        // arrange
+        let mut full = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let garbage_collection = GarbageCollection {
+            min_visits: 2,
+            interval: 100,
+        };
+        let mut collected = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_garbage_collection(garbage_collection)
+            .build();
+
+        // act
+        full.iterate_n_times(20000);
+        collected.iterate_n_times(20000);
+
+        // assert
+        assert_eq!(collected.get_garbage_collection(), Some(garbage_collection));
+        assert_eq!(
+            collected.get_config().garbage_collection,
+            Some(garbage_collection)
+        );
+        assert_eq!(
+            collected.get_most_perspective_move(),
+            full.get_most_perspective_move()
+        );
+        assert_eq!(
+            collected.get_root().value().stats.bound,
+            full.get_root().value().stats.bound
+        );
+    }
+
+    #[test]
+    fn single_child_expansion_creates_one_child_per_visit() {
+        // arrange
+        let search = |expansion_mode: ExpansionMode| {
+            MonteCarloTreeSearch::builder(TicTacToeBoard::new(11, 5))
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_expansion_mode(expansion_mode)
+                .build()
+        };
+        let mut single = search(ExpansionMode::SingleChild);
+        let mut all = search(ExpansionMode::AllChildren);
+
+        // act
+        single.iterate_n_times(50);
+        all.iterate_n_times(50);
+
+        // assert
+        let root = single.get_root();
+        assert_eq!(single.get_expansion_mode(), ExpansionMode::SingleChild);
+        assert_eq!(
+            single.get_config().expansion_mode,
+            ExpansionMode::SingleChild
+        );
+        assert_eq!(single.get_tree().nodes().len(), 51);
+        assert_eq!(root.children().count(), 50);
+        assert!(root.children().all(|x| x.value().stats.visits == 1));
+        let moves: Vec<_> = root
+            .children()
+            .map(|x| *x.value().prev_move().unwrap())
+            .collect();
+        assert_eq!(moves, (0..50).collect::<Vec<u8>>());
+        assert!(all.get_tree().nodes().len() > 50 * 120);
+    }
+
+    #[test]
+    fn single_child_expansion_solves_like_full_expansion() {
+        // arrange
+        let search = |board: TicTacToeBoard, expansion_mode: ExpansionMode| {
+            let mut mcts = MonteCarloTreeSearch::builder(board)
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_expansion_mode(expansion_mode)
+                .build();
+            mcts.iterate_n_times(20000);
+            mcts
+        };
+        let winning = TicTacToeBoard::from_moves(&[0, 3, 1, 4]);
+
+        // act
+        let single = search(TicTacToeBoard::default(), ExpansionMode::SingleChild);
+        let all = search(TicTacToeBoard::default(), ExpansionMode::AllChildren);
+        let single_winning = search(winning.clone(), ExpansionMode::SingleChild);
+
+        // assert
+        let root_stats = |mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>| {
+            let stats = mcts.get_root().value().stats;
+            (stats.bound, stats.is_fully_calculated)
+        };
+        assert_eq!(root_stats(&single), root_stats(&all));
+        assert_eq!(single.get_root().children().count(), 9);
+        assert_eq!(single_winning.get_most_perspective_move(), Some(2));
+        assert_eq!(
+            single_winning.get_root().value().stats.bound,
+            Bound::DefoWin
+        );
+    }
+
+    #[test]
+    fn merges_partially_expanded_searches() {
+        // arrange
+        let search = |seed: i64, iterations: u32| {
+            let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(CustomNumberGenerator::new(seed))
+                .with_expansion_mode(ExpansionMode::SingleChild)
+                .build();
+            mcts.iterate_n_times(iterations);
+            mcts
+        };
+        let mut mcts = search(1, 4);
+        let other = search(2, 7);
+
+        // act
+        mcts.merge(other);
+        let merged_children = mcts.get_root().children().count();
+        mcts.iterate_n_times(100);
+
+        // assert
+        let moves: Vec<_> = mcts
+            .get_root()
+            .children()
+            .map(|x| *x.value().prev_move().unwrap())
+            .collect();
+        assert_eq!(merged_children, 7);
+        assert_eq!(moves, (0..9).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn root_only_storage_matches_every_node_storage() {
+        // arrange
+        let mut every_node = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let mut root_only = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_board_storage(BoardStorage::RootOnly)
+            .build();
+
+        // act
+        every_node.iterate_n_times(5000);
+        root_only.iterate_n_times(5000);
+
+        // assert
+        let every_node_tree = every_node.get_tree();
+        let root_only_tree = root_only.get_tree();
+        assert!(root_only.get_root().value().board().is_some());
+        for (expected, actual) in every_node_tree
+            .root()
+            .descendants()
+            .zip(root_only_tree.root().descendants())
+        {
+            assert_eq!(expected.value().stats, actual.value().stats);
+            if actual.id() != root_only_tree.root().id() {
+                assert!(actual.value().board().is_none());
+            }
+            let expected_board = expected.value().board().unwrap();
+            assert_eq!(
+                root_only.get_board(actual.id()).get_hash(),
+                expected_board.get_hash()
+            );
         }
+    }
 
-        if mcts_node.outcome == GameOutcome::Win {
-            return Bound::DefoWin;
+    #[test]
+    fn draw_reward_prefers_a_forced_draw() {
+        // arrange
+        let mut board = TicTacToeBoard::default();
+        for b_move in [0, 4, 8, 5] {
+            board.perform_move(&b_move);
         }
+        let search = |draw_reward: f64| {
+            let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_draw_reward(draw_reward)
+                .build();
+            mcts.iterate_n_times(2000);
+            mcts
+        };
 
-        if mcts_node.outcome == GameOutcome::Lose {
-            return Bound::DefoLose;
+        // act
+        let losses_only = search(0.0);
+        let half_draws = search(0.5);
+
+        // assert
+        assert_ne!(losses_only.get_most_perspective_move(), Some(3));
+        assert_eq!(half_draws.get_most_perspective_move(), Some(3));
+        let block = half_draws
+            .get_root()
+            .children()
+            .find(|x| x.value().prev_move() == Some(&3));
+        assert!(block.unwrap().value().value_mean() >= 0.5);
+    }
+
+    #[test]
+    fn contempt_shades_the_draw_reward() {
+        // arrange
+        let mut board = TicTacToeBoard::default();
+        for b_move in [0, 4, 8, 5] {
+            board.perform_move(&b_move);
         }
+        let search = |draw_reward: f64, contempt: f64| {
+            let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_draw_reward(draw_reward)
+                .with_contempt(contempt)
+                .build();
+            mcts.iterate_n_times(2000);
+            mcts.get_most_perspective_move()
+        };
 
-        if node.children().count() == 0 {
-            return Bound::None;
+        // act
+        let avoiding_draws = search(0.5, 0.5);
+        let seeking_draws = search(0.0, -0.5);
+
+        // assert
+        assert_ne!(avoiding_draws, Some(3));
+        assert_eq!(seeking_draws, Some(3));
+    }
+
+    #[test]
+    fn selection_reports_the_path_to_the_leaf() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(100);
+
+        // act
+        mcts.execute_action();
+
+        // assert
+        let MctsAction::Expansion { L, SP } = mcts.get_next_mcts_action().clone() else {
+            panic!("expected an expansion");
+        };
+        assert!(SP.len() >= 2);
+        assert_eq!(SP.last().unwrap().node, L);
+        let mut parent = mcts.get_tree().root();
+        for step in &SP {
+            let node = mcts.get_tree().get(step.node).unwrap();
+            assert_eq!(node.parent().unwrap().id(), parent.id());
+            assert!(step.ucb.is_finite() && step.ucb > 0.0);
+            parent = node;
         }
+        let root_visits = mcts.get_root().value().stats.visits as f64;
+        let first = mcts.get_tree().get(SP[0].node).unwrap().value().stats;
+        let expected_ucb = first.value_sum / first.visits as f64
+            + std::f64::consts::SQRT_2 * (root_visits.ln() / first.visits as f64).sqrt();
+        assert!((SP[0].ucb - expected_ucb).abs() < 1e-12);
+    }
 
-        match mcts_node.current_player {
-            Player::Me => {
-                if node.children().all(|x| x.value().bound == Bound::DefoLose) {
-                    return Bound::DefoLose;
-                }
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_reports_phases_and_iterations() {
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
 
-                if node.children().any(|x| x.value().bound == Bound::DefoWin) {
-                    return Bound::DefoWin;
+        /// Records the names of spans and the messages of events.
+        #[derive(Clone, Default)]
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for Recorder {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    self.0.lock().unwrap().push(format!("{value:?}"));
                 }
             }
-            Player::Other => {
-                if node.children().all(|x| x.value().bound == Bound::DefoWin) {
-                    return Bound::DefoWin;
-                }
+        }
 
-                if node.children().any(|x| x.value().bound == Bound::DefoLose) {
-                    return Bound::DefoLose;
-                }
+        impl Subscriber for Recorder {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut names = self.0.lock().unwrap();
+                names.push(span.metadata().name().to_string());
+                Id::from_u64(names.len() as u64)
+            }
+
+            fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut self.clone());
             }
+
+            fn enter(&self, _span: &Id) {}
+
+            fn exit(&self, _span: &Id) {}
         }
 
-        Bound::None
-    }
+        // arrange
+        let recorder = Recorder::default();
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
 
-    /// Checks if a node can be considered fully calculated, meaning its outcome is certain.
-    fn is_fully_calculated(&self, node_id: NodeId, bound: Bound) -> bool {
-        if bound != Bound::None {
-            return true;
+        // act
+        tracing::subscriber::with_default(recorder.clone(), || mcts.iterate_n_times(5));
+
+        // assert
+        let records = recorder.0.lock().unwrap();
+        let count = |name: &str| records.iter().filter(|x| *x == name).count();
+        assert_eq!(count("iteration"), 5);
+        for phase in ["selection", "expansion", "simulation", "backpropagation"] {
+            assert_eq!(count(phase), 5, "{phase}");
         }
+        assert_eq!(count("expanded leaf"), 5);
+        assert_eq!(count("simulated playout"), 5);
+        assert_eq!(count("completed iteration"), 5);
+    }
 
-        let node = self.tree.get(node_id).unwrap();
-        if node.value().outcome != GameOutcome::InProgress {
-            return true;
+    #[test]
+    fn implicit_minimax_follows_the_heuristic() {
+        // arrange
+        let search = |weight: f64| {
+            let mut mcts = MonteCarloTreeSearch::builder(FirstMoveBoard::default())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_implicit_minimax(weight)
+                .build();
+            mcts.iterate_n_times(300);
+            mcts
+        };
+
+        // act
+        let averages_only = search(0.0);
+        let blended = search(0.8);
+
+        // assert
+        let most_visited = |mcts: &MonteCarloTreeSearch<_, _>| {
+            let root = mcts.get_root();
+            let child = root.children().max_by_key(|x| x.value().stats.visits);
+            *child.unwrap().value().prev_move().unwrap()
+        };
+        assert_ne!(most_visited(&averages_only), 8);
+        assert_eq!(most_visited(&blended), 8);
+        assert!(
+            averages_only
+                .get_root()
+                .value()
+                .stats
+                .minimax_value
+                .is_none()
+        );
+        for node in blended.get_tree().root().descendants() {
+            let values = node
+                .children()
+                .filter_map(|x| x.value().stats.minimax_value);
+            let expected = match node.value().current_player {
+                Player::Me => values.reduce(f64::max),
+                Player::Other => values.reduce(f64::min),
+            };
+            if node.value().stats.visits > 0 && expected.is_some() {
+                assert_eq!(node.value().stats.minimax_value, expected);
+            }
         }
+    }
 
-        if node.children().count() == 0 {
-            return false;
+    #[test]
+    fn reward_discount_prefers_the_fastest_win() {
+        // arrange
+        let mut board = TicTacToeBoard::default();
+        for b_move in [0, 1, 4, 7] {
+            board.perform_move(&b_move);
         }
+        let mut mcts = MonteCarloTreeSearch::builder(board)
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_reward_discount(0.9)
+            .build();
 
-        let all_children_calculated = node.children().all(|x| x.value().is_fully_calculated);
+        // act
+        mcts.iterate_n_times(2000);
 
-        all_children_calculated
+        // assert
+        assert_eq!(mcts.get_reward_discount(), 0.9);
+        assert_eq!(mcts.get_most_perspective_move(), Some(8));
+        let root = mcts.get_root();
+        let (wins, others): (Vec<_>, Vec<_>) = root
+            .children()
+            .partition(|x| x.value().prev_move() == Some(&8));
+        let win_value = wins[0].value().value_mean();
+        assert!((win_value - 0.95).abs() < 1e-9);
+        assert!(others.iter().all(|x| x.value().value_mean() < win_value));
     }
 
-    /// Calculates the UCB1 (Upper Confidence Bound 1) value for a node.
-    fn ucb_value(total_visits: i32, node_wins: i32, node_visit: i32) -> f64 {
-        const EXPLORATION_PARAMETER: f64 = std::f64::consts::SQRT_2;
+    #[test]
+    fn oracle_solves_nodes_with_bounds() {
+        // arrange
+        let center = TicTacToeBoard::from_moves(&[4]).get_hash();
+        let oracle =
+            move |board: &TicTacToeBoard| (board.get_hash() == center).then_some(GameOutcome::Win);
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_terminal_oracle(oracle)
+            .build();
 
-        if node_visit == 0 {
-            i32::MAX.into()
-        } else {
-            ((node_wins as f64) / (node_visit as f64))
-                + EXPLORATION_PARAMETER
-                    * f64::sqrt(f64::ln(total_visits as f64) / (node_visit as f64))
-        }
+        // act
+        mcts.iterate_n_times(100);
+
+        // assert
+        let root = mcts.get_root();
+        assert_eq!(root.value().stats.bound, Bound::DefoWin);
+        let center = root.children().find(|x| x.value().prev_move() == Some(&4));
+        assert!(center.unwrap().value().stats.is_fully_calculated);
+        assert_eq!(mcts.get_most_perspective_move(), Some(4));
     }
-}
 
-impl<T: Board> MonteCarloTreeSearch<T, StandardRandomGenerator> {
-    pub fn from_board(board: T) -> Self {
-        MonteCarloTreeSearchBuilder::new(board).build()
+    #[test]
+    fn oracle_short_circuits_playouts() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_alpha_beta_pruning(false)
+            .build();
+        mcts.set_terminal_oracle(|board: &TicTacToeBoard| {
+            (board.get_hash() != 0).then_some(GameOutcome::Draw)
+        });
+
+        // act
+        mcts.iterate_n_times(50);
+
+        // assert
+        let root = mcts.get_root();
+        assert_eq!(root.value().stats.draws, root.value().stats.visits);
+        assert!(root.children().all(|x| x.value().stats.is_fully_calculated));
+        assert_eq!(mcts.get_budget_report().total.playout_moves, 0);
+        assert!(mcts.take_terminal_oracle().is_some());
     }
-}
 
-/// Represents the four main stages of the MCTS algorithm.
-///
-/// This enum is used to manage the state of the search process.
-#[allow(non_snake_case)]
-#[derive(Debug, PartialEq, Clone)]
-pub enum MctsAction {
-    /// **Selection**: Start from the root `R` and select successive child nodes until a leaf node `L` is reached.
-    Selection {
-        /// The root of the current selection phase.
-        R: NodeId,
-        /// The path of nodes visited during the last backpropagation phase.
-        RP: Vec<NodeId>,
-    },
-    /// **Expansion**: Create one or more child nodes from the selected leaf node `L`.
-    Expansion {
-        /// The leaf node to be expanded.
-        L: NodeId,
-    },
-    /// **Simulation**: Run a random playout from a newly created child node `C`.
-    Simulation {
-        /// The child node from which the simulation will start.
-        C: NodeId,
-        /// All children created during the expansion phase.
-        AC: Vec<NodeId>,
-    },
-    /// **Backpropagation**: Update the statistics of the nodes on the path from `C` to the root `R`.
-    Backpropagation {
-        /// The child node from which the simulation was run.
-        C: NodeId,
-        /// The result of the simulation.
-        result: GameOutcome,
-    },
-    /// Represents a state where the entire tree has been explored and the outcome is certain.
-    EverythingIsCalculated,
-}
+    #[test]
+    fn annotations_survive_the_search() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let root_id = mcts.get_root().id();
+        mcts.set_annotation(root_id, "empty board");
+        mcts.warm_up(10, 1000);
+        mcts.iterate_n_times(10);
+        let center_id = mcts
+            .get_root()
+            .children()
+            .find(|x| x.value().prev_move() == Some(&4))
+            .unwrap()
+            .id();
 
-impl MctsAction {
-    /// Returns the name of the current MCTS action as a string.
-    pub fn get_name(&self) -> String {
-        match self {
-            MctsAction::Selection { R: _, RP: _ } => "Selection".to_string(),
-            MctsAction::Expansion { L: _ } => "Expansion".to_string(),
-            MctsAction::Simulation { C: _, AC: _ } => "Simulation".to_string(),
-            MctsAction::Backpropagation { C: _, result: _ } => "Backpropagation".to_string(),
-            MctsAction::EverythingIsCalculated => "EverythingIsCalculated".to_string(),
+        // act
+        let previous = mcts.set_annotation(center_id, 1u32);
+        let replaced = mcts.set_annotation(center_id, 2u32);
+        mcts.set_annotation(center_id, "center");
+        mcts.iterate_n_times(1000);
+
+        // assert
+        assert_eq!(previous, None);
+        assert_eq!(replaced, Some(1));
+        let tree = mcts.get_tree();
+        let center = tree.get(center_id).unwrap().value();
+        assert_eq!(center.annotation::<u32>(), Some(&2));
+        assert_eq!(center.annotation::<&str>(), Some(&"center"));
+        assert_eq!(center.annotation::<i64>(), None);
+        assert_eq!(
+            tree.root().value().annotation::<&str>(),
+            Some(&"empty board")
+        );
+        assert_eq!(mcts.remove_annotation::<u32>(center_id), Some(2));
+        assert_eq!(mcts.remove_annotation::<u32>(center_id), None);
+    }
+
+    #[test]
+    fn unpruned_children_grow_with_visits() {
+        let unpruning = ProgressiveUnpruning {
+            initial_children: 2,
+            threshold: 10.0,
+            growth: 2.0,
+        };
+        assert_eq!(unpruning.unpruned_children(0), 2);
+        assert_eq!(unpruning.unpruned_children(9), 2);
+        assert_eq!(unpruning.unpruned_children(10), 3);
+        assert_eq!(unpruning.unpruned_children(19), 3);
+        assert_eq!(unpruning.unpruned_children(20), 4);
+        assert_eq!(unpruning.unpruned_children(80), 6);
+    }
+
+    #[test]
+    fn progressive_unpruning_searches_the_best_priors_first() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(FirstMoveBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_progressive_unpruning(ProgressiveUnpruning {
+                initial_children: 2,
+                threshold: 100.0,
+                growth: 2.0,
+            })
+            .build();
+        let visited_moves = |mcts: &MonteCarloTreeSearch<_, _>| -> Vec<u8> {
+            let root = mcts.get_root();
+            let visited = root.children().filter(|x| x.value().stats.visits > 0);
+            visited.map(|x| *x.value().prev_move().unwrap()).collect()
+        };
+
+        // act
+        mcts.iterate_n_times(99);
+        let early = visited_moves(&mcts);
+        mcts.iterate_n_times(102);
+        let late = visited_moves(&mcts);
+
+        // assert
+        assert_eq!(mcts.get_root().children().count(), 9);
+        assert_eq!(early, vec![8, 7]);
+        assert_eq!(late, vec![8, 7, 6, 5]);
+    }
+
+    #[test]
+    fn cycle_policy_decides_repeated_playouts() {
+        // arrange
+        let search = |cycle_policy: CyclePolicy| {
+            let mut mcts = MonteCarloTreeSearch::builder(RingBoard::default())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_cycle_policy(cycle_policy)
+                .build();
+            mcts.iterate_n_times(20);
+            mcts
+        };
+
+        // act
+        let draws = search(CyclePolicy::default());
+        let losses = search(CyclePolicy::TreatAsLoss);
+        let limited = search(CyclePolicy::MaxPlayoutLength(10));
+
+        // assert
+        assert_eq!(draws.get_cycle_policy(), CyclePolicy::TreatAsDraw);
+        assert_eq!(draws.get_root().value().stats.draws, 20);
+        assert_eq!(losses.get_root().value().stats.wins, 0);
+        assert_eq!(losses.get_root().value().stats.draws, 0);
+        assert_eq!(limited.get_root().value().stats.draws, 20);
+        assert_eq!(limited.get_budget_report().total.playout_moves, 20 * 10);
+    }
+
+    #[test]
+    fn thompson_sampling_finds_the_same_move_as_ucb1() {
+        // arrange
+        // X must block the middle column.
+        let board = TicTacToeBoard::from_moves(&[0, 4, 8, 1]);
+        let search = |selection_policy: SelectionPolicy| {
+            let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_selection_policy(selection_policy)
+                .with_best_child_criterion(BestChildCriterion::MaxVisits)
+                .build();
+            mcts.iterate_n_times(2000);
+            mcts.execute_action();
+            mcts
+        };
+
+        // act
+        let ucb1 = search(SelectionPolicy::Ucb1);
+        let thompson = search(SelectionPolicy::Thompson);
+
+        // assert
+        assert_eq!(thompson.get_selection_policy(), SelectionPolicy::Thompson);
+        assert_eq!(ucb1.get_most_perspective_move(), Some(7));
+        assert_eq!(thompson.get_most_perspective_move(), Some(7));
+        if let MctsAction::Expansion { SP, .. } = thompson.get_next_mcts_action() {
+            assert!(SP.iter().all(|x| (0.0..=1.0).contains(&x.ucb)));
         }
     }
-}
 
-pub struct MctsTreeNode<'a, T: Board>(pub NodeRef<'a, MctsNode<T>>);
+    #[test]
+    fn node_ids_are_unique_and_increasing() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
 
-impl<'a, T: Board> Deref for MctsTreeNode<'a, T> {
-    type Target = NodeRef<'a, MctsNode<T>>;
+        // act
+        mcts.warm_up(100, 0);
+        mcts.iterate_n_times(500);
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+        // assert
+        let tree = mcts.get_tree();
+        let mut ids: Vec<_> = tree.root().descendants().map(|x| x.value().id).collect();
+        let node_count = ids.len();
+        assert_eq!(tree.root().value().id, 0);
+        assert!(tree.root().descendants().all(|node| {
+            node.children()
+                .all(|child| child.value().id > node.value().id)
+        }));
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), node_count);
+        assert_eq!(*ids.last().unwrap(), node_count as i32 - 1);
     }
-}
 
-impl<'a, T: Board> DerefMut for MctsTreeNode<'a, T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+    #[test]
+    fn hash_verification_detects_collisions() {
+        // arrange
+        let board = CollidingBoard::default();
+        let hash_calls = board.hash_calls.clone();
+        let search = |verify_hashes: bool| {
+            let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_hash_width(HashWidth::Bits64)
+                .with_hash_verification(verify_hashes)
+                .build();
+            mcts.iterate_n_times(200);
+            mcts
+        };
+
+        // act
+        let unverified = search(false);
+        let verified = search(true);
+
+        // assert
+        assert_eq!(hash_calls.load(Ordering::Relaxed), 0);
+        assert_eq!(unverified.get_statistics().hash_collisions, 0);
+        assert!(verified.get_statistics().hash_collisions > 0);
+        let draws = |mcts: &MonteCarloTreeSearch<_, _>| mcts.get_root().value().stats.draws;
+        assert!(draws(&verified) < draws(&unverified));
     }
-}
 
-impl<'a, T: Board> Into<NodeRef<'a, MctsNode<T>>> for MctsTreeNode<'a, T> {
-    fn into(self) -> NodeRef<'a, MctsNode<T>> {
-        self.0
+    #[test]
+    fn outcome_is_computed_once_per_state() {
+        // arrange
+        let board = CountingBoard::default();
+        let outcome_calls = board.outcome_calls.clone();
+        let mut mcts = MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(200);
+
+        // assert
+        assert_eq!(outcome_calls.load(Ordering::Relaxed), 1);
+        assert!(mcts.get_statistics().node_count > 200);
     }
-}
 
-impl<'a, T: Board> From<NodeRef<'a, MctsNode<T>>> for MctsTreeNode<'a, T> {
-    fn from(node: NodeRef<'a, MctsNode<T>>) -> Self {
-        Self(node)
+    #[test]
+    fn parameters_can_change_between_iterations() {
+        // arrange
+        let search = |late_exploration_constant: f64| {
+            let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(CustomNumberGenerator::default())
+                .with_alpha_beta_pruning(false)
+                .with_exploration_constant(5.0)
+                .build();
+            mcts.iterate_n_times(300);
+            mcts.set_exploration_constant(late_exploration_constant);
+            mcts.iterate_n_times(2000);
+            mcts
+        };
+
+        // act
+        let steady = search(5.0);
+        let adapted = search(0.1);
+
+        // assert
+        assert_eq!(adapted.get_exploration_constant(), 0.1);
+        let most_visits = |mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>| {
+            let root = mcts.get_root();
+            root.children()
+                .map(|x| x.value().stats.visits)
+                .max()
+                .unwrap()
+        };
+        assert!(most_visits(&adapted) > most_visits(&steady) * 2);
     }
-}
 
-impl<'a, T: Board> MctsTreeNode<'a, T> {
-    /// Returns the child of the given node that is considered the most promising, based on win rate.
-    pub fn get_best_child(&self) -> Option<MctsTreeNode<'a, T>> {
-        let mut best_child = None;
-        let mut best_child_value = f64::MIN;
+    #[test]
+    fn max_nodes_caps_the_tree() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_max_nodes(100)
+            .build();
 
-        // get the best child amount with DefoWin bound
-        for child in self
-            .children()
-            .filter(|x| x.value().bound == Bound::DefoWin)
-        {
-            let child_value = child.value().wins_rate();
-            if child_value > best_child_value {
-                best_child = Some(child);
-                best_child_value = child_value;
-            }
+        // act
+        mcts.iterate_n_times(1000);
+        let capped_size = mcts.get_statistics().node_count;
+        mcts.set_max_nodes(None);
+        mcts.iterate_n_times(100);
+
+        // assert
+        assert!((100..100 + 9).contains(&capped_size));
+        assert_eq!(mcts.get_root().value().stats.visits, 1100);
+        assert!(mcts.get_statistics().node_count > capped_size);
+        assert_eq!(mcts.get_max_nodes(), None);
+    }
+
+    #[test]
+    fn search_root_focuses_the_iterations() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(200);
+        let visits = |mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>| {
+            let root = mcts.get_root();
+            root.children()
+                .map(|x| x.value().stats.visits)
+                .collect::<Vec<_>>()
+        };
+        let visits_before = visits(&mcts);
+        let center_id = mcts.get_root().children().nth(4).unwrap().id();
+
+        // act
+        mcts.set_search_root(center_id);
+        mcts.iterate_n_times(300);
+        let search_root_id = mcts.get_search_root().id();
+        mcts.restore_search_root();
+        mcts.iterate_n_times(100);
+
+        // assert
+        assert_eq!(search_root_id, center_id);
+        assert_eq!(mcts.get_search_root().id(), mcts.get_root().id());
+        let visits_after = visits(&mcts);
+        assert!(visits_after[4] >= visits_before[4] + 300);
+        assert_eq!(mcts.get_root().value().stats.visits, 600);
+        assert_eq!(visits_after.iter().sum::<u32>(), 600);
+    }
+
+    #[test]
+    fn search_root_subtree_can_be_solved_alone() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_proven_subtree_pruning(true)
+            .build();
+        mcts.iterate_n_times(20);
+        let center_id = mcts.get_root().children().nth(4).unwrap().id();
+
+        // act
+        mcts.set_search_root(center_id);
+        while !matches!(
+            mcts.get_next_mcts_action(),
+            MctsAction::EverythingIsCalculated
+        ) {
+            mcts.do_iteration();
         }
 
-        if best_child.is_some() {
-            return best_child.map(|x| x.into());
+        // assert
+        assert!(mcts.get_search_root().value().stats.is_fully_calculated);
+        assert!(!mcts.get_root().value().stats.is_fully_calculated);
+        mcts.restore_search_root();
+        assert!(!mcts.do_iteration().is_empty());
+    }
+
+    #[test]
+    fn finds_nodes_by_their_moves() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(500);
+        let line = mcts
+            .get_tree()
+            .root()
+            .descendants()
+            .find(|x| x.value().height - mcts.get_root().value().height == 3)
+            .unwrap();
+
+        // act
+        let mut moves: Vec<_> = line
+            .ancestors()
+            .filter_map(|x| x.value().prev_move().copied())
+            .collect();
+        moves.reverse();
+        moves.push(*line.value().prev_move().unwrap());
+        let found = mcts.find_node(&moves);
+
+        // assert
+        assert_eq!(found, Some(line.id()));
+        assert_eq!(mcts.find_node(&[]), Some(mcts.get_root().id()));
+        assert_eq!(mcts.find_node(&[4, 4]), None);
+    }
+
+    #[test]
+    fn merges_the_statistics_of_another_search() {
+        // arrange
+        let search = |seed: i64, iterations: u32, board_storage: BoardStorage| {
+            let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(CustomNumberGenerator::new(seed))
+                .with_alpha_beta_pruning(false)
+                .with_board_storage(board_storage)
+                .build();
+            mcts.iterate_n_times(iterations);
+            mcts
+        };
+        let root_visits = |mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>| {
+            mcts.get_root()
+                .children()
+                .map(|x| (*x.value().prev_move().unwrap(), x.value().stats.visits))
+                .collect::<HashMap<_, _>>()
+        };
+        let mut mcts = search(1, 20, BoardStorage::EveryNode);
+        let other = search(2, 500, BoardStorage::RootOnly);
+        let (visits, other_visits) = (root_visits(&mcts), root_visits(&other));
+        let deep_node = other
+            .get_tree()
+            .root()
+            .descendants()
+            .find(|x| x.value().height == 4)
+            .unwrap();
+        let mut line: Vec<_> = deep_node
+            .ancestors()
+            .filter_map(|x| x.value().prev_move().copied())
+            .collect();
+        line.reverse();
+        line.push(*deep_node.value().prev_move().unwrap());
+        let deep_visits = deep_node.value().stats.visits;
+
+        // act
+        mcts.merge(other);
+
+        // assert
+        assert_eq!(mcts.get_root().value().stats.visits, 520);
+        assert_eq!(mcts.get_search_result().iterations, 520);
+        for (b_move, merged) in root_visits(&mcts) {
+            assert_eq!(merged, visits[&b_move] + other_visits[&b_move]);
         }
+        let copied = mcts.find_node(&line).unwrap();
+        let copied = mcts.get_tree().get(copied).unwrap();
+        assert_eq!(copied.value().stats.visits, deep_visits);
+        assert_eq!(
+            copied.value().board().map(|x| x.get_hash()),
+            Some(TicTacToeBoard::from_moves(&line).get_hash())
+        );
+        mcts.iterate_n_times(100);
+        assert_eq!(mcts.get_root().value().stats.visits, 620);
+    }
+
+    #[test]
+    #[should_panic(expected = "only searches of the same root position can be merged")]
+    fn merging_needs_the_same_root() {
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let other = MonteCarloTreeSearch::builder(TicTacToeBoard::from_moves(&[4]))
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.merge(other);
+    }
+
+    #[test]
+    fn queries_the_tree() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(500);
+        let root = mcts.get_root();
+
+        // act
+        let heights: Vec<_> = root.breadth_first().map(|x| x.value().height).collect();
+        let second_level = root.nodes_at_depth(2).count();
+        let busy_nodes: Vec<_> = root.filter_nodes(|x| x.stats.visits > 50).collect();
+        let sorted = root.children_sorted_by_visits();
+
+        // assert
+        assert_eq!(heights.len(), mcts.get_statistics().node_count);
+        assert!(heights.is_sorted());
+        let expected_second_level: usize = root.children().map(|x| x.children().count()).sum();
+        assert_eq!(second_level, expected_second_level);
+        assert_eq!(busy_nodes[0].id(), root.id());
+        assert!(busy_nodes.iter().all(|x| x.value().stats.visits > 50));
+        assert!(busy_nodes.len() > 1);
+        assert_eq!(sorted.len(), 9);
+        assert!(sorted.is_sorted_by_key(|x| std::cmp::Reverse(x.value().stats.visits)));
+        let most_visited = root
+            .get_best_child_by(BestChildCriterion::MaxVisits)
+            .unwrap();
+        assert_eq!(sorted[0].id(), most_visited.id());
+    }
+
+    /// Rates every position as a sure win for the player to move, recording the batches and the
+    /// player to move of every position.
+    #[derive(Default)]
+    struct RecordingEvaluator {
+        batches: Vec<Vec<u128>>,
+        players: Vec<Player>,
+    }
 
-        // get the best child overall
-        for child in self.children() {
-            let child_value = child.value().wins_rate();
-            if child_value > best_child_value {
-                best_child = Some(child);
-                best_child_value = child_value;
+    impl Evaluator<TicTacToeBoard> for RecordingEvaluator {
+        fn evaluate(&mut self, board: &TicTacToeBoard) -> Evaluation {
+            self.players.push(board.get_current_player());
+            Evaluation {
+                priors: vec![0.0; board.get_available_moves().len()],
+                value: 1.0,
             }
         }
 
-        best_child.map(|x| x.into())
+        fn evaluate_batch(&mut self, boards: &[TicTacToeBoard]) -> Vec<Evaluation> {
+            self.batches
+                .push(boards.iter().map(|x| x.get_hash()).collect());
+            boards.iter().map(|x| self.evaluate(x)).collect()
+        }
+    }
+
+    #[test]
+    fn batched_iterations_evaluate_diverging_leaves() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let mut evaluator = RecordingEvaluator::default();
+
+        // act
+        mcts.iterate_batched(&mut evaluator, 8, 200);
+
+        // assert
+        let root = mcts.get_root();
+        assert_eq!(mcts.get_statistics().iterations, 200);
+        assert_eq!(root.value().stats.visits, 200);
+        let child_visits: u32 = root.children().map(|x| x.value().stats.visits).sum();
+        assert_eq!(child_visits, 200);
+        assert!(evaluator.batches.len() >= 25);
+        assert!(evaluator.batches.iter().all(|x| x.len() <= 8));
+        let mut first_batch = evaluator.batches[0].clone();
+        first_batch.sort();
+        first_batch.dedup();
+        assert_eq!(first_batch.len(), 8);
+    }
+
+    #[test]
+    fn batched_iterations_back_up_values_for_me() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let mut evaluator = RecordingEvaluator::default();
+
+        // act
+        mcts.iterate_batched(&mut evaluator, 4, 12);
+
+        // assert
+        let stats = mcts.get_root().value().stats;
+        let my_positions = evaluator
+            .players
+            .iter()
+            .filter(|x| **x == Player::Me)
+            .count();
+        assert_eq!(evaluator.players.len(), 12);
+        assert_eq!(stats.value_sum, my_positions as f64);
+        assert_eq!(stats.wins, 0);
+    }
+
+    #[test]
+    fn batched_iterations_evaluate_a_repeated_leaf_once() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_max_nodes(1)
+            .build();
+        let mut evaluator = RecordingEvaluator::default();
+
+        // act
+        mcts.iterate_batched(&mut evaluator, 4, 8);
+
+        // assert
+        let stats = mcts.get_root().value().stats;
+        assert_eq!(stats.visits, 8);
+        assert_eq!(stats.value_sum, 8.0);
+        assert_eq!(stats.wins, 0);
+        assert_eq!(
+            evaluator.batches,
+            vec![vec![mcts.get_board(mcts.get_root().id()).get_hash()]; 2]
+        );
+    }
+
+    #[test]
+    fn batched_iterations_run_every_iteration_despite_pruning() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_proven_subtree_pruning(true)
+            .build();
+        let mut evaluator = RecordingEvaluator::default();
+
+        // act
+        mcts.iterate_batched(&mut evaluator, 16, 3000);
+
+        // assert
+        assert_eq!(mcts.get_statistics().iterations, 3000);
+        assert_eq!(mcts.get_root().value().stats.visits, 3000);
+    }
+
+    #[test]
+    fn batched_iterations_back_up_finished_games() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::from_moves(&[0, 3, 1, 4]))
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        let mut evaluator = |board: &TicTacToeBoard| Evaluation {
+            priors: vec![0.0; board.get_available_moves().len()],
+            value: 0.5,
+        };
+
+        // act
+        mcts.iterate_batched(&mut evaluator, 4, 500);
+
+        // assert
+        let best = mcts.get_root().get_best_child().unwrap();
+        assert_eq!(best.value().prev_move(), Some(&2));
+        assert_eq!(best.value().stats.bound, Bound::DefoWin);
     }
 }
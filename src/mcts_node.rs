@@ -1,4 +1,68 @@
 use crate::board::{Board, Bound, GameOutcome, Player};
+use std::any::{Any, TypeId};
+use std::fmt::{Debug, Formatter};
+
+/// The search statistics of a node.
+///
+/// These are the only fields read for every child during selection, so they are kept small and
+/// stored inline in the tree, while the game state lives behind a separate allocation.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct NodeStats {
+    /// The number of times this node has been visited during the search.
+    pub visits: u32,
+    /// The number of times simulations from this node have resulted in a win for the current player.
+    pub wins: u32,
+    /// The number of times simulations from this node have resulted in a draw.
+    pub draws: u32,
+    /// The sum of the values backed up through this node.
+    pub value_sum: f64,
+    /// The sum of the squares of the values backed up through this node.
+    pub value_sum_sq: f64,
+    /// The bound of the node, used for alpha-beta pruning.
+    pub bound: Bound,
+    /// A flag indicating whether the outcome of this node is definitively known.
+    pub is_fully_calculated: bool,
+    /// The minimax value of the heuristic estimates below this node, maintained by implicit
+    /// minimax backups.
+    pub minimax_value: Option<f64>,
+    /// The value of the best visited child of this node, maintained by max and mixed backups.
+    pub max_value: Option<f64>,
+}
+
+/// Rarely accessed per-node data, kept out of the tree's node storage.
+#[derive(Debug, Clone)]
+struct NodeData<B, M> {
+    board: Option<B>,
+    prev_move: Option<M>,
+    annotations: Annotations,
+}
+
+/// A value of any cloneable type attached to a node by the user.
+trait Annotation: Any + Send {
+    fn clone_box(&self) -> Box<dyn Annotation>;
+}
+
+impl<A: Any + Clone + Send> Annotation for A {
+    fn clone_box(&self) -> Box<dyn Annotation> {
+        Box::new(self.clone())
+    }
+}
+
+/// The annotations of a node, at most one per type.
+#[derive(Default)]
+struct Annotations(Vec<(TypeId, Box<dyn Annotation>)>);
+
+impl Clone for Annotations {
+    fn clone(&self) -> Self {
+        Self(self.0.iter().map(|(id, x)| (*id, x.clone_box())).collect())
+    }
+}
+
+impl Debug for Annotations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Annotations({})", self.0.len())
+    }
+}
 
 /// Represents a single node in the Monte Carlo search tree.
 ///
@@ -6,65 +70,209 @@ use crate::board::{Board, Bound, GameOutcome, Player};
 /// and information about the move that led to this state.
 #[derive(Debug, Clone)]
 pub struct MctsNode<T: Board> {
-    /// A unique identifier for the node.
+    /// The search statistics of the node.
+    pub stats: NodeStats,
+    /// A unique identifier for the node, allocated in increasing order by the search, `0` for the
+    /// initial root.
     pub id: i32,
     /// The depth of the node in the tree.
     pub height: i32,
-    /// The game state that this node represents.
-    pub board: Box<T>,
-    /// The move that led to this node's state from its parent. `None` for the root node.
-    pub prev_move: Option<T::Move>,
     /// The player whose turn it is in this node's game state.
     pub current_player: Player,
     /// The outcome of the game at this node, if it is terminal.
     pub outcome: GameOutcome,
-    /// The number of times this node has been visited during the search.
-    pub visits: i32,
-    /// The number of times simulations from this node have resulted in a win for the current player.
-    pub wins: i32,
-    /// The number of times simulations from this node have resulted in a draw.
-    pub draws: i32,
-    /// The bound of the node, used for alpha-beta pruning.
-    pub bound: Bound,
-    /// A flag indicating whether the outcome of this node is definitively known.
-    pub is_fully_calculated: bool,
+    data: Box<NodeData<T, T::Move>>,
 }
 
 impl<T: Board> MctsNode<T> {
     /// Creates a new `MctsNode` with the given ID and board state.
-    pub fn new(id: i32, boxed_board: Box<T>) -> Self {
-        let player = boxed_board.get_current_player();
-        let outcome = boxed_board.get_outcome();
+    pub fn new(id: i32, board: T) -> Self {
+        Self::with_move(id, board, None, 0)
+    }
+
+    /// Creates a new `MctsNode` for the state reached by `prev_move`, at the given depth.
+    pub fn with_move(id: i32, board: T, prev_move: Option<T::Move>, height: i32) -> Self {
+        let outcome = board.get_outcome();
+        Self::with_outcome(id, board, prev_move, height, outcome)
+    }
+
+    /// Creates a new `MctsNode` for a state whose outcome is already known, without calling
+    /// `Board::get_outcome` again.
+    pub(crate) fn with_outcome(
+        id: i32,
+        board: T,
+        prev_move: Option<T::Move>,
+        height: i32,
+        outcome: GameOutcome,
+    ) -> Self {
+        let player = board.get_current_player();
         MctsNode {
+            stats: NodeStats::default(),
             id,
-            height: 0,
-            board: boxed_board,
-            prev_move: None,
+            height,
             current_player: player,
             outcome,
-            visits: 0,
-            wins: 0,
-            draws: 0,
-            bound: Bound::None,
-            is_fully_calculated: false,
+            data: Box::new(NodeData {
+                board: Some(board),
+                prev_move,
+                annotations: Annotations::default(),
+            }),
         }
     }
 
+    /// The game state that this node represents.
+    ///
+    /// Returns `None` if the search does not store boards in this node, see `BoardStorage`.
+    /// `MonteCarloTreeSearch::get_board` works for every node.
+    pub fn board(&self) -> Option<&T> {
+        self.data.board.as_ref()
+    }
+
+    /// Drops the stored game state, keeping only the move that led to it.
+    pub(crate) fn drop_board(&mut self) {
+        self.data.board = None;
+    }
+
+    /// Stores a copy of the game state in the node.
+    pub(crate) fn set_board(&mut self, board: T) {
+        self.data.board = Some(board);
+    }
+
+    /// Returns the bytes the node keeps outside the node storage of the tree, split into those of
+    /// its stored board and those of the rest of its data, counting boards and annotations by their
+    /// inline size.
+    pub(crate) fn get_data_size(&self) -> (usize, usize) {
+        let board = match self.data.board {
+            Some(_) => size_of::<Option<T>>(),
+            None => 0,
+        };
+        let annotations =
+            self.data.annotations.0.capacity() * size_of::<(TypeId, Box<dyn Annotation>)>();
+        (
+            board,
+            size_of::<NodeData<T, T::Move>>() - board + annotations,
+        )
+    }
+
+    /// The move that led to this node's state from its parent. `None` for the root node.
+    pub fn prev_move(&self) -> Option<&T::Move> {
+        self.data.prev_move.as_ref()
+    }
+
+    /// Returns the annotation of type `A` attached to this node, if any.
+    pub fn annotation<A: Any>(&self) -> Option<&A> {
+        let (_, annotation) = self
+            .data
+            .annotations
+            .0
+            .iter()
+            .find(|(id, _)| *id == TypeId::of::<A>())?;
+        (annotation.as_ref() as &dyn Any).downcast_ref()
+    }
+
+    /// Returns a mutable reference to the annotation of type `A` attached to this node, if any.
+    pub fn annotation_mut<A: Any>(&mut self) -> Option<&mut A> {
+        let annotations = &mut self.data.annotations.0;
+        let (_, annotation) = annotations
+            .iter_mut()
+            .find(|(id, _)| *id == TypeId::of::<A>())?;
+        (annotation.as_mut() as &mut dyn Any).downcast_mut()
+    }
+
+    /// Attaches an annotation to this node, returning the previous annotation of the same type.
+    ///
+    /// A node holds at most one annotation per type, so wrapping values in dedicated types keeps
+    /// the annotations of different tools apart. Annotations are cloned with the node and kept
+    /// through the whole search, but they are not part of the interchange format.
+    pub fn set_annotation<A: Any + Clone + Send>(&mut self, annotation: A) -> Option<A> {
+        let previous = self.remove_annotation::<A>();
+        self.data
+            .annotations
+            .0
+            .push((TypeId::of::<A>(), Box::new(annotation)));
+        previous
+    }
+
+    /// Removes the annotation of type `A` from this node, returning it.
+    pub fn remove_annotation<A: Any>(&mut self) -> Option<A> {
+        let annotations = &mut self.data.annotations.0;
+        let index = annotations
+            .iter()
+            .position(|(id, _)| *id == TypeId::of::<A>())?;
+        let (_, annotation) = annotations.swap_remove(index);
+        let annotation: Box<dyn Any> = annotation;
+        annotation.downcast().ok().map(|x| *x)
+    }
+
     /// Calculates the win rate of this node.
     pub fn wins_rate(&self) -> f64 {
-        if self.visits == 0 {
+        if self.stats.visits == 0 {
             0.0
         } else {
-            (self.wins as f64) / (self.visits as f64)
+            (self.stats.wins as f64) / (self.stats.visits as f64)
+        }
+    }
+
+    /// Calculates the mean of the values backed up through this node.
+    pub fn value_mean(&self) -> f64 {
+        if self.stats.visits == 0 {
+            0.0
+        } else {
+            self.stats.value_sum / (self.stats.visits as f64)
+        }
+    }
+
+    /// Calculates the variance of the values backed up through this node.
+    ///
+    /// This is the population variance of all backed-up values, `0.0` for unvisited nodes.
+    pub fn value_variance(&self) -> f64 {
+        if self.stats.visits == 0 {
+            return 0.0;
         }
+
+        let mean = self.value_mean();
+        let mean_of_squares = self.stats.value_sum_sq / (self.stats.visits as f64);
+        (mean_of_squares - mean * mean).max(0.0)
+    }
+
+    /// Calculates the standard error of the value mean, `sqrt(variance / visits)`, which shrinks
+    /// as the node gathers visits. Returns `0.0` for unvisited nodes.
+    pub fn value_standard_error(&self) -> f64 {
+        if self.stats.visits == 0 {
+            return 0.0;
+        }
+
+        (self.value_variance() / self.stats.visits as f64).sqrt()
+    }
+
+    /// Returns the Wilson score interval of the value mean, which holds the true value with the
+    /// confidence given by the standard normal quantile `z`, e.g. `1.96` for 95%.
+    ///
+    /// The value mean is treated as a success rate, so the interval stays within `[0, 1]` and
+    /// remains meaningful for nodes with few visits or a mean of exactly `0` or `1`. Values other
+    /// than wins and losses only make it wider than needed. Returns `(0.0, 1.0)` for unvisited
+    /// nodes.
+    pub fn wilson_interval(&self, z: f64) -> (f64, f64) {
+        if self.stats.visits == 0 {
+            return (0.0, 1.0);
+        }
+
+        let visits = self.stats.visits as f64;
+        let mean = self.value_mean().clamp(0.0, 1.0);
+        let z_squared = z * z;
+        let denominator = 1.0 + z_squared / visits;
+        let center = (mean + z_squared / (2.0 * visits)) / denominator;
+        let margin = z / denominator
+            * (mean * (1.0 - mean) / visits + z_squared / (4.0 * visits * visits)).sqrt();
+        ((center - margin).max(0.0), (center + margin).min(1.0))
     }
 
     /// Calculates the draw rate of this node.
     pub fn draws_rate(&self) -> f64 {
-        if self.visits == 0 {
+        if self.stats.visits == 0 {
             0.0
         } else {
-            (self.draws as f64) / (self.visits as f64)
+            (self.stats.draws as f64) / (self.stats.visits as f64)
         }
     }
 }
@@ -0,0 +1,89 @@
+/// The counters reported to `Metrics` after every completed iteration.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct IterationMetrics {
+    /// The number of iterations completed so far, including this one.
+    pub iteration: u64,
+    /// The number of nodes created by the expansion of this iteration.
+    pub nodes_created: usize,
+    /// The number of moves played in the simulation of this iteration.
+    pub playout_length: u64,
+    /// The number of nodes in the tree after this iteration.
+    pub tree_size: usize,
+    /// The number of nodes proven to be a win or a loss during this iteration.
+    pub proven_nodes: usize,
+}
+
+/// Receives the counters of a running search, e.g. to export them to Prometheus or StatsD.
+///
+/// Any `FnMut(&IterationMetrics) + Send` closure is a `Metrics` sink, so a closure forwarding to
+/// the client of a metrics backend is usually all that is needed.
+pub trait Metrics: Send {
+    /// Records the counters of a completed iteration.
+    fn record(&mut self, metrics: &IterationMetrics);
+}
+
+impl<F: FnMut(&IterationMetrics) + Send> Metrics for F {
+    fn record(&mut self, metrics: &IterationMetrics) {
+        self(metrics)
+    }
+}
+
+/// A `Metrics` sink adding up the counters of every iteration.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct MetricsTotals {
+    /// The number of completed iterations.
+    pub iterations: u64,
+    /// The number of nodes created by expansions.
+    pub nodes_created: u64,
+    /// The number of moves played in simulations.
+    pub playout_moves: u64,
+    /// The number of nodes in the tree after the latest iteration.
+    pub tree_size: usize,
+    /// The number of nodes proven to be a win or a loss.
+    pub proven_nodes: u64,
+}
+
+impl Metrics for MetricsTotals {
+    fn record(&mut self, metrics: &IterationMetrics) {
+        self.iterations += 1;
+        self.nodes_created += metrics.nodes_created as u64;
+        self.playout_moves += metrics.playout_length;
+        self.tree_size = metrics.tree_size;
+        self.proven_nodes += metrics.proven_nodes as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::metrics::{Metrics, MetricsTotals};
+    use crate::random::CustomNumberGenerator;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn totals_match_the_statistics_of_the_search() {
+        // arrange
+        let totals = Arc::new(Mutex::new(MetricsTotals::default()));
+        let sink = totals.clone();
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_metrics(move |x: &_| sink.lock().unwrap().record(x))
+            .build();
+
+        // act
+        mcts.iterate_n_times(50000);
+
+        // assert
+        let totals = *totals.lock().unwrap();
+        let statistics = mcts.get_statistics();
+        assert_eq!(totals.iterations, statistics.iterations);
+        assert_eq!(totals.nodes_created as usize + 1, statistics.node_count);
+        assert_eq!(totals.tree_size, statistics.node_count);
+        assert_eq!(totals.proven_nodes as usize, statistics.proven_nodes);
+        assert_eq!(
+            totals.playout_moves,
+            mcts.get_budget_report().total.playout_moves
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crate::board::Board;
+
+/// A text notation for the positions and moves of a game, e.g. FEN and UCI moves for chess.
+///
+/// Tree exports and the protocol adapters use the notation to show positions and principal
+/// variations in a form people can read, instead of the `Debug` representation of moves.
+pub trait BoardNotation: Board {
+    /// Parses a position, or returns `None` if the notation is invalid.
+    fn parse_position(notation: &str) -> Option<Self>;
+
+    /// Formats the position, so that `parse_position` reads it back.
+    fn format_position(&self) -> String;
+
+    /// Parses a legal move of the position, or returns `None` if the notation is invalid or the
+    /// move is illegal.
+    fn parse_move(&self, notation: &str) -> Option<Self::Move>;
+
+    /// Formats a move of the position.
+    fn format_move(&self, b_move: &Self::Move) -> String;
+}
+
+/// Formats a line of moves played from `board`, every move in the position it is played in, e.g.
+/// to show a principal variation.
+pub fn format_line<'a, T: BoardNotation>(
+    board: &T,
+    moves: impl IntoIterator<Item = &'a T::Move>,
+) -> Vec<String>
+where
+    T::Move: 'a,
+{
+    let mut board = board.clone();
+    moves
+        .into_iter()
+        .map(|b_move| {
+            let notation = board.format_move(b_move);
+            board.perform_move(b_move);
+            notation
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::notation::{BoardNotation, format_line};
+
+    #[test]
+    fn formats_lines_in_their_positions() {
+        // arrange
+        let board = TicTacToeBoard::parse_position("x../.o./...").unwrap();
+
+        // act
+        let line = format_line(&board, &[2, 6]);
+
+        // assert
+        assert_eq!(line, vec!["c1", "a3"]);
+        assert_eq!(board.format_position(), "x../.o./...");
+    }
+}
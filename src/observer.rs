@@ -0,0 +1,21 @@
+use crate::board::GameOutcome;
+use ego_tree::NodeId;
+
+/// A callback invoked by the search after every completed iteration.
+pub type IterationObserver = Box<dyn FnMut(&IterationReport) + Send>;
+
+/// Describes a single completed iteration of the search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterationReport {
+    /// The number of iterations completed so far, including this one.
+    pub iteration: u64,
+    /// The leaf chosen during selection.
+    pub selected_leaf: NodeId,
+    /// The node the simulation was started from.
+    pub simulated_node: NodeId,
+    /// The outcome of the simulation, `GameOutcome::InProgress` if an evaluator valued the node
+    /// instead, see `MonteCarloTreeSearch::iterate_batched`.
+    pub outcome: GameOutcome,
+    /// The nodes updated during backpropagation, from the simulated node up to the root.
+    pub path: Vec<NodeId>,
+}
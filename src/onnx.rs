@@ -0,0 +1,129 @@
+//! An `Evaluator` running a policy/value model with ONNX Runtime.
+//!
+//! The runtime library is loaded at run time rather than linked, so the crate builds without it;
+//! point the `ORT_DYLIB_PATH` environment variable at `libonnxruntime` before the first model is
+//! loaded, or place the library where the system finds shared libraries.
+//!
+//! # Model
+//!
+//! The model takes a single `f32` input of shape `[b, ..TensorEncoding::get_tensor_shape]`, for a
+//! batch of `b` positions, and has two outputs: the policy logits of shape `[b, n]`, indexed by
+//! `TensorEncoding::get_policy_index`, and the value of every position for the player to move, of
+//! shape `[b, 1]` or `[b]`, from `-1.0` (a sure loss) to `1.0` (a sure win), as produced by a
+//! `tanh` head.
+
+use crate::board::Board;
+use crate::evaluator::{Evaluation, Evaluator};
+use ort::session::Session;
+use ort::value::Tensor;
+use std::path::Path;
+
+/// The tensor encoding of a board, the input of an `OnnxEvaluator`.
+pub trait TensorEncoding: Board {
+    /// Returns the shape of the tensor encoding a position, without the batch dimension.
+    fn get_tensor_shape(&self) -> Vec<usize>;
+
+    /// Returns the tensor encoding the position, in row-major order.
+    fn encode_tensor(&self) -> Vec<f32>;
+
+    /// Returns the index of `b_move` in the policy output of the model.
+    fn get_policy_index(&self, b_move: &Self::Move) -> usize;
+}
+
+/// An `Evaluator` running an ONNX policy/value model, see the module documentation.
+pub struct OnnxEvaluator {
+    session: Session,
+}
+
+impl OnnxEvaluator {
+    /// Loads the model stored at `path`.
+    pub fn load(path: impl AsRef<Path>) -> ort::Result<Self> {
+        let session = Session::builder()?.commit_from_file(path)?;
+        Ok(Self::from_session(session))
+    }
+
+    /// Wraps a session created with custom options, e.g. execution providers or thread counts.
+    pub fn from_session(session: Session) -> Self {
+        Self { session }
+    }
+
+    /// Runs the model on a position.
+    pub fn try_evaluate<T: TensorEncoding>(&mut self, board: &T) -> ort::Result<Evaluation> {
+        let mut evaluations = self.try_evaluate_batch(std::slice::from_ref(board))?;
+        Ok(evaluations.remove(0))
+    }
+
+    /// Runs the model once on a batch of positions, returning their evaluations in order.
+    pub fn try_evaluate_batch<T: TensorEncoding>(
+        &mut self,
+        boards: &[T],
+    ) -> ort::Result<Vec<Evaluation>> {
+        let Some(first) = boards.first() else {
+            return Ok(Vec::new());
+        };
+        let shape: Vec<usize> = [boards.len()]
+            .into_iter()
+            .chain(first.get_tensor_shape())
+            .collect();
+        let input: Vec<f32> = boards.iter().flat_map(|x| x.encode_tensor()).collect();
+        let input = Tensor::from_array((shape, input))?;
+        let outputs = self.session.run(ort::inputs![input])?;
+        let (_, logits) = outputs[0].try_extract_tensor::<f32>()?;
+        let (_, values) = outputs[1].try_extract_tensor::<f32>()?;
+        if logits.len() % boards.len() != 0 || values.len() != boards.len() {
+            return Err(ort::Error::new(format!(
+                "the outputs of the model do not match a batch of {} positions",
+                boards.len()
+            )));
+        }
+
+        let logits_per_board = logits.len() / boards.len();
+        boards
+            .iter()
+            .zip(logits.chunks(logits_per_board.max(1)))
+            .zip(values)
+            .map(|((board, logits), value)| {
+                let priors = board
+                    .get_available_moves()
+                    .iter()
+                    .map(|x| {
+                        let index = board.get_policy_index(x);
+                        logits.get(index).map(|x| *x as f64).ok_or_else(|| {
+                            ort::Error::new(format!(
+                                "policy index {index} outside the {} logits of the model",
+                                logits.len()
+                            ))
+                        })
+                    })
+                    .collect::<ort::Result<_>>()?;
+                Ok(Evaluation {
+                    priors,
+                    value: ((*value as f64 + 1.0) / 2.0).clamp(0.0, 1.0),
+                })
+            })
+            .collect()
+    }
+}
+
+impl<T: TensorEncoding> Evaluator<T> for OnnxEvaluator {
+    /// Runs the model on a position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inference fails, e.g. because the model does not match the encoding of the
+    /// board. Use `try_evaluate` to handle the error.
+    fn evaluate(&mut self, board: &T) -> Evaluation {
+        self.try_evaluate(board)
+            .unwrap_or_else(|x| panic!("the ONNX model failed to evaluate a position: {x}"))
+    }
+
+    /// Runs the model once on all the positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inference fails. Use `try_evaluate_batch` to handle the error.
+    fn evaluate_batch(&mut self, boards: &[T]) -> Vec<Evaluation> {
+        self.try_evaluate_batch(boards)
+            .unwrap_or_else(|x| panic!("the ONNX model failed to evaluate a batch: {x}"))
+    }
+}
@@ -0,0 +1,21 @@
+use crate::board::{Board, GameOutcome};
+
+/// Knows the exact value of some positions, e.g. from an endgame tablebase or a retrograde
+/// analysis.
+///
+/// The search consults the oracle for every node it creates and before every move of a
+/// simulation. A node the oracle solves is marked as fully calculated, with the bound its outcome
+/// implies, and a simulation reaching a solved position ends there with the exact outcome.
+///
+/// Any closure taking the board and returning an optional outcome implements this trait.
+pub trait TerminalOracle<T: Board>: Send {
+    /// Returns the outcome of the position under perfect play, for the side reported as
+    /// `Player::Me`, or `None` if the position is unknown.
+    fn probe(&self, board: &T) -> Option<GameOutcome>;
+}
+
+impl<T: Board, F: Fn(&T) -> Option<GameOutcome> + Send> TerminalOracle<T> for F {
+    fn probe(&self, board: &T) -> Option<GameOutcome> {
+        self(board)
+    }
+}
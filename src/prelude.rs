@@ -0,0 +1,21 @@
+//! Re-exports the types a typical integration needs.
+//!
+//! ```
+//! use mcts_lib::boards::tic_tac_toe::TicTacToeBoard;
+//! use mcts_lib::prelude::*;
+//!
+//! let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+//!     .with_random_generator(CustomNumberGenerator::default())
+//!     .build();
+//! mcts.iterate_n_times(1000);
+//! let result: SearchResult<u8> = mcts.get_search_result();
+//! assert!(result.best_move.is_some());
+//! ```
+
+pub use crate::board::{Board, GameOutcome, MoveList, Player, smallvec};
+pub use crate::budget::SearchBudget;
+pub use crate::mcts::{MonteCarloTreeSearch, MonteCarloTreeSearchBuilder};
+#[cfg(feature = "rand")]
+pub use crate::random::StandardRandomGenerator;
+pub use crate::random::{CustomNumberGenerator, RandomGenerator, SplitMix64, Xoshiro256PlusPlus};
+pub use crate::result::SearchResult;
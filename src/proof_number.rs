@@ -0,0 +1,221 @@
+use crate::board::{Board, GameOutcome, Player};
+
+/// The proof or disproof number of a node that can never be proven or disproven.
+const INFINITY: u64 = u64::MAX;
+
+/// Configures the proof-number search run on promising nodes of a search, see
+/// `MonteCarloTreeSearchBuilder::with_proof_number_search`.
+///
+/// Every node reaching `trigger_visits` visits without a proven bound is handed to
+/// `prove_outcome` with a budget of `node_budget` nodes. A proven win or loss becomes the bound of
+/// the node, which then is never selected again and lets its ancestors be proven in turn. Forced
+/// wins that averaging needs thousands of simulations to find are often proven in a few hundred
+/// nodes this way.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProofNumberSearch {
+    /// The number of visits at which a node is handed to the proof-number search.
+    pub trigger_visits: u32,
+    /// The maximum number of nodes the proof-number search may create for a single node.
+    pub node_budget: usize,
+}
+
+impl Default for ProofNumberSearch {
+    /// Returns a trigger at 20 visits with a budget of 2000 nodes.
+    fn default() -> Self {
+        Self {
+            trigger_visits: 20,
+            node_budget: 2000,
+        }
+    }
+}
+
+/// A node of the AND/OR tree of a proof-number search.
+struct PnNode<T> {
+    board: T,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// Whether the player trying to reach the target outcome is to move.
+    is_or_node: bool,
+    proof: u64,
+    disproof: u64,
+}
+
+/// Tries to prove the outcome of a position with proof-number search, creating at most
+/// `node_budget` nodes per attempt.
+///
+/// Returns `Some(GameOutcome::Win)` or `Some(GameOutcome::Lose)` if the searching player is proven
+/// to win or to lose against any defense, and `None` if neither could be proven within the
+/// budget. A position proven to be neither, e.g. a draw, also returns `None`. Positions are only
+/// judged by `Board::get_outcome`; the terminal oracle and heuristics of the search are not used.
+pub fn prove_outcome<T: Board>(board: &T, node_budget: usize) -> Option<GameOutcome> {
+    [GameOutcome::Win, GameOutcome::Lose]
+        .into_iter()
+        .find(|x| prove(board, *x, node_budget) == Some(true))
+}
+
+/// Runs a proof-number search for `target`, returning whether it was proven or disproven, or
+/// `None` if the budget ran out first.
+fn prove<T: Board>(board: &T, target: GameOutcome, node_budget: usize) -> Option<bool> {
+    let attacker = match target {
+        GameOutcome::Win => Player::Me,
+        _ => Player::Other,
+    };
+    let mut nodes = vec![new_node(
+        board.clone(),
+        board.get_outcome(),
+        None,
+        target,
+        attacker,
+    )];
+    loop {
+        if nodes[0].proof == 0 {
+            return Some(true);
+        }
+        if nodes[0].disproof == 0 {
+            return Some(false);
+        }
+        if nodes.len() >= node_budget {
+            return None;
+        }
+
+        let mut node_index = 0;
+        while !nodes[node_index].children.is_empty() {
+            let node = &nodes[node_index];
+            let children = node.children.iter().copied();
+            node_index = match node.is_or_node {
+                true => children.min_by_key(|x| nodes[*x].proof),
+                false => children.min_by_key(|x| nodes[*x].disproof),
+            }
+            .unwrap();
+        }
+
+        let moves = nodes[node_index].board.get_available_moves();
+        if moves.is_empty() {
+            // A game without moves that is not over is a draw.
+            nodes[node_index].proof = INFINITY;
+            nodes[node_index].disproof = 0;
+        }
+        for b_move in moves.iter() {
+            let mut child_board = nodes[node_index].board.clone();
+            let outcome = child_board.outcome_after_move(b_move);
+            let child = new_node(child_board, outcome, Some(node_index), target, attacker);
+            nodes.push(child);
+            let child_index = nodes.len() - 1;
+            nodes[node_index].children.push(child_index);
+        }
+
+        let mut ancestor = Some(node_index);
+        while let Some(node_index) = ancestor {
+            update_numbers(&mut nodes, node_index);
+            ancestor = nodes[node_index].parent;
+        }
+    }
+}
+
+/// Creates a leaf, proven or disproven at once if the game is over.
+fn new_node<T: Board>(
+    board: T,
+    outcome: GameOutcome,
+    parent: Option<usize>,
+    target: GameOutcome,
+    attacker: Player,
+) -> PnNode<T> {
+    let (proof, disproof) = match outcome {
+        GameOutcome::InProgress => (1, 1),
+        outcome if outcome == target => (0, INFINITY),
+        _ => (INFINITY, 0),
+    };
+    PnNode {
+        is_or_node: board.get_current_player() == attacker,
+        board,
+        parent,
+        children: Vec::new(),
+        proof,
+        disproof,
+    }
+}
+
+/// Recomputes the proof and disproof numbers of an expanded node from its children.
+fn update_numbers<T>(nodes: &mut [PnNode<T>], node_index: usize) {
+    let node = &nodes[node_index];
+    if node.children.is_empty() {
+        return;
+    }
+
+    let proofs = node.children.iter().map(|x| nodes[*x].proof);
+    let disproofs = node.children.iter().map(|x| nodes[*x].disproof);
+    let (proof, disproof) = match node.is_or_node {
+        true => (
+            proofs.min().unwrap(),
+            disproofs.fold(0, u64::saturating_add),
+        ),
+        false => (
+            proofs.fold(0, u64::saturating_add),
+            disproofs.min().unwrap(),
+        ),
+    };
+    nodes[node_index].proof = proof;
+    nodes[node_index].disproof = disproof;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Bound, GameOutcome};
+    use crate::boards::nim::NimBoard;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::proof_number::{ProofNumberSearch, prove_outcome};
+    use crate::random::CustomNumberGenerator;
+
+    #[test]
+    fn proves_nim_positions_like_the_theory() {
+        for piles in [[1, 2, 3], [2, 3, 4], [1, 4, 5], [3, 3, 1]] {
+            let board = NimBoard::new(&piles);
+            let expected = match board.is_winning_position() {
+                true => GameOutcome::Win,
+                false => GameOutcome::Lose,
+            };
+            assert_eq!(prove_outcome(&board, 100_000), Some(expected));
+        }
+    }
+
+    #[test]
+    fn draws_and_small_budgets_prove_nothing() {
+        assert_eq!(prove_outcome(&TicTacToeBoard::default(), 100_000), None);
+        assert_eq!(prove_outcome(&NimBoard::new(&[3, 5, 7]), 10), None);
+    }
+
+    #[test]
+    fn search_proves_the_root_sooner() {
+        // arrange
+        let search = |proof_number_search: Option<ProofNumberSearch>| {
+            let builder = MonteCarloTreeSearch::builder(NimBoard::new(&[2, 4, 5]))
+                .with_random_generator(CustomNumberGenerator::default());
+            let mut mcts = match proof_number_search {
+                Some(x) => builder.with_proof_number_search(x),
+                None => builder,
+            }
+            .build();
+            let mut iterations = 0;
+            while mcts.get_root().value().stats.bound == Bound::None && iterations < 100_000 {
+                mcts.do_iteration();
+                iterations += 1;
+            }
+            (mcts, iterations)
+        };
+
+        // act
+        let (pure, pure_iterations) = search(None);
+        let (hybrid, hybrid_iterations) = search(Some(ProofNumberSearch::default()));
+
+        // assert
+        assert_eq!(pure.get_root().value().stats.bound, Bound::DefoWin);
+        assert_eq!(hybrid.get_root().value().stats.bound, Bound::DefoWin);
+        assert!(hybrid_iterations * 4 < pure_iterations);
+        assert_eq!(
+            hybrid.get_proof_number_search(),
+            Some(ProofNumberSearch::default())
+        );
+    }
+}
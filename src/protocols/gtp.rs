@@ -0,0 +1,377 @@
+//! An engine loop speaking the [Go Text Protocol](https://www.lysator.liu.se/~gunnar/gtp/), version 2.
+//!
+//! `GtpEngine` answers commands from controllers such as GoGui, or from match scripts pitting the
+//! search against GNU Go or Pachi. Any board that implements `GtpBoard` can be played this way;
+//! `parse_vertex` and `format_vertex` help boards laid out on a grid to use the standard
+//! coordinates.
+//!
+//! Supported commands: `protocol_version`, `name`, `version`, `known_command`, `list_commands`,
+//! `boardsize`, `clear_board`, `komi`, `play`, `genmove`, `undo`, `showboard` and `quit`.
+
+use crate::clock::Stopwatch;
+use crate::mcts::{MctsAction, MonteCarloTreeSearch};
+use crate::notation::BoardNotation;
+use crate::random::RandomGenerator;
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+/// The commands understood by `GtpEngine`, as reported by `list_commands`.
+pub const COMMANDS: [&str; 13] = [
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "boardsize",
+    "clear_board",
+    "komi",
+    "play",
+    "genmove",
+    "undo",
+    "showboard",
+    "quit",
+];
+
+/// The letters naming the columns of a board. `I` is skipped, as the protocol requires.
+const COLUMNS: &str = "ABCDEFGHJKLMNOPQRSTUVWXYZ";
+
+/// The number of iterations run between two checks of the time limit.
+const ITERATIONS_PER_CHECK: u32 = 64;
+
+/// A color in the protocol.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GtpColor {
+    /// The player moving first.
+    Black,
+    /// The player moving second.
+    White,
+}
+
+impl GtpColor {
+    /// Parses `black`, `b`, `white` or `w`, ignoring case.
+    pub fn parse(color: &str) -> Option<Self> {
+        match color.to_ascii_lowercase().as_str() {
+            "black" | "b" => Some(GtpColor::Black),
+            "white" | "w" => Some(GtpColor::White),
+            _ => None,
+        }
+    }
+}
+
+/// A board that can be set up and played through the protocol.
+///
+/// Moves are read and written as vertices such as `D4` or `pass` with
+/// `BoardNotation::parse_move` and `BoardNotation::format_move`, and `showboard` prints
+/// `BoardNotation::format_position`.
+///
+/// The search plays for `Player::Me`, so the board must report the color to move as `Player::Me`
+/// and judge outcomes from its point of view.
+pub trait GtpBoard: BoardNotation {
+    /// Returns an empty board of the given size, or `None` if the size is not supported.
+    fn with_size(size: usize) -> Option<Self>;
+    /// Returns the color to move.
+    fn get_color_to_move(&self) -> GtpColor;
+    /// Sets the komi. Boards without komi ignore it.
+    fn set_komi(&mut self, _komi: f64) {}
+}
+
+/// Parses a vertex such as `D4` into zero-based `(column, row)` coordinates, with row `0` at the
+/// bottom. Returns `None` for `pass` and for vertices outside a board of the given size.
+pub fn parse_vertex(vertex: &str, size: usize) -> Option<(usize, usize)> {
+    let mut chars = vertex.chars();
+    let letter = chars.next()?.to_ascii_uppercase();
+    let column = COLUMNS.find(letter)?;
+    let row = chars.as_str().parse::<usize>().ok()?.checked_sub(1)?;
+    (column < size && row < size).then_some((column, row))
+}
+
+/// Formats zero-based `(column, row)` coordinates as a vertex such as `D4`.
+///
+/// # Panics
+///
+/// Panics if `column` is beyond the 25 columns the protocol supports.
+pub fn format_vertex(column: usize, row: usize) -> String {
+    let letter = COLUMNS.as_bytes()[column] as char;
+    format!("{letter}{}", row + 1)
+}
+
+/// A GTP engine driving `MonteCarloTreeSearch` on a `GtpBoard`.
+pub struct GtpEngine<T: GtpBoard, K: RandomGenerator> {
+    name: String,
+    version: String,
+    search_factory: Box<dyn Fn(T) -> MonteCarloTreeSearch<T, K>>,
+    iterations: u32,
+    time_limit: Option<Duration>,
+    size: usize,
+    komi: f64,
+    board: T,
+    history: Vec<T>,
+}
+
+impl<T: GtpBoard, K: RandomGenerator> GtpEngine<T, K>
+where
+    T::Move: Clone,
+{
+    /// Creates an engine with the given name playing on a board of the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the board does not support `size`.
+    pub fn new(name: &str, size: usize) -> Self
+    where
+        T: 'static,
+        K: 'static,
+    {
+        let board = T::with_size(size).expect("unsupported board size");
+        Self {
+            name: name.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            search_factory: Box::new(|board| MonteCarloTreeSearch::builder(board).build()),
+            iterations: 10_000,
+            time_limit: None,
+            size,
+            komi: 0.0,
+            board,
+            history: Vec::new(),
+        }
+    }
+
+    /// Sets the version reported in response to `version`. Defaults to the version of this crate.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Sets the function creating the search for every `genmove` command.
+    pub fn with_search_factory(
+        mut self,
+        search_factory: impl Fn(T) -> MonteCarloTreeSearch<T, K> + 'static,
+    ) -> Self {
+        self.search_factory = Box::new(search_factory);
+        self
+    }
+
+    /// Sets the number of iterations searched for every generated move.
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Limits the time searched for every generated move, on top of the number of iterations.
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Returns the current position.
+    pub fn get_board(&self) -> &T {
+        &self.board
+    }
+
+    /// Reads commands from `input` and writes responses to `output` until `quit` or the end of
+    /// the input.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (id, command) = match line.split_once(' ') {
+                Some((id, command)) if id.parse::<u32>().is_ok() => (id, command.trim()),
+                _ if line.parse::<u32>().is_ok() => (line, ""),
+                _ => ("", line),
+            };
+            let (name, args) = command.split_once(' ').unwrap_or((command, ""));
+            let args: Vec<_> = args.split_whitespace().collect();
+
+            match self.handle_command(name, &args) {
+                Ok(response) => write!(output, "={id} {response}")?,
+                Err(message) => write!(output, "?{id} {message}")?,
+            }
+            writeln!(output, "\n")?;
+            output.flush()?;
+
+            if name == "quit" {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a single command, returning the response or an error message.
+    fn handle_command(&mut self, name: &str, args: &[&str]) -> Result<String, String> {
+        match name {
+            "protocol_version" => Ok("2".to_string()),
+            "name" => Ok(self.name.clone()),
+            "version" => Ok(self.version.clone()),
+            "known_command" => {
+                let command = args.first().ok_or("syntax error")?;
+                Ok(COMMANDS.contains(command).to_string())
+            }
+            "list_commands" => Ok(COMMANDS.join("\n")),
+            "boardsize" => {
+                let size = args.first().and_then(|x| x.parse().ok());
+                let size = size.ok_or("syntax error")?;
+                let board = T::with_size(size).ok_or("unacceptable size")?;
+                self.size = size;
+                self.reset(board);
+                Ok(String::new())
+            }
+            "clear_board" => {
+                self.reset(T::with_size(self.size).unwrap());
+                Ok(String::new())
+            }
+            "komi" => {
+                let komi = args.first().and_then(|x| x.parse().ok());
+                self.komi = komi.ok_or("syntax error")?;
+                self.board.set_komi(self.komi);
+                Ok(String::new())
+            }
+            "play" => {
+                let [color, vertex] = args else {
+                    return Err("syntax error".to_string());
+                };
+                let color = GtpColor::parse(color).ok_or("syntax error")?;
+                if color != self.board.get_color_to_move() {
+                    return Err("illegal move".to_string());
+                }
+                let b_move = self.board.parse_move(vertex).ok_or("illegal move")?;
+                self.play(&b_move);
+                Ok(String::new())
+            }
+            "genmove" => {
+                let color = args.first().and_then(|x| GtpColor::parse(x));
+                if color.ok_or("syntax error")? != self.board.get_color_to_move() {
+                    return Err("illegal move".to_string());
+                }
+                match self.search() {
+                    Some(b_move) => {
+                        let vertex = self.board.format_move(&b_move);
+                        self.play(&b_move);
+                        Ok(vertex)
+                    }
+                    None => Ok("resign".to_string()),
+                }
+            }
+            "undo" => {
+                self.board = self.history.pop().ok_or("cannot undo")?;
+                Ok(String::new())
+            }
+            "showboard" => Ok(self.board.format_position()),
+            "quit" => Ok(String::new()),
+            _ => Err("unknown command".to_string()),
+        }
+    }
+
+    fn reset(&mut self, mut board: T) {
+        board.set_komi(self.komi);
+        self.board = board;
+        self.history.clear();
+    }
+
+    fn play(&mut self, b_move: &T::Move) {
+        self.history.push(self.board.clone());
+        self.board.perform_move(b_move);
+    }
+
+    /// Searches the current position, returning `None` if there is no move to play.
+    fn search(&self) -> Option<T::Move> {
+        let stopwatch = Stopwatch::start();
+        let mut mcts = (self.search_factory)(self.board.clone());
+        let mut remaining = self.iterations;
+        while remaining > 0
+            && *mcts.get_next_mcts_action() != MctsAction::EverythingIsCalculated
+            && self.time_limit.is_none_or(|x| stopwatch.elapsed() < x)
+        {
+            let iterations = remaining.min(ITERATIONS_PER_CHECK);
+            mcts.iterate_n_times(iterations);
+            remaining -= iterations;
+        }
+
+        let root = mcts.get_root();
+        let best_child = root.get_best_child_by(mcts.get_best_child_criterion())?;
+        best_child.value().prev_move().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::notation::BoardNotation;
+    use crate::protocols::gtp::{GtpBoard, GtpColor, GtpEngine, format_vertex, parse_vertex};
+    use crate::random::CustomNumberGenerator;
+    use std::io::Cursor;
+
+    /// Uses the vertices `A1` to `C3` of the notation of the board, with black moving first.
+    impl GtpBoard for TicTacToeBoard {
+        fn with_size(size: usize) -> Option<Self> {
+            (size == 3).then(TicTacToeBoard::default)
+        }
+
+        fn get_color_to_move(&self) -> GtpColor {
+            match self.get_available_moves().len() % 2 {
+                1 => GtpColor::Black,
+                _ => GtpColor::White,
+            }
+        }
+    }
+
+    fn run(commands: &str) -> (String, GtpEngine<TicTacToeBoard, CustomNumberGenerator>) {
+        let mut engine = GtpEngine::new("mcts-lib", 3)
+            .with_version("1.0")
+            .with_iterations(500)
+            .with_search_factory(|board| {
+                MonteCarloTreeSearch::builder(board)
+                    .with_random_generator(CustomNumberGenerator::default())
+                    .build()
+            });
+        let mut output = Vec::new();
+        engine
+            .run(Cursor::new(commands.to_string()), &mut output)
+            .unwrap();
+        (String::from_utf8(output).unwrap(), engine)
+    }
+
+    #[test]
+    fn vertices_skip_the_letter_i() {
+        assert_eq!(parse_vertex("d4", 19), Some((3, 3)));
+        assert_eq!(parse_vertex("J10", 19), Some((8, 9)));
+        assert_eq!(parse_vertex("I1", 19), None);
+        assert_eq!(parse_vertex("T20", 19), None);
+        assert_eq!(parse_vertex("pass", 19), None);
+        assert_eq!(format_vertex(8, 9), "J10");
+    }
+
+    #[test]
+    fn answers_administrative_commands() {
+        // act
+        let (output, _) = run("1 name\nversion\n3 known_command genmove\nfoo\nboardsize 19\n");
+
+        // assert
+        assert_eq!(
+            output,
+            "=1 mcts-lib\n\n= 1.0\n\n=3 true\n\n? unknown command\n\n? unacceptable size\n\n"
+        );
+    }
+
+    #[test]
+    fn plays_and_generates_moves() {
+        // act
+        let (output, engine) =
+            run("play b b2\nplay w b2\ngenmove w\nundo\nshowboard\nquit\nplay w a1\n");
+
+        // assert
+        let responses: Vec<_> = output.split("\n\n").collect();
+        assert_eq!(responses[0], "= ");
+        assert_eq!(responses[1], "? illegal move");
+        let vertex = responses[2].strip_prefix("= ").unwrap();
+        assert_eq!(responses[3..], ["= ", "= .../.x./...", "= ", ""]);
+        let board = engine.get_board();
+        assert_eq!(board.get_available_moves().len(), 8);
+        assert!(board.parse_move(vertex).is_some());
+    }
+}
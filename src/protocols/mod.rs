@@ -0,0 +1,6 @@
+//! Contains adapters that expose the search through standard engine protocols.
+
+/// A Go Text Protocol engine loop for grid-based boards.
+pub mod gtp;
+/// A Universal Chess Interface engine loop for chess-like boards.
+pub mod uci;
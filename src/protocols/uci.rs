@@ -0,0 +1,493 @@
+//! An engine loop speaking the [Universal Chess Interface](https://www.chessprogramming.org/UCI).
+//!
+//! `UciEngine` reads commands line by line, keeps track of the position set with `position`, and
+//! answers `go` by running the search on a background thread until the time control, the node
+//! limit or a `stop` command ends it. Any board that implements `UciBoard` can be played this way,
+//! so MCTS bots built on this crate can be loaded into standard chess GUIs.
+//!
+//! Supported commands: `uci`, `isready`, `ucinewgame`, `position`, `go`, `stop` and `quit`.
+
+use crate::clock::Stopwatch;
+use crate::filter::SearchMoves;
+use crate::mcts::{MctsAction, MonteCarloTreeSearch};
+use crate::notation::{BoardNotation, format_line};
+use crate::random::RandomGenerator;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// The number of iterations run between two checks of the limits of a search.
+const ITERATIONS_PER_CHECK: u64 = 64;
+
+/// The number of moves the remaining time is assumed to be spread over when `go` does not say.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/// The arguments of `go`, which end the list of moves following `searchmoves`.
+const GO_KEYWORDS: [&str; 12] = [
+    "searchmoves",
+    "ponder",
+    "wtime",
+    "btime",
+    "winc",
+    "binc",
+    "movestogo",
+    "depth",
+    "nodes",
+    "mate",
+    "movetime",
+    "infinite",
+];
+
+/// A board with a chess-like notation for positions and moves.
+///
+/// Positions given as `position fen` are read with `BoardNotation::parse_position`, which should
+/// accept Forsyth-Edwards Notation or a similar notation for other games, and moves are read and
+/// written with the notation of the protocol, e.g. `e2e4`.
+///
+/// The search plays for `Player::Me`, so after a `position` command the board must report the side
+/// to move as `Player::Me` and judge outcomes from its point of view.
+pub trait UciBoard: BoardNotation {
+    /// Returns the initial position of the game, used for `position startpos`.
+    fn start_position() -> Self;
+    /// Checks whether the first player, white in chess, is to move.
+    fn is_white_to_move(&self) -> bool;
+}
+
+/// The limits of a search, as given by a `go` command.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GoLimits {
+    /// The time left on the clock of white.
+    pub white_time: Option<Duration>,
+    /// The time left on the clock of black.
+    pub black_time: Option<Duration>,
+    /// The time white gains with every move.
+    pub white_increment: Option<Duration>,
+    /// The time black gains with every move.
+    pub black_increment: Option<Duration>,
+    /// The number of moves until the next time control.
+    pub moves_to_go: Option<u32>,
+    /// The exact time to search for.
+    pub move_time: Option<Duration>,
+    /// The number of iterations to search for.
+    pub nodes: Option<u64>,
+    /// Whether to keep searching until `stop`, even after the tree is fully calculated.
+    pub infinite: bool,
+    /// The moves the search is restricted to, in the notation of the protocol. Empty if all moves
+    /// may be searched.
+    pub search_moves: Vec<String>,
+}
+
+impl GoLimits {
+    /// Parses the arguments of a `go` command. Unknown or malformed arguments are ignored.
+    pub fn parse(args: &str) -> Self {
+        let mut limits = GoLimits::default();
+        let mut tokens = args.split_whitespace().peekable();
+        while let Some(token) = tokens.next() {
+            let mut number = || tokens.next().and_then(|x| x.parse::<u64>().ok());
+            match token {
+                "wtime" => limits.white_time = number().map(Duration::from_millis),
+                "btime" => limits.black_time = number().map(Duration::from_millis),
+                "winc" => limits.white_increment = number().map(Duration::from_millis),
+                "binc" => limits.black_increment = number().map(Duration::from_millis),
+                "movestogo" => limits.moves_to_go = number().map(|x| x as u32),
+                "movetime" => limits.move_time = number().map(Duration::from_millis),
+                "nodes" => limits.nodes = number(),
+                "infinite" => limits.infinite = true,
+                "searchmoves" => {
+                    while let Some(b_move) = tokens.next_if(|x| !GO_KEYWORDS.contains(x)) {
+                        limits.search_moves.push(b_move.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        limits
+    }
+
+    /// Returns how long the side to move should search, or `None` if there is no time limit.
+    ///
+    /// An exact `movetime` wins; otherwise the remaining time is spread over the moves to go and
+    /// half of the increment is added, never using more than half of the remaining time.
+    pub fn time_budget(&self, is_white_to_move: bool) -> Option<Duration> {
+        if self.move_time.is_some() {
+            return self.move_time;
+        }
+
+        let (time, increment) = if is_white_to_move {
+            (self.white_time?, self.white_increment.unwrap_or_default())
+        } else {
+            (self.black_time?, self.black_increment.unwrap_or_default())
+        };
+        let moves_to_go = self.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+        Some((time / moves_to_go + increment / 2).min(time / 2))
+    }
+}
+
+type SearchFactory<T, K> = dyn Fn(T) -> MonteCarloTreeSearch<T, K> + Send + Sync;
+
+/// A UCI engine driving `MonteCarloTreeSearch` on a `UciBoard`.
+pub struct UciEngine<T: UciBoard, K: RandomGenerator> {
+    name: String,
+    author: String,
+    search_factory: Arc<SearchFactory<T, K>>,
+    board: T,
+    stop: Arc<AtomicBool>,
+    search: Option<JoinHandle<()>>,
+    is_search_infinite: bool,
+}
+
+impl<T, K> UciEngine<T, K>
+where
+    T: UciBoard + Send + 'static,
+    T::Move: PartialEq + Send,
+    K: RandomGenerator + Send + 'static,
+{
+    /// Creates an engine with the given name that searches with the default configuration.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            author: String::new(),
+            search_factory: Arc::new(|board| MonteCarloTreeSearch::builder(board).build()),
+            board: T::start_position(),
+            stop: Arc::new(AtomicBool::new(false)),
+            search: None,
+            is_search_infinite: false,
+        }
+    }
+
+    /// Sets the author reported in response to `uci`.
+    pub fn with_author(mut self, author: &str) -> Self {
+        self.author = author.to_string();
+        self
+    }
+
+    /// Sets the function creating the search for every `go` command, so the search can be
+    /// configured, e.g. with a random generator or a `BestChildCriterion`.
+    pub fn with_search_factory(
+        mut self,
+        search_factory: impl Fn(T) -> MonteCarloTreeSearch<T, K> + Send + Sync + 'static,
+    ) -> Self {
+        self.search_factory = Arc::new(search_factory);
+        self
+    }
+
+    /// Returns the current position.
+    pub fn get_board(&self) -> &T {
+        &self.board
+    }
+
+    /// Reads commands from `input` and writes responses to `output` until `quit` or the end of
+    /// the input. At the end of the input, a running search is allowed to finish, except for an
+    /// infinite one, which is stopped as no `stop` command can follow.
+    pub fn run<R: BufRead, W: Write + Send + 'static>(
+        &mut self,
+        input: R,
+        output: W,
+    ) -> io::Result<()> {
+        let output = Arc::new(Mutex::new(output));
+        for line in input.lines() {
+            if !self.handle_command(line?.trim(), &output)? {
+                self.stop_search();
+                return Ok(());
+            }
+        }
+
+        if self.is_search_infinite {
+            self.stop_search();
+        } else {
+            self.wait_for_search();
+        }
+        Ok(())
+    }
+
+    /// Handles a single command. Returns `false` if the engine should quit.
+    fn handle_command<W: Write + Send + 'static>(
+        &mut self,
+        line: &str,
+        output: &Arc<Mutex<W>>,
+    ) -> io::Result<bool> {
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            "uci" => {
+                let mut output = output.lock().unwrap();
+                writeln!(output, "id name {}", self.name)?;
+                if !self.author.is_empty() {
+                    writeln!(output, "id author {}", self.author)?;
+                }
+                writeln!(output, "uciok")?;
+                output.flush()?;
+            }
+            "isready" => {
+                let mut output = output.lock().unwrap();
+                writeln!(output, "readyok")?;
+                output.flush()?;
+            }
+            "ucinewgame" => {
+                self.stop_search();
+                self.board = T::start_position();
+            }
+            "position" => {
+                self.stop_search();
+                if let Err(message) = self.set_position(args) {
+                    let mut output = output.lock().unwrap();
+                    writeln!(output, "info string {message}")?;
+                    output.flush()?;
+                }
+            }
+            "go" => {
+                self.stop_search();
+                self.start_search(GoLimits::parse(args), output.clone());
+            }
+            "stop" => self.stop_search(),
+            "quit" => return Ok(false),
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    /// Sets up the position described by the arguments of a `position` command.
+    fn set_position(&mut self, args: &str) -> Result<(), String> {
+        let (position, moves) = match args.split_once("moves") {
+            Some((position, moves)) => (position.trim(), moves),
+            None => (args.trim(), ""),
+        };
+
+        let mut board = if position == "startpos" {
+            T::start_position()
+        } else if let Some(fen) = position.strip_prefix("fen ") {
+            T::parse_position(fen.trim()).ok_or_else(|| format!("invalid position: {fen}"))?
+        } else {
+            return Err(format!("invalid position: {position}"));
+        };
+
+        for notation in moves.split_whitespace() {
+            let b_move = board
+                .parse_move(notation)
+                .ok_or_else(|| format!("invalid move: {notation}"))?;
+            board.perform_move(&b_move);
+        }
+
+        self.board = board;
+        Ok(())
+    }
+
+    /// Starts searching the current position on a background thread.
+    fn start_search<W: Write + Send + 'static>(&mut self, limits: GoLimits, output: Arc<Mutex<W>>) {
+        self.stop.store(false, Ordering::Relaxed);
+        self.is_search_infinite = limits.infinite;
+        let stop = self.stop.clone();
+        let board = self.board.clone();
+        let search_factory = self.search_factory.clone();
+
+        self.search = Some(std::thread::spawn(move || {
+            let stopwatch = Stopwatch::start();
+            let time_budget = limits.time_budget(board.is_white_to_move());
+            let mut mcts = search_factory(board.clone());
+            mcts.set_stop_condition(stop.clone());
+            let search_moves: Vec<_> = limits
+                .search_moves
+                .iter()
+                .filter_map(|x| board.parse_move(x))
+                .collect();
+            if !search_moves.is_empty() {
+                mcts.set_root_move_filter(SearchMoves(search_moves));
+            }
+
+            loop {
+                let iterations = mcts.get_root().value().stats.visits as u64;
+                let is_finished = stop.load(Ordering::Relaxed)
+                    || *mcts.get_next_mcts_action() == MctsAction::EverythingIsCalculated
+                    || time_budget.is_some_and(|x| stopwatch.elapsed() >= x)
+                    || limits.nodes.is_some_and(|x| iterations >= x);
+                if is_finished {
+                    break;
+                }
+
+                let remaining = limits.nodes.map_or(u64::MAX, |x| x - iterations);
+                mcts.iterate_n_times(remaining.min(ITERATIONS_PER_CHECK) as u32);
+            }
+
+            while limits.infinite && !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+
+            let mut principal_variation = Vec::new();
+            let mut node = mcts.get_root();
+            while let Some(child) = node.get_best_child_by(mcts.get_best_child_criterion()) {
+                principal_variation.extend(child.value().prev_move());
+                node = child;
+            }
+            let principal_variation = format_line(&board, principal_variation);
+            let mut output = output.lock().unwrap();
+            // A failing output cannot be reported from here; the command loop will notice it.
+            let _ = write!(
+                output,
+                "info nodes {} time {}",
+                mcts.get_root().value().stats.visits,
+                stopwatch.elapsed().as_millis()
+            );
+            if !principal_variation.is_empty() {
+                let _ = write!(output, " pv {}", principal_variation.join(" "));
+            }
+            let _ = writeln!(output);
+            let _ = writeln!(
+                output,
+                "bestmove {}",
+                principal_variation.first().map_or("0000", |x| x.as_str())
+            );
+            let _ = output.flush();
+        }));
+    }
+
+    /// Stops the running search, if any, and waits for it to report its move.
+    fn stop_search(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.wait_for_search();
+    }
+
+    fn wait_for_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.join().expect("search thread panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::notation::BoardNotation;
+    use crate::protocols::uci::{GoLimits, UciBoard, UciEngine};
+    use crate::random::CustomNumberGenerator;
+    use std::io::{Cursor, Write};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    impl UciBoard for TicTacToeBoard {
+        fn start_position() -> Self {
+            TicTacToeBoard::default()
+        }
+
+        fn is_white_to_move(&self) -> bool {
+            self.get_available_moves().len() % 2 == 1
+        }
+    }
+
+    /// A writer that can be inspected after the engine has taken ownership of it.
+    #[derive(Clone, Default)]
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn run(
+        commands: &str,
+    ) -> (
+        Vec<String>,
+        UciEngine<TicTacToeBoard, CustomNumberGenerator>,
+    ) {
+        let mut engine = UciEngine::new("mcts-lib")
+            .with_author("darkcodi")
+            .with_search_factory(|board| {
+                MonteCarloTreeSearch::builder(board)
+                    .with_random_generator(CustomNumberGenerator::default())
+                    .build()
+            });
+        let output = SharedOutput::default();
+        engine
+            .run(Cursor::new(commands.to_string()), output.clone())
+            .unwrap();
+        let text = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        (text.lines().map(str::to_string).collect(), engine)
+    }
+
+    #[test]
+    fn answers_handshake() {
+        let (lines, _) = run("uci\nisready\nquit\n");
+        assert_eq!(
+            lines,
+            vec!["id name mcts-lib", "id author darkcodi", "uciok", "readyok"]
+        );
+    }
+
+    #[test]
+    fn searches_the_given_position() {
+        // act
+        let (lines, engine) = run("position startpos moves b2 a1\ngo nodes 200\n");
+
+        // assert
+        assert_eq!(engine.get_board().get_available_moves().len(), 7);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("info nodes 200 time "));
+        let pv = lines[0].split_once(" pv ").unwrap().1;
+        assert!(pv.starts_with(lines[1].strip_prefix("bestmove ").unwrap()));
+        let best_move = lines[1].strip_prefix("bestmove ").unwrap();
+        assert!(engine.get_board().parse_move(best_move).is_some());
+    }
+
+    #[test]
+    fn searches_only_the_given_moves() {
+        // act
+        let (lines, _) = run("position startpos\ngo nodes 200 searchmoves c3 a3\n");
+
+        // assert
+        let best_move = lines[1].strip_prefix("bestmove ").unwrap();
+        assert!(["a3", "c3"].contains(&best_move), "{best_move}");
+        assert_eq!(
+            GoLimits::parse("searchmoves a1 b2 nodes 5").search_moves,
+            vec!["a1", "b2"]
+        );
+        assert_eq!(GoLimits::parse("searchmoves a1 b2 nodes 5").nodes, Some(5));
+    }
+
+    #[test]
+    fn reports_invalid_positions() {
+        let (lines, engine) = run("position startpos moves b2 b2\nposition fen xx.......\n");
+        assert_eq!(
+            lines,
+            vec![
+                "info string invalid move: b2",
+                "info string invalid position: xx......."
+            ]
+        );
+        assert_eq!(engine.get_board().get_available_moves().len(), 9);
+    }
+
+    #[test]
+    fn stop_ends_an_infinite_search() {
+        let (lines, _) = run("position fen xo./.../...\ngo infinite\nisready\nstop\n");
+        assert_eq!(lines[0], "readyok");
+        assert!(lines[1].starts_with("info nodes "));
+        assert!(lines[2].starts_with("bestmove "));
+    }
+
+    #[test]
+    fn end_of_input_ends_an_infinite_search() {
+        let (lines, _) = run("position fen xo./.../...\ngo infinite\n");
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("info nodes "));
+        assert!(lines[1].starts_with("bestmove "));
+    }
+
+    #[test]
+    fn budgets_time_from_the_clock() {
+        let limits = GoLimits::parse("wtime 60000 btime 1000 winc 2000 movestogo 20 bogus");
+        assert_eq!(limits.time_budget(true), Some(Duration::from_millis(4000)));
+        assert_eq!(limits.time_budget(false), Some(Duration::from_millis(50)));
+        assert_eq!(
+            GoLimits::parse("movetime 300").time_budget(true),
+            Some(Duration::from_millis(300))
+        );
+        assert_eq!(GoLimits::parse("infinite").time_budget(true), None);
+    }
+}
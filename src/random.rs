@@ -1,49 +1,270 @@
-use rand::{Rng, random};
+#[cfg(feature = "rand")]
+use rand::{Rng, RngCore, SeedableRng, random};
 
-const MULTIPLIER_A: i64 = 1103515245;
-const INCREMENT_C: i64 = 12345;
+const MULTIPLIER_A: u64 = 6364136223846793005;
+const INCREMENT_C: u64 = 1442695040888963407;
 const DEFAULT_SEED: i64 = 3819201;
 
 /// A trait for random number generation.
 ///
 /// This allows for different random number generation strategies to be used with the MCTS search,
 /// which is particularly useful for testing and ensuring reproducibility.
+///
+/// Implementations only provide `next_u64`; every other method is derived from it and produces
+/// uniformly distributed values without modulo bias.
 pub trait RandomGenerator: Default {
+    /// Returns the next random `u64`, with all 64 bits uniformly distributed.
+    fn next_u64(&mut self) -> u64;
+
     /// Returns the next random `i32`.
-    fn next(&mut self) -> i32;
+    fn next(&mut self) -> i32 {
+        (self.next_u64() >> 32) as i32
+    }
+
+    /// Returns a random index in `0..len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    fn next_index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "cannot pick an index from an empty range");
+
+        // Lemire's multiply-and-reject method: the high half of the product is the index, and the
+        // few values of the low half that would favor some indices are rejected.
+        let range = len as u64;
+        let threshold = range.wrapping_neg() % range;
+        loop {
+            let product = self.next_u64() as u128 * range as u128;
+            if product as u64 >= threshold {
+                return (product >> 64) as usize;
+            }
+        }
+    }
+
     /// Returns a random `i32` within the specified range (exclusive of `to`).
-    fn next_range(&mut self, from: i32, to: i32) -> i32;
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    fn next_range(&mut self, from: i32, to: i32) -> i32 {
+        assert!(from < to, "cannot pick a number from an empty range");
+        let offset = self.next_index((to as i64 - from as i64) as usize);
+        (from as i64 + offset as i64) as i32
+    }
+
+    /// Returns a random `f64` in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a random index into `weights`, picking every index with a probability proportional
+    /// to its weight, e.g. to sample moves by their priors or their visit counts.
+    ///
+    /// Takes a single `next_f64` and time linear in the number of weights. Indices with a zero
+    /// weight are never picked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights` is empty, if a weight is negative or not finite, or if the weights sum
+    /// to zero or to infinity.
+    fn next_weighted(&mut self, weights: &[f64]) -> usize {
+        assert!(
+            weights.iter().all(|x| x.is_finite() && *x >= 0.0),
+            "weights must be finite and not negative"
+        );
+        let total: f64 = weights.iter().sum();
+        assert!(
+            total > 0.0 && total.is_finite(),
+            "weights must have a positive finite sum"
+        );
+
+        let mut threshold = self.next_f64() * total;
+        weights
+            .iter()
+            .position(|x| {
+                threshold -= x;
+                threshold < 0.0
+            })
+            // Rounding can leave the threshold just above zero after the last weight.
+            .unwrap_or_else(|| weights.iter().rposition(|x| *x > 0.0).unwrap())
+    }
+
+    /// Returns a sample of the Beta(`alpha`, `beta`) distribution, in `[0, 1]`, e.g. a win
+    /// probability drawn from the posterior of a node for Thompson sampling.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` or `beta` is not positive and finite.
+    fn next_beta(&mut self, alpha: f64, beta: f64) -> f64 {
+        assert!(
+            alpha > 0.0 && alpha.is_finite() && beta > 0.0 && beta.is_finite(),
+            "alpha and beta must be positive and finite"
+        );
+
+        let x = next_gamma(self, alpha);
+        let y = next_gamma(self, beta);
+        if x + y == 0.0 {
+            // Both samples can underflow to zero for tiny shapes.
+            return (alpha >= beta) as u8 as f64;
+        }
+        x / (x + y)
+    }
+
+    /// Returns a sample of the symmetric Dirichlet distribution of `len` components with
+    /// concentration `alpha`, i.e. `len` non-negative weights summing to one, e.g. the exploration
+    /// noise of self-play. Small concentrations put most of the weight on a few components.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` is not positive and finite.
+    fn next_dirichlet(&mut self, alpha: f64, len: usize) -> Vec<f64> {
+        assert!(
+            alpha > 0.0 && alpha.is_finite(),
+            "alpha must be positive and finite"
+        );
 
-    /// Selects a random element from a vector.
-    fn get_random_from_vec<'a, K>(&mut self, vec: &'a Vec<K>) -> &'a K {
-        vec.get(self.next_range(0, vec.len() as i32) as usize)
-            .unwrap()
+        let mut samples: Vec<_> = (0..len).map(|_| next_gamma(self, alpha)).collect();
+        let sum: f64 = samples.iter().sum();
+        if sum == 0.0 {
+            // All samples can underflow to zero for tiny concentrations.
+            if len > 0 {
+                let index = self.next_index(len);
+                samples[index] = 1.0;
+            }
+            return samples;
+        }
+        samples.iter_mut().for_each(|x| *x /= sum);
+        samples
+    }
+
+    /// Selects a random element from a slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice is empty.
+    fn get_random_from_vec<'a, K>(&mut self, vec: &'a [K]) -> &'a K {
+        &vec[self.next_index(vec.len())]
+    }
+}
+
+/// Returns a sample of the Gamma(`shape`, 1) distribution with the method of Marsaglia and Tsang.
+fn next_gamma<R: RandomGenerator>(rng: &mut R, shape: f64) -> f64 {
+    if shape < 1.0 {
+        // Boosts the shape above one and scales the sample back down.
+        let uniform = 1.0 - rng.next_f64();
+        return next_gamma(rng, shape + 1.0) * uniform.powf(1.0 / shape);
+    }
+
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = next_normal(rng);
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let uniform = 1.0 - rng.next_f64();
+        if uniform.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+            return d * v;
+        }
     }
 }
 
+/// Returns a sample of the standard normal distribution with the Box-Muller transform.
+fn next_normal<R: RandomGenerator>(rng: &mut R) -> f64 {
+    let radius = (-2.0 * (1.0 - rng.next_f64()).ln()).sqrt();
+    radius * (std::f64::consts::TAU * rng.next_f64()).cos()
+}
+
+/// A `RandomGenerator` whose state can be saved and restored, e.g. by search checkpoints.
+///
+/// A generator restored from a saved state continues with the same sequence of numbers as the
+/// generator the state was saved from.
+pub trait RandomState: RandomGenerator {
+    /// Returns the current state of the generator.
+    fn save_state(&self) -> Vec<u64>;
+
+    /// Creates a generator from a state returned by `save_state`, or returns `None` if the state
+    /// is invalid.
+    fn restore_state(state: &[u64]) -> Option<Self>;
+}
+
 /// A `RandomGenerator` that uses the `rand` crate for random number generation.
+#[cfg(feature = "rand")]
 pub struct StandardRandomGenerator;
 
+#[cfg(feature = "rand")]
 impl Default for StandardRandomGenerator {
     fn default() -> Self {
         StandardRandomGenerator
     }
 }
 
+#[cfg(feature = "rand")]
 impl RandomGenerator for StandardRandomGenerator {
-    fn next(&mut self) -> i32 {
+    fn next_u64(&mut self) -> u64 {
         random()
     }
 
-    fn next_range(&mut self, from: i32, to: i32) -> i32 {
-        rand::rng().random_range(from..to)
+    fn next_index(&mut self, len: usize) -> usize {
+        rand::rng().random_range(0..len)
+    }
+}
+
+/// The thread-local generator of `rand` cannot be saved, so the state is empty and a restored
+/// generator simply continues to draw from it.
+#[cfg(feature = "rand")]
+impl RandomState for StandardRandomGenerator {
+    fn save_state(&self) -> Vec<u64> {
+        Vec::new()
+    }
+
+    fn restore_state(state: &[u64]) -> Option<Self> {
+        state.is_empty().then_some(StandardRandomGenerator)
+    }
+}
+
+/// A `RandomGenerator` backed by any generator of the `rand` crate, e.g. a seeded `StdRng`.
+///
+/// The default instance is seeded from the thread-local generator of `rand`.
+#[cfg(feature = "rand")]
+#[derive(Debug, Clone)]
+pub struct RandRngAdapter<R> {
+    rng: R,
+}
+
+#[cfg(feature = "rand")]
+impl<R: SeedableRng> Default for RandRngAdapter<R> {
+    fn default() -> Self {
+        RandRngAdapter::new(R::from_rng(&mut rand::rng()))
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<R: RngCore + SeedableRng> RandomGenerator for RandRngAdapter<R> {
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<R> RandRngAdapter<R> {
+    /// Wraps a generator of the `rand` crate.
+    pub const fn new(rng: R) -> Self {
+        Self { rng }
+    }
+
+    /// Returns the wrapped generator.
+    pub fn into_inner(self) -> R {
+        self.rng
     }
 }
 
 /// A deterministic pseudo-random number generator.
 ///
 /// This generator is useful for testing, as it will always produce the same sequence of numbers
-/// for a given seed.
+/// for a given seed. It is a 64-bit linear congruential generator, which is fast but has weak
+/// statistical properties.
 pub struct CustomNumberGenerator {
     seed: i64,
 }
@@ -55,13 +276,24 @@ impl Default for CustomNumberGenerator {
 }
 
 impl RandomGenerator for CustomNumberGenerator {
-    fn next(&mut self) -> i32 {
-        self.seed = (self.seed * MULTIPLIER_A + INCREMENT_C) % (i32::MAX as i64);
-        self.seed as i32
+    fn next_u64(&mut self) -> u64 {
+        // The low bits of an LCG are of poor quality, so only the high half of two steps is used.
+        let high = self.step() >> 32;
+        let low = self.step() >> 32;
+        (high << 32) | low
     }
+}
 
-    fn next_range(&mut self, from: i32, to: i32) -> i32 {
-        (self.next() % (to - from)).abs() + from
+impl RandomState for CustomNumberGenerator {
+    fn save_state(&self) -> Vec<u64> {
+        vec![self.seed as u64]
+    }
+
+    fn restore_state(state: &[u64]) -> Option<Self> {
+        match state {
+            [seed] => Some(CustomNumberGenerator::new(*seed as i64)),
+            _ => None,
+        }
     }
 }
 
@@ -70,19 +302,159 @@ impl CustomNumberGenerator {
     pub const fn new(seed: i64) -> Self {
         Self { seed }
     }
+
+    fn step(&mut self) -> u64 {
+        let state = (self.seed as u64)
+            .wrapping_mul(MULTIPLIER_A)
+            .wrapping_add(INCREMENT_C);
+        self.seed = state as i64;
+        state
+    }
+}
+
+/// A fast, seedable pseudo-random number generator with a single 64-bit state.
+///
+/// Every seed, including zero, yields a full-period sequence. `Xoshiro256PlusPlus` has better
+/// statistical properties; this generator is mostly used to seed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl Default for SplitMix64 {
+    fn default() -> Self {
+        SplitMix64::new(DEFAULT_SEED as u64)
+    }
+}
+
+impl RandomGenerator for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl RandomState for SplitMix64 {
+    fn save_state(&self) -> Vec<u64> {
+        vec![self.state]
+    }
+
+    fn restore_state(state: &[u64]) -> Option<Self> {
+        match state {
+            [state] => Some(SplitMix64::new(*state)),
+            _ => None,
+        }
+    }
+}
+
+impl SplitMix64 {
+    /// Creates a new `SplitMix64` with the specified seed.
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+/// A fast, high-quality, seedable pseudo-random number generator, suited for tight playout loops.
+///
+/// This is the xoshiro256++ generator by Blackman and Vigna.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Xoshiro256PlusPlus {
+    state: [u64; 4],
+}
+
+impl Default for Xoshiro256PlusPlus {
+    fn default() -> Self {
+        Xoshiro256PlusPlus::new(DEFAULT_SEED as u64)
+    }
+}
+
+impl RandomGenerator for Xoshiro256PlusPlus {
+    fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = self.state;
+        let result = s0.wrapping_add(s3).rotate_left(23).wrapping_add(s0);
+
+        let t = s1 << 17;
+        let s2 = s2 ^ s0;
+        let s3 = s3 ^ s1;
+        let s1 = s1 ^ s2;
+        let s0 = s0 ^ s3;
+        self.state = [s0, s1, s2 ^ t, s3.rotate_left(45)];
+        result
+    }
+}
+
+impl RandomState for Xoshiro256PlusPlus {
+    fn save_state(&self) -> Vec<u64> {
+        self.state.to_vec()
+    }
+
+    fn restore_state(state: &[u64]) -> Option<Self> {
+        let state: [u64; 4] = state.try_into().ok()?;
+        (state != [0; 4]).then(|| Xoshiro256PlusPlus::from_state(state))
+    }
+}
+
+impl Xoshiro256PlusPlus {
+    /// Creates a new `Xoshiro256PlusPlus`, expanding the seed into the full state with
+    /// `SplitMix64`.
+    pub fn new(seed: u64) -> Self {
+        let mut seeder = SplitMix64::new(seed);
+        Self {
+            state: std::array::from_fn(|_| seeder.next_u64()),
+        }
+    }
+
+    /// Creates a new `Xoshiro256PlusPlus` from its raw state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is all zeros, which the generator can never leave.
+    pub fn from_state(state: [u64; 4]) -> Self {
+        assert!(state != [0; 4], "the state must not be all zeros");
+        Self { state }
+    }
+
+    /// Advances the generator by 2^128 steps, as if that many numbers had been drawn.
+    ///
+    /// Jumping a generator repeatedly splits its sequence into non-overlapping streams, e.g. one
+    /// per thread or per self-play game.
+    pub fn jump(&mut self) {
+        const JUMP: [u64; 4] = [
+            0x180ec6d33cfd0aba,
+            0xd5a61266f0c9392c,
+            0xa9582618e03fc9aa,
+            0x39abdc4529b1661c,
+        ];
+
+        let mut state = [0; 4];
+        for word in JUMP {
+            for bit in 0..64 {
+                if word & (1 << bit) != 0 {
+                    state.iter_mut().zip(self.state).for_each(|(x, s)| *x ^= s);
+                }
+                self.next_u64();
+            }
+        }
+        self.state = state;
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::random::{CustomNumberGenerator, RandomGenerator};
+    use crate::random::{
+        CustomNumberGenerator, RandomGenerator, RandomState, SplitMix64, Xoshiro256PlusPlus,
+    };
 
     #[test]
     fn outputs_same_numbers() {
         let mut crg = CustomNumberGenerator::new(42);
-        assert_eq!(crg.next_range(0, 10), 8);
+        assert_eq!(crg.next_range(0, 10), 5);
         assert_eq!(crg.next_range(0, 10), 4);
-        assert_eq!(crg.next_range(0, 10), 1);
-        assert_eq!(crg.next_range(0, 10), 2);
+        assert_eq!(crg.next_range(0, 10), 6);
+        assert_eq!(crg.next_range(0, 10), 0);
         assert_eq!(crg.next_range(0, 10), 4);
     }
 
@@ -90,10 +462,181 @@ mod tests {
     fn random_from_vec_should_be_same() {
         let vec = vec![432, 6542, 534, 6, 13, 645, 88, 2352, 345, 2667, 8287];
         let mut crg = CustomNumberGenerator::default();
-        assert_eq!(*crg.get_random_from_vec(&vec), 6);
-        assert_eq!(*crg.get_random_from_vec(&vec), 2667);
+        assert_eq!(*crg.get_random_from_vec(&vec), 13);
+        assert_eq!(*crg.get_random_from_vec(&vec), 432);
         assert_eq!(*crg.get_random_from_vec(&vec), 534);
-        assert_eq!(*crg.get_random_from_vec(&vec), 8287);
-        assert_eq!(*crg.get_random_from_vec(&vec), 6);
+        assert_eq!(*crg.get_random_from_vec(&vec), 534);
+        assert_eq!(*crg.get_random_from_vec(&vec), 2352);
+    }
+
+    #[test]
+    fn bounded_values_stay_in_range() {
+        let mut crg = CustomNumberGenerator::default();
+        for _ in 0..1000 {
+            assert!((-5..5).contains(&crg.next_range(-5, 5)));
+            assert!(crg.next_index(3) < 3);
+            assert!((0.0..1.0).contains(&crg.next_f64()));
+        }
+        assert_eq!(crg.next_range(i32::MIN, i32::MIN + 1), i32::MIN);
+        assert_eq!(crg.next_index(1), 0);
+    }
+
+    #[test]
+    fn indices_are_uniform() {
+        // 3 does not divide 2^64, so a modulo reduction would be biased towards the low indices.
+        let mut crg = CustomNumberGenerator::new(7);
+        let mut counts = [0u32; 3];
+        for _ in 0..30000 {
+            counts[crg.next_index(3)] += 1;
+        }
+        assert!(counts.iter().all(|x| x.abs_diff(10000) < 300));
+    }
+
+    #[test]
+    fn weighted_indices_follow_the_weights() {
+        let mut crg = CustomNumberGenerator::new(7);
+        let weights = [1.0, 0.0, 3.0, 6.0];
+        let mut counts = [0u32; 4];
+        for _ in 0..10000 {
+            counts[crg.next_weighted(&weights)] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!(counts[0].abs_diff(1000) < 150);
+        assert!(counts[2].abs_diff(3000) < 200);
+        assert!(counts[3].abs_diff(6000) < 200);
+        assert_eq!(crg.next_weighted(&[0.0, 2.5]), 1);
+    }
+
+    #[test]
+    fn beta_samples_match_the_distribution() {
+        let mut crg = CustomNumberGenerator::new(11);
+        for (alpha, beta) in [(1.0, 1.0), (2.0, 8.0), (30.0, 10.0), (0.5, 0.5)] {
+            let samples: Vec<_> = (0..20000).map(|_| crg.next_beta(alpha, beta)).collect();
+            assert!(samples.iter().all(|x| (0.0..=1.0).contains(x)));
+            let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+            let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 20000.0;
+            let expected_mean = alpha / (alpha + beta);
+            let expected_variance = alpha * beta / ((alpha + beta).powi(2) * (alpha + beta + 1.0));
+            assert!((mean - expected_mean).abs() < 0.01);
+            assert!((variance - expected_variance).abs() < 0.1 * expected_variance);
+        }
+    }
+
+    #[test]
+    fn dirichlet_samples_sum_to_one() {
+        let mut crg = CustomNumberGenerator::new(3);
+        for alpha in [0.03, 0.3, 10.0] {
+            let samples = crg.next_dirichlet(alpha, 9);
+            assert_eq!(samples.len(), 9);
+            assert!(samples.iter().all(|x| *x >= 0.0));
+            assert!((samples.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+        let peaked = crg.next_dirichlet(0.03, 9);
+        let flat = crg.next_dirichlet(100.0, 9);
+        assert!(peaked.iter().copied().fold(0.0, f64::max) > 0.5);
+        assert!(flat.iter().all(|x| (x - 1.0 / 9.0).abs() < 0.05));
+    }
+
+    #[test]
+    #[should_panic(expected = "weights must have a positive finite sum")]
+    fn weighted_indices_need_a_positive_weight() {
+        CustomNumberGenerator::default().next_weighted(&[0.0, 0.0]);
+    }
+
+    #[test]
+    fn splitmix_matches_reference_values() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xe220a8397b1dcdaf);
+        assert_eq!(rng.next_u64(), 0x6e789e6aa1b965f4);
+        assert_eq!(rng.next_u64(), 0x06c45d188009454f);
+    }
+
+    #[test]
+    fn xoshiro_matches_reference_values() {
+        let mut rng = Xoshiro256PlusPlus::from_state([1, 2, 3, 4]);
+        assert_eq!(rng.next_u64(), 41943041);
+        assert_eq!(rng.next_u64(), 58720359);
+        assert_eq!(rng.next_u64(), 3588806011781223);
+    }
+
+    #[test]
+    fn xoshiro_is_reproducible_from_a_seed() {
+        let mut a = Xoshiro256PlusPlus::new(42);
+        let mut b = Xoshiro256PlusPlus::new(42);
+        let mut c = Xoshiro256PlusPlus::new(43);
+        let a: Vec<_> = (0..10).map(|_| a.next_u64()).collect();
+        let b: Vec<_> = (0..10).map(|_| b.next_u64()).collect();
+        let c: Vec<_> = (0..10).map(|_| c.next_u64()).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn jumps_split_the_sequence_into_streams() {
+        let mut a = Xoshiro256PlusPlus::new(42);
+        let mut b = a.clone();
+        b.jump();
+        let mut c = b.clone();
+        c.jump();
+        let mut jumped_again = Xoshiro256PlusPlus::new(42);
+        jumped_again.jump();
+
+        assert_eq!(jumped_again, b);
+        let a: Vec<_> = (0..10).map(|_| a.next_u64()).collect();
+        let b: Vec<_> = (0..10).map(|_| b.next_u64()).collect();
+        let c: Vec<_> = (0..10).map(|_| c.next_u64()).collect();
+        assert_ne!(a, b);
+        assert_ne!(b, c);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn restored_generators_continue_the_sequence() {
+        let mut a = Xoshiro256PlusPlus::new(42);
+        a.next_u64();
+        let mut b = Xoshiro256PlusPlus::restore_state(&a.save_state()).unwrap();
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        let mut a = CustomNumberGenerator::new(42);
+        a.next_u64();
+        let mut b = CustomNumberGenerator::restore_state(&a.save_state()).unwrap();
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        assert!(Xoshiro256PlusPlus::restore_state(&[0; 4]).is_none());
+        assert!(SplitMix64::restore_state(&[1, 2]).is_none());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn adapter_forwards_to_the_wrapped_generator() {
+        use crate::random::RandRngAdapter;
+        use rand::rngs::StdRng;
+        use rand::{RngCore, SeedableRng};
+
+        let mut expected = StdRng::seed_from_u64(42);
+        let mut adapter = RandRngAdapter::new(StdRng::seed_from_u64(42));
+        assert_eq!(adapter.next_u64(), expected.next_u64());
+        assert_eq!(adapter.next_u64(), expected.next_u64());
+        assert_eq!(adapter.into_inner().next_u64(), expected.next_u64());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn adapter_drives_a_reproducible_search() {
+        use crate::boards::tic_tac_toe::TicTacToeBoard;
+        use crate::mcts::MonteCarloTreeSearch;
+        use crate::random::RandRngAdapter;
+        use crate::testing::tree_fingerprint;
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let search = || {
+            let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+                .with_random_generator(RandRngAdapter::new(StdRng::seed_from_u64(7)))
+                .build();
+            mcts.iterate_n_times(500);
+            tree_fingerprint(mcts.get_tree())
+        };
+        assert_eq!(search(), search());
     }
 }
@@ -0,0 +1,212 @@
+use crate::arena::MatchResult;
+
+/// Converts an expected score in `(0, 1)` into the Elo difference that predicts it under the
+/// logistic model. Scores of exactly `0` or `1` give infinite differences.
+pub fn elo_from_score(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+/// Converts an Elo difference into the expected score of the stronger side under the logistic
+/// model.
+pub fn score_from_elo(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+impl MatchResult {
+    /// Returns the Elo difference between the first and the second contestant estimated from the
+    /// score.
+    pub fn elo_difference(&self) -> f64 {
+        elo_from_score(self.score())
+    }
+
+    /// Returns the Elo difference at the bounds of `confidence_interval`.
+    pub fn elo_confidence_interval(&self, z: f64) -> (f64, f64) {
+        let (low, high) = self.confidence_interval(z);
+        (elo_from_score(low), elo_from_score(high))
+    }
+}
+
+/// The verdict of a sequential probability ratio test.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SprtDecision {
+    /// Neither hypothesis is supported strongly enough yet.
+    Continue,
+    /// The Elo difference is at most `elo0`.
+    AcceptH0,
+    /// The Elo difference is at least `elo1`.
+    AcceptH1,
+}
+
+/// A sequential probability ratio test deciding whether the first contestant of a match is at
+/// least `elo1` stronger than the second, or at most `elo0`, using the trinomial model of wins,
+/// draws and losses.
+///
+/// The log-likelihood ratio uses the normal approximation of the score, the same one used by
+/// common engine testing frameworks. Check `decide` after every pair of games and stop as soon as
+/// it returns something other than `SprtDecision::Continue`; the probability of accepting the
+/// wrong hypothesis is then bounded by `alpha` and `beta`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sprt {
+    elo0: f64,
+    elo1: f64,
+    alpha: f64,
+    beta: f64,
+}
+
+impl Sprt {
+    /// Creates a test of `elo0` against `elo1`, with error rates of 5%.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `elo0` is not below `elo1`.
+    pub fn new(elo0: f64, elo1: f64) -> Self {
+        assert!(elo0 < elo1, "elo0 must be below elo1");
+        Self {
+            elo0,
+            elo1,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+
+    /// Sets the probabilities of accepting H1 when H0 holds (`alpha`) and of accepting H0 when H1
+    /// holds (`beta`).
+    pub fn with_error_rates(mut self, alpha: f64, beta: f64) -> Self {
+        self.alpha = alpha;
+        self.beta = beta;
+        self
+    }
+
+    /// Returns the log-likelihood ratio of H1 against H0 given the result.
+    ///
+    /// A result in which every game ended the same way gives no spread to estimate the variance
+    /// of the score from, so it is counted with one extra win and one extra loss.
+    pub fn log_likelihood_ratio(&self, result: &MatchResult) -> f64 {
+        if result.games() == 0 {
+            return 0.0;
+        }
+
+        let is_uniform = [result.wins, result.draws, result.losses]
+            .iter()
+            .any(|x| *x == result.games());
+        let wins = result.wins + is_uniform as u32;
+        let losses = result.losses + is_uniform as u32;
+        let games = (wins + result.draws + losses) as f64;
+        let score = (wins as f64 + result.draws as f64 / 2.0) / games;
+        let variance = (wins as f64 * (1.0 - score).powi(2)
+            + result.draws as f64 * (0.5 - score).powi(2)
+            + losses as f64 * score.powi(2))
+            / games;
+
+        let (score0, score1) = (score_from_elo(self.elo0), score_from_elo(self.elo1));
+        games * (score1 - score0) * (2.0 * score - score0 - score1) / (2.0 * variance)
+    }
+
+    /// Returns the bounds of the log-likelihood ratio below which H0 and above which H1 is
+    /// accepted.
+    pub fn get_bounds(&self) -> (f64, f64) {
+        let lower = (self.beta / (1.0 - self.alpha)).ln();
+        let upper = ((1.0 - self.beta) / self.alpha).ln();
+        (lower, upper)
+    }
+
+    /// Decides between the hypotheses given the result so far.
+    pub fn decide(&self, result: &MatchResult) -> SprtDecision {
+        let llr = self.log_likelihood_ratio(result);
+        let (lower, upper) = self.get_bounds();
+        if llr >= upper {
+            SprtDecision::AcceptH1
+        } else if llr <= lower {
+            SprtDecision::AcceptH0
+        } else {
+            SprtDecision::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::{Arena, MatchResult};
+    use crate::boards::breakthrough::BreakthroughBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+    use crate::rating::{Sprt, SprtDecision, elo_from_score, score_from_elo};
+    use std::sync::atomic::AtomicBool;
+
+    fn result(wins: u32, draws: u32, losses: u32) -> MatchResult {
+        MatchResult {
+            first: "a".to_string(),
+            second: "b".to_string(),
+            wins,
+            draws,
+            losses,
+        }
+    }
+
+    #[test]
+    fn elo_and_score_are_inverse() {
+        assert_eq!(elo_from_score(0.5), 0.0);
+        assert!((score_from_elo(400.0) - 10.0 / 11.0).abs() < 1e-12);
+        assert!((elo_from_score(score_from_elo(-123.0)) + 123.0).abs() < 1e-9);
+        assert!((result(3, 0, 1).elo_difference() - 190.848).abs() < 1e-3);
+    }
+
+    #[test]
+    fn sprt_decides_on_clear_results() {
+        // arrange
+        let sprt = Sprt::new(0.0, 20.0);
+
+        // act
+        let undecided = sprt.decide(&result(55, 10, 45));
+        let better = sprt.decide(&result(600, 200, 400));
+        let worse = sprt.decide(&result(400, 200, 600));
+
+        // assert
+        assert_eq!(undecided, SprtDecision::Continue);
+        assert_eq!(better, SprtDecision::AcceptH1);
+        assert_eq!(worse, SprtDecision::AcceptH0);
+        let (lower, upper) = sprt.get_bounds();
+        assert!((lower + 2.944).abs() < 1e-3 && (upper - 2.944).abs() < 1e-3);
+    }
+
+    #[test]
+    fn sprt_continues_after_a_few_draws() {
+        for sprt in [Sprt::new(0.0, 5.0), Sprt::new(-5.0, 5.0)] {
+            let llr = sprt.log_likelihood_ratio(&result(0, 2, 0));
+            assert!(llr.is_finite());
+            assert_eq!(sprt.decide(&result(0, 2, 0)), SprtDecision::Continue);
+        }
+        let sprt = Sprt::new(0.0, 20.0);
+        assert_eq!(sprt.decide(&result(2, 0, 0)), SprtDecision::Continue);
+        assert_eq!(sprt.decide(&result(0, 10000, 0)), SprtDecision::AcceptH0);
+    }
+
+    #[test]
+    fn arena_stops_once_the_test_is_decided() {
+        // arrange
+        let mut arena = Arena::new(BreakthroughBoard::new(5, 3))
+            .with_iterations_per_move(200)
+            .with_random_opening_plies(2)
+            .with_contestant("search", |mut board: BreakthroughBoard| {
+                board.set_root_player(board.get_color_to_move());
+                MonteCarloTreeSearch::builder(board)
+                    .with_random_generator(CustomNumberGenerator::default())
+                    .build()
+            })
+            .with_contestant("first move", |board| {
+                MonteCarloTreeSearch::builder(board)
+                    .with_random_generator(CustomNumberGenerator::default())
+                    .with_stop_condition(AtomicBool::new(true))
+                    .build()
+            });
+        let sprt = Sprt::new(0.0, 100.0).with_error_rates(0.1, 0.1);
+
+        // act
+        let (result, decision) = arena.run_sprt(&sprt, 200);
+
+        // assert
+        assert_eq!(decision, SprtDecision::AcceptH1);
+        assert!(result.games() < 200);
+        assert_eq!(result.games() % 2, 0);
+    }
+}
@@ -0,0 +1,405 @@
+use crate::board::{Board, Bound};
+use crate::budget::SearchBudget;
+use crate::clock::Stopwatch;
+use crate::mcts::{MctsAction, MctsTreeNode, MonteCarloTreeSearch};
+use crate::mcts_node::MctsNode;
+use crate::random::RandomGenerator;
+use ego_tree::NodeRef;
+use std::time::Duration;
+
+/// The number of iterations run between two checks of the time budget.
+const ITERATIONS_PER_CHECK: u64 = 64;
+
+/// The statistics of a single root move in a `SearchResult`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveStats<M> {
+    /// The move.
+    pub b_move: M,
+    /// The number of times the move was visited.
+    pub visits: u32,
+    /// The estimated value of the move, in `[0, 1]`.
+    pub value: f64,
+    /// The proven bound of the move.
+    pub bound: Bound,
+}
+
+/// A root move with the line of play expected after it, see
+/// `MonteCarloTreeSearch::get_top_moves`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandidateLine<M> {
+    /// The statistics of the root move.
+    pub stats: MoveStats<M>,
+    /// The chain of best moves starting with the root move.
+    pub principal_variation: Vec<M>,
+}
+
+/// A summary of the search from the root: the chosen move, the ranking of all root moves and the
+/// expected line of play.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult<M> {
+    /// The move the search recommends, if the root has any children.
+    pub best_move: Option<M>,
+    /// All root moves, ranked by visits in descending order.
+    pub moves: Vec<MoveStats<M>>,
+    /// The chain of best moves starting at the root.
+    pub principal_variation: Vec<M>,
+    /// The proven bound of the root, `Bound::None` while its value is uncertain.
+    pub root_bound: Bound,
+    /// The number of iterations completed so far.
+    pub iterations: u64,
+    /// The time spent running iterations so far.
+    pub elapsed: Duration,
+}
+
+/// The change of a single root move between two `SearchResult`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveDiff<M> {
+    /// The move.
+    pub b_move: M,
+    /// The rank of the move in the original result, or `None` if it was absent.
+    pub old_rank: Option<usize>,
+    /// The rank of the move in the other result, or `None` if it is absent.
+    pub new_rank: Option<usize>,
+    /// The value of the move in the original result.
+    pub old_value: Option<f64>,
+    /// The value of the move in the other result.
+    pub new_value: Option<f64>,
+}
+
+impl<M> MoveDiff<M> {
+    /// Returns the change of the value of the move, if it is present in both results.
+    pub fn value_delta(&self) -> Option<f64> {
+        Some(self.new_value? - self.old_value?)
+    }
+}
+
+/// The structured difference between two `SearchResult`s, see `SearchResult::diff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchDiff<M> {
+    /// The recommended moves of the two results, if they differ.
+    pub best_move_change: Option<(Option<M>, Option<M>)>,
+    /// Every move whose rank or value differs, in the order of the original ranking followed by
+    /// moves only present in the other result.
+    pub moves: Vec<MoveDiff<M>>,
+    /// The index of the first ply at which the principal variations differ, or `None` if they are
+    /// identical.
+    pub pv_divergence: Option<usize>,
+}
+
+impl<M> SearchDiff<M> {
+    /// Returns `true` if the two results are identical.
+    pub fn is_empty(&self) -> bool {
+        self.best_move_change.is_none() && self.moves.is_empty() && self.pv_divergence.is_none()
+    }
+}
+
+impl<M: Clone + PartialEq> SearchResult<M> {
+    /// Returns the statistics and the rank of a root move.
+    pub fn find_move(&self, b_move: &M) -> Option<(usize, &MoveStats<M>)> {
+        self.moves
+            .iter()
+            .enumerate()
+            .find(|(_, x)| x.b_move == *b_move)
+    }
+
+    /// Compares this result with `other`, reporting changes in move ranking and values and the
+    /// point at which the principal variations diverge.
+    pub fn diff(&self, other: &Self) -> SearchDiff<M> {
+        let best_move_change = if self.best_move != other.best_move {
+            Some((self.best_move.clone(), other.best_move.clone()))
+        } else {
+            None
+        };
+
+        let mut moves = Vec::new();
+        for (old_rank, old) in self.moves.iter().enumerate() {
+            let new = other.find_move(&old.b_move);
+            let is_same = new.is_some_and(|(new_rank, new)| new_rank == old_rank && new == old);
+            if !is_same {
+                moves.push(MoveDiff {
+                    b_move: old.b_move.clone(),
+                    old_rank: Some(old_rank),
+                    new_rank: new.map(|(rank, _)| rank),
+                    old_value: Some(old.value),
+                    new_value: new.map(|(_, x)| x.value),
+                });
+            }
+        }
+
+        for (new_rank, new) in other.moves.iter().enumerate() {
+            if self.find_move(&new.b_move).is_none() {
+                moves.push(MoveDiff {
+                    b_move: new.b_move.clone(),
+                    old_rank: None,
+                    new_rank: Some(new_rank),
+                    old_value: None,
+                    new_value: Some(new.value),
+                });
+            }
+        }
+
+        let common_length = self
+            .principal_variation
+            .iter()
+            .zip(&other.principal_variation)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let pv_divergence = if common_length == self.principal_variation.len()
+            && common_length == other.principal_variation.len()
+        {
+            None
+        } else {
+            Some(common_length)
+        };
+
+        SearchDiff {
+            best_move_change,
+            moves,
+            pv_divergence,
+        }
+    }
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Clone,
+{
+    /// Runs the search within the budget and summarizes it, see `get_search_result`.
+    ///
+    /// The search also ends early once the tree is fully calculated or the stop condition holds.
+    /// The time budget is checked every 64 iterations.
+    pub fn search(&mut self, budget: SearchBudget) -> SearchResult<T::Move> {
+        let stopwatch = Stopwatch::start();
+        let mut remaining = budget.iterations.unwrap_or(u64::MAX);
+        while remaining > 0
+            && *self.get_next_mcts_action() != MctsAction::EverythingIsCalculated
+            && !self.is_stop_requested()
+            && budget.time.is_none_or(|x| stopwatch.elapsed() < x)
+        {
+            let iterations = remaining.min(ITERATIONS_PER_CHECK);
+            self.iterate_n_times(iterations as u32);
+            remaining -= iterations;
+        }
+        self.get_search_result()
+    }
+
+    /// Runs the search within the budget like `search`, calling `after_iteration` after every
+    /// iteration and stopping at its first error.
+    #[cfg(any(feature = "telemetry", feature = "tui"))]
+    pub(crate) fn search_each<E>(
+        &mut self,
+        budget: SearchBudget,
+        mut after_iteration: impl FnMut(&Self) -> Result<(), E>,
+    ) -> Result<SearchResult<T::Move>, E> {
+        let stopwatch = Stopwatch::start();
+        let mut remaining = budget.iterations.unwrap_or(u64::MAX);
+        while remaining > 0
+            && *self.get_next_mcts_action() != MctsAction::EverythingIsCalculated
+            && !self.is_stop_requested()
+            && budget.time.is_none_or(|x| stopwatch.elapsed() < x)
+        {
+            self.do_iteration();
+            remaining -= 1;
+            after_iteration(self)?;
+        }
+        Ok(self.get_search_result())
+    }
+
+    /// Summarizes the current state of the search from the root.
+    pub fn get_search_result(&self) -> SearchResult<T::Move> {
+        let mut moves: Vec<_> = self.get_root().children().map(move_stats).collect();
+        moves.sort_by_key(|x| std::cmp::Reverse(x.visits));
+        let principal_variation = self.principal_variation_from(self.get_root());
+
+        let statistics = self.get_statistics();
+        SearchResult {
+            best_move: principal_variation.first().cloned(),
+            moves,
+            principal_variation,
+            root_bound: self.get_root().value().stats.bound,
+            iterations: statistics.iterations,
+            elapsed: statistics.elapsed,
+        }
+    }
+
+    /// Returns the `k` most visited root moves, each with the line of play expected after it, for
+    /// analysis tools showing several candidate lines.
+    ///
+    /// Moves with equal visits keep the order they were created in.
+    pub fn get_top_moves(&self, k: usize) -> Vec<CandidateLine<T::Move>> {
+        let mut children: Vec<_> = self.get_root().children().collect();
+        children.sort_by_key(|x| std::cmp::Reverse(x.value().stats.visits));
+        children
+            .into_iter()
+            .take(k)
+            .map(|x| {
+                let mut principal_variation = vec![x.value().prev_move().unwrap().clone()];
+                principal_variation.extend(self.principal_variation_from(x.into()));
+                CandidateLine {
+                    stats: move_stats(x),
+                    principal_variation,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the chain of best moves below the given node.
+    pub(crate) fn principal_variation_from(&self, mut node: MctsTreeNode<'_, T>) -> Vec<T::Move> {
+        let mut principal_variation = Vec::new();
+        while let Some(child) = node.get_best_child_by(self.get_best_child_criterion()) {
+            principal_variation.push(child.value().prev_move().unwrap().clone());
+            node = child;
+        }
+        principal_variation
+    }
+}
+
+/// Returns the statistics of the move leading to a node.
+fn move_stats<T: Board>(node: NodeRef<'_, MctsNode<T>>) -> MoveStats<T::Move>
+where
+    T::Move: Clone,
+{
+    let mcts_node = node.value();
+    MoveStats {
+        b_move: mcts_node.prev_move().unwrap().clone(),
+        visits: mcts_node.stats.visits,
+        value: mcts_node.value_mean(),
+        bound: mcts_node.stats.bound,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Bound;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::budget::SearchBudget;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+    use crate::result::{MoveStats, SearchResult};
+    use std::time::Duration;
+
+    fn stats(b_move: u8, visits: u32, value: f64) -> MoveStats<u8> {
+        MoveStats {
+            b_move,
+            visits,
+            value,
+            bound: Bound::None,
+        }
+    }
+
+    #[test]
+    fn summarizes_the_root() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        mcts.iterate_n_times(20000);
+        let result = mcts.get_search_result();
+
+        // assert
+        assert_eq!(result.best_move, Some(4));
+        assert_eq!(result.principal_variation[0], 4);
+        assert_eq!(result.moves.len(), 9);
+        assert!(result.moves.windows(2).all(|x| x[0].visits >= x[1].visits));
+        assert!(result.diff(&result).is_empty());
+    }
+
+    #[test]
+    fn reports_ranking_value_and_pv_changes() {
+        // arrange
+        let old = SearchResult {
+            best_move: Some(4),
+            moves: vec![stats(4, 60, 0.7), stats(0, 30, 0.6), stats(8, 10, 0.5)],
+            principal_variation: vec![4, 0, 8],
+            root_bound: Bound::None,
+            iterations: 100,
+            elapsed: Duration::ZERO,
+        };
+        let new = SearchResult {
+            best_move: Some(0),
+            moves: vec![stats(0, 60, 0.75), stats(4, 30, 0.7), stats(2, 10, 0.4)],
+            principal_variation: vec![4, 0, 2],
+            root_bound: Bound::None,
+            iterations: 100,
+            elapsed: Duration::ZERO,
+        };
+
+        // act
+        let diff = old.diff(&new);
+
+        // assert
+        assert_eq!(diff.best_move_change, Some((Some(4), Some(0))));
+        assert_eq!(diff.pv_divergence, Some(2));
+        let changes: Vec<_> = diff
+            .moves
+            .iter()
+            .map(|x| (x.b_move, x.old_rank, x.new_rank))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (4, Some(0), Some(1)),
+                (0, Some(1), Some(0)),
+                (8, Some(2), None),
+                (2, None, Some(2)),
+            ]
+        );
+        assert!((diff.moves[1].value_delta().unwrap() - 0.15).abs() < 1e-9);
+        assert_eq!(diff.moves[2].value_delta(), None);
+    }
+
+    #[test]
+    fn search_runs_within_the_budget() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        let partial = mcts.search(SearchBudget::iterations(1000));
+        let complete = mcts.search(SearchBudget::default());
+
+        // assert
+        assert_eq!(partial.iterations, 1000);
+        assert_eq!(partial.root_bound, Bound::None);
+        assert_eq!(complete.iterations, 37659);
+        assert_eq!(complete.best_move, Some(4));
+        assert!(mcts.get_root().value().stats.is_fully_calculated);
+        assert!(complete.elapsed >= partial.elapsed);
+        assert_eq!(
+            mcts.search(SearchBudget::time(Duration::from_secs(1))),
+            complete
+        );
+    }
+
+    #[test]
+    fn reports_the_top_moves_with_their_lines() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(5000);
+
+        // act
+        let top_moves = mcts.get_top_moves(3);
+
+        // assert
+        let result = mcts.get_search_result();
+        assert_eq!(top_moves.len(), 3);
+        for (line, stats) in top_moves.iter().zip(&result.moves) {
+            assert_eq!(&line.stats, stats);
+            assert_eq!(line.principal_variation[0], stats.b_move);
+            assert!(line.principal_variation.len() > 1);
+        }
+        let best_line = top_moves
+            .iter()
+            .find(|x| Some(x.stats.b_move) == result.best_move)
+            .unwrap();
+        assert_eq!(best_line.principal_variation, result.principal_variation);
+        assert_eq!(mcts.get_top_moves(20).len(), 9);
+    }
+}
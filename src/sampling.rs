@@ -0,0 +1,171 @@
+use crate::board::Board;
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::RandomGenerator;
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Clone,
+{
+    /// Samples a root move with probability proportional to `visits^(1 / temperature)`.
+    ///
+    /// A temperature of `1.0` samples in proportion to the visit counts, higher temperatures
+    /// flatten the distribution, and a temperature of `0.0` always picks the most visited move.
+    /// If no root child has been visited yet, all moves are equally likely. Returns `None` if the
+    /// root has not been expanded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `temperature` is negative or NaN.
+    pub fn sample_move<R: RandomGenerator>(
+        &self,
+        temperature: f64,
+        rng: &mut R,
+    ) -> Option<T::Move> {
+        let visits = self.root_child_visits();
+        let index = sample_index(&visits, temperature, rng)?;
+        let child = self.get_root().children().nth(index)?;
+        child.value().prev_move().cloned()
+    }
+
+    /// Returns the move to play once the search is over, sampled with the temperature of the
+    /// search from its own random generator, see `sample_move` and `set_temperature`.
+    ///
+    /// A temperature of `0.0` returns the best child by the best child criterion, like
+    /// `get_most_perspective_move`. Returns `None` if the root has not been expanded.
+    pub fn choose_final_move(&mut self) -> Option<T::Move> {
+        if self.get_temperature() == 0.0 {
+            return self.get_most_perspective_move();
+        }
+        let visits = self.root_child_visits();
+        let index = sample_index(
+            &visits,
+            self.get_temperature(),
+            self.get_random_generator_mut(),
+        )?;
+        let child = self.get_root().children().nth(index)?;
+        child.value().prev_move().cloned()
+    }
+
+    /// Returns the visit counts of the root children in their order.
+    fn root_child_visits(&self) -> Vec<u32> {
+        let root = self.get_root();
+        root.children().map(|x| x.value().stats.visits).collect()
+    }
+}
+
+/// Samples an index with probability proportional to `visits^(1 / temperature)`, or returns
+/// `None` if there is nothing to sample from.
+fn sample_index<R: RandomGenerator>(
+    visits: &[u32],
+    temperature: f64,
+    rng: &mut R,
+) -> Option<usize> {
+    assert!(temperature >= 0.0, "temperature must not be negative");
+
+    let max_visits = *visits.iter().max()?;
+    let index = if temperature == 0.0 {
+        visits.iter().position(|x| *x == max_visits).unwrap()
+    } else if max_visits == 0 {
+        rng.next_index(visits.len())
+    } else {
+        // The weights are scaled by the largest one so that low temperatures cannot overflow.
+        let weights: Vec<_> = visits
+            .iter()
+            .map(|x| (*x as f64 / max_visits as f64).powf(1.0 / temperature))
+            .collect();
+        rng.next_weighted(&weights)
+    };
+    Some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+    use std::collections::{HashMap, HashSet};
+
+    fn searched(iterations: u32) -> MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator> {
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(iterations);
+        mcts
+    }
+
+    fn sample_counts(
+        mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>,
+        temperature: f64,
+        samples: u32,
+    ) -> HashMap<u8, u32> {
+        let mut rng = CustomNumberGenerator::new(7);
+        let mut counts = HashMap::new();
+        for _ in 0..samples {
+            let b_move = mcts.sample_move(temperature, &mut rng).unwrap();
+            *counts.entry(b_move).or_default() += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn zero_temperature_picks_the_most_visited_move() {
+        // arrange
+        let mcts = searched(2000);
+
+        // act
+        let counts = sample_counts(&mcts, 0.0, 50);
+
+        // assert
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts.get(&4), Some(&50));
+    }
+
+    #[test]
+    fn temperature_controls_the_spread() {
+        // arrange
+        let mcts = searched(2000);
+        let visit_share = mcts
+            .get_root()
+            .children()
+            .find(|x| *x.value().prev_move().unwrap() == 4)
+            .map(|x| x.value().stats.visits as f64 / 2000.0)
+            .unwrap();
+
+        // act
+        let proportional = sample_counts(&mcts, 1.0, 5000);
+        let flat = sample_counts(&mcts, 100.0, 5000);
+
+        // assert
+        let proportional_share = proportional[&4] as f64 / 5000.0;
+        assert!((proportional_share - visit_share).abs() < 0.03);
+        assert_eq!(flat.len(), 9);
+        assert!(flat.values().all(|x| *x > 400));
+    }
+
+    #[test]
+    fn unexpanded_root_has_nothing_to_sample() {
+        let mcts = searched(0);
+        let mut rng = CustomNumberGenerator::default();
+        assert_eq!(mcts.sample_move(1.0, &mut rng), None);
+    }
+
+    #[test]
+    fn final_move_follows_the_runtime_temperature() {
+        // arrange
+        let mut mcts = searched(2000);
+        let best_move = mcts.get_most_perspective_move();
+
+        // act
+        let cold: HashSet<_> = (0..50).map(|_| mcts.choose_final_move()).collect();
+        mcts.set_temperature(5.0);
+        let hot: HashSet<_> = (0..50).map(|_| mcts.choose_final_move()).collect();
+        mcts.set_temperature(0.0);
+
+        // assert
+        assert_eq!(cold, HashSet::from([best_move]));
+        assert!(hot.len() > 1);
+        assert_eq!(mcts.choose_final_move(), best_move);
+        assert_eq!(mcts.get_config().temperature, 0.0);
+    }
+}
@@ -0,0 +1,163 @@
+//! Root move filters written in [Rhai](https://rhai.rs), so the behavior of a bot can be tweaked
+//! without recompiling.
+//!
+//! A script defines a function `allow(board, move)` returning `true` for the moves the search may
+//! consider:
+//!
+//! ```
+//! use mcts_lib::boards::tic_tac_toe::TicTacToeBoard;
+//! use mcts_lib::mcts::MonteCarloTreeSearch;
+//! use mcts_lib::random::CustomNumberGenerator;
+//! use mcts_lib::scripting::ScriptedMoveFilter;
+//!
+//! let filter = ScriptedMoveFilter::new("fn allow(board, move) { move != 4 }").unwrap();
+//! let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+//!     .with_random_generator(CustomNumberGenerator::default())
+//!     .with_root_move_filter(filter)
+//!     .build();
+//! mcts.iterate_n_times(100);
+//! assert_eq!(mcts.get_root().children().count(), 8);
+//! ```
+//!
+//! Integer moves are passed to the script as Rhai integers. The board and moves of any other
+//! type are passed as they are; register getters for them on a `rhai::Engine` and create the
+//! filter with `ScriptedMoveFilter::with_engine`.
+
+use crate::board::Board;
+use crate::filter::MoveFilter;
+use rhai::{AST, Dynamic, Engine, INT, ParseError, Scope};
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// The name of the function a filter script must define.
+pub const FILTER_FUNCTION: &str = "allow";
+
+/// An error raised while loading a filter script.
+#[derive(Debug)]
+pub enum ScriptError {
+    /// The script could not be parsed.
+    Parse(ParseError),
+    /// The script does not define `allow(board, move)`.
+    MissingFunction,
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptError::Parse(error) => write!(f, "failed to parse script: {error}"),
+            ScriptError::MissingFunction => {
+                write!(f, "script does not define `{FILTER_FUNCTION}(board, move)`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// A `MoveFilter` evaluating a Rhai script.
+///
+/// Moves for which the script fails or returns something other than a boolean are allowed.
+pub struct ScriptedMoveFilter {
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptedMoveFilter {
+    /// Compiles a filter script with a default engine.
+    pub fn new(script: &str) -> Result<Self, ScriptError> {
+        Self::with_engine(Engine::new(), script)
+    }
+
+    /// Compiles a filter script with an engine that may have custom types and functions
+    /// registered.
+    pub fn with_engine(engine: Engine, script: &str) -> Result<Self, ScriptError> {
+        let ast = engine.compile(script).map_err(ScriptError::Parse)?;
+        let has_function = ast
+            .iter_functions()
+            .any(|x| x.name == FILTER_FUNCTION && x.params.len() == 2);
+        if !has_function {
+            return Err(ScriptError::MissingFunction);
+        }
+
+        Ok(Self { engine, ast })
+    }
+}
+
+impl<T> MoveFilter<T> for ScriptedMoveFilter
+where
+    T: Board + Send + Sync + 'static,
+    T::Move: Clone + Send + Sync + 'static,
+{
+    fn allows(&self, board: &T, b_move: &T::Move) -> bool {
+        let args = (to_script_value(board), to_script_value(b_move));
+        self.engine
+            .call_fn::<bool>(&mut Scope::new(), &self.ast, FILTER_FUNCTION, args)
+            .unwrap_or(true)
+    }
+}
+
+/// Converts a value for use in a script, turning primitive integers into Rhai integers so they
+/// can be compared with integer literals.
+fn to_script_value<V: Any + Clone + Send + Sync>(value: &V) -> Dynamic {
+    let any = value as &dyn Any;
+    let integer = any
+        .downcast_ref::<u8>()
+        .map(|x| *x as INT)
+        .or_else(|| any.downcast_ref::<u16>().map(|x| *x as INT))
+        .or_else(|| any.downcast_ref::<u32>().map(|x| *x as INT))
+        .or_else(|| any.downcast_ref::<usize>().map(|x| *x as INT))
+        .or_else(|| any.downcast_ref::<i8>().map(|x| *x as INT))
+        .or_else(|| any.downcast_ref::<i16>().map(|x| *x as INT))
+        .or_else(|| any.downcast_ref::<i32>().map(|x| *x as INT))
+        .or_else(|| any.downcast_ref::<i64>().map(|x| *x as INT));
+
+    match integer {
+        Some(integer) => Dynamic::from_int(integer),
+        None => Dynamic::from(value.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::filter::MoveFilter;
+    use crate::scripting::{ScriptError, ScriptedMoveFilter};
+    use rhai::Engine;
+
+    #[test]
+    fn rejects_invalid_scripts() {
+        assert!(matches!(
+            ScriptedMoveFilter::new("fn allow(board, move) {"),
+            Err(ScriptError::Parse(_))
+        ));
+        assert!(matches!(
+            ScriptedMoveFilter::new("fn allow(move) { true }"),
+            Err(ScriptError::MissingFunction)
+        ));
+    }
+
+    #[test]
+    fn evaluates_registered_board_functions() {
+        // arrange
+        let mut engine = Engine::new();
+        engine.register_fn("is_empty", |board: &mut TicTacToeBoard| {
+            board.get_available_moves().len() == 9
+        });
+        let script = "fn allow(board, move) { !board.is_empty() || move % 2 == 0 }";
+        let filter = ScriptedMoveFilter::with_engine(engine, script).unwrap();
+        let board = TicTacToeBoard::default();
+
+        // act
+        let allowed: Vec<u8> = (0..9).filter(|x| filter.allows(&board, x)).collect();
+
+        // assert
+        assert_eq!(allowed, vec![0, 2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn failing_script_allows_the_move() {
+        let filter = ScriptedMoveFilter::new("fn allow(board, move) { move.missing() }").unwrap();
+        assert!(filter.allows(&TicTacToeBoard::default(), &4));
+    }
+}
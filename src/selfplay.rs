@@ -0,0 +1,592 @@
+//! Generating training data for AlphaZero-style learning by letting a search play against itself.
+//!
+//! Every position of a self-play game becomes a `TrainingSample` holding the position encoded by
+//! a user function, the visit distribution of the search over the available moves, and the final
+//! outcome of the game for the player to move there.
+//!
+//! `play_games_parallel` plays many games on several threads, every game with its own stream of
+//! random numbers, and writes their samples to one file. Searches sharing a model should evaluate
+//! through a `SharedEvaluator`, which batches the requests of all the threads.
+//!
+//! # Format
+//!
+//! `SampleWriter` writes the magic bytes `MCTSSELF` and the format version as a little-endian
+//! `u32`, followed by the samples. A sample is the length of its state as a `u32` and the state
+//! as `f32`s, the length of its policy as a `u32` and the policy as `f32`s, and the outcome as an
+//! `f32`, all little-endian. `read_samples` reads such a file back.
+
+use crate::agent::SearchFactory;
+use crate::board::{Board, GameOutcome, Player};
+use crate::budget::SearchBudget;
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::{RandomGenerator, Xoshiro256PlusPlus};
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+/// The version of the sample format produced by this release.
+pub const SAMPLE_FORMAT_VERSION: u32 = 1;
+
+const MAGIC: &[u8; 8] = b"MCTSSELF";
+
+/// A function encoding a position as the input of a model.
+type Encoder<T> = Box<dyn Fn(&T) -> Vec<f32>>;
+
+/// How `SelfPlay` searches and picks its moves.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfPlayConfig {
+    /// The number of iterations searched for every move.
+    pub iterations: u32,
+    /// The temperature the played move is sampled with, see `MonteCarloTreeSearch::sample_move`.
+    pub temperature: f64,
+    /// The number of moves played with the temperature, after which the most visited move is
+    /// played.
+    pub temperature_moves: u32,
+    /// The concentration of the Dirichlet noise mixed into the move choice.
+    pub dirichlet_alpha: f64,
+    /// The share of the Dirichlet noise in the move choice, `0.0` to disable the noise.
+    pub noise_fraction: f64,
+    /// The number of moves after which a game is cut off and scored as a draw, if any.
+    pub max_plies: Option<u32>,
+}
+
+impl Default for SelfPlayConfig {
+    /// Returns the settings of AlphaZero for chess, with fewer iterations.
+    fn default() -> Self {
+        Self {
+            iterations: 800,
+            temperature: 1.0,
+            temperature_moves: 30,
+            dirichlet_alpha: 0.3,
+            noise_fraction: 0.25,
+            max_plies: None,
+        }
+    }
+}
+
+/// A position of a self-play game with its training targets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingSample {
+    /// The position, as encoded by the function given to `SelfPlay`.
+    pub state: Vec<f32>,
+    /// The share of the root visits of every available move, in the order of
+    /// `Board::get_available_moves`, summing to one.
+    pub policy: Vec<f32>,
+    /// The final outcome of the game for the player to move, `1.0` for a win, `0.0` for a draw
+    /// and `-1.0` for a loss.
+    pub outcome: f32,
+}
+
+/// Plays games in which a search picks the moves of both players, recording a `TrainingSample`
+/// for every position.
+///
+/// The played move is sampled from the visit counts of the root with the configured temperature,
+/// mixed with Dirichlet noise so games cover more openings. As the search itself has no priors,
+/// the noise perturbs the move choice rather than the root priors as in AlphaZero; the recorded
+/// policy is always the plain visit distribution.
+pub struct SelfPlay<T: Board, K: RandomGenerator> {
+    search_factory: SearchFactory<T, K>,
+    encoder: Encoder<T>,
+    config: SelfPlayConfig,
+}
+
+impl<T: Board, K: RandomGenerator> SelfPlay<T, K>
+where
+    T::Move: Clone + PartialEq,
+{
+    /// Creates a self-play driver running the searches from the factory, which receives the board
+    /// whenever a move is due, and encoding positions with `encoder`.
+    pub fn new(
+        search_factory: impl FnMut(T) -> MonteCarloTreeSearch<T, K> + 'static,
+        encoder: impl Fn(&T) -> Vec<f32> + 'static,
+    ) -> Self {
+        Self {
+            search_factory: Box::new(search_factory),
+            encoder: Box::new(encoder),
+            config: SelfPlayConfig::default(),
+        }
+    }
+
+    /// Sets how the searches run and how moves are picked.
+    pub fn with_config(mut self, config: SelfPlayConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Returns how the searches run and how moves are picked.
+    pub fn get_config(&self) -> SelfPlayConfig {
+        self.config
+    }
+
+    /// Plays a game from `board`, picking the moves with `rng`, and returns a sample for every
+    /// position a move was played in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the temperature is negative, or if the noise is enabled with a concentration that
+    /// is not positive and finite.
+    pub fn play_game<R: RandomGenerator>(&mut self, board: T, rng: &mut R) -> Vec<TrainingSample> {
+        assert!(
+            self.config.temperature >= 0.0,
+            "temperature must not be negative"
+        );
+
+        let mut board = board;
+        let mut positions = Vec::new();
+        let mut plies = 0;
+        while board.get_outcome() == GameOutcome::InProgress
+            && self.config.max_plies.is_none_or(|x| plies < x)
+        {
+            let moves = board.get_available_moves();
+            if moves.is_empty() {
+                break;
+            }
+
+            let policy = self.search_policy(&board, &moves);
+            let temperature = match plies < self.config.temperature_moves {
+                true => self.config.temperature,
+                false => 0.0,
+            };
+            let mut weights = apply_temperature(&policy, temperature);
+            if self.config.noise_fraction > 0.0 {
+                let noise = rng.next_dirichlet(self.config.dirichlet_alpha, moves.len());
+                let fraction = self.config.noise_fraction;
+                weights
+                    .iter_mut()
+                    .zip(noise)
+                    .for_each(|(x, n)| *x = (1.0 - fraction) * *x + fraction * n);
+            }
+
+            positions.push((
+                (self.encoder)(&board),
+                policy.iter().map(|x| *x as f32).collect(),
+                board.get_current_player(),
+            ));
+            board.perform_move(&moves[rng.next_weighted(&weights)]);
+            plies += 1;
+        }
+
+        let outcome = match board.get_outcome() {
+            GameOutcome::Win => 1.0,
+            GameOutcome::Lose => -1.0,
+            _ => 0.0,
+        };
+        positions
+            .into_iter()
+            .map(|(state, policy, player)| TrainingSample {
+                state,
+                policy,
+                outcome: match player {
+                    Player::Me => outcome,
+                    Player::Other => -outcome,
+                },
+            })
+            .collect()
+    }
+
+    /// Searches the board and returns the share of the root visits of every move, uniform if no
+    /// move was visited.
+    fn search_policy(&mut self, board: &T, moves: &[T::Move]) -> Vec<f64> {
+        let mut mcts = (self.search_factory)(board.clone());
+        mcts.search(SearchBudget {
+            iterations: Some(self.config.iterations as u64),
+            time: None,
+        });
+
+        let root = mcts.get_root();
+        let visits: Vec<_> = moves
+            .iter()
+            .map(|b_move| {
+                root.children()
+                    .find(|x| x.value().prev_move() == Some(b_move))
+                    .map_or(0, |x| x.value().stats.visits)
+            })
+            .collect();
+        let total: u32 = visits.iter().sum();
+        if total == 0 {
+            return vec![1.0 / moves.len() as f64; moves.len()];
+        }
+        visits.iter().map(|x| *x as f64 / total as f64).collect()
+    }
+}
+
+/// Plays `games` games on `threads` threads and writes their samples to `writer`, returning the
+/// number of samples written.
+///
+/// Every thread takes the next game not yet played and asks `new_game` for the driver and the
+/// starting board of that game index. Game `i` picks its moves with the `i`-th stream of a
+/// `Xoshiro256PlusPlus` seeded with `seed`, i.e. the generator jumped `i` times, so the streams
+/// never overlap. The games are written in the order of their index as soon as all the games
+/// before them are written, so with iteration budgets and searches seeded by `new_game` the file
+/// is exactly the one written by playing the games one after another.
+///
+/// Once a write fails no further game is started, and the first error is returned after the
+/// running games end.
+///
+/// # Panics
+///
+/// Panics if `threads` is zero, or if a game panics.
+pub fn play_games_parallel<T: Board, K: RandomGenerator, W: Write>(
+    games: usize,
+    threads: usize,
+    seed: u64,
+    new_game: impl Fn(usize) -> (SelfPlay<T, K>, T) + Sync,
+    writer: &mut SampleWriter<W>,
+) -> io::Result<usize>
+where
+    T::Move: Clone + PartialEq,
+{
+    assert!(threads > 0, "at least one thread is needed");
+
+    let mut stream = Xoshiro256PlusPlus::new(seed);
+    let streams: Vec<_> = (0..games)
+        .map(|_| {
+            let rng = stream.clone();
+            stream.jump();
+            rng
+        })
+        .collect();
+    let next_index = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..threads.min(games) {
+            let sender = sender.clone();
+            let (streams, next_index, new_game) = (&streams, &next_index, &new_game);
+            scope.spawn(move || {
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some(rng) = streams.get(index) else {
+                        break;
+                    };
+                    let (mut self_play, board) = new_game(index);
+                    let samples = self_play.play_game(board, &mut rng.clone());
+                    if sender.send((index, samples)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        // The games finish out of order; each one waits here until the games before it are written.
+        let mut finished = BTreeMap::new();
+        let mut next_write = 0;
+        let mut written = 0;
+        let mut error = None;
+        for (index, samples) in receiver {
+            finished.insert(index, samples);
+            while let Some(samples) = finished.remove(&next_write) {
+                next_write += 1;
+                if error.is_some() {
+                    continue;
+                }
+                match writer.write_samples(&samples) {
+                    Ok(()) => written += samples.len(),
+                    Err(x) => {
+                        next_index.store(games, Ordering::Relaxed);
+                        error = Some(x);
+                    }
+                }
+            }
+        }
+        match error {
+            Some(x) => Err(x),
+            None => Ok(written),
+        }
+    })
+}
+
+/// Returns the distribution proportional to `policy^(1 / temperature)`, putting all the weight on
+/// the first largest share for a temperature of `0.0`.
+fn apply_temperature(policy: &[f64], temperature: f64) -> Vec<f64> {
+    let max = policy.iter().copied().fold(0.0, f64::max);
+    if temperature == 0.0 {
+        let index = policy.iter().position(|x| *x == max).unwrap_or(0);
+        return (0..policy.len())
+            .map(|x| (x == index) as u8 as f64)
+            .collect();
+    }
+    // The shares are scaled by the largest one so that low temperatures cannot underflow.
+    let weights: Vec<_> = policy
+        .iter()
+        .map(|x| (x / max).powf(1.0 / temperature))
+        .collect();
+    let sum: f64 = weights.iter().sum();
+    weights.iter().map(|x| x / sum).collect()
+}
+
+/// Writes training samples in the format described in the module documentation.
+pub struct SampleWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> SampleWriter<W> {
+    /// Creates a writer, writing the header of the format right away.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&SAMPLE_FORMAT_VERSION.to_le_bytes())?;
+        Ok(Self { writer })
+    }
+
+    /// Appends samples, e.g. those of one game.
+    pub fn write_samples(&mut self, samples: &[TrainingSample]) -> io::Result<()> {
+        for sample in samples {
+            write_floats(&mut self.writer, &sample.state)?;
+            write_floats(&mut self.writer, &sample.policy)?;
+            self.writer.write_all(&sample.outcome.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the samples written and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads every sample written by a `SampleWriter`.
+pub fn read_samples<R: Read>(mut reader: R) -> io::Result<Vec<TrainingSample>> {
+    let mut header = [0; 12];
+    reader.read_exact(&mut header)?;
+    if &header[..8] != MAGIC {
+        return Err(invalid_data("not a self-play sample file".into()));
+    }
+    let version = u32::from_le_bytes(header[8..].try_into().unwrap());
+    if version != SAMPLE_FORMAT_VERSION {
+        return Err(invalid_data(format!(
+            "unsupported sample format version {version}, expected {SAMPLE_FORMAT_VERSION}"
+        )));
+    }
+
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let mut rest = &bytes[..];
+    let mut samples = Vec::new();
+    while !rest.is_empty() {
+        let state = read_floats(&mut rest)?;
+        let policy = read_floats(&mut rest)?;
+        let outcome = f32::from_le_bytes(take(&mut rest, 4)?.try_into().unwrap());
+        samples.push(TrainingSample {
+            state,
+            policy,
+            outcome,
+        });
+    }
+    Ok(samples)
+}
+
+fn write_floats<W: Write>(writer: &mut W, values: &[f32]) -> io::Result<()> {
+    writer.write_all(&(values.len() as u32).to_le_bytes())?;
+    for value in values {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_floats(rest: &mut &[u8]) -> io::Result<Vec<f32>> {
+    let len = u32::from_le_bytes(take(rest, 4)?.try_into().unwrap()) as usize;
+    let bytes = take(rest, len.saturating_mul(4))?;
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|x| f32::from_le_bytes(x.try_into().unwrap()))
+        .collect())
+}
+
+/// Splits the first `len` bytes off `rest`, failing if the file ends before.
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if rest.len() < len {
+        return Err(invalid_data("truncated sample".into()));
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head)
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::{TTTPlayer, TicTacToeBoard};
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::{CustomNumberGenerator, Xoshiro256PlusPlus};
+    use crate::selfplay::{
+        SampleWriter, SelfPlay, SelfPlayConfig, TrainingSample, play_games_parallel, read_samples,
+    };
+    use std::io::ErrorKind;
+
+    fn encode(board: &TicTacToeBoard) -> Vec<f32> {
+        (0..9)
+            .map(|x| match board.get_cell(x) {
+                Some(TTTPlayer::X) => 1.0,
+                Some(TTTPlayer::O) => -1.0,
+                None => 0.0,
+            })
+            .collect()
+    }
+
+    fn self_play(config: SelfPlayConfig) -> SelfPlay<TicTacToeBoard, CustomNumberGenerator> {
+        SelfPlay::new(
+            |board| {
+                MonteCarloTreeSearch::builder(board)
+                    .with_random_generator(CustomNumberGenerator::default())
+                    .with_alpha_beta_pruning(false)
+                    .build()
+            },
+            encode,
+        )
+        .with_config(config)
+    }
+
+    #[test]
+    fn records_every_position_of_a_game() {
+        // arrange
+        let mut self_play = self_play(SelfPlayConfig {
+            iterations: 200,
+            ..SelfPlayConfig::default()
+        });
+
+        // act
+        let samples = self_play.play_game(
+            TicTacToeBoard::default(),
+            &mut CustomNumberGenerator::new(1),
+        );
+
+        // assert
+        assert!(samples.len() >= 5);
+        assert_eq!(samples[0].state, vec![0.0; 9]);
+        for (ply, sample) in samples.iter().enumerate() {
+            assert_eq!(sample.policy.len(), 9 - ply);
+            assert!((sample.policy.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+            assert_eq!(sample.state.iter().filter(|x| **x != 0.0).count(), ply);
+        }
+        for pair in samples.windows(2) {
+            assert_eq!(pair[0].outcome, -pair[1].outcome);
+        }
+    }
+
+    #[test]
+    fn temperature_and_noise_vary_the_games() {
+        // arrange
+        let greedy = SelfPlayConfig {
+            iterations: 100,
+            temperature_moves: 0,
+            noise_fraction: 0.0,
+            ..SelfPlayConfig::default()
+        };
+        let noisy = SelfPlayConfig {
+            noise_fraction: 0.5,
+            ..greedy
+        };
+        let openings = |config: SelfPlayConfig| {
+            let mut self_play = self_play(config);
+            let mut openings: Vec<_> = (0..10)
+                .map(|x| {
+                    let mut rng = CustomNumberGenerator::new(x);
+                    let samples = self_play.play_game(TicTacToeBoard::default(), &mut rng);
+                    samples[1].state.clone()
+                })
+                .collect();
+            openings.sort_by(|x, y| x.partial_cmp(y).unwrap());
+            openings.dedup();
+            openings.len()
+        };
+
+        // act
+        let greedy_openings = openings(greedy);
+        let noisy_openings = openings(noisy);
+
+        // assert
+        assert_eq!(greedy_openings, 1);
+        assert!(noisy_openings > 1);
+    }
+
+    #[test]
+    fn cut_off_games_are_scored_as_draws() {
+        let mut self_play = self_play(SelfPlayConfig {
+            iterations: 50,
+            max_plies: Some(3),
+            ..SelfPlayConfig::default()
+        });
+        let samples = self_play.play_game(
+            TicTacToeBoard::default(),
+            &mut CustomNumberGenerator::new(1),
+        );
+        assert_eq!(samples.len(), 3);
+        assert!(samples.iter().all(|x| x.outcome == 0.0));
+        let finished = TicTacToeBoard::from_moves(&[0, 3, 1, 4, 2]);
+        assert!(
+            self_play
+                .play_game(finished, &mut CustomNumberGenerator::new(1))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parallel_games_write_the_sequential_file() {
+        // arrange
+        let config = SelfPlayConfig {
+            iterations: 50,
+            ..SelfPlayConfig::default()
+        };
+        let mut sequential = SampleWriter::new(Vec::new()).unwrap();
+        let mut rng = Xoshiro256PlusPlus::new(7);
+        for _ in 0..6 {
+            let samples = self_play(config).play_game(TicTacToeBoard::default(), &mut rng.clone());
+            sequential.write_samples(&samples).unwrap();
+            rng.jump();
+        }
+        let sequential = read_samples(&sequential.finish().unwrap()[..]).unwrap();
+
+        // act
+        let mut parallel = SampleWriter::new(Vec::new()).unwrap();
+        let written = play_games_parallel(
+            6,
+            3,
+            7,
+            |_| (self_play(config), TicTacToeBoard::default()),
+            &mut parallel,
+        )
+        .unwrap();
+        let parallel = read_samples(&parallel.finish().unwrap()[..]).unwrap();
+
+        // assert
+        assert_eq!(written, sequential.len());
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn samples_survive_a_round_trip() {
+        // arrange
+        let samples = vec![
+            TrainingSample {
+                state: vec![1.0, -1.0, 0.5],
+                policy: vec![0.25, 0.75],
+                outcome: 1.0,
+            },
+            TrainingSample {
+                state: vec![],
+                policy: vec![1.0],
+                outcome: -1.0,
+            },
+        ];
+
+        // act
+        let mut writer = SampleWriter::new(Vec::new()).unwrap();
+        writer.write_samples(&samples[..1]).unwrap();
+        writer.write_samples(&samples[1..]).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        // assert
+        assert_eq!(bytes.len(), 12 + (4 + 12 + 4 + 8 + 4) + (4 + 4 + 4 + 4));
+        assert_eq!(read_samples(&bytes[..]).unwrap(), samples);
+        let error = read_samples(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        let error = read_samples(&b"MCTSCKPT\x01\0\0\0"[..]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::board::{Board, Bound, GameOutcome, Player};
+use crate::mcts::{MctsAction, MonteCarloTreeSearch};
+use crate::mcts_node::MctsNode;
+use crate::random::RandomGenerator;
+use ego_tree::NodeRef;
+use std::collections::HashMap;
+
+/// The exact solution of a position, see `MonteCarloTreeSearch::solve`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution<M> {
+    /// The outcome of the root position for the searching player when both players play
+    /// perfectly: `Win`, `Lose` or `Draw`.
+    pub value: GameOutcome,
+    /// The moves of both players along an optimal line from the root.
+    pub principal_variation: Vec<M>,
+    /// The number of iterations it took to solve the position.
+    pub iterations: u64,
+    strategy: HashMap<u128, M>,
+}
+
+impl<M> Solution<M> {
+    /// Returns the optimal move in the given position, which must be reachable from the root of
+    /// the search, or `None` if the position was not solved or the game is over.
+    ///
+    /// The move achieves the value of the position for the player to move, winning as fast or
+    /// losing as slowly as possible. Positions are looked up by `Board::get_hash`.
+    pub fn get_best_move<T: Board<Move = M>>(&self, board: &T) -> Option<&M> {
+        self.strategy.get(&board.get_hash())
+    }
+
+    /// Returns the optimal move of every solved position, keyed by `Board::get_hash`.
+    pub fn get_strategy(&self) -> &HashMap<u128, M> {
+        &self.strategy
+    }
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: Clone,
+{
+    /// Solves the root position exactly by driving the search until the whole tree is
+    /// calculated, returning its game-theoretic value and an optimal strategy.
+    ///
+    /// While solving, alpha-beta pruning is enabled, proven subtrees are kept so the strategy
+    /// covers them, and playouts end at once as draws instead of playing random moves, so only
+    /// the order in which nodes are expanded depends on the random generator. The configuration
+    /// is restored afterwards, and the tree can be searched further as usual. A tree searched
+    /// before without alpha-beta pruning may hold fully calculated nodes without bounds, which
+    /// are taken as draws.
+    ///
+    /// Returns `None` if the stop condition holds before the position is solved; calling `solve`
+    /// again continues where it stopped. Games whose positions can repeat forever are only solved
+    /// if a `TerminalOracle` or the board ends such lines.
+    pub fn solve(&mut self) -> Option<Solution<T::Move>> {
+        let iterations = self.get_statistics().iterations;
+        self.with_solver_settings(|mcts| {
+            while !matches!(
+                mcts.get_next_mcts_action(),
+                MctsAction::EverythingIsCalculated
+            ) {
+                if mcts.is_stop_requested() {
+                    return;
+                }
+                mcts.do_iteration();
+            }
+        });
+        if !matches!(
+            self.get_next_mcts_action(),
+            MctsAction::EverythingIsCalculated
+        ) {
+            return None;
+        }
+
+        let mut strategy = HashMap::new();
+        let (value, _) = self.solve_node(self.get_tree().root(), &mut strategy)?;
+        let mut principal_variation = Vec::new();
+        let mut board = self.get_board(self.get_tree().root().id());
+        while let Some(b_move) = strategy.get(&board.get_hash()) {
+            board.perform_move(b_move);
+            principal_variation.push(b_move.clone());
+        }
+
+        Some(Solution {
+            value,
+            principal_variation,
+            iterations: self.get_statistics().iterations - iterations,
+            strategy,
+        })
+    }
+
+    /// Returns the exact value of a node with the number of moves until the game ends under
+    /// optimal play, recording the optimal move of every node solved below it, or `None` if the
+    /// value does not follow from the tree.
+    fn solve_node(
+        &self,
+        node: NodeRef<MctsNode<T>>,
+        strategy: &mut HashMap<u128, T::Move>,
+    ) -> Option<(GameOutcome, u32)> {
+        let mcts_node = node.value();
+        if mcts_node.outcome != GameOutcome::InProgress {
+            return Some((mcts_node.outcome, 0));
+        }
+
+        // Every child is solved first, so the strategy also covers the moves that are not chosen.
+        let children: Vec<_> = node
+            .children()
+            .map(|x| (x, self.solve_node(x, strategy)))
+            .collect();
+        // The player to move prefers better outcomes, then faster wins and slower losses.
+        let preference = |(outcome, distance): (GameOutcome, u32)| {
+            let rank = match mcts_node.current_player {
+                Player::Me => rank(outcome),
+                Player::Other => 2 - rank(outcome),
+            };
+            let distance = distance as i64;
+            (rank, if rank == 2 { -distance } else { distance })
+        };
+        let best_child = children
+            .iter()
+            .filter_map(|(child, value)| value.map(|x| (child, x)))
+            .max_by_key(|x| preference(x.1));
+        let is_decided = best_child.is_some_and(|x| preference(x.1).0 == 2);
+        let outcome = match mcts_node.stats.bound {
+            Bound::DefoWin => GameOutcome::Win,
+            Bound::DefoLose => GameOutcome::Lose,
+            Bound::None if is_decided => best_child?.1.0,
+            Bound::None if children.iter().any(|x| x.1.is_none()) => return None,
+            Bound::None => match best_child {
+                Some(best_child) => best_child.1.0,
+                // Only a pruned subtree leaves a fully calculated node without children.
+                None if mcts_node.stats.is_fully_calculated => GameOutcome::Draw,
+                None => return None,
+            },
+        };
+
+        match best_child {
+            Some((child, (child_outcome, distance))) if child_outcome == outcome => {
+                let b_move = child.value().prev_move().unwrap().clone();
+                strategy.insert(self.get_board(node.id()).get_hash(), b_move);
+                Some((outcome, distance + 1))
+            }
+            _ => Some((outcome, 0)),
+        }
+    }
+}
+
+/// Orders outcomes from the worst to the best for the searching player.
+fn rank(outcome: GameOutcome) -> u8 {
+    match outcome {
+        GameOutcome::Win => 2,
+        GameOutcome::Draw => 1,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome};
+    use crate::boards::nim::NimBoard;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+
+    fn search<T: Board>(board: T) -> MonteCarloTreeSearch<T, CustomNumberGenerator> {
+        MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .build()
+    }
+
+    #[test]
+    fn solves_tic_tac_toe_as_a_draw() {
+        // arrange
+        let mut mcts = search(TicTacToeBoard::default());
+
+        // act
+        let solution = mcts.solve().unwrap();
+
+        // assert
+        assert_eq!(solution.value, GameOutcome::Draw);
+        assert!(solution.iterations > 0);
+        let mut board = TicTacToeBoard::default();
+        for b_move in &solution.principal_variation {
+            assert_eq!(solution.get_best_move(&board), Some(b_move));
+            board.perform_move(b_move);
+        }
+        assert_eq!(board.get_outcome(), GameOutcome::Draw);
+        assert_eq!(solution.principal_variation.len(), 9);
+        assert_eq!(mcts.get_cycle_policy(), Default::default());
+    }
+
+    #[test]
+    fn strategy_plays_the_winning_move() {
+        // arrange
+        // X wins by completing the middle row before O completes the top row.
+        let board = TicTacToeBoard::from_moves(&[4, 0, 3, 1]);
+        let mut mcts = search(board.clone());
+
+        // act
+        let solution = mcts.solve().unwrap();
+
+        // assert
+        assert_eq!(solution.value, GameOutcome::Win);
+        assert_eq!(solution.get_best_move(&board), Some(&5));
+        assert_eq!(solution.principal_variation, vec![5]);
+    }
+
+    #[test]
+    fn solves_nim_like_the_theory() {
+        for piles in [[1, 2, 3], [2, 3, 4], [1, 4, 4]] {
+            let board = NimBoard::new(&piles);
+            let mut mcts = search(board.clone());
+            let solution = mcts.solve().unwrap();
+            let value = match board.is_winning_position() {
+                true => GameOutcome::Win,
+                false => GameOutcome::Lose,
+            };
+            assert_eq!(solution.value, value);
+        }
+    }
+}
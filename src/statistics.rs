@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+/// General information about the state of a search, as returned by
+/// `MonteCarloTreeSearch::get_statistics`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct SearchStatistics {
+    /// The number of nodes in the tree, including the root.
+    pub node_count: usize,
+    /// The largest depth of any node below the root.
+    pub max_depth: usize,
+    /// The average number of children of the nodes that have been expanded.
+    pub average_branching_factor: f64,
+    /// The number of nodes with a proven win or loss.
+    pub proven_nodes: usize,
+    /// The number of completed iterations.
+    pub iterations: u64,
+    /// The wall-clock time spent running iterations.
+    pub elapsed: Duration,
+    /// The number of hash collisions detected in playouts, only counted with hash verification.
+    pub hash_collisions: u64,
+}
+
+/// An estimate of the memory taken by a search, as returned by `MonteCarloTreeSearch::memory_usage`.
+///
+/// Boards, moves and annotations are counted by their inline size, so memory they own on the heap,
+/// e.g. the vector of a board, is left out.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// The number of nodes allocated by the tree, including those detached by pruning.
+    pub node_count: usize,
+    /// The bytes taken by the nodes: their statistics, links, moves and annotations.
+    pub nodes: usize,
+    /// The bytes taken by the boards stored in the nodes, see `BoardStorage`.
+    pub boards: usize,
+    /// The bytes taken by the auxiliary tables of the search, such as the positions seen in the
+    /// current playout and the budgets of the subtrees.
+    pub tables: usize,
+}
+
+impl MemoryReport {
+    /// Returns the total number of bytes.
+    pub fn total(&self) -> usize {
+        self.nodes + self.boards + self.tables
+    }
+}
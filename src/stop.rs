@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Decides whether a running search should stop.
+///
+/// The search polls the condition between the phases of an iteration, so a condition that is set
+/// from another thread interrupts `iterate_n_times` promptly. An interrupted iteration is resumed
+/// from the phase it stopped at once the condition no longer holds.
+pub trait StopCondition: Send {
+    /// Returns `true` if the search should stop.
+    fn should_stop(&self) -> bool;
+}
+
+impl StopCondition for AtomicBool {
+    fn should_stop(&self) -> bool {
+        self.load(Ordering::Relaxed)
+    }
+}
+
+impl<S: StopCondition + Sync + ?Sized> StopCondition for Arc<S> {
+    fn should_stop(&self) -> bool {
+        self.as_ref().should_stop()
+    }
+}
@@ -0,0 +1,313 @@
+//! A WebSocket feed streaming the growth of the search tree as JSON, for browser visualizers.
+//!
+//! A `TelemetryTracker` turns the nodes touched by the iterations of a search into
+//! `TelemetryFrame`s holding only what changed since the previous frame: the nodes added to the
+//! tree and the new statistics of the nodes updated by backpropagation. A `TelemetryFeed` runs a
+//! search and sends the frames as JSON text messages over a WebSocket, one every few iterations.
+//!
+//! Nodes are identified by the `id` of their `MctsNode`, and every node is added after its parent,
+//! so a client can rebuild the tree by applying the frames in order.
+
+use crate::board::{Board, Bound};
+use crate::budget::SearchBudget;
+use crate::mcts::MonteCarloTreeSearch;
+use crate::observer::IterationReport;
+use crate::random::RandomGenerator;
+use crate::result::SearchResult;
+use ego_tree::NodeId;
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use tungstenite::{Message, WebSocket};
+
+/// A node added to the tree.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NodeAdded<M> {
+    /// The id of the node.
+    pub id: i32,
+    /// The id of the parent of the node, or `None` for the root.
+    pub parent: Option<i32>,
+    /// The move leading to the node, or `None` for the root.
+    pub prev_move: Option<M>,
+    /// The height of the node in the tree.
+    pub height: i32,
+}
+
+/// The statistics of a node after an update.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NodeUpdate {
+    /// The id of the node.
+    pub id: i32,
+    /// The number of times the node was visited.
+    pub visits: u32,
+    /// The number of simulations through the node that were won.
+    pub wins: u32,
+    /// The number of simulations through the node that were drawn.
+    pub draws: u32,
+    /// The mean of the values backed up through the node.
+    pub value: f64,
+    /// The proven bound of the node.
+    pub bound: Bound,
+}
+
+/// The changes to the tree since the previous frame.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TelemetryFrame<M> {
+    /// The number of iterations completed so far.
+    pub iteration: u64,
+    /// The nodes added to the tree, every node after its parent.
+    pub added: Vec<NodeAdded<M>>,
+    /// The nodes whose statistics changed, with their new statistics.
+    pub updated: Vec<NodeUpdate>,
+}
+
+/// Collects the nodes touched by the iterations of a search and turns them into frames.
+#[derive(Debug, Default)]
+pub struct TelemetryTracker {
+    touched: Arc<Mutex<Vec<NodeId>>>,
+    known: HashSet<NodeId>,
+}
+
+impl TelemetryTracker {
+    /// Creates a tracker that has not sent any node yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns an observer recording the nodes touched by every iteration, to be set on the
+    /// search with `set_iteration_observer`.
+    pub fn observer(&self) -> impl FnMut(&IterationReport) + Send + 'static {
+        let touched = self.touched.clone();
+        move |report: &IterationReport| {
+            let mut touched = touched.lock().unwrap();
+            touched.push(report.selected_leaf);
+            touched.extend_from_slice(&report.path);
+        }
+    }
+
+    /// Returns the changes to the tree since the previous frame.
+    ///
+    /// The first frame adds the root. The children of every touched node that were not sent
+    /// before are added, and every touched node is reported as updated.
+    pub fn next_frame<T: Board, K: RandomGenerator>(
+        &mut self,
+        mcts: &MonteCarloTreeSearch<T, K>,
+    ) -> TelemetryFrame<T::Move>
+    where
+        T::Move: Clone,
+    {
+        let tree = mcts.get_tree();
+        let mut touched = std::mem::take(&mut *self.touched.lock().unwrap());
+        touched.retain(|x| tree.get(*x).is_some());
+        touched.sort_by_key(|x| (tree.get(*x).unwrap().value().height, *x));
+        touched.dedup();
+
+        let mut added = Vec::new();
+        let root = tree.root();
+        let new_nodes = touched
+            .iter()
+            .flat_map(|x| tree.get(*x).unwrap().children())
+            .chain(std::iter::once(root));
+        let mut new_nodes: Vec<_> = new_nodes
+            .filter(|x| !self.known.contains(&x.id()))
+            .collect();
+        new_nodes.sort_by_key(|x| (x.value().height, x.id()));
+        for node in new_nodes {
+            if self.known.insert(node.id()) {
+                let mcts_node = node.value();
+                added.push(NodeAdded {
+                    id: mcts_node.id,
+                    parent: node.parent().map(|x| x.value().id),
+                    prev_move: mcts_node.prev_move().cloned(),
+                    height: mcts_node.height,
+                });
+            }
+        }
+
+        let updated = touched
+            .iter()
+            .map(|x| {
+                let mcts_node = tree.get(*x).unwrap().value();
+                NodeUpdate {
+                    id: mcts_node.id,
+                    visits: mcts_node.stats.visits,
+                    wins: mcts_node.stats.wins,
+                    draws: mcts_node.stats.draws,
+                    value: mcts_node.value_mean(),
+                    bound: mcts_node.stats.bound,
+                }
+            })
+            .collect();
+
+        TelemetryFrame {
+            iteration: mcts.get_statistics().iterations,
+            added,
+            updated,
+        }
+    }
+}
+
+/// Streams the frames of searches to a WebSocket client.
+pub struct TelemetryFeed<S: Read + Write> {
+    socket: WebSocket<S>,
+    interval: u64,
+}
+
+impl<S: Read + Write> TelemetryFeed<S> {
+    /// Accepts the WebSocket handshake of a client connected through `stream`, e.g. a `TcpStream`
+    /// returned by `TcpListener::accept`, and sends a frame every 100 iterations.
+    pub fn accept(stream: S) -> io::Result<Self> {
+        let socket = tungstenite::accept(stream).map_err(|x| io::Error::other(x.to_string()))?;
+        Ok(Self::new(socket))
+    }
+
+    /// Creates a feed sending on an established WebSocket every 100 iterations.
+    pub fn new(socket: WebSocket<S>) -> Self {
+        Self {
+            socket,
+            interval: 100,
+        }
+    }
+
+    /// Sets the number of iterations between two frames.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn with_interval(mut self, interval: u64) -> Self {
+        assert!(interval > 0, "interval must be positive");
+        self.interval = interval;
+        self
+    }
+
+    /// Sends a frame as a JSON text message.
+    pub fn send<M: serde::Serialize>(&mut self, frame: &TelemetryFrame<M>) -> io::Result<()> {
+        let json = serde_json::to_string(frame)?;
+        self.socket
+            .send(Message::text(json))
+            .map_err(io::Error::other)
+    }
+
+    /// Runs the search within the budget like `MonteCarloTreeSearch::search`, sending a frame
+    /// every `interval` iterations and once more when it ends.
+    ///
+    /// The nodes are tracked by an iteration observer installed for the duration of the search.
+    /// An observer already set on the search keeps receiving every iteration and is restored
+    /// afterwards. Every search streamed starts with a frame adding the root.
+    pub fn search<T: Board, K: RandomGenerator>(
+        &mut self,
+        mcts: &mut MonteCarloTreeSearch<T, K>,
+        budget: SearchBudget,
+    ) -> io::Result<SearchResult<T::Move>>
+    where
+        T::Move: Clone + serde::Serialize,
+    {
+        let mut tracker = TelemetryTracker::new();
+        let interval = self.interval;
+        let result = mcts.observe_during(tracker.observer(), |mcts| {
+            mcts.search_each(budget, |mcts| {
+                match mcts.get_statistics().iterations.is_multiple_of(interval) {
+                    true => self.send(&tracker.next_frame(mcts)),
+                    false => Ok(()),
+                }
+            })
+        })?;
+        self.send(&tracker.next_frame(mcts))?;
+        Ok(result)
+    }
+
+    /// Closes the WebSocket.
+    pub fn close(mut self) -> io::Result<()> {
+        match self.socket.close(None) {
+            Ok(()) | Err(tungstenite::Error::ConnectionClosed) => {}
+            Err(error) => return Err(io::Error::other(error)),
+        }
+        // Flush the close frame; the client may already have gone.
+        let _ = self.socket.flush();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::budget::SearchBudget;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+    use crate::telemetry::{TelemetryFeed, TelemetryFrame, TelemetryTracker};
+    use std::collections::HashMap;
+    use std::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn frames_rebuild_the_tree() {
+        // arrange
+        let mut tracker = TelemetryTracker::new();
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_iteration_observer(tracker.observer())
+            .build();
+
+        // act
+        let mut frames = Vec::new();
+        for _ in 0..5 {
+            mcts.iterate_n_times(20);
+            frames.push(tracker.next_frame(&mcts));
+        }
+
+        // assert
+        assert_eq!(frames[0].added[0].parent, None);
+        assert_eq!(frames[4].iteration, 100);
+        let mut visits = HashMap::new();
+        for frame in &frames {
+            for node in &frame.added {
+                assert!(node.parent.is_none_or(|x| visits.contains_key(&x)));
+                visits.insert(node.id, 0);
+            }
+            for node in &frame.updated {
+                visits.insert(node.id, node.visits);
+            }
+        }
+        assert_eq!(visits.len(), mcts.get_tree().nodes().count());
+        for node in mcts.get_tree().nodes() {
+            assert_eq!(visits[&node.value().id], node.value().stats.visits);
+        }
+    }
+
+    #[test]
+    fn streams_frames_over_websocket() {
+        // arrange
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let stream = TcpStream::connect(address).unwrap();
+            let (mut socket, _) = tungstenite::client(format!("ws://{address}/"), stream).unwrap();
+            let mut frames = Vec::new();
+            while let Ok(message) = socket.read() {
+                if message.is_text() {
+                    let text = message.into_text().unwrap();
+                    frames.push(serde_json::from_str::<TelemetryFrame<u8>>(&text).unwrap());
+                }
+            }
+            frames
+        });
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+
+        // act
+        let (stream, _) = listener.accept().unwrap();
+        let mut feed = TelemetryFeed::accept(stream).unwrap().with_interval(50);
+        let result = feed
+            .search(&mut mcts, SearchBudget::iterations(120))
+            .unwrap();
+        feed.close().unwrap();
+        let frames = client.join().unwrap();
+
+        // assert
+        let iterations: Vec<_> = frames.iter().map(|x| x.iteration).collect();
+        assert_eq!(iterations, vec![50, 100, 120]);
+        assert_eq!(result.iterations, 120);
+        let added: usize = frames.iter().map(|x| x.added.len()).sum();
+        assert_eq!(added, mcts.get_tree().nodes().count());
+    }
+}
@@ -0,0 +1,391 @@
+//! Helpers for locking in the behavior of the engine on custom boards.
+//!
+//! A seeded search is fully deterministic, so the shape and statistics of the resulting tree can
+//! be reduced to a single fingerprint and compared against a known value in a test:
+//!
+//! ```
+//! use mcts_lib::assert_search_snapshot;
+//! use mcts_lib::boards::tic_tac_toe::TicTacToeBoard;
+//! use mcts_lib::testing::{SnapshotConfig, search_fingerprint};
+//!
+//! let config = SnapshotConfig { iterations: 500, ..SnapshotConfig::default() };
+//! let expected = search_fingerprint(TicTacToeBoard::default(), &config);
+//! assert_search_snapshot!(TicTacToeBoard::default(), config, expected);
+//! ```
+//!
+//! Most bugs show up in the board rather than the engine, so `board_test_suite` checks the
+//! invariants the engine relies on along random games of any `Board` implementation:
+//!
+//! ```
+//! use mcts_lib::boards::tic_tac_toe::TicTacToeBoard;
+//! use mcts_lib::testing::board_test_suite;
+//!
+//! board_test_suite::<TicTacToeBoard>();
+//! ```
+
+use crate::board::{Board, Bound, GameOutcome};
+use crate::mcts::MonteCarloTreeSearch;
+use crate::mcts_node::MctsNode;
+use crate::random::{CustomNumberGenerator, RandomGenerator};
+use ego_tree::Tree;
+use std::fmt::Debug;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// The parameters of the seeded search run by `search_fingerprint`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SnapshotConfig {
+    /// The number of iterations to run.
+    pub iterations: u32,
+    /// The seed of the `CustomNumberGenerator` driving the search.
+    pub seed: i64,
+    /// Whether alpha-beta pruning is enabled.
+    pub use_alpha_beta_pruning: bool,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 1000,
+            seed: 42,
+            use_alpha_beta_pruning: true,
+        }
+    }
+}
+
+/// Runs a seeded search on `board` and returns the fingerprint of the resulting tree.
+pub fn search_fingerprint<T: Board>(board: T, config: &SnapshotConfig) -> u64 {
+    let mut mcts = MonteCarloTreeSearch::builder(board)
+        .with_random_generator(CustomNumberGenerator::new(config.seed))
+        .with_alpha_beta_pruning(config.use_alpha_beta_pruning)
+        .build();
+    mcts.iterate_n_times(config.iterations);
+    tree_fingerprint(mcts.get_tree())
+}
+
+/// Returns a hash of the shape and the statistics of a search tree.
+///
+/// The fingerprint covers the structure of the tree and, for every node, its depth, outcome and
+/// statistics. It does not depend on node identifiers or on the `Hash` implementations of the
+/// standard library, so it is stable across platforms and compiler versions.
+pub fn tree_fingerprint<T: Board>(tree: &Tree<MctsNode<T>>) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut feed = |value: u64| {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    for node in tree.root().descendants() {
+        let mcts_node = node.value();
+        let stats = &mcts_node.stats;
+        feed(mcts_node.height as u64);
+        feed(outcome_code(mcts_node.outcome));
+        feed(stats.visits as u64);
+        feed(stats.wins as u64);
+        feed(stats.draws as u64);
+        feed(bound_code(stats.bound));
+        feed(stats.is_fully_calculated as u64);
+        feed(node.children().count() as u64);
+    }
+    hash
+}
+
+fn outcome_code(outcome: GameOutcome) -> u64 {
+    match outcome {
+        GameOutcome::InProgress => 0,
+        GameOutcome::Win => 1,
+        GameOutcome::Lose => 2,
+        GameOutcome::Draw => 3,
+    }
+}
+
+fn bound_code(bound: Bound) -> u64 {
+    match bound {
+        Bound::None => 0,
+        Bound::DefoWin => 1,
+        Bound::DefoLose => 2,
+    }
+}
+
+/// The parameters of the random games played by `check_board`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BoardSuiteConfig {
+    /// The number of random games to play from the board.
+    pub games: u32,
+    /// The number of moves after which a game is abandoned, for games that may never end.
+    pub max_moves: u32,
+    /// The seed of the `CustomNumberGenerator` choosing the moves.
+    pub seed: i64,
+}
+
+impl Default for BoardSuiteConfig {
+    fn default() -> Self {
+        Self {
+            games: 20,
+            max_moves: 500,
+            seed: 42,
+        }
+    }
+}
+
+/// Checks the default position of a board type with `check_board` and the default
+/// `BoardSuiteConfig`.
+///
+/// # Panics
+///
+/// Panics if the board breaks any of the checked invariants.
+pub fn board_test_suite<T: Board + Default>()
+where
+    T::Move: PartialEq + Debug,
+{
+    check_board(T::default(), &BoardSuiteConfig::default());
+}
+
+/// Plays random games from `board` and checks, in every position reached, the invariants of a
+/// `Board` implementation the engine relies on:
+///
+/// - Repeated calls of `get_outcome`, `get_current_player`, `get_available_moves`, the hashes and
+///   the fingerprint return the same results.
+/// - A finished game has no moves, and a game in progress has at least one.
+/// - The available moves are distinct.
+/// - Performing a move changes the hash and leaves the board it was cloned from untouched.
+/// - Performing the same move on two clones yields the same hashes and fingerprints.
+/// - `outcome_after_move` agrees with `perform_move` followed by `get_outcome`.
+/// - Move priors are finite, and heuristic values lie within `0.0` and `1.0`.
+///
+/// Moves that are illegal but returned anyway can only be caught by the board itself, e.g. with
+/// debug assertions in `perform_move`, which the random games then exercise.
+///
+/// # Panics
+///
+/// Panics with the moves leading to the offending position if an invariant is broken.
+pub fn check_board<T: Board>(board: T, config: &BoardSuiteConfig)
+where
+    T::Move: PartialEq + Debug,
+{
+    let mut rng = CustomNumberGenerator::new(config.seed);
+    for _ in 0..config.games {
+        let mut board = board.clone();
+        let mut path = Vec::new();
+        for _ in 0..config.max_moves {
+            check_position(&board, &path);
+            let mut moves = board.get_available_moves();
+            if moves.is_empty() {
+                break;
+            }
+            let b_move = moves.swap_remove(rng.next_index(moves.len()));
+            board.perform_move(&b_move);
+            path.push(b_move);
+        }
+    }
+}
+
+/// Checks the invariants of a single position and of its moves.
+fn check_position<T: Board>(board: &T, path: &[T::Move])
+where
+    T::Move: PartialEq + Debug,
+{
+    let fail = |message: &str| -> ! { panic!("{message} after moves {path:?}") };
+    let outcome = board.get_outcome();
+    let moves = board.get_available_moves();
+    if board.get_outcome() != outcome
+        || board.get_current_player() != board.get_current_player()
+        || board.get_available_moves() != moves
+        || board.get_hash() != board.get_hash()
+        || board.get_hash64() != board.get_hash64()
+        || board.get_fingerprint() != board.get_fingerprint()
+    {
+        fail("repeated calls on the same board disagree");
+    }
+    if outcome != GameOutcome::InProgress && !moves.is_empty() {
+        fail("a finished game has moves");
+    }
+    if outcome == GameOutcome::InProgress && moves.is_empty() {
+        fail("a game in progress has no moves");
+    }
+    if let Some(value) = board.get_heuristic_value()
+        && !(0.0..=1.0).contains(&value)
+    {
+        fail(&format!("the heuristic value {value} is out of range"));
+    }
+
+    for (index, b_move) in moves.iter().enumerate() {
+        if moves[..index].contains(b_move) {
+            fail(&format!("the move {b_move:?} is available twice"));
+        }
+        if !board.get_move_prior(b_move).is_finite() {
+            fail(&format!("the prior of the move {b_move:?} is not finite"));
+        }
+
+        let mut child = board.clone();
+        child.perform_move(b_move);
+        let mut other_child = board.clone();
+        let outcome_after_move = other_child.outcome_after_move(b_move);
+        if child.get_hash() == board.get_hash() {
+            fail(&format!("the move {b_move:?} does not change the hash"));
+        }
+        if board.get_hash() != board.clone().get_hash()
+            || board.get_available_moves() != moves
+            || board.get_outcome() != outcome
+        {
+            fail(&format!(
+                "the move {b_move:?} changes the board it was cloned from"
+            ));
+        }
+        if child.get_hash() != other_child.get_hash()
+            || child.get_hash64() != other_child.get_hash64()
+            || child.get_fingerprint() != other_child.get_fingerprint()
+        {
+            fail(&format!(
+                "the move {b_move:?} yields different states from equal boards"
+            ));
+        }
+        if outcome_after_move != child.get_outcome() {
+            fail(&format!(
+                "outcome_after_move disagrees with perform_move for the move {b_move:?}"
+            ));
+        }
+    }
+}
+
+/// Asserts that a seeded search on a board produces a tree with the expected fingerprint.
+///
+/// Takes the board, a `SnapshotConfig` and the expected fingerprint as returned by
+/// `search_fingerprint`.
+#[macro_export]
+macro_rules! assert_search_snapshot {
+    ($board:expr, $config:expr, $expected:expr $(,)?) => {{
+        let expected: u64 = $expected;
+        let actual = $crate::testing::search_fingerprint($board, &$config);
+        assert_eq!(
+            actual, expected,
+            "search fingerprint changed: expected {:#018x}, got {:#018x}",
+            expected, actual
+        );
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, GameOutcome, MoveList, Player};
+    use crate::boards::breakthrough::BreakthroughBoard;
+    use crate::boards::checkers::CheckersBoard;
+    use crate::boards::goofspiel::GoofspielBoard;
+    use crate::boards::hex::HexBoard;
+    use crate::boards::kalah::KalahBoard;
+    use crate::boards::nim::NimBoard;
+    use crate::boards::nine_mens_morris::NineMensMorrisBoard;
+    use crate::boards::othello::OthelloBoard;
+    use crate::boards::pig::PigBoard;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::boards::ultimate_tic_tac_toe::UltimateTicTacToeBoard;
+    use crate::testing::{
+        BoardSuiteConfig, SnapshotConfig, board_test_suite, check_board, search_fingerprint,
+    };
+
+    #[test]
+    fn fingerprint_is_locked_in() {
+        assert_search_snapshot!(
+            TicTacToeBoard::default(),
+            SnapshotConfig::default(),
+            0x06b7167a955b595a
+        );
+    }
+
+    #[test]
+    fn fingerprint_depends_on_the_config() {
+        let config = SnapshotConfig::default();
+        let fingerprint = search_fingerprint(TicTacToeBoard::default(), &config);
+        let other_seed = SnapshotConfig { seed: 7, ..config };
+        let no_pruning = SnapshotConfig {
+            use_alpha_beta_pruning: false,
+            ..config
+        };
+
+        assert_eq!(
+            search_fingerprint(TicTacToeBoard::default(), &config),
+            fingerprint
+        );
+        assert_ne!(
+            search_fingerprint(TicTacToeBoard::default(), &other_seed),
+            fingerprint
+        );
+        assert_ne!(
+            search_fingerprint(TicTacToeBoard::default(), &no_pruning),
+            fingerprint
+        );
+    }
+
+    #[test]
+    fn bundled_boards_pass_the_suite() {
+        board_test_suite::<BreakthroughBoard>();
+        board_test_suite::<CheckersBoard>();
+        board_test_suite::<GoofspielBoard>();
+        board_test_suite::<HexBoard>();
+        board_test_suite::<KalahBoard>();
+        board_test_suite::<NineMensMorrisBoard>();
+        board_test_suite::<OthelloBoard>();
+        board_test_suite::<PigBoard>();
+        board_test_suite::<TicTacToeBoard>();
+        board_test_suite::<UltimateTicTacToeBoard>();
+        check_board(NimBoard::new(&[3, 4, 5]), &BoardSuiteConfig::default());
+    }
+
+    /// A countdown game, won by taking the last token, that forgets to stop offering moves once
+    /// it is over.
+    #[derive(Clone)]
+    struct CountdownBoard {
+        tokens: u8,
+        is_my_turn: bool,
+    }
+
+    impl Default for CountdownBoard {
+        fn default() -> Self {
+            Self {
+                tokens: 5,
+                is_my_turn: true,
+            }
+        }
+    }
+
+    impl Board for CountdownBoard {
+        type Move = u8;
+
+        fn get_current_player(&self) -> Player {
+            match self.is_my_turn {
+                true => Player::Me,
+                false => Player::Other,
+            }
+        }
+
+        fn get_outcome(&self) -> GameOutcome {
+            match (self.tokens, self.is_my_turn) {
+                (0, true) => GameOutcome::Lose,
+                (0, false) => GameOutcome::Win,
+                _ => GameOutcome::InProgress,
+            }
+        }
+
+        fn get_available_moves(&self) -> MoveList<u8> {
+            MoveList::from_slice(&[1, 2])
+        }
+
+        fn perform_move(&mut self, b_move: &u8) {
+            self.tokens = self.tokens.saturating_sub(*b_move);
+            self.is_my_turn = !self.is_my_turn;
+        }
+
+        fn get_hash(&self) -> u128 {
+            (self.tokens as u128) << 1 | self.is_my_turn as u128
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "a finished game has moves")]
+    fn catches_broken_boards() {
+        board_test_suite::<CountdownBoard>();
+    }
+}
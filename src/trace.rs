@@ -0,0 +1,119 @@
+use crate::mcts::MctsAction;
+
+/// A record of the actions executed by the search, in order.
+///
+/// Every entry is the value of `MonteCarloTreeSearch::get_next_mcts_action` after an action was
+/// executed, so the entries carry the results of the phases: the selected leaf and the path to
+/// it in `MctsAction::Expansion`, the expanded children in `MctsAction::Simulation`, the outcome
+/// of the simulation in `MctsAction::Backpropagation` and the updated path in
+/// `MctsAction::Selection`.
+/// The first entry is the action that was pending when recording started. Stepping through the
+/// entries replays the search, e.g. in a visualizer or a test.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ActionTrace {
+    actions: Vec<MctsAction>,
+}
+
+impl ActionTrace {
+    /// Creates an empty trace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded actions, oldest first.
+    pub fn get_actions(&self) -> &[MctsAction] {
+        &self.actions
+    }
+
+    /// Returns the number of recorded actions.
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Checks whether no action has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Returns the recorded actions split into iterations, each ending with the
+    /// `MctsAction::Selection` that completed it. A trailing unfinished iteration is included.
+    pub fn iterations(&self) -> impl Iterator<Item = &[MctsAction]> {
+        self.actions
+            .split_inclusive(|x| matches!(x, MctsAction::Selection { R: _, RP: _ }))
+    }
+
+    /// Removes every recorded action.
+    pub fn clear(&mut self) {
+        self.actions.clear();
+    }
+
+    /// Appends an action to the trace.
+    pub(crate) fn push(&mut self, action: MctsAction) {
+        self.actions.push(action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::GameOutcome;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::{MctsAction, MonteCarloTreeSearch};
+    use crate::random::CustomNumberGenerator;
+
+    #[test]
+    fn trace_records_every_phase() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_action_trace(true)
+            .build();
+
+        // act
+        mcts.iterate_n_times(10);
+
+        // assert
+        let trace = mcts.get_action_trace().unwrap();
+        assert_eq!(trace.len(), 41);
+        assert_eq!(trace.iterations().count(), 11);
+        let first_iteration: Vec<_> = trace.iterations().nth(1).unwrap().to_vec();
+        let names: Vec<_> = first_iteration.iter().map(|x| x.get_name()).collect();
+        assert_eq!(
+            names,
+            vec!["Expansion", "Simulation", "Backpropagation", "Selection"]
+        );
+
+        let root_id = mcts.get_root().id();
+        let MctsAction::Simulation { C, AC } = &first_iteration[1] else {
+            panic!("expected a simulation");
+        };
+        let root_children: Vec<_> = mcts.get_tree().root().children().map(|x| x.id()).collect();
+        assert_eq!(AC, &root_children);
+        assert!(AC.contains(C));
+        let MctsAction::Backpropagation { result, .. } = first_iteration[2] else {
+            panic!("expected a backpropagation");
+        };
+        assert_ne!(result, GameOutcome::InProgress);
+        let MctsAction::Selection { RP, .. } = &first_iteration[3] else {
+            panic!("expected a selection");
+        };
+        assert_eq!(RP, &vec![*C, root_id]);
+    }
+
+    #[test]
+    fn trace_can_be_taken_and_disabled() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_action_trace(true)
+            .build();
+        mcts.iterate_n_times(3);
+
+        // act
+        let trace = mcts.take_action_trace().unwrap();
+        mcts.iterate_n_times(3);
+
+        // assert
+        assert_eq!(trace.len(), 13);
+        assert!(mcts.get_action_trace().is_none());
+    }
+}
@@ -0,0 +1,301 @@
+//! A terminal viewer showing the top of the search tree while the search runs.
+//!
+//! `LiveViewer` runs a search within a `SearchBudget` and redraws a `TreeView` of the root moves
+//! (visits, win rates, values, proven bounds and principal variations) every few iterations, as
+//! signalled by an iteration observer. It draws on any ratatui `Terminal`, e.g. one backed by
+//! crossterm for a real terminal, or by `TestBackend` in tests.
+
+use crate::board::{Board, Bound};
+use crate::budget::SearchBudget;
+use crate::mcts::MonteCarloTreeSearch;
+use crate::observer::IterationReport;
+use crate::random::RandomGenerator;
+use crate::result::SearchResult;
+use ratatui::Terminal;
+use ratatui::backend::Backend;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, Row, Table, Widget};
+use std::fmt::Debug;
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// A root move as shown by a `TreeView`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeViewRow {
+    /// The move, formatted with `Debug`.
+    pub b_move: String,
+    /// The number of times the move was visited.
+    pub visits: u32,
+    /// The fraction of simulations through the move that were won.
+    pub win_rate: f64,
+    /// The mean of the values backed up through the move.
+    pub value: f64,
+    /// The proven bound of the move.
+    pub bound: Bound,
+    /// The line of play expected after the move, formatted with `Debug`.
+    pub principal_variation: Vec<String>,
+}
+
+/// A snapshot of the top of the search tree, rendered as a ratatui widget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeView {
+    /// The number of iterations completed so far.
+    pub iterations: u64,
+    /// The time spent in iterations so far.
+    pub elapsed: Duration,
+    /// The proven bound of the root.
+    pub root_bound: Bound,
+    /// The most visited root moves, most visited first.
+    pub rows: Vec<TreeViewRow>,
+}
+
+impl TreeView {
+    /// Takes a snapshot of the `rows` most visited root moves of the search.
+    pub fn from_search<T: Board, K: RandomGenerator>(
+        mcts: &MonteCarloTreeSearch<T, K>,
+        rows: usize,
+    ) -> Self
+    where
+        T::Move: Clone + Debug,
+    {
+        let mut children: Vec<_> = mcts.get_root().children().collect();
+        children.sort_by_key(|x| std::cmp::Reverse(x.value().stats.visits));
+        let rows = children
+            .into_iter()
+            .take(rows)
+            .map(|x| {
+                let mcts_node = x.value();
+                TreeViewRow {
+                    b_move: format!("{:?}", mcts_node.prev_move().unwrap()),
+                    visits: mcts_node.stats.visits,
+                    win_rate: mcts_node.wins_rate(),
+                    value: mcts_node.value_mean(),
+                    bound: mcts_node.stats.bound,
+                    principal_variation: mcts
+                        .principal_variation_from(x.into())
+                        .iter()
+                        .map(|x| format!("{x:?}"))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let statistics = mcts.get_statistics();
+        Self {
+            iterations: statistics.iterations,
+            elapsed: statistics.elapsed,
+            root_bound: mcts.get_root().value().stats.bound,
+            rows,
+        }
+    }
+}
+
+impl Widget for &TreeView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = format!(
+            " {} iterations in {:.1?}, root {:?} ",
+            self.iterations, self.elapsed, self.root_bound
+        );
+        let header = Row::new(["move", "visits", "win %", "value", "bound", "pv"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let rows = self.rows.iter().map(|x| {
+            Row::new([
+                x.b_move.clone(),
+                x.visits.to_string(),
+                format!("{:.1}", x.win_rate * 100.0),
+                format!("{:.3}", x.value),
+                format!("{:?}", x.bound),
+                x.principal_variation.join(" "),
+            ])
+        });
+        let widths = [
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Length(7),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ];
+        Table::new(rows, widths)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .render(area, buf);
+    }
+}
+
+/// Runs searches while drawing a live `TreeView` of them on a terminal.
+pub struct LiveViewer<B: Backend> {
+    terminal: Terminal<B>,
+    refresh_interval: u64,
+    rows: usize,
+}
+
+impl<B: Backend> LiveViewer<B> {
+    /// Creates a viewer drawing on the terminal every 1000 iterations, showing the 10 most visited
+    /// root moves.
+    pub fn new(terminal: Terminal<B>) -> Self {
+        Self {
+            terminal,
+            refresh_interval: 1000,
+            rows: 10,
+        }
+    }
+
+    /// Sets the number of iterations between two redraws.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `refresh_interval` is zero.
+    pub fn with_refresh_interval(mut self, refresh_interval: u64) -> Self {
+        assert!(refresh_interval > 0, "refresh interval must be positive");
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    /// Sets the number of root moves shown.
+    pub fn with_rows(mut self, rows: usize) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    /// Returns the terminal the viewer draws on.
+    pub fn get_terminal(&self) -> &Terminal<B> {
+        &self.terminal
+    }
+
+    /// Draws a snapshot of the search.
+    pub fn draw<T: Board, K: RandomGenerator>(
+        &mut self,
+        mcts: &MonteCarloTreeSearch<T, K>,
+    ) -> io::Result<()>
+    where
+        T::Move: Clone + Debug,
+    {
+        let view = TreeView::from_search(mcts, self.rows);
+        self.terminal
+            .draw(|frame| frame.render_widget(&view, frame.area()))?;
+        Ok(())
+    }
+
+    /// Runs the search within the budget like `MonteCarloTreeSearch::search`, redrawing it every
+    /// `refresh_interval` iterations and once more when it ends.
+    ///
+    /// The redraws are requested by an iteration observer installed for the duration of the
+    /// search. An observer already set on the search keeps receiving every iteration and is
+    /// restored afterwards.
+    pub fn search<T: Board, K: RandomGenerator>(
+        &mut self,
+        mcts: &mut MonteCarloTreeSearch<T, K>,
+        budget: SearchBudget,
+    ) -> io::Result<SearchResult<T::Move>>
+    where
+        T::Move: Clone + Debug,
+    {
+        let is_redraw_due = Arc::new(AtomicBool::new(false));
+        let refresh_interval = self.refresh_interval;
+        let observer = {
+            let is_redraw_due = is_redraw_due.clone();
+            move |report: &IterationReport| {
+                if report.iteration.is_multiple_of(refresh_interval) {
+                    is_redraw_due.store(true, Ordering::Relaxed);
+                }
+            }
+        };
+
+        let result = mcts.observe_during(observer, |mcts| {
+            mcts.search_each(budget, |mcts| {
+                match is_redraw_due.swap(false, Ordering::Relaxed) {
+                    true => self.draw(mcts),
+                    false => Ok(()),
+                }
+            })
+        })?;
+        self.draw(mcts)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::budget::SearchBudget;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::observer::IterationReport;
+    use crate::random::CustomNumberGenerator;
+    use crate::tui::{LiveViewer, TreeView};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn screen(viewer: &LiveViewer<TestBackend>) -> Vec<String> {
+        let buffer = viewer.get_terminal().backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|x| x.iter().map(|x| x.symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn snapshots_the_most_visited_moves() {
+        // arrange
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        mcts.iterate_n_times(500);
+
+        // act
+        let view = TreeView::from_search(&mcts, 3);
+
+        // assert
+        assert_eq!(view.iterations, 500);
+        assert_eq!(view.rows.len(), 3);
+        assert!(view.rows.windows(2).all(|x| x[0].visits >= x[1].visits));
+        let result = mcts.get_search_result();
+        assert_eq!(view.rows[0].visits, result.moves[0].visits);
+        assert_eq!(
+            view.rows[0].principal_variation.len() + 1,
+            result.principal_variation.len()
+        );
+    }
+
+    #[test]
+    fn draws_the_search_and_keeps_the_observer() {
+        // arrange
+        let seen = Arc::new(AtomicU64::new(0));
+        let mut mcts = MonteCarloTreeSearch::builder(TicTacToeBoard::default())
+            .with_alpha_beta_pruning(false)
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_iteration_observer({
+                let seen = seen.clone();
+                move |_: &IterationReport| {
+                    seen.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+            .build();
+        let mut viewer = LiveViewer::new(Terminal::new(TestBackend::new(80, 8)).unwrap())
+            .with_refresh_interval(100)
+            .with_rows(3);
+
+        // act
+        let result = viewer
+            .search(&mut mcts, SearchBudget::iterations(300))
+            .unwrap();
+        mcts.iterate_n_times(10);
+
+        // assert
+        assert_eq!(result.iterations, 300);
+        assert_eq!(seen.load(Ordering::Relaxed), 310);
+        let screen = screen(&viewer);
+        assert!(screen[0].contains(" 300 iterations in "));
+        assert!(screen[1].contains("move"));
+        assert!(screen[1].contains("visits"));
+        let best_move = format!("│{:<8}", result.best_move.unwrap());
+        assert!(screen[2].starts_with(&best_move));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::board::{Board, GameOutcome, Player};
+
+/// Returns the exact outcome of a position for the searching player under perfect play of both
+/// players, found by an exhaustive alpha-beta search.
+///
+/// Meant as a reference for cross-checking the search on tiny games such as Tic-Tac-Toe or small
+/// Nim positions; the running time grows exponentially with the length of the game, and games
+/// whose positions can repeat forever never return. A position without moves that is not over
+/// counts as a draw.
+pub fn minimax_outcome<T: Board>(board: &T) -> GameOutcome {
+    match alpha_beta(board, -1, 1) {
+        1 => GameOutcome::Win,
+        -1 => GameOutcome::Lose,
+        _ => GameOutcome::Draw,
+    }
+}
+
+/// Returns every move of the player to move achieving the exact outcome of the position, in the
+/// order of `Board::get_available_moves`. Empty if the game is over.
+pub fn optimal_moves<T: Board>(board: &T) -> Vec<T::Move>
+where
+    T::Move: Clone,
+{
+    if board.get_outcome() != GameOutcome::InProgress {
+        return Vec::new();
+    }
+
+    let value = alpha_beta(board, -1, 1);
+    board
+        .get_available_moves()
+        .into_iter()
+        .filter(|x| {
+            let mut child = board.clone();
+            child.perform_move(x);
+            alpha_beta(&child, -1, 1) == value
+        })
+        .collect()
+}
+
+/// Returns the value of a position for the searching player, 1 for a win, 0 for a draw and -1 for
+/// a loss, exact if it lies within `alpha` and `beta`.
+fn alpha_beta<T: Board>(board: &T, mut alpha: i8, mut beta: i8) -> i8 {
+    match board.get_outcome() {
+        GameOutcome::Win => return 1,
+        GameOutcome::Lose => return -1,
+        GameOutcome::Draw => return 0,
+        GameOutcome::InProgress => {}
+    }
+
+    let moves = board.get_available_moves();
+    if moves.is_empty() {
+        return 0;
+    }
+
+    let is_maximizing = board.get_current_player() == Player::Me;
+    let mut best = if is_maximizing { -1 } else { 1 };
+    for b_move in moves.iter() {
+        let mut child = board.clone();
+        child.perform_move(b_move);
+        let value = alpha_beta(&child, alpha, beta);
+        if is_maximizing {
+            best = best.max(value);
+            alpha = alpha.max(value);
+        } else {
+            best = best.min(value);
+            beta = beta.min(value);
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Bound, GameOutcome};
+    use crate::boards::nim::NimBoard;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::{MctsAction, MonteCarloTreeSearch};
+    use crate::random::CustomNumberGenerator;
+    use crate::verify::{minimax_outcome, optimal_moves};
+
+    /// Searches a position until its tree is fully calculated, then checks the bound of the root
+    /// and the solved best move against the reference.
+    fn cross_check<T: Board>(board: T)
+    where
+        T::Move: Clone + PartialEq + std::fmt::Debug,
+    {
+        let outcome = minimax_outcome(&board);
+        let mut mcts = MonteCarloTreeSearch::builder(board.clone())
+            .with_random_generator(CustomNumberGenerator::default())
+            .build();
+        while !matches!(
+            mcts.get_next_mcts_action(),
+            MctsAction::EverythingIsCalculated
+        ) {
+            mcts.do_iteration();
+        }
+
+        let bound = mcts.get_root().value().stats.bound;
+        let expected_bound = match outcome {
+            GameOutcome::Win => Bound::DefoWin,
+            GameOutcome::Lose => Bound::DefoLose,
+            _ => Bound::None,
+        };
+        assert_eq!(bound, expected_bound);
+        let solution = mcts.solve().unwrap();
+        assert_eq!(solution.value, outcome);
+        let best_move = solution.get_best_move(&board).unwrap();
+        assert!(optimal_moves(&board).contains(best_move));
+    }
+
+    #[test]
+    fn knows_the_values_of_small_games() {
+        assert_eq!(
+            minimax_outcome(&TicTacToeBoard::default()),
+            GameOutcome::Draw
+        );
+        // X wins by completing the middle row, but loses by letting O complete the top row.
+        let moves = optimal_moves(&TicTacToeBoard::from_moves(&[4, 0, 3, 1]));
+        assert!(moves.contains(&5));
+        assert!(!moves.contains(&8));
+        for piles in [[1, 2, 3], [2, 3, 4], [1, 4, 5]] {
+            let board = NimBoard::new(&piles);
+            let expected = match board.is_winning_position() {
+                true => GameOutcome::Win,
+                false => GameOutcome::Lose,
+            };
+            assert_eq!(minimax_outcome(&board), expected);
+        }
+    }
+
+    #[test]
+    fn search_agrees_on_tic_tac_toe() {
+        for moves in [
+            &[][..],
+            &[4, 0],
+            &[0, 4, 8, 1],
+            &[4, 0, 3, 1],
+            &[0, 3, 1, 4],
+        ] {
+            cross_check(TicTacToeBoard::from_moves(moves));
+        }
+    }
+
+    #[test]
+    fn search_agrees_on_nim() {
+        for piles in [[1, 2, 3], [2, 3, 4], [1, 4, 5], [2, 2, 3]] {
+            cross_check(NimBoard::new(&piles));
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::board::{Board, Player};
+use crate::mcts::MonteCarloTreeSearch;
+use crate::random::RandomGenerator;
+use ego_tree::NodeId;
+
+/// An external estimate of a root move, e.g. from a previous game, a book or a network, added to
+/// the tree by `MonteCarloTreeSearch::warm_start`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RootEstimate<M> {
+    /// The move the estimate is about.
+    pub b_move: M,
+    /// The number of virtual visits the estimate is worth, i.e. how much the search trusts it.
+    pub visits: u32,
+    /// The estimated value of the move for the player to move at the root, from `0.0` (a sure
+    /// loss) to `1.0` (a sure win).
+    pub value: f64,
+}
+
+impl<T: Board, K: RandomGenerator> MonteCarloTreeSearch<T, K>
+where
+    T::Move: PartialEq,
+{
+    /// Seeds the children of the root with virtual visits, as if every visit of an estimate had
+    /// been a simulation through its move backing up its value.
+    ///
+    /// The first iterations then refine the estimates instead of rediscovering them, and the
+    /// virtual visits count towards the best child criterion like real ones. The values are backed
+    /// up to the root as well, so its mean value reflects the estimates. Virtual wins are the
+    /// rounded share of the visits given by the value. Estimates of moves the root does not have
+    /// are ignored. The root is expanded first if needed, so this is best called before the first
+    /// iteration. Returns `false`, leaving the tree untouched, if the root cannot be expanded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value of an estimate is outside `0.0..=1.0`.
+    pub fn warm_start(
+        &mut self,
+        estimates: impl IntoIterator<Item = RootEstimate<T::Move>>,
+    ) -> bool {
+        if !self.expand_root() {
+            return false;
+        }
+
+        let root = self.get_tree().root();
+        let is_me_to_move = root.value().current_player == Player::Me;
+        let root_id = root.id();
+        let children: Vec<_> = root.children().map(|x| x.id()).collect();
+        for estimate in estimates {
+            assert!(
+                (0.0..=1.0).contains(&estimate.value),
+                "the value of an estimate must be within 0 and 1"
+            );
+            let Some(child_id) = children.iter().copied().find(|x| {
+                let node = self.get_tree().get(*x).unwrap();
+                node.value().prev_move() == Some(&estimate.b_move)
+            }) else {
+                continue;
+            };
+
+            let value = if is_me_to_move {
+                estimate.value
+            } else {
+                1.0 - estimate.value
+            };
+            self.add_virtual_visits(child_id, estimate.visits, value);
+            self.add_virtual_visits(root_id, estimate.visits, value);
+        }
+        true
+    }
+
+    /// Adds `visits` virtual simulations backing up `value` to the statistics of a node.
+    fn add_virtual_visits(&mut self, node_id: NodeId, visits: u32, value: f64) {
+        let mut node = self.get_tree_mut().get_mut(node_id).unwrap();
+        let stats = &mut node.value().stats;
+        let weight = visits as f64;
+        stats.visits += visits;
+        stats.wins += (weight * value).round() as u32;
+        stats.value_sum += weight * value;
+        stats.value_sum_sq += weight * value * value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Board;
+    use crate::boards::tic_tac_toe::TicTacToeBoard;
+    use crate::mcts::MonteCarloTreeSearch;
+    use crate::random::CustomNumberGenerator;
+    use crate::warm_start::RootEstimate;
+
+    fn search(
+        board: TicTacToeBoard,
+    ) -> MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator> {
+        MonteCarloTreeSearch::builder(board)
+            .with_random_generator(CustomNumberGenerator::default())
+            .with_alpha_beta_pruning(false)
+            .build()
+    }
+
+    fn child_stats(
+        mcts: &MonteCarloTreeSearch<TicTacToeBoard, CustomNumberGenerator>,
+        b_move: u8,
+    ) -> (u32, u32, f64) {
+        let root = mcts.get_root();
+        let child = root
+            .children()
+            .find(|x| x.value().prev_move() == Some(&b_move))
+            .unwrap();
+        let stats = &child.value().stats;
+        (stats.visits, stats.wins, stats.value_sum)
+    }
+
+    #[test]
+    fn seeds_the_root_children() {
+        // arrange
+        let mut mcts = search(TicTacToeBoard::default());
+        let estimates = [
+            RootEstimate {
+                b_move: 4,
+                visits: 100,
+                value: 0.75,
+            },
+            RootEstimate {
+                b_move: 1,
+                visits: 10,
+                value: 0.2,
+            },
+        ];
+
+        // act
+        let is_seeded = mcts.warm_start(estimates);
+
+        // assert
+        assert!(is_seeded);
+        assert_eq!(child_stats(&mcts, 4), (100, 75, 75.0));
+        assert_eq!(child_stats(&mcts, 1), (10, 2, 2.0));
+        assert_eq!(child_stats(&mcts, 0), (0, 0, 0.0));
+        assert_eq!(mcts.get_root().value().stats.visits, 110);
+        assert_eq!(mcts.get_root().value().stats.value_sum, 77.0);
+        assert_eq!(mcts.get_most_perspective_move(), Some(4));
+    }
+
+    #[test]
+    fn values_are_taken_for_the_player_to_move() {
+        // arrange
+        let mut board = TicTacToeBoard::default();
+        board.perform_move(&0);
+        let mut mcts = search(board);
+
+        // act
+        mcts.warm_start([RootEstimate {
+            b_move: 4,
+            visits: 8,
+            value: 0.75,
+        }]);
+
+        // assert
+        assert_eq!(child_stats(&mcts, 4), (8, 2, 2.0));
+    }
+
+    #[test]
+    fn estimates_steer_the_first_iterations() {
+        // arrange
+        // X to move wins with c1, and must take it before O completes the middle row.
+        let board = TicTacToeBoard::from_moves(&[0, 3, 1, 4]);
+        let mut mcts = search(board);
+        mcts.warm_start([RootEstimate {
+            b_move: 2,
+            visits: 20,
+            value: 1.0,
+        }]);
+
+        // act
+        mcts.iterate_n_times(10);
+
+        // assert
+        assert_eq!(mcts.get_most_perspective_move(), Some(2));
+        assert_eq!(mcts.get_root().value().stats.visits, 30);
+    }
+}